@@ -225,6 +225,7 @@ fn compute_inner(tree: &mut impl LayoutBlockContainer, node_id: NodeId, inputs:
             tree,
             &mut items,
             container_outer_width,
+            container_content_box_size.height,
             content_box_inset,
             resolved_content_box_inset,
             text_align,
@@ -309,8 +310,10 @@ fn generate_item_list(
         .enumerate()
         .map(|(order, (child_node_id, child_style))| {
             let aspect_ratio = child_style.aspect_ratio();
-            let padding = child_style.padding().resolve_or_zero(node_inner_size, |val, basis| tree.calc(val, basis));
-            let border = child_style.border().resolve_or_zero(node_inner_size, |val, basis| tree.calc(val, basis));
+            let padding =
+                child_style.padding().resolve_or_zero(node_inner_size.width, |val, basis| tree.calc(val, basis));
+            let border =
+                child_style.border().resolve_or_zero(node_inner_size.width, |val, basis| tree.calc(val, basis));
             let pb_sum = (padding + border).sum_axes();
             let box_sizing_adjustment =
                 if child_style.box_sizing() == BoxSizing::ContentBox { pb_sum } else { Size::ZERO };
@@ -390,10 +393,12 @@ fn determine_content_based_container_width(
 
 /// Compute each child's final size and position
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn perform_final_layout_on_in_flow_children(
     tree: &mut impl LayoutPartialTree,
     items: &mut [BlockItem],
     container_outer_width: f32,
+    container_inner_height: Option<f32>,
     content_box_inset: Rect<f32>,
     resolved_content_box_inset: Rect<f32>,
     text_align: TextAlign,
@@ -469,10 +474,10 @@ fn perform_final_layout_on_in_flow_children(
                 bottom: bottom_margin_set.resolve(),
             };
 
-            // Resolve item inset
-            let inset = item.inset.zip_size(Size { width: container_inner_width, height: 0.0 }, |p, s| {
-                p.maybe_resolve(s, |val, basis| tree.calc(val, basis))
-            });
+            // Resolve item inset. Vertical percentages behave as `auto` if the container's height is indefinite.
+            let inset_relative_size = Size { width: Some(container_inner_width), height: container_inner_height };
+            let inset =
+                item.inset.zip_size(inset_relative_size, |p, s| p.maybe_resolve(s, |val, basis| tree.calc(val, basis)));
             let inset_offset = Point {
                 x: inset.left.or(inset.right.map(|x| -x)).unwrap_or(0.0),
                 y: inset.top.or(inset.bottom.map(|x| -x)).unwrap_or(0.0),
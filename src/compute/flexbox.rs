@@ -2,8 +2,8 @@
 use crate::compute::common::alignment::compute_alignment_offset;
 use crate::geometry::{Line, Point, Rect, Size};
 use crate::style::{
-    AlignContent, AlignItems, AlignSelf, AvailableSpace, FlexWrap, JustifyContent, LengthPercentageAuto, Overflow,
-    Position,
+    AlignContent, AlignItems, AlignSelf, AvailableSpace, Dimension, Direction, FlexWrap, JustifyContent,
+    LengthPercentageAuto, Overflow, Position,
 };
 use crate::style::{CoreStyle, FlexDirection, FlexboxContainerStyle, FlexboxItemStyle};
 use crate::style_helpers::{TaffyMaxContent, TaffyMinContent};
@@ -125,7 +125,14 @@ struct AlgoConstants {
     /// Is wrapping enabled (in either direction)
     is_wrap: bool,
     /// Is the wrap direction inverted
+    ///
+    /// This is also the case for columns in right-to-left containers, as their cross axis starts from the right.
     is_wrap_reverse: bool,
+    /// Is the inline axis right-to-left
+    ///
+    /// This reverses the main axis of rows (which is accounted for in `dir`) and the cross axis of columns
+    /// (which is accounted for in `is_wrap_reverse`).
+    is_rtl: bool,
 
     /// The item's min_size style
     min_size: Size<Option<f32>>,
@@ -286,6 +293,12 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
         let inner_container_size = constants.inner_container_size;
         let new_gap = style.gap().resolve_or_zero(inner_container_size.map(Some), |val, basis| tree.calc(val, basis));
         constants.gap = new_gap;
+
+        // Re-resolve percentage min and max widths of items now that we have determined container width.
+        // An auto height remains indefinite for percentages, so percentage min and max heights are still ignored.
+        if constants.is_row {
+            resolve_percentage_main_size_limits(tree, &mut flex_lines, &constants);
+        }
     }
 
     // 6. Resolve the flexible lengths of all the flex items to find their used main size.
@@ -383,6 +396,10 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
     debug_log!("final_layout_pass");
     let inflow_content_size = final_layout_pass(tree, &mut flex_lines, &constants);
 
+    // Set detailed flexbox information
+    #[cfg(feature = "detailed_layout_info")]
+    tree.set_detailed_flexbox_info(node, DetailedFlexboxInfo::from_flex_lines(&flex_lines, &constants));
+
     // Before returning we perform absolute layout on all absolutely positioned children
     debug_log!("perform_absolute_layout_on_absolute_children");
     let absolute_content_size = perform_absolute_layout_on_absolute_children(tree, node, &constants);
@@ -414,10 +431,8 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
             .iter()
             .find(|item| constants.is_column || item.align_self == AlignSelf::Baseline)
             .or_else(|| flex_lines[0].items.iter().next())
-            .map(|child| {
-                let offset_vertical = if constants.is_row { child.offset_cross } else { child.offset_main };
-                offset_vertical + child.baseline
-            })
+            // The baseline of each item was offset by its position within the container when it was laid out
+            .map(|child| child.baseline)
     };
 
     LayoutOutput::from_sizes_and_baselines(
@@ -435,11 +450,16 @@ fn compute_constants(
     known_dimensions: Size<Option<f32>>,
     parent_size: Size<Option<f32>>,
 ) -> AlgoConstants {
-    let dir = style.flex_direction();
+    let is_rtl = style.direction() == Direction::Rtl;
+    let dir = match (style.flex_direction(), is_rtl) {
+        (FlexDirection::Row, true) => FlexDirection::RowReverse,
+        (FlexDirection::RowReverse, true) => FlexDirection::Row,
+        (dir, _) => dir,
+    };
     let is_row = dir.is_row();
     let is_column = dir.is_column();
     let is_wrap = matches!(style.flex_wrap(), FlexWrap::Wrap | FlexWrap::WrapReverse);
-    let is_wrap_reverse = style.flex_wrap() == FlexWrap::WrapReverse;
+    let is_wrap_reverse = (style.flex_wrap() == FlexWrap::WrapReverse) != (is_rtl && is_column);
 
     let aspect_ratio = style.aspect_ratio();
     let margin = style.margin().resolve_or_zero(parent_size.width, |val, basis| tree.calc(val, basis));
@@ -449,9 +469,21 @@ fn compute_constants(
     let box_sizing_adjustment =
         if style.box_sizing() == BoxSizing::ContentBox { padding_border_sum } else { Size::ZERO };
 
-    let align_items = style.align_items().unwrap_or(AlignItems::Stretch);
-    let align_content = style.align_content().unwrap_or(AlignContent::Stretch);
-    let justify_content = style.justify_content();
+    // `start` and `end` alignment refer to the inline axis, which is right-to-left in the cross axis of RTL columns
+    // and in the main axis of RTL rows. In the latter `stretch` behaves as `flex-start`, which is on the right.
+    let mut align_items = style.align_items().unwrap_or(AlignItems::Stretch);
+    let mut align_content = style.align_content().unwrap_or(AlignContent::Stretch);
+    let mut justify_content = style.justify_content();
+    if is_rtl && is_column {
+        align_items = align_items.swap_start_end();
+        align_content = align_content.swap_start_end();
+    }
+    if is_rtl && is_row {
+        justify_content = justify_content.map(|justify_content| match justify_content {
+            JustifyContent::Stretch => JustifyContent::FlexStart,
+            other => other.swap_start_end(),
+        });
+    }
 
     // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`.
     // However, the axis are switched (transposed) because a node that scrolls vertically needs
@@ -478,6 +510,7 @@ fn compute_constants(
         is_column,
         is_wrap,
         is_wrap_reverse,
+        is_rtl,
         min_size: style
             .min_size()
             .maybe_resolve(parent_size, |val, basis| tree.calc(val, basis))
@@ -503,6 +536,19 @@ fn compute_constants(
     }
 }
 
+/// Resolve an item's `align-self`, falling back to the container's `align-items`
+///
+/// `start` and `end` are flipped in the cross axis of right-to-left columns, as they refer to the inline axis.
+/// The container's `align-items` is already flipped in [`compute_constants`].
+#[inline]
+fn resolve_align_self(align_self: Option<AlignSelf>, constants: &AlgoConstants) -> AlignSelf {
+    match align_self {
+        Some(align_self) if constants.is_rtl && constants.is_column => align_self.swap_start_end(),
+        Some(align_self) => align_self,
+        None => constants.align_items,
+    }
+}
+
 /// Generate anonymous flex items.
 ///
 /// # [9.1. Initial Setup](https://www.w3.org/TR/css-flexbox-1/#box-manip)
@@ -530,19 +576,20 @@ fn generate_anonymous_flex_items(
             let pb_sum = (padding + border).sum_axes();
             let box_sizing_adjustment =
                 if child_style.box_sizing() == BoxSizing::ContentBox { pb_sum } else { Size::ZERO };
+            let size = child_style.size().maybe_resolve(constants.node_inner_size, |val, basis| tree.calc(val, basis));
+            let min_size =
+                child_style.min_size().maybe_resolve(constants.node_inner_size, |val, basis| tree.calc(val, basis));
+            // A min size is only transferred through the aspect ratio when neither size is definite
+            let min_size = if size.width.is_none() && size.height.is_none() {
+                min_size.maybe_apply_aspect_ratio(aspect_ratio)
+            } else {
+                min_size
+            };
             FlexItem {
                 node: child,
                 order: index as u32,
-                size: child_style
-                    .size()
-                    .maybe_resolve(constants.node_inner_size, |val, basis| tree.calc(val, basis))
-                    .maybe_apply_aspect_ratio(aspect_ratio)
-                    .maybe_add(box_sizing_adjustment),
-                min_size: child_style
-                    .min_size()
-                    .maybe_resolve(constants.node_inner_size, |val, basis| tree.calc(val, basis))
-                    .maybe_apply_aspect_ratio(aspect_ratio)
-                    .maybe_add(box_sizing_adjustment),
+                size: size.maybe_apply_aspect_ratio(aspect_ratio).maybe_add(box_sizing_adjustment),
+                min_size: min_size.maybe_add(box_sizing_adjustment),
                 max_size: child_style
                     .max_size()
                     .maybe_resolve(constants.node_inner_size, |val, basis| tree.calc(val, basis))
@@ -562,7 +609,7 @@ fn generate_anonymous_flex_items(
                 border: child_style
                     .border()
                     .resolve_or_zero(constants.node_inner_size.width, |val, basis| tree.calc(val, basis)),
-                align_self: child_style.align_self().unwrap_or(constants.align_items),
+                align_self: resolve_align_self(child_style.align_self(), constants),
                 overflow: child_style.overflow(),
                 scrollbar_width: child_style.scrollbar_width(),
                 flex_grow: child_style.flex_grow(),
@@ -1184,6 +1231,52 @@ fn determine_container_main_size(
     constants.node_inner_size.set_main(constants.dir, Some(inner_main_size));
 }
 
+/// Resolve the percentage min and max main sizes of the items against the container's newly determined main size,
+/// and clamp their hypothetical main sizes by them.
+///
+/// While the container's main size is indefinite, percentage min and max main sizes behave as `auto` and `none`,
+/// so that they don't contribute to the container's size. Once its width is determined, percentage min and max
+/// widths apply as usual.
+///
+/// See https://drafts.csswg.org/css-sizing-3/#min-percentage-contribution
+fn resolve_percentage_main_size_limits(
+    tree: &impl LayoutFlexboxContainer,
+    flex_lines: &mut [FlexLine],
+    constants: &AlgoConstants,
+) {
+    let dir = constants.dir;
+    let inner_main_size = constants.node_inner_size.main(dir);
+    for child in flex_lines.iter_mut().flat_map(|line| line.items.iter_mut()) {
+        let child_style = tree.get_flexbox_child_style(child.node);
+        let box_sizing_adjustment = match child_style.box_sizing() {
+            BoxSizing::ContentBox => child.padding.main_axis_sum(dir) + child.border.main_axis_sum(dir),
+            BoxSizing::BorderBox => 0.0,
+        };
+        let resolve = |limit: Dimension| {
+            limit.maybe_resolve(inner_main_size, |val, basis| tree.calc(val, basis)).maybe_add(box_sizing_adjustment)
+        };
+        let min_main_size = child.min_size.main(dir).or_else(|| resolve(child_style.min_size().main(dir)));
+        let max_main_size = child.max_size.main(dir).or_else(|| resolve(child_style.max_size().main(dir)));
+        drop(child_style);
+        if min_main_size == child.min_size.main(dir) && max_main_size == child.max_size.main(dir) {
+            continue;
+        }
+
+        if let Some(min_main_size) = min_main_size {
+            child.resolved_minimum_main_size = min_main_size;
+        }
+        child.min_size.set_main(dir, min_main_size);
+        child.max_size.set_main(dir, max_main_size);
+
+        let padding_border_sum = child.padding.main_axis_sum(dir) + child.border.main_axis_sum(dir);
+        let hypothetical_inner_min_main = child.resolved_minimum_main_size.max(padding_border_sum);
+        let hypothetical_inner_size =
+            child.flex_basis.maybe_clamp(Some(hypothetical_inner_min_main), child.max_size.main(dir));
+        child.hypothetical_inner_size.set_main(dir, hypothetical_inner_size);
+        child.hypothetical_outer_size.set_main(dir, hypothetical_inner_size + child.margin.main_axis_sum(dir));
+    }
+}
+
 /// Resolve the flexible lengths of the items within a flex line.
 /// Sets the `main` component of each item's `target_size` and `outer_target_size`
 ///
@@ -1628,10 +1721,10 @@ fn determine_used_cross_size(
                     // a reasonable interpretation. Although it seems to me that the spec *should* apply aspect_ratio here.
                     let padding = child_style
                         .padding()
-                        .resolve_or_zero(constants.node_inner_size, |val, basis| tree.calc(val, basis));
+                        .resolve_or_zero(constants.node_inner_size.width, |val, basis| tree.calc(val, basis));
                     let border = child_style
                         .border()
-                        .resolve_or_zero(constants.node_inner_size, |val, basis| tree.calc(val, basis));
+                        .resolve_or_zero(constants.node_inner_size.width, |val, basis| tree.calc(val, basis));
                     let pb_sum = (padding + border).sum_axes();
                     let box_sizing_adjustment =
                         if child_style.box_sizing() == BoxSizing::ContentBox { pb_sum } else { Size::ZERO };
@@ -1671,10 +1764,7 @@ fn determine_used_cross_size(
 #[inline]
 fn distribute_remaining_free_space(flex_lines: &mut [FlexLine], constants: &AlgoConstants) {
     for line in flex_lines {
-        let total_main_axis_gap = sum_axis_gaps(constants.gap.main(constants.dir), line.items.len());
-        let used_space: f32 = total_main_axis_gap
-            + line.items.iter().map(|child| child.outer_target_size.main(constants.dir)).sum::<f32>();
-        let free_space = constants.inner_container_size.main(constants.dir) - used_space;
+        let free_space = main_axis_free_space(line, constants);
         let mut num_auto_margins = 0;
 
         for child in line.items.iter_mut() {
@@ -1728,6 +1818,27 @@ fn distribute_remaining_free_space(flex_lines: &mut [FlexLine], constants: &Algo
     }
 }
 
+/// The main-axis space left over in a line once its items and the gaps between them have been sized
+///
+/// This is negative if the items overflow the line.
+#[inline]
+fn main_axis_free_space(line: &FlexLine, constants: &AlgoConstants) -> f32 {
+    let total_main_axis_gap = sum_axis_gaps(constants.gap.main(constants.dir), line.items.len());
+    let used_space: f32 =
+        total_main_axis_gap + line.items.iter().map(|child| child.outer_target_size.main(constants.dir)).sum::<f32>();
+    constants.inner_container_size.main(constants.dir) - used_space
+}
+
+/// Computes the free space of a line before its items are grown or shrunk, as used to choose between the flex grow
+/// and flex shrink factors in [`resolve_flexible_lengths`]
+#[cfg(feature = "detailed_layout_info")]
+fn initial_main_axis_free_space(line: &FlexLine, constants: &AlgoConstants) -> f32 {
+    let total_main_axis_gap = sum_axis_gaps(constants.gap.main(constants.dir), line.items.len());
+    let used_space: f32 = total_main_axis_gap
+        + line.items.iter().map(|child| child.hypothetical_outer_size.main(constants.dir)).sum::<f32>();
+    constants.inner_container_size.main(constants.dir) - used_space
+}
+
 /// Resolve cross-axis `auto` margins.
 ///
 /// # [9.6. Cross-Axis Alignment](https://www.w3.org/TR/css-flexbox-1/#cross-alignment)
@@ -2102,7 +2213,7 @@ fn perform_absolute_layout_on_absolute_children(
         let overflow = child_style.overflow();
         let scrollbar_width = child_style.scrollbar_width();
         let aspect_ratio = child_style.aspect_ratio();
-        let align_self = child_style.align_self().unwrap_or(constants.align_items);
+        let align_self = resolve_align_self(child_style.align_self(), constants);
         let margin = child_style
             .margin()
             .map(|margin| margin.resolve_to_option(inset_relative_size.width, |val, basis| tree.calc(val, basis)));
@@ -2231,33 +2342,41 @@ fn perform_absolute_layout_on_absolute_children(
                 - end
                 - resolved_margin.main_end(constants.dir)
         } else {
+            let main_start =
+                constants.content_box_inset.main_start(constants.dir) + resolved_margin.main_start(constants.dir);
+            let main_end = constants.container_size.main(constants.dir)
+                - constants.content_box_inset.main_end(constants.dir)
+                - final_size.main(constants.dir)
+                - resolved_margin.main_end(constants.dir);
+            let main_center = (constants.container_size.main(constants.dir)
+                + constants.content_box_inset.main_start(constants.dir)
+                - constants.content_box_inset.main_end(constants.dir)
+                - final_size.main(constants.dir)
+                + resolved_margin.main_start(constants.dir)
+                - resolved_margin.main_end(constants.dir))
+                / 2.0;
+
+            // The main axis of a right-to-left row starts on the right. Its `start` and `end` have already been
+            // flipped in `compute_constants`, but the default and the flex-relative values are flipped here.
+            let is_rtl_row = constants.is_rtl && constants.is_row;
+            let default_justify_content = if is_rtl_row { JustifyContent::End } else { JustifyContent::Start };
+            let is_wrap_reverse = constants.is_wrap_reverse != (constants.is_rtl && constants.is_column);
+            let (flex_start, flex_end) = if is_rtl_row { (main_end, main_start) } else { (main_start, main_end) };
+
             // Stretch is an invalid value for justify_content in the flexbox algorithm, so we
             // treat it as if it wasn't set (and thus we default to FlexStart behaviour)
-            match (constants.justify_content.unwrap_or(JustifyContent::Start), constants.is_wrap_reverse) {
+            match (constants.justify_content.unwrap_or(default_justify_content), is_wrap_reverse) {
+                (JustifyContent::Start, _) => main_start,
+                (JustifyContent::End, _) => main_end,
                 (JustifyContent::SpaceBetween, _)
-                | (JustifyContent::Start, _)
                 | (JustifyContent::Stretch, false)
                 | (JustifyContent::FlexStart, false)
-                | (JustifyContent::FlexEnd, true) => {
-                    constants.content_box_inset.main_start(constants.dir) + resolved_margin.main_start(constants.dir)
-                }
-                (JustifyContent::End, _)
-                | (JustifyContent::FlexEnd, false)
+                | (JustifyContent::FlexEnd, true) => flex_start,
+                (JustifyContent::FlexEnd, false)
                 | (JustifyContent::FlexStart, true)
-                | (JustifyContent::Stretch, true) => {
-                    constants.container_size.main(constants.dir)
-                        - constants.content_box_inset.main_end(constants.dir)
-                        - final_size.main(constants.dir)
-                        - resolved_margin.main_end(constants.dir)
-                }
+                | (JustifyContent::Stretch, true) => flex_end,
                 (JustifyContent::SpaceEvenly, _) | (JustifyContent::SpaceAround, _) | (JustifyContent::Center, _) => {
-                    (constants.container_size.main(constants.dir)
-                        + constants.content_box_inset.main_start(constants.dir)
-                        - constants.content_box_inset.main_end(constants.dir)
-                        - final_size.main(constants.dir)
-                        + resolved_margin.main_start(constants.dir)
-                        - resolved_margin.main_end(constants.dir))
-                        / 2.0
+                    main_center
                 }
             }
         };
@@ -2365,3 +2484,78 @@ fn sum_axis_gaps(gap: f32, num_items: usize) -> f32 {
         gap * (num_items - 1) as f32
     }
 }
+
+/// Information from the computation of flexbox
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "detailed_layout_info")]
+pub struct DetailedFlexboxInfo {
+    /// The flex lines of the container, in the order items are collected into them (so the last line comes first
+    /// in the cross axis under `flex-wrap: wrap-reverse`)
+    pub lines: Vec<DetailedFlexLineInfo>,
+    /// The cross-axis space left over once the lines and the gaps between them were sized, distributed by
+    /// `align-content`. Negative if the lines overflow the container.
+    pub cross_free_space: f32,
+}
+
+/// Information from the computation of a single flex line
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "detailed_layout_info")]
+pub struct DetailedFlexLineInfo {
+    /// The offset of the cross-start edge of the line from the container's border box, after `align-content`
+    pub cross_offset: f32,
+    /// The used cross size of the line
+    pub cross_size: f32,
+    /// The main-axis space left over once the line's items and gaps were sized, distributed by `justify-content`
+    /// (or by `auto` margins). Negative if the items overflow the line.
+    pub main_free_space: f32,
+    /// The main-axis space left over once the line's items were given their hypothetical main sizes, before they
+    /// were grown or shrunk. Items grow if it is positive and shrink if it is negative.
+    pub initial_main_free_space: f32,
+    /// The main-axis space placed before each item in the line by `justify-content`, including the gap before it.
+    /// Items are listed in the order they appear in the line, and the space is zero where `auto` margins absorbed
+    /// the free space instead.
+    pub main_offsets: Vec<f32>,
+    /// The flex base size of each item in the line, as resolved from its `flex-basis` (or its size or content) and
+    /// floored at its padding and border, before the items are grown or shrunk. Items are listed in the same order
+    /// as `main_offsets`.
+    pub flex_bases: Vec<f32>,
+}
+
+#[cfg(feature = "detailed_layout_info")]
+impl DetailedFlexboxInfo {
+    /// Construct DetailedFlexboxInfo from the flex lines after the final layout pass
+    fn from_flex_lines(flex_lines: &[FlexLine], constants: &AlgoConstants) -> Self {
+        // The lines are stacked in the cross axis as in `final_layout_pass`
+        let mut cross_offsets: Vec<f32> = new_vec_with_capacity(flex_lines.len());
+        let mut total_offset_cross = constants.content_box_inset.cross_start(constants.dir);
+        let mut stack_line = |line: &FlexLine| {
+            cross_offsets.push(total_offset_cross + line.offset_cross);
+            total_offset_cross += line.offset_cross + line.cross_size;
+        };
+        if constants.is_wrap_reverse {
+            flex_lines.iter().rev().for_each(&mut stack_line);
+            cross_offsets.reverse();
+        } else {
+            flex_lines.iter().for_each(&mut stack_line);
+        }
+
+        let lines = flex_lines
+            .iter()
+            .zip(cross_offsets)
+            .map(|(line, cross_offset)| DetailedFlexLineInfo {
+                cross_offset,
+                cross_size: line.cross_size,
+                main_free_space: main_axis_free_space(line, constants),
+                initial_main_free_space: initial_main_axis_free_space(line, constants),
+                main_offsets: line.items.iter().map(|item| item.offset_main).collect(),
+                flex_bases: line.items.iter().map(|item| item.flex_basis).collect(),
+            })
+            .collect();
+
+        let total_cross_size: f32 = flex_lines.iter().map(|line| line.cross_size).sum();
+        let total_cross_axis_gap = sum_axis_gaps(constants.gap.cross(constants.dir), flex_lines.len());
+        let cross_free_space =
+            constants.inner_container_size.cross(constants.dir) - total_cross_size - total_cross_axis_gap;
+        DetailedFlexboxInfo { lines, cross_free_space }
+    }
+}
@@ -658,6 +658,8 @@ pub struct DetailedGridTracksInfo {
     pub gutters: Vec<f32>,
     /// The used size of the tracks
     pub sizes: Vec<f32>,
+    /// The offset of the start of each track from the grid container's border box, after content alignment
+    pub offsets: Vec<f32>,
 }
 
 #[cfg(feature = "detailed_layout_info")]
@@ -674,6 +676,11 @@ impl DetailedGridTracksInfo {
             .collect()
     }
 
+    /// Get the offsets of the tracks
+    fn offsets_from_grid_track_layout(grid_tracks: &[GridTrack]) -> Vec<f32> {
+        grid_tracks.iter().filter(|track| track.kind == GridTrackKind::Track).map(|track| track.offset).collect()
+    }
+
     /// Get the sizes of the gutters
     fn gutters_from_grid_track_layout(grid_tracks: &[GridTrack]) -> Vec<f32> {
         DetailedGridTracksInfo::grid_track_base_size_of_kind(grid_tracks, GridTrackKind::Gutter)
@@ -692,6 +699,7 @@ impl DetailedGridTracksInfo {
             positive_implicit_tracks: track_count.positive_implicit,
             gutters: DetailedGridTracksInfo::gutters_from_grid_track_layout(&grid_tracks),
             sizes: DetailedGridTracksInfo::sizes_from_grid_track_layout(&grid_tracks),
+            offsets: DetailedGridTracksInfo::offsets_from_grid_track_layout(&grid_tracks),
         }
     }
 }
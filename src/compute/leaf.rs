@@ -20,6 +20,26 @@ pub fn compute_leaf_layout<MeasureFunction>(
 ) -> LayoutOutput
 where
     MeasureFunction: FnOnce(Size<Option<f32>>, Size<AvailableSpace>) -> Size<f32>,
+{
+    compute_leaf_layout_with_baselines(inputs, style, resolve_calc_value, |known_dimensions, available_space| {
+        (measure_function(known_dimensions, available_space), Point::NONE)
+    })
+}
+
+/// Compute the size of a leaf node (node with no children), with a measure function that also returns the
+/// node's first baselines
+///
+/// The baselines are offsets from the top (`y`) and left (`x`) edges of the node's content box, and are used
+/// to align the node with `align-items: baseline`. A node without a baseline (`None`) is aligned as if its
+/// baseline were the bottom edge of its border box.
+pub fn compute_leaf_layout_with_baselines<MeasureFunction>(
+    inputs: LayoutInput,
+    style: &impl CoreStyle,
+    resolve_calc_value: impl Fn(*const (), f32) -> f32,
+    measure_function: MeasureFunction,
+) -> LayoutOutput
+where
+    MeasureFunction: FnOnce(Size<Option<f32>>, Size<AvailableSpace>) -> (Size<f32>, Point<Option<f32>>),
 {
     let LayoutInput { known_dimensions, parent_size, available_space, sizing_mode, run_mode, .. } = inputs;
 
@@ -43,18 +63,25 @@ where
         }
         SizingMode::InherentSize => {
             let aspect_ratio = style.aspect_ratio();
-            let style_size = style
-                .size()
-                .maybe_resolve(parent_size, &resolve_calc_value)
-                .maybe_apply_aspect_ratio(aspect_ratio)
-                .maybe_add(box_sizing_adjustment);
-            let style_min_size = style
-                .min_size()
-                .maybe_resolve(parent_size, &resolve_calc_value)
+            let raw_size = style.size().maybe_resolve(parent_size, &resolve_calc_value);
+            let raw_min_size = style.min_size().maybe_resolve(parent_size, &resolve_calc_value);
+            let raw_max_size = style.max_size().maybe_resolve(parent_size, &resolve_calc_value);
+
+            // A definite size is clamped by the min and max sizes in its own axis *before* it is transferred through
+            // the aspect ratio, and the transferred size is clamped in turn. Min sizes are only transferred when
+            // neither size is definite, as a definite size wins over a min size in the other axis.
+            let style_size = raw_size
+                .maybe_clamp(raw_min_size, raw_max_size)
                 .maybe_apply_aspect_ratio(aspect_ratio)
+                .maybe_clamp(raw_min_size, raw_max_size)
                 .maybe_add(box_sizing_adjustment);
-            let style_max_size =
-                style.max_size().maybe_resolve(parent_size, &resolve_calc_value).maybe_add(box_sizing_adjustment);
+            let style_min_size = if raw_size.width.is_none() && raw_size.height.is_none() {
+                raw_min_size.maybe_apply_aspect_ratio(aspect_ratio)
+            } else {
+                raw_min_size
+            }
+            .maybe_add(box_sizing_adjustment);
+            let style_max_size = raw_max_size.maybe_add(box_sizing_adjustment);
 
             let node_size = known_dimensions.or(style_size);
             (node_size, style_min_size, style_max_size, aspect_ratio)
@@ -133,7 +160,7 @@ where
     debug_log!("About to measure with available_space", dbg:available_space);
 
     // Measure node
-    let measured_size = measure_function(
+    let (measured_size, measured_baselines) = measure_function(
         match run_mode {
             RunMode::ComputeSize => known_dimensions,
             RunMode::PerformLayout => Size::NONE,
@@ -145,9 +172,14 @@ where
         .or(node_size)
         .unwrap_or(measured_size + content_box_inset.sum_axes())
         .maybe_clamp(node_min_size, node_max_size);
-    let size = Size {
-        width: clamped_size.width,
-        height: f32_max(clamped_size.height, aspect_ratio.map(|ratio| clamped_size.width / ratio).unwrap_or(0.0)),
+    // The height is only derived from the width through the aspect ratio when nothing else determines it
+    let size = match (aspect_ratio, known_dimensions.height.or(node_size.height)) {
+        (Some(ratio), None) => Size {
+            width: clamped_size.width,
+            height: f32_max(clamped_size.height, clamped_size.width / ratio)
+                .maybe_clamp(node_min_size.height, node_max_size.height),
+        },
+        _ => clamped_size,
     };
     let size = size.maybe_max(padding_border.sum_axes().map(Some));
 
@@ -155,7 +187,10 @@ where
         size,
         #[cfg(feature = "content_size")]
         content_size: measured_size + padding.sum_axes(),
-        first_baselines: Point::NONE,
+        first_baselines: Point {
+            x: measured_baselines.x.map(|baseline| baseline + content_box_inset.left),
+            y: measured_baselines.y.map(|baseline| baseline + content_box_inset.top),
+        },
         top_margin: CollapsibleMarginSet::ZERO,
         bottom_margin: CollapsibleMarginSet::ZERO,
         margins_can_collapse_through: !has_styles_preventing_being_collapsed_through
@@ -19,6 +19,8 @@
 //! | Function                          | Requires                                                                                                                                                                                           | Purpose                                                              |
 //! | ---                               | ---                                                                                                                                                                                                | ---                                                                  |
 //! | [`round_layout`]                  | [`RoundTree`]                                                                                                                                                                                      | Round a tree of float-valued layouts to integer pixels               |
+//! | [`round_leaf_layouts`]            | [`RoundTree`]                                                                                                                                                                                      | Round the layouts of the leaves of a tree to integer pixels          |
+//! | [`round_layout_positions`]        | [`RoundTree`]                                                                                                                                                                                      | Round the positions of the nodes of a tree to integer pixels         |
 //! | [`print_tree`](crate::print_tree) | [`PrintTree`](crate::PrintTree)                                                                                                                                                                    | Print a debug representation of a node tree and it's computed layout |
 //!
 pub(crate) mod common;
@@ -33,7 +35,7 @@ pub(crate) mod flexbox;
 #[cfg(feature = "grid")]
 pub(crate) mod grid;
 
-pub use leaf::compute_leaf_layout;
+pub use leaf::{compute_leaf_layout, compute_leaf_layout_with_baselines};
 
 #[cfg(feature = "block_layout")]
 pub use self::block::compute_block_layout;
@@ -56,11 +58,30 @@ use crate::{BoxSizing, CacheTree, MaybeMath, MaybeResolve};
 
 /// Compute layout for the root node in the tree
 pub fn compute_root_layout(tree: &mut impl LayoutPartialTree, root: NodeId, available_space: Size<AvailableSpace>) {
+    compute_root_layout_with_containing_block(tree, root, available_space, available_space.into_options())
+}
+
+/// Compute layout for the root node in the tree, resolving its percentages against a containing block that may
+/// differ from the available space
+///
+/// `containing_block` is the size that percentage sizes, margins, padding, borders and insets of `root` resolve
+/// against (horizontal padding and margins against its width), where `None` makes them behave like `auto`.
+/// `available_space` still drives the intrinsic sizing of `root`, and the stretch-fitting of a block root.
+/// Descendants of `root` resolve their percentages against their own parents as usual.
+///
+/// Cached layouts are keyed by available space only, so the cache of `root` must be cleared before laying it out
+/// within a different containing block than the last time.
+pub fn compute_root_layout_with_containing_block(
+    tree: &mut impl LayoutPartialTree,
+    root: NodeId,
+    available_space: Size<AvailableSpace>,
+    containing_block: Size<Option<f32>>,
+) {
     let mut known_dimensions = Size::NONE;
 
     #[cfg(feature = "block_layout")]
     {
-        let parent_size = available_space.into_options();
+        let parent_size = containing_block;
         let style = tree.get_core_container_style(root);
 
         if style.is_block() {
@@ -116,19 +137,16 @@ pub fn compute_root_layout(tree: &mut impl LayoutPartialTree, root: NodeId, avai
     let output = tree.perform_child_layout(
         root,
         known_dimensions,
-        available_space.into_options(),
+        containing_block,
         available_space,
         SizingMode::InherentSize,
         Line::FALSE,
     );
 
     let style = tree.get_core_container_style(root);
-    let padding =
-        style.padding().resolve_or_zero(available_space.width.into_option(), |val, basis| tree.calc(val, basis));
-    let border =
-        style.border().resolve_or_zero(available_space.width.into_option(), |val, basis| tree.calc(val, basis));
-    let margin =
-        style.margin().resolve_or_zero(available_space.width.into_option(), |val, basis| tree.calc(val, basis));
+    let padding = style.padding().resolve_or_zero(containing_block.width, |val, basis| tree.calc(val, basis));
+    let border = style.border().resolve_or_zero(containing_block.width, |val, basis| tree.calc(val, basis));
+    let margin = style.margin().resolve_or_zero(containing_block.width, |val, basis| tree.calc(val, basis));
     let scrollbar_size = Size {
         width: if style.overflow().y == Overflow::Scroll { style.scrollbar_width() } else { 0.0 },
         height: if style.overflow().x == Overflow::Scroll { style.scrollbar_width() } else { 0.0 },
@@ -208,33 +226,13 @@ pub fn round_layout(tree: &mut impl RoundTree, node_id: NodeId) {
     /// Recursive function to apply rounding to all descendents
     fn round_layout_inner(tree: &mut impl RoundTree, node_id: NodeId, cumulative_x: f32, cumulative_y: f32) {
         let unrounded_layout = *tree.get_unrounded_layout(node_id);
-        let mut layout = unrounded_layout;
 
         let cumulative_x = cumulative_x + unrounded_layout.location.x;
         let cumulative_y = cumulative_y + unrounded_layout.location.y;
 
+        let mut layout = round_edges(unrounded_layout, cumulative_x, cumulative_y);
         layout.location.x = round(unrounded_layout.location.x);
         layout.location.y = round(unrounded_layout.location.y);
-        layout.size.width = round(cumulative_x + unrounded_layout.size.width) - round(cumulative_x);
-        layout.size.height = round(cumulative_y + unrounded_layout.size.height) - round(cumulative_y);
-        layout.scrollbar_size.width = round(unrounded_layout.scrollbar_size.width);
-        layout.scrollbar_size.height = round(unrounded_layout.scrollbar_size.height);
-        layout.border.left = round(cumulative_x + unrounded_layout.border.left) - round(cumulative_x);
-        layout.border.right = round(cumulative_x + unrounded_layout.size.width)
-            - round(cumulative_x + unrounded_layout.size.width - unrounded_layout.border.right);
-        layout.border.top = round(cumulative_y + unrounded_layout.border.top) - round(cumulative_y);
-        layout.border.bottom = round(cumulative_y + unrounded_layout.size.height)
-            - round(cumulative_y + unrounded_layout.size.height - unrounded_layout.border.bottom);
-        layout.padding.left = round(cumulative_x + unrounded_layout.padding.left) - round(cumulative_x);
-        layout.padding.right = round(cumulative_x + unrounded_layout.size.width)
-            - round(cumulative_x + unrounded_layout.size.width - unrounded_layout.padding.right);
-        layout.padding.top = round(cumulative_y + unrounded_layout.padding.top) - round(cumulative_y);
-        layout.padding.bottom = round(cumulative_y + unrounded_layout.size.height)
-            - round(cumulative_y + unrounded_layout.size.height - unrounded_layout.padding.bottom);
-
-        #[cfg(feature = "content_size")]
-        round_content_size(&mut layout, unrounded_layout.content_size, cumulative_x, cumulative_y);
-
         tree.set_final_layout(node_id, &layout);
 
         let child_count = tree.child_count(node_id);
@@ -243,22 +241,109 @@ pub fn round_layout(tree: &mut impl RoundTree, node_id: NodeId) {
             round_layout_inner(tree, child, cumulative_x, cumulative_y);
         }
     }
+}
 
-    #[cfg(feature = "content_size")]
-    #[inline(always)]
-    /// Round content size variables.
-    /// This is split into a separate function to make it easier to feature flag.
-    fn round_content_size(
-        layout: &mut Layout,
-        unrounded_content_size: Size<f32>,
-        cumulative_x: f32,
-        cumulative_y: f32,
+/// Rounds the calculated layout of leaf nodes (nodes without children) to exact pixel values, leaving the layout of
+/// every other node unrounded
+///
+/// Leaves are rounded as by [`round_layout`], based on their cumulative x/y coordinates, so that each leaf's box
+/// starts and ends on a whole pixel relative to the viewport. As the containers they are positioned in are not
+/// rounded, a leaf's location relative to its parent may be fractional. Leaves are therefore still aligned with each
+/// other, but not necessarily with the edges of their containers, which may differ from them by up to half a pixel.
+///
+/// In order to prevent innacuracies caused by rounding already-rounded values, we read from `unrounded_layout`
+/// and write to `final_layout`.
+pub fn round_leaf_layouts(tree: &mut impl RoundTree, node_id: NodeId) {
+    return round_leaf_layouts_inner(tree, node_id, 0.0, 0.0);
+
+    /// Recursive function to apply rounding to all descendent leaves
+    fn round_leaf_layouts_inner(
+        tree: &mut impl RoundTree,
+        node_id: NodeId,
+        parent_cumulative_x: f32,
+        parent_cumulative_y: f32,
     ) {
-        layout.content_size.width = round(cumulative_x + unrounded_content_size.width) - round(cumulative_x);
-        layout.content_size.height = round(cumulative_y + unrounded_content_size.height) - round(cumulative_y);
+        let unrounded_layout = *tree.get_unrounded_layout(node_id);
+
+        let cumulative_x = parent_cumulative_x + unrounded_layout.location.x;
+        let cumulative_y = parent_cumulative_y + unrounded_layout.location.y;
+
+        let child_count = tree.child_count(node_id);
+        if child_count == 0 {
+            let mut layout = round_edges(unrounded_layout, cumulative_x, cumulative_y);
+            layout.location.x = round(cumulative_x) - parent_cumulative_x;
+            layout.location.y = round(cumulative_y) - parent_cumulative_y;
+            tree.set_final_layout(node_id, &layout);
+            return;
+        }
+
+        tree.set_final_layout(node_id, &unrounded_layout);
+        for index in 0..child_count {
+            let child = tree.get_child_id(node_id, index);
+            round_leaf_layouts_inner(tree, child, cumulative_x, cumulative_y);
+        }
+    }
+}
+
+/// Rounds the location of each node's layout to exact pixel values, leaving its size and every other layout value
+/// unrounded
+///
+/// Locations are rounded relative to their parent, as by [`round_layout`], so every node starts on the same whole
+/// pixel as with full rounding. Sizes are left exactly as computed, so items of equal computed size keep equal sizes,
+/// but the far edges of boxes can fall between pixels, and a node may no longer end exactly where the next begins.
+///
+/// In order to prevent innacuracies caused by rounding already-rounded values, we read from `unrounded_layout`
+/// and write to `final_layout`.
+pub fn round_layout_positions(tree: &mut impl RoundTree, node_id: NodeId) {
+    let mut layout = *tree.get_unrounded_layout(node_id);
+    layout.location.x = round(layout.location.x);
+    layout.location.y = round(layout.location.y);
+    tree.set_final_layout(node_id, &layout);
+
+    let child_count = tree.child_count(node_id);
+    for index in 0..child_count {
+        let child = tree.get_child_id(node_id, index);
+        round_layout_positions(tree, child);
     }
 }
 
+/// Rounds the size, scrollbar size, border, padding and content size of a layout whose unrounded location
+/// relative to the viewport is (`cumulative_x`, `cumulative_y`), leaving its location unrounded
+fn round_edges(unrounded_layout: Layout, cumulative_x: f32, cumulative_y: f32) -> Layout {
+    let mut layout = unrounded_layout;
+
+    layout.size.width = round(cumulative_x + unrounded_layout.size.width) - round(cumulative_x);
+    layout.size.height = round(cumulative_y + unrounded_layout.size.height) - round(cumulative_y);
+    layout.scrollbar_size.width = round(unrounded_layout.scrollbar_size.width);
+    layout.scrollbar_size.height = round(unrounded_layout.scrollbar_size.height);
+    layout.border.left = round(cumulative_x + unrounded_layout.border.left) - round(cumulative_x);
+    layout.border.right = round(cumulative_x + unrounded_layout.size.width)
+        - round(cumulative_x + unrounded_layout.size.width - unrounded_layout.border.right);
+    layout.border.top = round(cumulative_y + unrounded_layout.border.top) - round(cumulative_y);
+    layout.border.bottom = round(cumulative_y + unrounded_layout.size.height)
+        - round(cumulative_y + unrounded_layout.size.height - unrounded_layout.border.bottom);
+    layout.padding.left = round(cumulative_x + unrounded_layout.padding.left) - round(cumulative_x);
+    layout.padding.right = round(cumulative_x + unrounded_layout.size.width)
+        - round(cumulative_x + unrounded_layout.size.width - unrounded_layout.padding.right);
+    layout.padding.top = round(cumulative_y + unrounded_layout.padding.top) - round(cumulative_y);
+    layout.padding.bottom = round(cumulative_y + unrounded_layout.size.height)
+        - round(cumulative_y + unrounded_layout.size.height - unrounded_layout.padding.bottom);
+
+    #[cfg(feature = "content_size")]
+    round_content_size(&mut layout, unrounded_layout.content_size, cumulative_x, cumulative_y);
+
+    layout
+}
+
+#[cfg(feature = "content_size")]
+#[inline(always)]
+/// Round content size variables.
+/// This is split into a separate function to make it easier to feature flag.
+fn round_content_size(layout: &mut Layout, unrounded_content_size: Size<f32>, cumulative_x: f32, cumulative_y: f32) {
+    layout.content_size.width = round(cumulative_x + unrounded_content_size.width) - round(cumulative_x);
+    layout.content_size.height = round(cumulative_y + unrounded_content_size.height) - round(cumulative_y);
+}
+
 /// Creates a layout for this node and its children, recursively.
 /// Each hidden node has zero size and is placed at the origin
 pub fn compute_hidden_layout(tree: &mut (impl LayoutPartialTree + CacheTree), node: NodeId) -> LayoutOutput {
@@ -278,8 +363,10 @@ pub fn compute_hidden_layout(tree: &mut (impl LayoutPartialTree + CacheTree), no
 /// A module for unified re-exports of detailed layout info structs, used by low level API
 #[cfg(feature = "detailed_layout_info")]
 pub mod detailed_info {
+    #[cfg(feature = "flexbox")]
+    pub use super::flexbox::{DetailedFlexLineInfo, DetailedFlexboxInfo};
     #[cfg(feature = "grid")]
-    pub use super::grid::{DetailedGridInfo, DetailedGridTracksInfo};
+    pub use super::grid::{DetailedGridInfo, DetailedGridItemsInfo, DetailedGridTracksInfo};
 }
 
 #[cfg(test)]
@@ -105,7 +105,9 @@ pub use crate::compute::compute_grid_layout;
 pub use crate::compute::detailed_info::*;
 #[doc(inline)]
 pub use crate::compute::{
-    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout,
+    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_leaf_layout_with_baselines,
+    compute_root_layout, compute_root_layout_with_containing_block, round_layout, round_layout_positions,
+    round_leaf_layouts,
 };
 #[doc(inline)]
 pub use crate::style::Style;
@@ -122,3 +124,25 @@ pub use crate::geometry::*;
 pub use crate::style::*;
 pub use crate::tree::*;
 pub use crate::util::*;
+
+/// The version of taffy that was compiled, e.g. `"0.8.1"`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Each of taffy's optional Cargo features, and whether it was enabled when taffy was compiled
+///
+/// This is intended for diagnostics, such as attaching build information to bug reports.
+pub const FEATURES: &[(&str, bool)] = &[
+    ("std", cfg!(feature = "std")),
+    ("alloc", cfg!(feature = "alloc")),
+    ("taffy_tree", cfg!(feature = "taffy_tree")),
+    ("flexbox", cfg!(feature = "flexbox")),
+    ("grid", cfg!(feature = "grid")),
+    ("block_layout", cfg!(feature = "block_layout")),
+    ("calc", cfg!(feature = "calc")),
+    ("content_size", cfg!(feature = "content_size")),
+    ("detailed_layout_info", cfg!(feature = "detailed_layout_info")),
+    ("serde", cfg!(feature = "serde")),
+    ("camel_case_style", cfg!(feature = "camel_case_style")),
+    ("debug", cfg!(feature = "debug")),
+    ("profile", cfg!(feature = "profile")),
+];
@@ -31,6 +31,20 @@ pub enum AlignItems {
     /// Stretch to fill the container
     Stretch,
 }
+
+#[cfg(feature = "flexbox")]
+impl AlignItems {
+    /// Swaps `Start` and `End`, which refer to the start and end of the axis in the writing mode, for use when the
+    /// direction of the axis is reversed
+    pub(crate) fn swap_start_end(self) -> Self {
+        match self {
+            Self::Start => Self::End,
+            Self::End => Self::Start,
+            other => other,
+        }
+    }
+}
+
 /// Used to control how child nodes are aligned.
 /// Does not apply to Flexbox, and will be ignored if specified on a flex container
 /// For Grid it controls alignment in the inline axis
@@ -93,6 +107,19 @@ pub enum AlignContent {
     SpaceAround,
 }
 
+#[cfg(feature = "flexbox")]
+impl AlignContent {
+    /// Swaps `Start` and `End`, which refer to the start and end of the axis in the writing mode, for use when the
+    /// direction of the axis is reversed
+    pub(crate) fn swap_start_end(self) -> Self {
+        match self {
+            Self::Start => Self::End,
+            Self::End => Self::Start,
+            other => other,
+        }
+    }
+}
+
 /// Sets the distribution of space between and around content items
 /// For Flexbox it controls alignment in the main axis
 /// For Grid it controls alignment in the inline axis
@@ -332,6 +332,34 @@ impl CompactLength {
         self.0.value()
     }
 
+    /// Get the 64-bit encoding used to serialize the `CompactLength`: the tag in the high 32 bits
+    /// and the bits of the numeric value (if any) in the low 32 bits
+    #[inline(always)]
+    #[cfg(feature = "serde")]
+    pub fn to_serialized(self) -> u64 {
+        self.0.serialized()
+    }
+
+    /// Decode a `CompactLength` from its serialized 64-bit encoding (see [`CompactLength::to_serialized`])
+    ///
+    /// Returns `None` if the encoding does not carry a valid tag. `calc()` values can't be decoded.
+    #[cfg(feature = "serde")]
+    pub fn from_serialized(bits: u64) -> Option<Self> {
+        let value = Self(CompactLengthInner::from_serialized(bits));
+        matches!(
+            value.tag(),
+            CompactLength::LENGTH_TAG
+                | CompactLength::PERCENT_TAG
+                | CompactLength::AUTO_TAG
+                | CompactLength::MIN_CONTENT_TAG
+                | CompactLength::MAX_CONTENT_TAG
+                | CompactLength::FIT_CONTENT_PX_TAG
+                | CompactLength::FIT_CONTENT_PERCENT_TAG
+                | CompactLength::FR_TAG
+        )
+        .then_some(value)
+    }
+
     /// Get the calc pointer of the `CompactLength`
     #[inline(always)]
     #[cfg(feature = "calc")]
@@ -522,22 +550,7 @@ impl<'de> serde::Deserialize<'de> for CompactLength {
         D: serde::Deserializer<'de>,
     {
         let bits: u64 = u64::deserialize(deserializer)?;
-        let value = Self(CompactLengthInner::from_serialized(bits));
         // Note: validation intentionally excludes the CALC_TAG as deserializing calc() values is not supported
-        if matches!(
-            value.tag(),
-            CompactLength::LENGTH_TAG
-                | CompactLength::PERCENT_TAG
-                | CompactLength::AUTO_TAG
-                | CompactLength::MIN_CONTENT_TAG
-                | CompactLength::MAX_CONTENT_TAG
-                | CompactLength::FIT_CONTENT_PX_TAG
-                | CompactLength::FIT_CONTENT_PERCENT_TAG
-                | CompactLength::FR_TAG
-        ) {
-            Ok(value)
-        } else {
-            Err(serde::de::Error::custom("Cannot deserialize Calc value"))
-        }
+        Self::from_serialized(bits).ok_or_else(|| serde::de::Error::custom("Cannot deserialize Calc value"))
     }
 }
@@ -198,6 +198,14 @@ impl<'de> serde::Deserialize<'de> for LengthPercentageAuto {
 /// A unit of linear measurement
 ///
 /// This is commonly combined with [`Rect`], [`Point`](crate::geometry::Point) and [`Size<T>`](crate::geometry::Size).
+///
+/// Unlike in CSS, a `Dimension` can't be an intrinsic sizing keyword such as `min-content`, `max-content` or
+/// `fit-content()`, and these fail to deserialize. They are only supported as grid track sizing functions and as
+/// the [`AvailableSpace`](crate::style::AvailableSpace) that a layout is computed in. To size a node to fit its
+/// content, use `auto` in a context where `auto` already shrinks to fit: along the main axis of a flex container
+/// (the basis is the max-content size, shrunk to fit the container), along the cross axis with an `align_self` other
+/// than `stretch`, for an absolutely positioned node, or for the root of a layout computed in
+/// `AvailableSpace::MinContent` or `AvailableSpace::MaxContent`.
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 #[cfg_attr(all(feature = "serde", feature = "std"), derive(ts_rs::TS))]
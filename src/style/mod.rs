@@ -67,6 +67,11 @@ pub trait CoreStyle {
     fn box_sizing(&self) -> BoxSizing {
         BoxSizing::BorderBox
     }
+    /// The direction of the inline axis (the direction in which text flows)
+    #[inline(always)]
+    fn direction(&self) -> Direction {
+        Style::DEFAULT.direction
+    }
 
     // Overflow properties
     /// How children overflowing their container should affect layout
@@ -275,6 +280,28 @@ impl Default for BoxSizing {
     }
 }
 
+/// The direction of the inline axis: whether text and inline-level content flows from left to right or from right to left
+///
+/// In a right-to-left Flexbox container, `FlexDirection::Row` lays out items from right to left, a column container
+/// aligns its items and lines starting from the right, and `start` / `end` alignment apply to the right and left
+/// edges respectively.
+///
+/// Unlike in CSS, `direction` is not inherited: it must be set on each container that should be laid out right-to-left.
+/// It currently only affects Flexbox containers.
+///
+/// See <https://developer.mozilla.org/en-US/docs/Web/CSS/direction>
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "serde", feature = "std"), derive(ts_rs::TS))]
+#[cfg_attr(all(feature = "serde", feature = "std"), ts(export))]
+pub enum Direction {
+    /// Left-to-right, as in English
+    #[default]
+    Ltr,
+    /// Right-to-left, as in Arabic or Hebrew
+    Rtl,
+}
+
 /// How children overflowing their container should affect layout
 ///
 /// In CSS the primary effect of this property is to control whether contents of a parent container that overflow that container should
@@ -345,9 +372,14 @@ impl Overflow {
 /// this [introduction to the box model](https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_Box_Model/Introduction_to_the_CSS_box_model).
 ///
 /// If the behavior does not match the flexbox layout algorithm on the web, please file a bug!
+///
+/// With the `serde` feature, fields are (de)serialized under their Rust names, e.g. `grid_template_rows`. The
+/// `camel_case_style` feature renames them to camelCase, e.g. `gridTemplateRows`, to match JavaScript conventions.
+/// Only the field names of `Style` itself change: the values, including the names of enum variants, are the same.
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "camel_case_style", serde(rename_all = "camelCase"))]
 #[cfg_attr(all(feature = "serde", feature = "std"), derive(ts_rs::TS))]
 #[cfg_attr(all(feature = "serde", feature = "std"), ts(export))]
 pub struct Style {
@@ -361,6 +393,8 @@ pub struct Style {
     pub item_is_replaced: bool,
     /// Should size styles apply to the content box or the border box of the node
     pub box_sizing: BoxSizing,
+    /// Does inline content flow from left to right or from right to left?
+    pub direction: Direction,
 
     // Overflow properties
     /// How children overflowing their container should affect layout
@@ -372,6 +406,14 @@ pub struct Style {
     /// What should the `position` value of this struct use as a base offset?
     pub position: Position,
     /// How should the position of this element be tweaked relative to the layout defined?
+    ///
+    /// For [`Position::Absolute`] items, each inset is an offset from the corresponding edge of the parent's padding
+    /// box (the parent's border box minus its border and scrollbar gutter), or of the item's grid area in a grid
+    /// container. Percentages resolve against the width (`left` and `right`) or height (`top` and `bottom`) of that
+    /// same box, not against the parent's content box or border box.
+    ///
+    /// For [`Position::Relative`] items, percentages resolve against the width or height of the parent's content box.
+    /// A vertical percentage behaves as `auto` if the parent's height depends on its content.
     #[cfg_attr(feature = "serde", serde(default = "style_helpers::auto"))]
     pub inset: Rect<LengthPercentageAuto>,
 
@@ -442,6 +484,12 @@ pub struct Style {
 
     // Flexbox item properties
     /// Sets the initial main axis size of the item
+    ///
+    /// As in CSS, `auto` uses the item's main size property (`width` in a row, `height` in a column) if it is not
+    /// `auto` either, and the item's max-content size (e.g. the size returned by the measure function) otherwise.
+    ///
+    /// There is no `content` value, which would ignore the main size property. To size an item purely from its
+    /// content, leave both `flex_basis` and the main size property `auto`.
     #[cfg(feature = "flexbox")]
     pub flex_basis: Dimension,
     /// The relative rate at which this item grows when it is expanding to fill space
@@ -494,6 +542,7 @@ impl Style {
         item_is_table: false,
         item_is_replaced: false,
         box_sizing: BoxSizing::BorderBox,
+        direction: Direction::Ltr,
         overflow: Point { x: Overflow::Visible, y: Overflow::Visible },
         scrollbar_width: 0.0,
         position: Position::Relative,
@@ -580,6 +629,10 @@ impl CoreStyle for Style {
         self.box_sizing
     }
     #[inline(always)]
+    fn direction(&self) -> Direction {
+        self.direction
+    }
+    #[inline(always)]
     fn overflow(&self) -> Point<Overflow> {
         self.overflow
     }
@@ -643,6 +696,10 @@ impl<T: CoreStyle> CoreStyle for &'_ T {
         (*self).box_sizing()
     }
     #[inline(always)]
+    fn direction(&self) -> Direction {
+        (*self).direction()
+    }
+    #[inline(always)]
     fn overflow(&self) -> Point<Overflow> {
         (*self).overflow()
     }
@@ -977,6 +1034,7 @@ mod tests {
             item_is_table: false,
             item_is_replaced: false,
             box_sizing: Default::default(),
+            direction: Default::default(),
             overflow: Default::default(),
             scrollbar_width: 0.0,
             position: Default::default(),
@@ -1060,6 +1118,7 @@ mod tests {
         // Display and Position
         assert_type_size::<Display>(1);
         assert_type_size::<BoxSizing>(1);
+        assert_type_size::<Direction>(1);
         assert_type_size::<Position>(1);
         assert_type_size::<Overflow>(1);
 
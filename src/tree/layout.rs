@@ -2,6 +2,8 @@
 use crate::geometry::{AbsoluteAxis, Line, Point, Rect, Size};
 use crate::style::AvailableSpace;
 use crate::style_helpers::TaffyMaxContent;
+#[cfg(all(feature = "detailed_layout_info", any(feature = "flexbox", feature = "grid")))]
+use crate::util::sys::Box;
 use crate::util::sys::{f32_max, f32_min};
 
 /// Whether we are performing a full layout, or we merely need to size the node
@@ -349,6 +351,9 @@ impl Layout {
 #[cfg(feature = "detailed_layout_info")]
 #[derive(Debug, Clone, PartialEq)]
 pub enum DetailedLayoutInfo {
+    /// Enum variant for [`DetailedFlexboxInfo`](crate::compute::flexbox::DetailedFlexboxInfo)
+    #[cfg(feature = "flexbox")]
+    Flexbox(Box<crate::compute::flexbox::DetailedFlexboxInfo>),
     /// Enum variant for [`DetailedGridInfo`](crate::compute::grid::DetailedGridInfo)
     #[cfg(feature = "grid")]
     Grid(Box<crate::compute::grid::DetailedGridInfo>),
@@ -5,7 +5,7 @@ use slotmap::SecondaryMap;
 use slotmap::SparseSecondaryMap as SecondaryMap;
 use slotmap::{DefaultKey, SlotMap};
 
-use crate::geometry::Size;
+use crate::geometry::{Point, Size};
 use crate::style::{AvailableSpace, Display, Style};
 use crate::tree::{
     Cache, ClearState, Layout, LayoutInput, LayoutOutput, LayoutPartialTree, NodeId, PrintTree, RoundTree, RunMode,
@@ -15,7 +15,8 @@ use crate::util::debug::{debug_log, debug_log_node};
 use crate::util::sys::{new_vec_with_capacity, ChildrenVec, Vec};
 
 use crate::compute::{
-    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout,
+    compute_cached_layout, compute_hidden_layout, compute_leaf_layout_with_baselines,
+    compute_root_layout_with_containing_block, round_layout, round_layout_positions, round_leaf_layouts,
 };
 use crate::CacheTree;
 #[cfg(feature = "block_layout")]
@@ -25,10 +26,14 @@ use crate::{compute::compute_flexbox_layout, LayoutFlexboxContainer};
 #[cfg(feature = "grid")]
 use crate::{compute::compute_grid_layout, LayoutGridContainer};
 
+#[cfg(all(feature = "detailed_layout_info", feature = "flexbox"))]
+use crate::compute::flexbox::DetailedFlexboxInfo;
 #[cfg(all(feature = "detailed_layout_info", feature = "grid"))]
 use crate::compute::grid::DetailedGridInfo;
 #[cfg(feature = "detailed_layout_info")]
 use crate::tree::layout::DetailedLayoutInfo;
+#[cfg(all(feature = "detailed_layout_info", any(feature = "flexbox", feature = "grid")))]
+use crate::util::sys::Box;
 
 /// The error Taffy generates on invalid operations
 pub type TaffyResult<T> = Result<T, TaffyError>;
@@ -76,11 +81,17 @@ impl std::error::Error for TaffyError {}
 pub(crate) struct TaffyConfig {
     /// Whether to round layout values
     pub(crate) use_rounding: bool,
+    /// Whether rounding only applies to the layout values of leaf nodes
+    pub(crate) round_leaves_only: bool,
+    /// Whether rounding only applies to the locations of nodes, leaving their sizes unrounded
+    pub(crate) round_positions_only: bool,
+    /// Whether to reuse cached layout results during layout
+    pub(crate) use_cache: bool,
 }
 
 impl Default for TaffyConfig {
     fn default() -> Self {
-        Self { use_rounding: true }
+        Self { use_rounding: true, round_leaves_only: false, round_positions_only: false, use_cache: true }
     }
 }
 
@@ -159,6 +170,9 @@ pub struct TaffyTree<NodeContext = ()> {
 
     /// Layout mode configuration
     config: TaffyConfig,
+
+    /// Whether any node has been created or marked dirty since the last layout left every root clean
+    has_dirty: bool,
 }
 
 impl Default for TaffyTree {
@@ -229,6 +243,7 @@ impl<NodeContext> CacheTree for TaffyTree<NodeContext> {
 
     fn cache_clear(&mut self, node_id: NodeId) {
         self.nodes[node_id.into()].cache.clear();
+        self.has_dirty = true;
     }
 }
 
@@ -273,8 +288,13 @@ impl<NodeContext> PrintTree for TaffyTree<NodeContext> {
 /// which makes the lifetimes of the context much more flexible.
 pub(crate) struct TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     /// A reference to the TaffyTree
     pub(crate) taffy: &'t mut TaffyTree<NodeContext>,
@@ -285,8 +305,13 @@ where
 // TraversePartialTree impl for TaffyView
 impl<NodeContext, MeasureFunction> TraversePartialTree for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     type ChildIter<'a>
         = TaffyTreeChildIter<'a>
@@ -311,16 +336,26 @@ where
 
 // TraverseTree impl for TaffyView
 impl<NodeContext, MeasureFunction> TraverseTree for TaffyView<'_, NodeContext, MeasureFunction> where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>)
 {
 }
 
 // LayoutPartialTree impl for TaffyView
 impl<NodeContext, MeasureFunction> LayoutPartialTree for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     type CoreContainerStyle<'a>
         = &'a Style
@@ -387,7 +422,7 @@ where
                         (tree.measure_function)(known_dimensions, available_space, node, node_context, style)
                     };
                     // TODO: implement calc() in high-level API
-                    compute_leaf_layout(inputs, style, |_, _| 0.0, measure_function)
+                    compute_leaf_layout_with_baselines(inputs, style, |_, _| 0.0, measure_function)
                 }
             }
         })
@@ -396,8 +431,13 @@ where
 
 impl<NodeContext, MeasureFunction> CacheTree for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     fn cache_get(
         &self,
@@ -406,6 +446,9 @@ where
         available_space: Size<AvailableSpace>,
         run_mode: RunMode,
     ) -> Option<LayoutOutput> {
+        if !self.taffy.config.use_cache {
+            return None;
+        }
         self.taffy.nodes[node_id.into()].cache.get(known_dimensions, available_space, run_mode)
     }
 
@@ -428,8 +471,13 @@ where
 #[cfg(feature = "block_layout")]
 impl<NodeContext, MeasureFunction> LayoutBlockContainer for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     type BlockContainerStyle<'a>
         = &'a Style
@@ -454,8 +502,13 @@ where
 #[cfg(feature = "flexbox")]
 impl<NodeContext, MeasureFunction> LayoutFlexboxContainer for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     type FlexboxContainerStyle<'a>
         = &'a Style
@@ -475,13 +528,25 @@ where
     fn get_flexbox_child_style(&self, child_node_id: NodeId) -> Self::FlexboxItemStyle<'_> {
         &self.taffy.nodes[child_node_id.into()].style
     }
+
+    #[inline(always)]
+    #[cfg(feature = "detailed_layout_info")]
+    fn set_detailed_flexbox_info(&mut self, node_id: NodeId, detailed_flexbox_info: DetailedFlexboxInfo) {
+        self.taffy.nodes[node_id.into()].detailed_layout_info =
+            DetailedLayoutInfo::Flexbox(Box::new(detailed_flexbox_info));
+    }
 }
 
 #[cfg(feature = "grid")]
 impl<NodeContext, MeasureFunction> LayoutGridContainer for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     type GridContainerStyle<'a>
         = &'a Style
@@ -512,8 +577,13 @@ where
 // RoundTree impl for TaffyView
 impl<NodeContext, MeasureFunction> RoundTree for TaffyView<'_, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+    ) -> (Size<f32>, Point<Option<f32>>),
 {
     #[inline(always)]
     fn get_unrounded_layout(&self, node: NodeId) -> &Layout {
@@ -547,12 +617,31 @@ impl<NodeContext> TaffyTree<NodeContext> {
             parents: SlotMap::with_capacity(capacity),
             node_context_data: SecondaryMap::with_capacity(capacity),
             config: TaffyConfig::default(),
+            has_dirty: false,
         }
     }
 
     /// Enable rounding of layout values. Rounding is enabled by default.
     pub fn enable_rounding(&mut self) {
         self.config.use_rounding = true;
+        self.config.round_leaves_only = false;
+        self.config.round_positions_only = false;
+    }
+
+    /// Enable rounding of the layout values of leaf nodes only, leaving the layout values of nodes with children
+    /// unrounded. See [`round_leaf_layouts`] for how this affects alignment.
+    pub fn enable_leaf_rounding(&mut self) {
+        self.config.use_rounding = true;
+        self.config.round_leaves_only = true;
+        self.config.round_positions_only = false;
+    }
+
+    /// Enable rounding of the locations of nodes only, leaving their sizes and other layout values unrounded. See
+    /// [`round_layout_positions`] for how this affects alignment.
+    pub fn enable_position_rounding(&mut self) {
+        self.config.use_rounding = true;
+        self.config.round_leaves_only = false;
+        self.config.round_positions_only = true;
     }
 
     /// Disable rounding of layout values. Rounding is enabled by default.
@@ -560,11 +649,26 @@ impl<NodeContext> TaffyTree<NodeContext> {
         self.config.use_rounding = false;
     }
 
+    /// Enable the reuse of cached layout results. Caching is enabled by default.
+    pub fn enable_caching(&mut self) {
+        self.config.use_cache = true;
+    }
+
+    /// Disable the reuse of cached layout results, so that every node is laid out (and every leaf measured) each
+    /// time an algorithm asks for its size. Results are still stored in the cache, and reused once caching is
+    /// enabled again. Caching is enabled by default.
+    ///
+    /// This makes layout much slower, and is meant for tests and debugging.
+    pub fn disable_caching(&mut self) {
+        self.config.use_cache = false;
+    }
+
     /// Creates and adds a new unattached leaf node to the tree, and returns the node of the new node
     pub fn new_leaf(&mut self, layout: Style) -> TaffyResult<NodeId> {
         let id = self.nodes.insert(NodeData::new(layout));
         let _ = self.children.insert(new_vec_with_capacity(0));
         let _ = self.parents.insert(None);
+        self.has_dirty = true;
 
         Ok(id.into())
     }
@@ -581,6 +685,7 @@ impl<NodeContext> TaffyTree<NodeContext> {
 
         let _ = self.children.insert(new_vec_with_capacity(0));
         let _ = self.parents.insert(None);
+        self.has_dirty = true;
 
         Ok(id.into())
     }
@@ -595,6 +700,7 @@ impl<NodeContext> TaffyTree<NodeContext> {
 
         let _ = self.children.insert(children.iter().copied().collect::<_>());
         let _ = self.parents.insert(None);
+        self.has_dirty = true;
 
         Ok(id)
     }
@@ -604,6 +710,7 @@ impl<NodeContext> TaffyTree<NodeContext> {
         self.nodes.clear();
         self.children.clear();
         self.parents.clear();
+        self.has_dirty = false;
     }
 
     /// Remove a specific node from the tree and drop it
@@ -809,6 +916,17 @@ impl<NodeContext> TaffyTree<NodeContext> {
         self.nodes.len()
     }
 
+    /// Returns the ids of every node in the tree, in an unspecified order
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.keys().map(NodeId::from)
+    }
+
+    /// Returns `true` if the specified node is in the tree
+    #[inline]
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.nodes.contains_key(node.into())
+    }
+
     /// Returns the `NodeId` of the parent node of the specified node (if it exists)
     ///
     /// - Return None if the specified node has no parent
@@ -885,6 +1003,7 @@ impl<NodeContext> TaffyTree<NodeContext> {
         }
 
         mark_dirty_recursive(&mut self.nodes, &self.parents, node.into());
+        self.has_dirty = true;
 
         Ok(())
     }
@@ -895,24 +1014,95 @@ impl<NodeContext> TaffyTree<NodeContext> {
         Ok(self.nodes[node.into()].cache.is_empty())
     }
 
+    /// Indicates whether the layout of any node in the tree needs to be recomputed
+    ///
+    /// This is O(1): the tree tracks a flag which is set whenever a node is created or marked dirty (including by
+    /// [`mark_dirty`](Self::mark_dirty), [`set_style`](Self::set_style) and the methods that change a node's children
+    /// or context), and which is recomputed after each layout. Laying out one root leaves the flag set if another root
+    /// is still dirty.
+    ///
+    /// The flag is conservative: removing a dirty node that had no parent leaves it set until the next layout.
+    #[inline]
+    pub fn has_dirty(&self) -> bool {
+        self.has_dirty
+    }
+
     /// Updates the stored layout of the provided `node` and its children
     pub fn compute_layout_with_measure<MeasureFunction>(
         &mut self,
         node_id: NodeId,
         available_space: Size<AvailableSpace>,
-        measure_function: MeasureFunction,
+        mut measure_function: MeasureFunction,
     ) -> Result<(), TaffyError>
     where
         MeasureFunction:
             FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
     {
-        let use_rounding = self.config.use_rounding;
+        self.compute_layout_with_measure_and_baselines(
+            node_id,
+            available_space,
+            |known_dimensions, available_space, node_id, node_context, style| {
+                (measure_function(known_dimensions, available_space, node_id, node_context, style), Point::NONE)
+            },
+        )
+    }
+
+    /// Updates the stored layout of the provided `node` and its children, with a measure function that also returns
+    /// the first baselines of each measured node
+    ///
+    /// See [`compute_leaf_layout_with_baselines`](crate::compute_leaf_layout_with_baselines) for how the baselines are
+    /// interpreted.
+    pub fn compute_layout_with_measure_and_baselines<MeasureFunction>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        measure_function: MeasureFunction,
+    ) -> Result<(), TaffyError>
+    where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+        ) -> (Size<f32>, Point<Option<f32>>),
+    {
+        self.compute_root_layout_with_measure(
+            node_id,
+            available_space,
+            available_space.into_options(),
+            measure_function,
+        );
+        Ok(())
+    }
+
+    /// Lays out the tree rooted at `node_id`, resolving the percentages of `node_id` against `containing_block`
+    fn compute_root_layout_with_measure<MeasureFunction>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        containing_block: Size<Option<f32>>,
+        measure_function: MeasureFunction,
+    ) where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+        ) -> (Size<f32>, Point<Option<f32>>),
+    {
+        let TaffyConfig { use_rounding, round_leaves_only, round_positions_only, .. } = self.config;
         let mut taffy_view = TaffyView { taffy: self, measure_function };
-        compute_root_layout(&mut taffy_view, node_id, available_space);
-        if use_rounding {
+        compute_root_layout_with_containing_block(&mut taffy_view, node_id, available_space, containing_block);
+        if use_rounding && round_leaves_only {
+            round_leaf_layouts(&mut taffy_view, node_id);
+        } else if use_rounding && round_positions_only {
+            round_layout_positions(&mut taffy_view, node_id);
+        } else if use_rounding {
             round_layout(&mut taffy_view, node_id);
         }
-        Ok(())
+        self.has_dirty = self.parents.iter().any(|(key, parent)| parent.is_none() && self.nodes[key].cache.is_empty());
     }
 
     /// Updates the stored layout of the provided `node` and its children
@@ -920,6 +1110,52 @@ impl<NodeContext> TaffyTree<NodeContext> {
         self.compute_layout_with_measure(node, available_space, |_, _, _, _, _| Size::ZERO)
     }
 
+    /// Updates the stored layout of the provided `node` and its children, resolving the percentages of `node`
+    /// against `containing_block` instead of `available_space`
+    ///
+    /// This is for laying out a tree embedded in another layout engine, whose percentage base comes from the host.
+    /// See [`compute_root_layout_with_containing_block`] for how the two sizes are used. The cached layout of `node`
+    /// is discarded first, since the cache doesn't record the containing block it was computed in.
+    pub fn compute_layout_with_containing_block(
+        &mut self,
+        node: NodeId,
+        available_space: Size<AvailableSpace>,
+        containing_block: Size<Option<f32>>,
+    ) -> Result<(), TaffyError> {
+        self.compute_layout_with_measure_and_containing_block(
+            node,
+            available_space,
+            containing_block,
+            |_, _, _, _, _| Size::ZERO,
+        )
+    }
+
+    /// Updates the stored layout of the provided `node` and its children as
+    /// [`compute_layout_with_containing_block`](Self::compute_layout_with_containing_block) does, sizing leaves with
+    /// a measure function
+    pub fn compute_layout_with_measure_and_containing_block<MeasureFunction>(
+        &mut self,
+        node: NodeId,
+        available_space: Size<AvailableSpace>,
+        containing_block: Size<Option<f32>>,
+        mut measure_function: MeasureFunction,
+    ) -> Result<(), TaffyError>
+    where
+        MeasureFunction:
+            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    {
+        self.nodes[node.into()].cache.clear();
+        self.compute_root_layout_with_measure(
+            node,
+            available_space,
+            containing_block,
+            |known_dimensions, available_space, node_id, node_context, style| {
+                (measure_function(known_dimensions, available_space, node_id, node_context, style), Point::NONE)
+            },
+        );
+        Ok(())
+    }
+
     /// Prints a debug representation of the tree's layout
     #[cfg(feature = "std")]
     pub fn print_tree(&mut self, root: NodeId) {
@@ -929,7 +1165,7 @@ impl<NodeContext> TaffyTree<NodeContext> {
     /// Returns an instance of LayoutTree representing the TaffyTree
     #[cfg(test)]
     pub(crate) fn as_layout_tree(&mut self) -> impl LayoutPartialTree + CacheTree + '_ {
-        TaffyView { taffy: self, measure_function: |_, _, _, _, _| Size::ZERO }
+        TaffyView { taffy: self, measure_function: |_, _, _, _, _| (Size::ZERO, Point::NONE) }
     }
 }
 
@@ -1018,6 +1254,16 @@ mod tests {
         let _ = taffy.remove(node).unwrap();
     }
 
+    #[test]
+    fn contains() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        assert!(taffy.contains(node));
+
+        taffy.remove(node).unwrap();
+        assert!(!taffy.contains(node));
+    }
+
     #[test]
     fn remove_node_should_detach_hierarchy() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
@@ -1325,6 +1571,96 @@ mod tests {
         assert_eq!(taffy.dirty(node), Ok(true));
     }
 
+    #[test]
+    fn test_has_dirty() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        assert!(!taffy.has_dirty());
+
+        let child = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child]).unwrap();
+        assert!(taffy.has_dirty());
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        assert!(!taffy.has_dirty());
+
+        taffy.set_style(child, Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+        assert!(taffy.has_dirty());
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        assert!(!taffy.has_dirty());
+
+        let other = taffy.new_leaf(Style::default()).unwrap();
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        assert!(taffy.has_dirty(), "the unattached leaf is still dirty");
+        taffy.add_child(node, other).unwrap();
+        assert!(taffy.has_dirty());
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        assert!(!taffy.has_dirty());
+
+        taffy.mark_dirty(child).unwrap();
+        assert!(taffy.has_dirty());
+    }
+
+    #[test]
+    fn node_ids_lists_every_node() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy.new_leaf(Style::default()).unwrap();
+        let removed = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child]).unwrap();
+        taffy.remove(removed).unwrap();
+
+        let mut node_ids: Vec<_> = taffy.node_ids().collect();
+        node_ids.sort_by_key(|&node| u64::from(node));
+        assert_eq!(node_ids, vec![child, node]);
+    }
+
+    #[test]
+    fn percentages_of_root_resolve_against_containing_block() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+        let root = taffy
+            .new_with_children(Style { size: percent(0.5), padding: percent(0.1), ..Default::default() }, &[child])
+            .unwrap();
+        let available_space = Size { width: AvailableSpace::Definite(400.0), height: AvailableSpace::Definite(400.0) };
+
+        taffy.compute_layout(root, available_space).unwrap();
+        assert_eq!(taffy.layout(root).unwrap().size, Size { width: 200.0, height: 200.0 });
+
+        let containing_block = Size { width: Some(200.0), height: Some(100.0) };
+        taffy.compute_layout_with_containing_block(root, available_space, containing_block).unwrap();
+        let layout = taffy.layout(root).unwrap();
+        assert_eq!(layout.size, Size { width: 100.0, height: 50.0 });
+        assert_eq!(layout.padding.left, 20.0);
+        // The child resolves its percentages against the content box of the root as usual
+        assert_eq!(taffy.layout(child).unwrap().size, Size { width: 30.0, height: 5.0 });
+    }
+
+    #[test]
+    fn disable_caching_measures_leaves_on_every_pass() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[leaf]).unwrap();
+        let measure_calls = |taffy: &mut TaffyTree<()>| {
+            let mut calls = 0;
+            taffy
+                .compute_layout_with_measure(node, Size::MAX_CONTENT, |_, _, _, _, _| {
+                    calls += 1;
+                    Size { width: 10.0, height: 10.0 }
+                })
+                .unwrap();
+            calls
+        };
+        assert!(measure_calls(&mut taffy) > 0);
+        assert_eq!(measure_calls(&mut taffy), 0, "the clean tree is served from the cache");
+
+        taffy.disable_caching();
+        let uncached_calls = measure_calls(&mut taffy);
+        assert!(uncached_calls > 0);
+        assert_eq!(measure_calls(&mut taffy), uncached_calls);
+        assert!(!taffy.dirty(node).unwrap());
+
+        taffy.enable_caching();
+        assert_eq!(measure_calls(&mut taffy), 0);
+    }
+
     #[test]
     fn compute_layout_should_produce_valid_result() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
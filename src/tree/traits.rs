@@ -138,6 +138,8 @@ use crate::style::{GridContainerStyle, GridItemStyle};
 #[cfg(feature = "block_layout")]
 use crate::{BlockContainerStyle, BlockItemStyle};
 
+#[cfg(all(feature = "flexbox", feature = "detailed_layout_info"))]
+use crate::compute::flexbox::DetailedFlexboxInfo;
 #[cfg(all(feature = "grid", feature = "detailed_layout_info"))]
 use crate::compute::grid::DetailedGridInfo;
 
@@ -260,6 +262,15 @@ pub trait LayoutFlexboxContainer: LayoutPartialTree {
 
     /// Get the child's styles
     fn get_flexbox_child_style(&self, child_node_id: NodeId) -> Self::FlexboxItemStyle<'_>;
+
+    /// Set the node's detailed flexbox information
+    ///
+    /// Implementing this method is optional. Doing so allows you to access details about the flex lines such as
+    /// their computed cross-axis positions and the free space distributed along each line.
+    #[cfg(feature = "detailed_layout_info")]
+    fn set_detailed_flexbox_info(&mut self, _node_id: NodeId, _detailed_flexbox_info: DetailedFlexboxInfo) {
+        debug_log!("LayoutFlexboxContainer::set_detailed_flexbox_info called");
+    }
 }
 
 #[cfg(feature = "grid")]
@@ -22,6 +22,8 @@ mod std {
     #[cfg(feature = "grid")]
     /// A vector of grid tracks
     pub(crate) type GridTrackVec<A> = std::vec::Vec<A>;
+    /// A heap-allocated box
+    pub(crate) type Box<A> = std::boxed::Box<A>;
 
     /// Creates a new vector with the capacity for the specified number of items before it must be resized
     #[must_use]
@@ -83,6 +85,8 @@ mod alloc {
     #[cfg(feature = "grid")]
     /// A vector of grid tracks
     pub(crate) type GridTrackVec<A> = alloc::vec::Vec<A>;
+    /// A heap-allocated box
+    pub(crate) type Box<A> = alloc::boxed::Box<A>;
 
     /// Creates a new vector with the capacity for the specified number of items before it must be resized
     #[must_use]
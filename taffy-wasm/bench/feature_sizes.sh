@@ -0,0 +1,22 @@
+#!/bin/sh
+# Builds the release bindings with each combination of the layout features and prints the size of each .wasm
+# binary, raw and gzipped. The sizes depend on the versions of the tools, so no table of them is kept in
+# README_FEATURES.md. Needs wasm-pack and the wasm32-unknown-unknown target.
+set -e
+cd "$(dirname "$0")/.."
+out="$(mktemp -d)"
+trap 'rm -rf "$out"' EXIT
+
+size() {
+	name="$1"
+	shift
+	wasm-pack build --target web --release --out-dir "$out/$name" "$@" >/dev/null 2>&1
+	wasm="$out/$name/taffy_wasm_bg.wasm"
+	printf '%-22s %8s bytes %8s bytes gzipped\n' "$name" "$(wc -c <"$wasm")" "$(gzip -9c "$wasm" | wc -c)"
+}
+
+size "flexbox" --no-default-features
+size "flexbox + block" --no-default-features --features block
+size "flexbox + grid" # the default
+size "flexbox + grid + block" --features block
+size "default + debug-logging" --features debug-logging
@@ -0,0 +1,36 @@
+// Compares reading computed layouts through `layout_buffer` against building an array of objects with the
+// per-node accessors. Run `npm run build` first, then `npm run bench`.
+import init, { TaffyTree } from '../pkg/taffy_wasm.js';
+import { readFileSync } from 'fs';
+
+await init(readFileSync(new URL('../pkg/taffy_wasm_bg.wasm', import.meta.url)));
+
+const NODE_COUNT = 10_000;
+const ITERATIONS = 50;
+
+const tree = new TaffyTree();
+const root = tree.new_leaf({ display: 'Flex', flex_wrap: 'Wrap' });
+for (let i = 0; i < NODE_COUNT; i++) {
+  tree.add_child(root, tree.new_leaf({}));
+}
+tree.compute_layout(root, 1000, 1000);
+const ids = tree.node_order(root);
+
+function bench(name, fn) {
+  fn(); // warm up
+  const start = performance.now();
+  for (let i = 0; i < ITERATIONS; i++) fn();
+  const elapsed = (performance.now() - start) / ITERATIONS;
+  console.log(`${name}: ${elapsed.toFixed(3)}ms per read of ${ids.length} nodes`);
+}
+
+bench('object array', () =>
+  Array.from(ids, (id) => ({
+    x: tree.layout_left(id),
+    y: tree.layout_top(id),
+    width: tree.layout_width(id),
+    height: tree.layout_height(id),
+  }))
+);
+bench('layout_buffer', () => tree.layout_buffer(root));
+bench('layout_buffer with content size', () => tree.layout_buffer(root, true));
@@ -61,11 +61,9 @@ export type GridTrackRepetition =
   | "AutoFit"
   | { Count: number };
 
-/** Represents grid line placement */
-export type GridPlacement = 
-  | "Auto"
-  | { Line: number }
-  | { Span: number };
+// GridPlacement and its helpers are generated into GridPlacement.js, so that they can be run as plain JavaScript
+import type { GridPlacement } from './GridPlacement.js';
+export { GridPlacement } from './GridPlacement.js';
 
 /** Helper types for grid areas */
 export type GridArea = {
@@ -113,8 +111,170 @@ export interface GridItemStyle {
 }
 "#;
 
+    // With the camel-case-style feature, Style fields are (de)serialized in camelCase, so match them here
+    let grid_types_ts = if std::env::var_os("CARGO_FEATURE_CAMEL_CASE_STYLE").is_some() {
+        let field_regex = regex::Regex::new(r"\b(grid(?:_[a-z]+)+)(\??:)").unwrap();
+        field_regex
+            .replace_all(grid_types_ts, |caps: &regex::Captures| {
+                let mut words = caps[1].split('_');
+                let mut field = words.next().unwrap_or_default().to_string();
+                for word in words {
+                    field.push_str(&word[..1].to_uppercase());
+                    field.push_str(&word[1..]);
+                }
+                format!("{field}{}", &caps[2])
+            })
+            .into_owned()
+    } else {
+        grid_types_ts.to_string()
+    };
+
     std::fs::write("pkg/GridTypes.ts", grid_types_ts).expect("failed to write GridTypes.ts");
 
+    // Generate LayoutAvailableSpace.ts for the argument of compute_layout_v2
+    let layout_available_space_ts = r#"
+// TypeScript definitions for the available space argument of compute_layout_v2
+// Generated automatically by build.rs - do not edit manually
+
+/** The space available in one axis: a number of pixels, an intrinsic sizing keyword, or max-content space in which
+ * the root is at most `limit` pixels in size */
+export type AvailableAxis = number | "min-content" | "max-content" | { maxContent: true; limit: number };
+
+/** The space available to a layout, with an independent mode for each axis */
+export interface LayoutAvailableSpace {
+  /** The space available horizontally */
+  width: AvailableAxis;
+  /** The space available vertically */
+  height: AvailableAxis;
+}
+"#;
+
+    std::fs::write("pkg/LayoutAvailableSpace.ts", layout_available_space_ts)
+        .expect("failed to write LayoutAvailableSpace.ts");
+
+    // Generate TreeBuilder.js and its declarations, with the h and buildTree helpers for declarative tree construction.
+    // They are plain JavaScript so that they can be run without compiling TypeScript first.
+    let tree_builder_js = r#"
+// Declarative tree construction helpers for taffy-wasm
+// Generated automatically by build.rs - do not edit manually
+
+export function h(style, ...children) {
+  return { style, children: children.flat() };
+}
+
+export function buildTree(taffy, vnode) {
+  // The nodes being built, each with the ids of the children created for it so far. Children are created before
+  // their parent, so every node is created with all its children in a single call.
+  const stack = [{ vnode, childIds: [] }];
+  for (;;) {
+    const top = stack[stack.length - 1];
+    const children = top.vnode.children;
+    if (top.childIds.length < children.length) {
+      stack.push({ vnode: children[top.childIds.length], childIds: [] });
+      continue;
+    }
+    const id = taffy.new_with_children(top.vnode.style, Uint32Array.from(top.childIds));
+    stack.pop();
+    if (stack.length === 0) {
+      return id;
+    }
+    stack[stack.length - 1].childIds.push(id);
+  }
+}
+"#;
+
+    let tree_builder_d_ts = r#"
+// TypeScript declarations for TreeBuilder.js
+// Generated automatically by build.rs - do not edit manually
+
+import type { TaffyTree } from './taffy_wasm.js';
+
+/** A node to be created by `buildTree`: its style object, as passed to `new_leaf`, and the nodes to create as its
+ * children */
+export interface VNode {
+  style: object;
+  children: VNode[];
+}
+
+/**
+ * Describes a node with `style` and `children`, e.g. `h(rowStyle, h(itemStyle), h(itemStyle))`
+ *
+ * Arrays of children are flattened, so that a list can be passed as it is: `h(listStyle, items.map(item => h(item)))`.
+ * Nothing is created until the description is passed to `buildTree`.
+ */
+export function h(style: object, ...children: (VNode | VNode[])[]): VNode;
+
+/**
+ * Creates the nodes described by `vnode` in `taffy`, returning the id of its root
+ *
+ * The nodes are created bottom-up with `new_with_children`, so each node is created with all its children in one
+ * call, and the tree is only walked once. Throws the error of the first node that fails to be created, e.g. a
+ * `STYLE_DECODE` error for an invalid style; the nodes created before it are left in the tree, without a parent.
+ */
+export function buildTree(taffy: Pick<TaffyTree, 'new_with_children'>, vnode: VNode): number;
+"#;
+
+    std::fs::write("pkg/TreeBuilder.js", tree_builder_js).expect("failed to write TreeBuilder.js");
+    std::fs::write("pkg/TreeBuilder.d.ts", tree_builder_d_ts).expect("failed to write TreeBuilder.d.ts");
+
+    // Generate GridPlacement.js and its declarations, with the GridPlacement helpers. Like TreeBuilder.js, they are
+    // plain JavaScript so that they can be run, and tested, without compiling TypeScript first.
+    let grid_placement_js = r#"
+// GridPlacement helpers for taffy-wasm
+// Generated automatically by build.rs - do not edit manually
+
+export const GridPlacement = {
+  // Place the item according to the auto-placement algorithm
+  auto: () => "Auto",
+
+  // Place the item at the given grid line
+  line: (index) => {
+    if (!Number.isInteger(index) || index === 0 || index < -32768 || index > 32767) {
+      throw new RangeError(`GridPlacement.line: expected a non-zero integer line index, got ${index}`);
+    }
+    return { Line: index };
+  },
+
+  // Make the item span the given number of tracks
+  span: (count) => {
+    if (!Number.isInteger(count) || count <= 0 || count > 65535) {
+      throw new RangeError(`GridPlacement.span: expected a positive integer span, got ${count}`);
+    }
+    return { Span: count };
+  }
+};
+"#;
+
+    let grid_placement_d_ts = r#"
+// TypeScript declarations for GridPlacement.js
+// Generated automatically by build.rs - do not edit manually
+
+/** Represents grid line placement */
+export type GridPlacement = 
+  | "Auto"
+  | { Line: number }
+  | { Span: number };
+
+/**
+ * Helpers for constructing GridPlacement values
+ *
+ * Lines are 1-based and may be negative to count back from the end of the explicit grid
+ * (-1 is the last line). Line 0 is not a valid grid line.
+ * Spans must be positive: a zero or negative span throws.
+ */
+export declare const GridPlacement: {
+  /** Place the item according to the auto-placement algorithm */
+  auto(): GridPlacement;
+  /** Place the item at the given grid line, a non-zero integer from -32768 to 32767; throws a RangeError otherwise */
+  line(index: number): GridPlacement;
+  /** Make the item span the given number of tracks, an integer from 1 to 65535; throws a RangeError otherwise */
+  span(count: number): GridPlacement;
+};
+"#;
+
+    std::fs::write("pkg/GridPlacement.js", grid_placement_js).expect("failed to write GridPlacement.js");
+    std::fs::write("pkg/GridPlacement.d.ts", grid_placement_d_ts).expect("failed to write GridPlacement.d.ts");
+
     // Generate a comprehensive index.ts file for easy imports
     let index_ts = r#"
 // Main exports for taffy-wasm
@@ -143,6 +303,7 @@ export { AlignItems } from './AlignItems.js';
 export { Display } from './Display.js';
 export { Position } from './Position.js';
 export { BoxSizing } from './BoxSizing.js';
+export { Direction } from './Direction.js';
 export { Overflow } from './Overflow.js';
 export { TextAlign } from './TextAlign.js';
 export { GridAutoFlow } from './GridAutoFlow.js';
@@ -157,6 +318,10 @@ export { LengthPercentage } from './LengthPercentage.js';
 export { LengthPercentageAuto } from './LengthPercentageAuto.js';
 export { Dimension } from './Dimension.js';
 export { AvailableSpace } from './AvailableSpace.js';
+export { AvailableAxis, LayoutAvailableSpace } from './LayoutAvailableSpace.js';
+
+// Declarative tree construction
+export { VNode, h, buildTree } from './TreeBuilder.js';
 "#;
 
     std::fs::write("pkg/index.ts", index_ts).expect("failed to write index.ts");
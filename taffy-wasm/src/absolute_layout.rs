@@ -0,0 +1,356 @@
+//! Root-relative layouts, for anchoring overlays to nodes without walking up the tree from JS
+use crate::layout_buffer::paint_order;
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use taffy::Point;
+use wasm_bindgen::prelude::*;
+
+/// The box of a node relative to the root of its tree, returned by `absolute_layout`, or to one of its ancestors,
+/// returned by `layout_relative_to`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AbsoluteLayout {
+    /// The distance from the left edge of the root's parent, or of the ancestor's border box, to the left edge of the
+    /// node's border box
+    pub x: f32,
+    /// The distance from the top edge of the root's parent, or of the ancestor's border box, to the top edge of the
+    /// node's border box
+    pub y: f32,
+    /// The width of the node's border box
+    pub width: f32,
+    /// The height of the node's border box
+    pub height: f32,
+}
+
+/// The box of a node as fractions of the size of the root of its tree, returned by `layout_normalized`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct NormalizedLayout {
+    /// The distance from the left edge of the root to the left edge of the node, as a fraction of the root's width
+    pub x: f32,
+    /// The distance from the top edge of the root to the top edge of the node, as a fraction of the root's height
+    pub y: f32,
+    /// The width of the node, as a fraction of the root's width
+    pub width: f32,
+    /// The height of the node, as a fraction of the root's height
+    pub height: f32,
+}
+
+impl AbsoluteLayout {
+    /// Returns the box as inline CSS declarations that position an element absolutely at it
+    fn css(&self) -> String {
+        format!("position:absolute;left:{}px;top:{}px;width:{}px;height:{}px;", self.x, self.y, self.width, self.height)
+    }
+}
+
+impl TaffyTree {
+    /// Returns the box of a node, summing the locations of the node and its ancestors
+    pub(crate) fn absolute_box(&self, node_id: u32) -> Result<AbsoluteLayout, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let mut location = Point::ZERO;
+        for ancestor in std::iter::successors(Some(node), |&ancestor| tree.parent(ancestor)) {
+            location = location + tree.layout(ancestor)?.location;
+        }
+        let size = tree.layout(node)?.size;
+        Ok(AbsoluteLayout { x: location.x, y: location.y, width: size.width, height: size.height })
+    }
+
+    /// Returns the box of a node relative to the border box of `ancestor`, summing the locations of the node and its
+    /// ancestors below `ancestor`
+    fn relative_box(&self, node_id: u32, ancestor_id: u32) -> Result<AbsoluteLayout, TaffyError> {
+        let tree = self.tree()?;
+        let (node, ancestor) = (resolve_node(&tree, node_id)?, resolve_node(&tree, ancestor_id)?);
+        let not_an_ancestor =
+            || TaffyError::InvalidArgument(format!("node {ancestor_id} is not an ancestor of node {node_id}"));
+        if node == ancestor {
+            return Err(not_an_ancestor());
+        }
+        let mut location = Point::ZERO;
+        let mut current = node;
+        while current != ancestor {
+            location = location + tree.layout(current)?.location;
+            current = tree.parent(current).ok_or_else(not_an_ancestor)?;
+        }
+        let size = tree.layout(node)?.size;
+        Ok(AbsoluteLayout { x: location.x, y: location.y, width: size.width, height: size.height })
+    }
+
+    /// Returns the unrounded box of a node relative to the border box of the root of its tree, as fractions of the
+    /// root's size, with 0 in an axis in which the root has no size
+    fn normalized_box(&self, node_id: u32) -> Result<NormalizedLayout, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let mut location = Point::ZERO;
+        let mut root = node;
+        while let Some(parent) = tree.parent(root) {
+            location = location + tree.unrounded_layout(root).location;
+            root = parent;
+        }
+        let root_size = tree.unrounded_layout(root).size;
+        let size = tree.unrounded_layout(node).size;
+        let fraction = |pixels: f32, of: f32| if of > 0.0 { pixels / of } else { 0.0 };
+        Ok(NormalizedLayout {
+            x: fraction(location.x, root_size.width),
+            y: fraction(location.y, root_size.height),
+            width: fraction(size.width, root_size.width),
+            height: fraction(size.height, root_size.height),
+        })
+    }
+
+    /// Returns the smallest box enclosing the boxes of the given nodes, or an empty box at the origin if there are none
+    fn union_box(&self, node_ids: &[u32]) -> Result<AbsoluteLayout, TaffyError> {
+        {
+            let tree = self.tree()?;
+            let mut seen = BTreeSet::new();
+            let invalid: Vec<u32> = node_ids
+                .iter()
+                .copied()
+                .filter(|&node_id| resolve_node(&tree, node_id).is_err() && seen.insert(node_id))
+                .collect();
+            if !invalid.is_empty() {
+                return Err(TaffyError::InvalidNodes(invalid));
+            }
+        }
+        let boxes = node_ids.iter().map(|&node_id| self.absolute_box(node_id)).collect::<Result<Vec<_>, _>>()?;
+        let Some((first, rest)) = boxes.split_first() else {
+            return Ok(AbsoluteLayout { x: 0.0, y: 0.0, width: 0.0, height: 0.0 });
+        };
+        let (mut left, mut top) = (first.x, first.y);
+        let (mut right, mut bottom) = (first.x + first.width, first.y + first.height);
+        for layout in rest {
+            left = left.min(layout.x);
+            top = top.min(layout.y);
+            right = right.max(layout.x + layout.width);
+            bottom = bottom.max(layout.y + layout.height);
+        }
+        Ok(AbsoluteLayout { x: left, y: top, width: right - left, height: bottom - top })
+    }
+
+    /// Returns the CSS of the box of every node in the subtree rooted at `root`, keyed by node id
+    fn subtree_css(&self, root: u32) -> Result<BTreeMap<String, String>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let mut css = BTreeMap::new();
+        for (node, location) in paint_order(&tree, root)? {
+            let size = tree.layout(node)?.size;
+            let layout = AbsoluteLayout { x: location.x, y: location.y, width: size.width, height: size.height };
+            css.insert(js_node_id(node).to_string(), layout.css());
+        }
+        Ok(css)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the position and size of a node relative to the root of its tree, as `{ x, y, width, height }`
+    ///
+    /// `x` and `y` are the sum of the layout locations of the node and all of its ancestors, i.e. they are relative
+    /// to the origin the root was laid out in, just like the positions in `layout_buffer`. Use `layout_buffer` to
+    /// get the absolute boxes of a whole subtree in one call.
+    #[wasm_bindgen]
+    pub fn absolute_layout(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let layout = self.absolute_box(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the position and size of a node relative to the border box of one of its ancestors, as
+    /// `{ x, y, width, height }`
+    ///
+    /// `x` and `y` are the sum of the layout locations of the node and of its ancestors below `ancestor_id`, i.e. the
+    /// offset of the node's border box from the top left corner of the ancestor's border box, so that an overlay
+    /// positioned inside the ancestor, e.g. a tooltip inside a scroll container, lines up with the node. The offset
+    /// doesn't account for the ancestor's scroll position. With the root as `ancestor_id`, this is `absolute_layout`
+    /// less the location of the root.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `ancestor_id` is not an ancestor of `node_id`, including if it is
+    /// `node_id` itself.
+    #[wasm_bindgen]
+    pub fn layout_relative_to(&self, node_id: u32, ancestor_id: u32) -> Result<JsValue, TaffyError> {
+        let layout = self.relative_box(node_id, ancestor_id)?;
+        Ok(serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the position and size of a node as fractions of the size of the root of its tree, as
+    /// `{ x, y, width, height }`
+    ///
+    /// `x` and `width` are fractions of the root's width, and `y` and `height` of its height, with `x` and `y`
+    /// measured from the top left corner of the root's border box: the root itself is `{ x: 0, y: 0, width: 1,
+    /// height: 1 }`, and a node centred in it has its centre at 0.5. Multiplying the fractions by the size of any
+    /// canvas scales the layout to it, e.g. to store a design independently of the viewport it was laid out in.
+    /// The layout is read before it was rounded to whole pixels, so that the fractions are exact.
+    ///
+    /// A root with no width or height can't be divided by: every fraction in that axis is 0 instead.
+    #[wasm_bindgen]
+    pub fn layout_normalized(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let layout = self.normalized_box(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the smallest box enclosing the boxes of all the given nodes, relative to the root of their tree as in
+    /// `absolute_layout`, as `{ x, y, width, height }`
+    ///
+    /// This is the outline of a selection of nodes, e.g. for drawing it or zooming to fit it, computed without
+    /// fetching each node's box. The nodes don't need to share a parent, but their boxes are only comparable if they
+    /// are in the same tree. For an empty list of nodes this returns an empty box at the origin,
+    /// `{ x: 0, y: 0, width: 0, height: 0 }`.
+    ///
+    /// Throws an `INVALID_NODE` error listing every id that is not a node in `nodeIds`.
+    #[wasm_bindgen]
+    pub fn bounding_box(&self, node_ids: Vec<u32>) -> Result<JsValue, TaffyError> {
+        let layout = self.union_box(&node_ids)?;
+        Ok(serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the box of a node as in `absolute_layout`, formatted as inline CSS that positions an element at it,
+    /// e.g. `"position:absolute;left:10px;top:20px;width:100px;height:50px;"`
+    ///
+    /// Rendering every node of a tree as a sibling element with this style, all inside one positioned container,
+    /// reproduces the layout in HTML, e.g. to compare it with a browser's.
+    #[wasm_bindgen]
+    pub fn layout_css(&self, node_id: u32) -> Result<String, TaffyError> {
+        Ok(self.absolute_box(node_id)?.css())
+    }
+
+    /// Returns the `layout_css` of every node in the subtree rooted at `root` as an object `{ [nodeId]: css }`
+    #[wasm_bindgen]
+    pub fn layout_css_all(&self, root: u32) -> Result<JsValue, TaffyError> {
+        let css = self.subtree_css(root)?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        Ok(css.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::prelude::{auto, length, Position, Rect, Size, Style, TaffyMaxContent};
+
+    #[test]
+    fn node_at_the_centre_of_the_root_is_normalized_around_one_half() {
+        let tree = TaffyTree::new();
+        let (root, centred) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style { size: length(30.0), ..Default::default() }).unwrap();
+            let centring = Style {
+                justify_content: Some(taffy::JustifyContent::Center),
+                align_items: Some(taffy::AlignItems::Center),
+                size: Size { width: length(300.0), height: length(150.0) },
+                padding: length(10.0),
+                ..Default::default()
+            };
+            let wrapper = inner.new_with_children(centring, &[leaf]).unwrap();
+            (js_node_id(wrapper), js_node_id(leaf))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        let layout = tree.normalized_box(centred).unwrap();
+        assert_eq!(layout, NormalizedLayout { x: 135.0 / 300.0, y: 60.0 / 150.0, width: 0.1, height: 0.2 });
+        let centre = (layout.x + layout.width / 2.0, layout.y + layout.height / 2.0);
+        assert!((centre.0 - 0.5).abs() < 1e-6 && (centre.1 - 0.5).abs() < 1e-6, "{centre:?}");
+        assert_eq!(tree.normalized_box(root), Ok(NormalizedLayout { x: 0.0, y: 0.0, width: 1.0, height: 1.0 }));
+
+        // A root without a size gives fractions of 0 rather than dividing by zero
+        let empty = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        tree.compute_subtree(empty, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.normalized_box(empty), Ok(NormalizedLayout { x: 0.0, y: 0.0, width: 0.0, height: 0.0 }));
+    }
+
+    #[test]
+    fn absolute_layout_sums_ancestor_locations() {
+        let tree = TaffyTree::new();
+        let (root, nested) = {
+            let mut inner = tree.inner.borrow_mut();
+            let padded = |padding: f32| Style { padding: length(padding), ..Default::default() };
+            let leaf = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let spacer = inner.new_leaf(Style { size: length(30.0), ..Default::default() }).unwrap();
+            let middle = inner.new_with_children(padded(7.0), &[leaf]).unwrap();
+            let outer = inner.new_with_children(padded(5.0), &[spacer, middle]).unwrap();
+            let root = inner.new_with_children(padded(1.0), &[outer]).unwrap();
+            (js_node_id(root), [js_node_id(outer), js_node_id(middle), js_node_id(leaf)])
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        let mut expected = (tree.layout_left(root).unwrap(), tree.layout_top(root).unwrap());
+        for node in nested {
+            expected = (expected.0 + tree.layout_left(node).unwrap(), expected.1 + tree.layout_top(node).unwrap());
+        }
+        let layout = tree.absolute_box(nested[2]).unwrap();
+        assert_eq!(AbsoluteLayout { x: 1.0 + 5.0 + 30.0 + 7.0, y: 1.0 + 5.0 + 7.0, width: 10.0, height: 10.0 }, layout);
+        assert_eq!((layout.x, layout.y), expected);
+    }
+
+    #[test]
+    fn layout_css_positions_nodes_absolutely() {
+        let tree = TaffyTree::new();
+        let (root, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let size = Size { width: length(100.0), height: length(50.0) };
+            let leaf = inner.new_leaf(Style { size, margin: length(10.0), ..Default::default() }).unwrap();
+            let root = inner.new_with_children(Style { padding: length(10.0), ..Default::default() }, &[leaf]).unwrap();
+            (js_node_id(root), js_node_id(leaf))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(tree.layout_css(leaf).unwrap(), "position:absolute;left:20px;top:20px;width:100px;height:50px;");
+        let css = tree.subtree_css(root).unwrap();
+        assert_eq!(css.len(), 2);
+        assert_eq!(css[&leaf.to_string()], tree.layout_css(leaf).unwrap());
+        assert_eq!(css[&root.to_string()], "position:absolute;left:0px;top:0px;width:140px;height:90px;");
+    }
+
+    #[test]
+    fn layout_relative_to_a_mid_tree_ancestor() {
+        let tree = TaffyTree::new();
+        let (root, [outer, middle, leaf], sibling) = {
+            let mut inner = tree.inner.borrow_mut();
+            let padded = |padding: f32| Style { padding: length(padding), ..Default::default() };
+            let leaf = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let spacer = inner.new_leaf(Style { size: length(30.0), ..Default::default() }).unwrap();
+            let middle = inner.new_with_children(padded(7.0), &[leaf]).unwrap();
+            let outer = inner.new_with_children(padded(5.0), &[spacer, middle]).unwrap();
+            let root = inner.new_with_children(padded(1.0), &[outer]).unwrap();
+            (js_node_id(root), [outer, middle, leaf].map(js_node_id), js_node_id(spacer))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // The leaf is inside the middle node's 7px padding, which is after the 30px spacer inside the outer padding
+        let boxed = |x, y| AbsoluteLayout { x, y, width: 10.0, height: 10.0 };
+        assert_eq!(tree.relative_box(leaf, middle), Ok(boxed(7.0, 7.0)));
+        assert_eq!(tree.relative_box(leaf, outer), Ok(boxed(5.0 + 30.0 + 7.0, 5.0 + 7.0)));
+        assert_eq!(tree.relative_box(leaf, root), tree.absolute_box(leaf));
+
+        for (node, ancestor) in [(leaf, sibling), (middle, leaf), (leaf, leaf)] {
+            assert!(matches!(tree.relative_box(node, ancestor), Err(TaffyError::InvalidArgument(_))));
+        }
+    }
+
+    #[test]
+    fn bounding_box_encloses_scattered_nodes() {
+        let tree = TaffyTree::new();
+        let (root, [a, b, c]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let mut placed = |left: f32, top: f32, size: f32| {
+                let inset = Rect { left: length(left), top: length(top), right: auto(), bottom: auto() };
+                let style = Style { position: Position::Absolute, inset, size: length(size), ..Default::default() };
+                inner.new_leaf(style).unwrap()
+            };
+            let items = [placed(10.0, 40.0, 20.0), placed(70.0, 5.0, 10.0), placed(30.0, 90.0, 5.0)];
+            let root = Style { size: length(200.0), padding: length(3.0), ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &items).unwrap()), items.map(js_node_id))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // From the left of `a` and the top of `b` to the right of `b` and the bottom of `c`
+        assert_eq!(tree.union_box(&[a, b, c]), Ok(AbsoluteLayout { x: 10.0, y: 5.0, width: 70.0, height: 90.0 }));
+        assert_eq!(tree.union_box(&[b]), tree.absolute_box(b));
+        assert_eq!(tree.union_box(&[]), Ok(AbsoluteLayout { x: 0.0, y: 0.0, width: 0.0, height: 0.0 }));
+        assert_eq!(tree.union_box(&[a, 100, 101, 100]), Err(TaffyError::InvalidNodes(vec![100, 101])));
+    }
+
+    #[test]
+    fn absolute_layout_invalid_node() {
+        let tree = TaffyTree::new();
+        assert_eq!(tree.absolute_box(1), Err(TaffyError::InvalidNode(1)));
+    }
+}
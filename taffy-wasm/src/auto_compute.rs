@@ -0,0 +1,97 @@
+//! Laying out a tree automatically when a layout is read after it changed, e.g. for reactive UIs
+use crate::node_ids::{js_node_id, node_from_js_id};
+use crate::{resolve_node, TaffyError, TaffyTree};
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Lays out the root of a node's tree again in the space it was last laid out in, if automatic layout is
+    /// enabled and the root has changed since
+    ///
+    /// The root is the outermost ancestor of the node, or the node itself, that has been laid out as a root. Nothing
+    /// is laid out for an id that is not a node, or for a node whose tree has never been laid out.
+    pub(crate) fn compute_if_stale(&self, node_id: u32) -> Result<(), TaffyError> {
+        if !self.auto_compute.get() || self.in_batch() {
+            return Ok(());
+        }
+        let stale_root = {
+            let tree = self.tree()?;
+            let Ok(node) = resolve_node(&tree, node_id) else {
+                return Ok(());
+            };
+            let node_states = self.node_states.borrow();
+            let root = std::iter::successors(Some(node), |&ancestor| tree.parent(ancestor))
+                .map(js_node_id)
+                .filter_map(|ancestor| Some((ancestor, node_states.get(ancestor).last_available_space?)))
+                .last();
+            match root {
+                Some((root, available_space)) if tree.dirty(node_from_js_id(root))? => Some((root, available_space)),
+                _ => None,
+            }
+        };
+        match stale_root {
+            Some((root, available_space)) => self.compute_subtree(root, available_space),
+            None => Ok(()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Enables or disables laying out a tree automatically when one of its layouts is read after it changed
+    ///
+    /// While enabled, `layout_left`, `layout_top`, `layout_width`, `layout_height`, `resolved_margin` and
+    /// `try_layout` first lay out the node's tree again if anything in it was marked dirty since its last layout, as
+    /// `recompute` does without a measure function, so that styles can be changed freely without calling
+    /// `compute_layout`. The tree is laid out at most once per change: reads of a tree that is up to date return
+    /// its stored layout. Only trees that have been laid out as a root before are laid out automatically, in the
+    /// space they were last laid out in; before that, reads return what they would without this mode. Leaves are
+    /// sized by their content size or object fit, since no measure function is kept between layouts. Disabled by
+    /// default.
+    ///
+    /// A read may therefore lay out the tree, which borrows it mutably: reading a layout while the tree is borrowed,
+    /// e.g. from a measure function called during a layout, throws a `BORROW_CONFLICT` error where it would
+    /// otherwise return the stale layout. A read that lays out the tree also throws any error the layout does, such
+    /// as `LAYOUT_TOO_DEEP`, and replaces the statistics returned by `last_compute_stats`.
+    #[wasm_bindgen]
+    pub fn set_auto_compute(&self, enabled: bool) {
+        self.auto_compute.set(enabled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_stats::ComputeStats;
+    use taffy::prelude::{length, AvailableSpace, Size, Style};
+
+    #[test]
+    fn reads_after_a_style_change_lay_out_the_tree_once() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            (js_node_id(inner.new_with_children(Style::default(), &[child]).unwrap()), js_node_id(child))
+        };
+        tree.set_auto_compute(true);
+        // A tree that has never been laid out isn't laid out by reading it
+        assert_eq!(tree.layout_width(child), Ok(0.0));
+
+        let available_space = Size { width: AvailableSpace::Definite(300.0), height: AvailableSpace::Definite(200.0) };
+        tree.compute_subtree(root, available_space).unwrap();
+        let wider = Style { size: Size { width: length(40.0), height: length(10.0) }, ..Default::default() };
+        tree.inner.borrow_mut().set_style(node_from_js_id(child), wider).unwrap();
+        assert_eq!(tree.layout_width(child), Ok(40.0));
+        assert_eq!(tree.layout_width(root), Ok(40.0));
+        assert_eq!(tree.last_compute_stats.get().nodes_laid_out, 2);
+
+        // Reads of the up-to-date tree don't lay it out again
+        tree.last_compute_stats.set(ComputeStats::default());
+        assert_eq!(tree.layout_height(child), Ok(10.0));
+        assert!(tree.current_layout(root).unwrap().is_some());
+        assert_eq!(tree.last_compute_stats.get(), ComputeStats::default());
+
+        tree.set_auto_compute(false);
+        tree.inner.borrow_mut().set_style(node_from_js_id(child), Style::default()).unwrap();
+        assert_eq!(tree.layout_width(child), Ok(40.0));
+    }
+}
@@ -0,0 +1,385 @@
+//! Decoding the space available to a layout from its CSS-like JS form, `{ width: 300, height: "max-content" }`
+use crate::node_ids::node_from_js_id;
+use crate::{TaffyError, TaffyTree};
+use serde::Deserialize;
+use taffy::{prelude::*, ResolveOrZero};
+use wasm_bindgen::prelude::*;
+
+/// The space available in one axis, decoded from a number of pixels, `"min-content"`, `"max-content"`,
+/// `{ maxContent: true, limit }` or `{ percent, base }`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum AvailableAxis {
+    /// A definite amount of space, in pixels
+    Definite(f32),
+    /// An intrinsic sizing keyword
+    Keyword(AvailableKeyword),
+    /// Max-content space, with an upper bound on the size of the root
+    Bounded(BoundedMaxContent),
+    /// A definite amount of space, as a percentage of a number of pixels
+    Percentage(PercentageOfBase),
+}
+
+/// The intrinsic sizing keywords that an axis of available space can take
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+enum AvailableKeyword {
+    /// The content is laid out as narrowly (or as short) as it can be
+    #[serde(rename = "min-content")]
+    MinContent,
+    /// The content is laid out without being wrapped or squashed
+    #[serde(rename = "max-content")]
+    MaxContent,
+}
+
+/// Max-content space whose root may be at most `limit` pixels in size, decoded from `{ maxContent: true, limit }`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct BoundedMaxContent {
+    /// Whether the axis is max-content, which must be `true`
+    max_content: bool,
+    /// The largest size of the root in this axis, in pixels
+    limit: f32,
+}
+
+/// Definite space of `percent` percent of `base` pixels, decoded from `{ percent, base }`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PercentageOfBase {
+    /// The percentage of `base` that is available, e.g. 50 for half of it
+    percent: f32,
+    /// The number of pixels the percentage is of
+    base: f32,
+}
+
+impl PercentageOfBase {
+    /// Returns the number of pixels available, resolved as taffy resolves a `percent(percent / 100)` length
+    /// against `base`
+    fn resolve(self) -> f32 {
+        LengthPercentage::percent(self.percent / 100.0).resolve_or_zero(Some(self.base), |_, _| 0.0)
+    }
+}
+
+impl AvailableAxis {
+    /// Returns the largest size of the root in this axis, if it is bounded
+    fn limit(self) -> Option<f32> {
+        match self {
+            AvailableAxis::Bounded(bounded) => Some(bounded.limit),
+            _ => None,
+        }
+    }
+}
+
+impl From<AvailableAxis> for AvailableSpace {
+    fn from(axis: AvailableAxis) -> Self {
+        match axis {
+            AvailableAxis::Definite(pixels) => AvailableSpace::Definite(pixels),
+            AvailableAxis::Percentage(percentage) => AvailableSpace::Definite(percentage.resolve()),
+            AvailableAxis::Keyword(AvailableKeyword::MinContent) => AvailableSpace::MinContent,
+            AvailableAxis::Keyword(AvailableKeyword::MaxContent) | AvailableAxis::Bounded(_) => {
+                AvailableSpace::MaxContent
+            }
+        }
+    }
+}
+
+/// The space available to a layout, decoded from a `LayoutAvailableSpace` object
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct LayoutAvailableSpace {
+    /// The space available horizontally
+    width: AvailableAxis,
+    /// The space available vertically
+    height: AvailableAxis,
+}
+
+/// The space available to a layout, along with the largest size of the root in each axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BoundedAvailableSpace {
+    /// The space to lay the root out within
+    pub(crate) space: Size<AvailableSpace>,
+    /// The largest size of the root in each bounded max-content axis
+    pub(crate) limits: Size<Option<f32>>,
+}
+
+impl From<LayoutAvailableSpace> for BoundedAvailableSpace {
+    fn from(available: LayoutAvailableSpace) -> Self {
+        BoundedAvailableSpace {
+            space: Size { width: available.width.into(), height: available.height.into() },
+            limits: Size { width: available.width.limit(), height: available.height.limit() },
+        }
+    }
+}
+
+impl BoundedAvailableSpace {
+    /// Returns the space to lay the root out within again if `size`, its size when laid out within `self.space`,
+    /// exceeds a limit: the same space, but definite and equal to the limit in the axes that exceed it
+    pub(crate) fn relayout_space(&self, size: Size<f32>) -> Option<Size<AvailableSpace>> {
+        let exceeds = |size: f32, limit: Option<f32>| limit.filter(|&limit| size > limit);
+        let (width, height) = (exceeds(size.width, self.limits.width), exceeds(size.height, self.limits.height));
+        if width.is_none() && height.is_none() {
+            return None;
+        }
+        Some(Size {
+            width: width.map_or(self.space.width, AvailableSpace::Definite),
+            height: height.map_or(self.space.height, AvailableSpace::Definite),
+        })
+    }
+}
+
+/// Checks that the pixels of a definite, bounded or percentage axis of available space are usable
+fn validate(available: LayoutAvailableSpace) -> Result<BoundedAvailableSpace, TaffyError> {
+    for (name, axis) in [("width", available.width), ("height", available.height)] {
+        let fields = match axis {
+            AvailableAxis::Definite(pixels) => vec![(name.to_string(), pixels)],
+            AvailableAxis::Bounded(bounded) if !bounded.max_content => {
+                return Err(TaffyError::InvalidArgument(format!(
+                    "available.{name}.maxContent must be true when a limit is given"
+                )))
+            }
+            AvailableAxis::Bounded(bounded) => vec![(format!("{name}.limit"), bounded.limit)],
+            AvailableAxis::Percentage(percentage) => {
+                if !percentage.percent.is_finite() || percentage.percent < 0.0 {
+                    return Err(TaffyError::InvalidArgument(format!(
+                        "available.{name}.percent must be a non-negative percentage, got {}",
+                        percentage.percent
+                    )));
+                }
+                vec![(format!("{name}.base"), percentage.base), (name.to_string(), percentage.resolve())]
+            }
+            AvailableAxis::Keyword(_) => continue,
+        };
+        for (field, pixels) in fields {
+            if !pixels.is_finite() || pixels < 0.0 {
+                return Err(TaffyError::InvalidArgument(format!(
+                    "available.{field} must be a non-negative number of pixels, got {pixels}"
+                )));
+            }
+        }
+    }
+    Ok(available.into())
+}
+
+/// Decodes a `LayoutAvailableSpace` object passed from JS
+pub(crate) fn decode_available_space(available: JsValue) -> Result<BoundedAvailableSpace, TaffyError> {
+    let available = serde_wasm_bindgen::from_value(available)
+        .map_err(|e| TaffyError::InvalidArgument(format!("available: {e}")))?;
+    validate(available)
+}
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `root` within `available`, laying it out again within the limits it exceeds
+    fn compute_bounded_subtree(&self, root: u32, available: BoundedAvailableSpace) -> Result<(), TaffyError> {
+        self.compute_subtree(root, available.space)?;
+        let size = self.tree()?.unrounded_layout(node_from_js_id(root)).size;
+        match available.relayout_space(size) {
+            Some(space) => self.compute_subtree(root, space),
+            None => Ok(()),
+        }
+    }
+
+    /// Lays out the subtree rooted at `root` within `available`, returning the final size of `root`
+    fn compute_bounded_root_size(&self, root: u32, available: BoundedAvailableSpace) -> Result<Size<f32>, TaffyError> {
+        self.compute_bounded_subtree(root, available)?;
+        Ok(self.tree()?.layout(node_from_js_id(root))?.size)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `root` within `available`, a `LayoutAvailableSpace`
+    ///
+    /// Each axis of `available` is either a number of pixels or one of the keywords `"min-content"` and
+    /// `"max-content"`, chosen independently, e.g. `{ width: 300, height: "max-content" }` lays out `root` in a
+    /// 300px wide column of unlimited height. This matches the `Size<AvailableSpace>` taken by the Rust API, and:
+    ///
+    /// - `compute_layout(root, width, height)` is `compute_layout_v2(root, { width, height })`
+    /// - `"max-content"` lays out the content without wrapping or shrinking it, as if there were unlimited space
+    /// - `"min-content"` lays out the content as narrowly (or as short) as it can be, e.g. wrapping text at every
+    ///   opportunity
+    /// - `{ maxContent: true, limit: 1200 }` is `"max-content"`, except that `root` is at most 1200px in that axis,
+    ///   like a `max-width` or `max-height` on the root. A root whose max-content size exceeds the limit is laid out
+    ///   again within `limit` pixels of definite space, so its content wraps or shrinks to fit as far as it can; one
+    ///   that fits is laid out once and unaffected.
+    /// - `{ percent: 50, base: 800 }` is 50% of 800px, i.e. `400`, e.g. for a subtree embedded in a parent whose
+    ///   size is known as a percentage of another. The percentage is resolved as taffy resolves percentage lengths
+    ///   in styles, so it comes out exactly as the same percentage would inside the tree.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available` is not a valid `LayoutAvailableSpace`, or if an axis is a
+    /// negative or non-finite number of pixels.
+    #[wasm_bindgen]
+    pub fn compute_layout_v2(&self, root: u32, available: JsValue) -> Result<(), TaffyError> {
+        self.compute_bounded_subtree(root, decode_available_space(available)?)
+    }
+
+    /// Computes the layout of the tree rooted at `root` within `available` like `compute_layout_v2`, and returns
+    /// the final size of `root` as `{ width, height }`
+    ///
+    /// This is the one-call form of laying out an auto-sized container and reading back how big it turned out,
+    /// e.g. `compute_root_size(root, { width: "max-content", height: "max-content" })` for the natural size of its
+    /// content. The size is rounded like the one returned by `layout_width` and `layout_height`, and the layouts
+    /// of every node in the tree are updated as by `compute_layout_v2`.
+    ///
+    /// Throws the same errors as `compute_layout_v2`.
+    #[wasm_bindgen]
+    pub fn compute_root_size(&self, root: u32, available: JsValue) -> Result<JsValue, TaffyError> {
+        let size = self.compute_bounded_root_size(root, decode_available_space(available)?)?;
+        Ok(serde_wasm_bindgen::to_value(&size).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::style::FlexWrap;
+
+    /// Decodes a `LayoutAvailableSpace` through serde_json, since serde_wasm_bindgen needs a JS host
+    fn decode_bounded(json: serde_json::Value) -> Result<BoundedAvailableSpace, TaffyError> {
+        let available = serde_json::from_value(json).map_err(|e| TaffyError::InvalidArgument(e.to_string()))?;
+        validate(available)
+    }
+
+    /// Decodes a `LayoutAvailableSpace` without limits
+    fn decode(json: serde_json::Value) -> Result<Size<AvailableSpace>, TaffyError> {
+        decode_bounded(json).map(|available| available.space)
+    }
+
+    /// The JS form of each per-axis mode, along with the `AvailableSpace` it decodes to
+    fn axis_modes() -> [(serde_json::Value, AvailableSpace); 3] {
+        [
+            (serde_json::json!(100), AvailableSpace::Definite(100.0)),
+            (serde_json::json!("min-content"), AvailableSpace::MinContent),
+            (serde_json::json!("max-content"), AvailableSpace::MaxContent),
+        ]
+    }
+
+    #[test]
+    fn each_axis_decodes_independently() {
+        for (width_json, width) in axis_modes() {
+            for (height_json, height) in axis_modes() {
+                let json = serde_json::json!({ "width": width_json, "height": height_json });
+                assert_eq!(decode(json), Ok(Size { width, height }));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_available_space_is_rejected() {
+        let invalid = [
+            serde_json::json!({ "width": 100 }),
+            serde_json::json!({ "width": "fit-content", "height": 100 }),
+            serde_json::json!({ "width": "MaxContent", "height": 100 }),
+            serde_json::json!({ "width": { "Definite": 100 }, "height": 100 }),
+            serde_json::json!({ "width": -1, "height": 100 }),
+            serde_json::json!({ "width": { "maxContent": false, "limit": 100 }, "height": 100 }),
+            serde_json::json!({ "width": { "maxContent": true, "limit": -1 }, "height": 100 }),
+            serde_json::json!({ "width": { "maxContent": true }, "height": 100 }),
+            serde_json::json!({ "width": { "percent": -50, "base": 800 }, "height": 100 }),
+            serde_json::json!({ "width": { "percent": 50, "base": -800 }, "height": 100 }),
+            serde_json::json!({ "width": { "percent": 50 }, "height": 100 }),
+            serde_json::json!({ "width": { "percent": 50, "base": 800, "limit": 100 }, "height": 100 }),
+        ];
+        for json in invalid {
+            assert!(matches!(decode(json.clone()), Err(TaffyError::InvalidArgument(_))), "{json} should be rejected");
+        }
+    }
+
+    #[test]
+    fn layout_with_every_combination_of_axis_modes() {
+        // A wrapping row of two 60x10 items, which fit side by side in max-content space but not in 100px
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(60.0), height: length(10.0) }, ..Default::default() };
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item).unwrap()];
+            let row = Style { flex_wrap: FlexWrap::Wrap, ..Default::default() };
+            js_node_id(inner.new_with_children(row, &items).unwrap())
+        };
+
+        for (width_json, width) in axis_modes() {
+            for (height_json, _) in axis_modes() {
+                let available = decode(serde_json::json!({ "width": width_json, "height": height_json })).unwrap();
+                tree.compute_subtree(root, available).unwrap();
+                let expected = match width {
+                    AvailableSpace::Definite(_) => (100.0, 20.0),
+                    AvailableSpace::MinContent => (60.0, 20.0),
+                    AvailableSpace::MaxContent => (120.0, 10.0),
+                };
+                assert_eq!(
+                    (tree.layout_width(root).unwrap(), tree.layout_height(root).unwrap()),
+                    expected,
+                    "{available:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn percentage_lays_out_as_the_equivalent_definite_space() {
+        // A wrapping row of 30% wide items, which resolve against the available width
+        let tree = TaffyTree::new();
+        let (root, items) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: percent(0.3), height: length(10.0) }, ..Default::default() };
+            let items: Vec<_> = (0..4).map(|_| inner.new_leaf(item.clone()).unwrap()).collect();
+            let row = Style { flex_wrap: FlexWrap::Wrap, size: percent(1.0), ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items)
+        };
+        let layouts = |json| {
+            tree.compute_bounded_subtree(root, decode_bounded(json).unwrap()).unwrap();
+            let inner = tree.inner.borrow();
+            items.iter().map(|&item| *inner.unrounded_layout(item)).collect::<Vec<_>>()
+        };
+
+        let percentage =
+            serde_json::json!({ "width": { "percent": 33.3, "base": 1001 }, "height": { "percent": 50, "base": 800 } });
+        let available = decode(percentage.clone()).unwrap();
+        let definite = Size { width: 1001.0 * (33.3 / 100.0), height: 400.0 };
+        assert_eq!(available, definite.map(AvailableSpace::Definite));
+        assert_eq!(layouts(percentage), layouts(serde_json::json!({ "width": definite.width, "height": 400 })));
+    }
+
+    #[test]
+    fn max_content_limit_bounds_the_root() {
+        // A wrapping row of ten 200x10 items, which is 2000px wide at max-content
+        let tree = TaffyTree::new();
+        let (root, item) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(200.0), height: length(10.0) }, ..Default::default() };
+            let items: Vec<_> = (0..10).map(|_| inner.new_leaf(item.clone()).unwrap()).collect();
+            let row = Style { flex_wrap: FlexWrap::Wrap, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), js_node_id(items[9]))
+        };
+        let layout = |limit| {
+            let json = serde_json::json!({ "width": { "maxContent": true, "limit": limit }, "height": "max-content" });
+            let available = decode_bounded(json).unwrap();
+            assert_eq!(available.space, Size::MAX_CONTENT);
+            tree.compute_bounded_subtree(root, available).unwrap();
+            (tree.layout_width(root).unwrap(), tree.layout_height(root).unwrap())
+        };
+
+        // Content wider than the limit wraps to fit within it...
+        assert_eq!(layout(1200.0), (1200.0, 20.0));
+        assert_eq!((tree.layout_left(item), tree.layout_top(item)), (Ok(600.0), Ok(10.0)));
+        // ...and content that fits is laid out at its max-content size
+        assert_eq!(layout(5000.0), (2000.0, 10.0));
+        assert_eq!((tree.layout_left(item), tree.layout_top(item)), (Ok(1800.0), Ok(0.0)));
+    }
+
+    #[test]
+    fn root_size_of_content_sized_column() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let item =
+                |width| Style { size: Size { width: length(width), height: length(10.0) }, ..Default::default() };
+            let items = [inner.new_leaf(item(40.0)).unwrap(), inner.new_leaf(item(90.0)).unwrap()];
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            js_node_id(inner.new_with_children(column, &items).unwrap())
+        };
+
+        let available = decode_bounded(serde_json::json!({ "width": "max-content", "height": "max-content" })).unwrap();
+        // The column is as wide as its widest item, and as tall as its items stacked
+        assert_eq!(tree.compute_bounded_root_size(root, available), Ok(Size { width: 90.0, height: 20.0 }));
+        assert_eq!(tree.compute_bounded_root_size(100, available), Err(TaffyError::InvalidNode(100)));
+    }
+}
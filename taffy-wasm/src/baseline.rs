@@ -0,0 +1,212 @@
+//! Reading the first baseline of a node from its most recent layout, e.g. to align it within an outer layout
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::{prelude::*, CacheTree, DetailedLayoutInfo, RunMode};
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns the first baseline of a node from the layout output cached by its most recent layout, if any
+    fn node_first_baseline(&self, node_id: u32) -> Result<Option<f32>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        // The final layout of a node is cached with its output, and is found by looking it up at its own size
+        let size = tree.unrounded_layout(node).size;
+        let output = tree.cache_get(node, size.map(Some), Size::MAX_CONTENT, RunMode::PerformLayout);
+        Ok(output.and_then(|output| output.first_baselines.y))
+    }
+
+    /// Returns whether the baseline of a node set the first baseline of its parent in the parent's last layout
+    fn baseline_participant(&self, node_id: u32) -> Result<bool, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let Some(parent) = tree.parent(node) else {
+            return Ok(false);
+        };
+        // Only in-flow children are items, kept in order by both algorithms
+        let mut items = Vec::new();
+        for child in tree.children(parent)? {
+            let style = tree.style(child)?;
+            if style.display != Display::None && style.position != Position::Absolute {
+                items.push(child);
+            }
+        }
+        let parent_style = tree.style(parent)?;
+        let is_baseline_aligned = |item: &NodeId| {
+            let align_self = tree.style(*item).map(|style| style.align_self).unwrap_or_default();
+            align_self.or(parent_style.align_items) == Some(AlignItems::Baseline)
+        };
+        // As in CSS, the first baseline-aligned item of the first line or row sets the baseline, or else its first
+        // item, while a flex column always takes the baseline of its first item
+        let first_line: Vec<NodeId> = match tree.detailed_layout_info(parent) {
+            DetailedLayoutInfo::Flexbox(info) => {
+                let is_column =
+                    matches!(parent_style.flex_direction, FlexDirection::Column | FlexDirection::ColumnReverse);
+                let line_length = info.lines.first().map_or(0, |line| line.flex_bases.len());
+                items.into_iter().take(if is_column { line_length.min(1) } else { line_length }).collect()
+            }
+            #[cfg(feature = "grid")]
+            DetailedLayoutInfo::Grid(info) => {
+                let first_row = info.items.iter().map(|item| item.row_start).min();
+                let rows = info.items.iter().map(|item| Some(item.row_start));
+                items.into_iter().zip(rows).filter(|(_, row)| *row == first_row).map(|(item, _)| item).collect()
+            }
+            _ => return Ok(false),
+        };
+        let participant = first_line.iter().find(|item| is_baseline_aligned(item)).or(first_line.first());
+        Ok(participant == Some(&node))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the first baseline of a node from its most recent layout, as an offset from the top of its border box,
+    /// or `undefined` if it has none
+    ///
+    /// A leaf's baseline is the `firstBaseline` returned by its measure function, offset by its padding and border.
+    /// A container's baseline is derived from its children as in CSS: for a flex row, from its first item aligned
+    /// with `alignItems: "Baseline"` (or else its first item) in the first line, which is where all the
+    /// baseline-aligned items of the line line up, and for a grid, likewise from its first row. Nodes without one, such as a leaf measured without a
+    /// `firstBaseline`, inherit none. Use the baseline to align a laid out component within an outer layout.
+    ///
+    /// The value is unrounded, and is `undefined` for a node that hasn't been laid out since it was last marked
+    /// dirty.
+    #[wasm_bindgen]
+    pub fn first_baseline(&self, node_id: u32) -> Result<Option<f32>, TaffyError> {
+        self.node_first_baseline(node_id)
+    }
+
+    /// Indicates whether the baseline of a node was used to set the first baseline of its parent in the parent's
+    /// last layout
+    ///
+    /// A flex row or a grid takes its baseline from the first item aligned with `alignItems: "Baseline"` (or
+    /// `alignSelf`) in its first line or row, or from the first item of that line or row if none is, and a flex
+    /// column from its first item, so exactly one child of a laid out flex or grid container with items
+    /// participates. Children that are absolutely positioned or have `display: "None"` are not items and never
+    /// participate, nor do the items of later lines or rows, or baseline-aligned items after the first. This is
+    /// `false` for roots and for the children of nodes that are not flex or grid containers or have not been laid
+    /// out yet.
+    #[wasm_bindgen]
+    pub fn is_baseline_participant(&self, node_id: u32) -> Result<bool, TaffyError> {
+        self.baseline_participant(node_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_stats::now_ms;
+    use crate::measure_budget::MeasureBudget;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use taffy::Point;
+
+    #[test]
+    fn baseline_aligned_row_has_the_baseline_of_its_line() {
+        // Three text leaves with different heights and baselines, aligned by their baselines in a row
+        let texts = [(20.0, 15.0), (40.0, 30.0), (10.0, 8.0)];
+        let tree = TaffyTree::new();
+        let (row, leaves) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaves = texts.map(|_| inner.new_leaf(Style::default()).unwrap());
+            let row = Style { align_items: Some(AlignItems::Baseline), padding: length(5.0), ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &leaves).unwrap()), leaves)
+        };
+        let measure = |_, _, node, _: Option<&mut _>| {
+            let (height, baseline) = texts[leaves.iter().position(|&leaf| leaf == node).unwrap()];
+            (Size { width: 30.0, height }, Point { x: None, y: Some(baseline) })
+        };
+        tree.compute_layout_with_budget(row, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, measure).unwrap();
+
+        let leaf_baselines = leaves.map(|leaf| tree.first_baseline(js_node_id(leaf)).unwrap());
+        assert_eq!(leaf_baselines, texts.map(|(_, baseline)| Some(baseline)));
+        // The tallest ascent sets the line's baseline, below the row's 5px top padding
+        assert_eq!(tree.first_baseline(row), Ok(Some(5.0 + 30.0)));
+        let leaf_tops = leaves.map(|leaf| tree.layout_top(js_node_id(leaf)).unwrap());
+        assert_eq!(leaf_tops, [20.0, 5.0, 27.0]);
+
+        tree.mark_dirty(row).unwrap();
+        assert_eq!(tree.first_baseline(row), Ok(None));
+    }
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn baseline_aligned_grid_row_shares_a_baseline() {
+        // Three text leaves with different heights and baselines, each in its own column of a single grid row
+        let texts = [(20.0, 15.0), (40.0, 30.0), (10.0, 8.0)];
+        let tree = TaffyTree::new();
+        let (grid, leaves) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaves = texts.map(|_| inner.new_leaf(Style::default()).unwrap());
+            let grid = Style {
+                display: Display::Grid,
+                grid_template_columns: vec![length(30.0); 3],
+                align_items: Some(AlignItems::Baseline),
+                padding: length(5.0),
+                ..Default::default()
+            };
+            (js_node_id(inner.new_with_children(grid, &leaves).unwrap()), leaves)
+        };
+        let measure = |_, _, node, _: Option<&mut _>| {
+            let (height, baseline) = texts[leaves.iter().position(|&leaf| leaf == node).unwrap()];
+            (Size { width: 30.0, height }, Point { x: None, y: Some(baseline) })
+        };
+        tree.compute_layout_with_budget(grid, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, measure).unwrap();
+
+        // Each leaf is shifted down so that its baseline lines up with the deepest one, 30px into the row
+        let leaf_tops = leaves.map(|leaf| tree.layout_top(js_node_id(leaf)).unwrap());
+        assert_eq!(leaf_tops, [20.0, 5.0, 27.0]);
+        let shared_baselines = leaves.map(|leaf| {
+            let leaf = js_node_id(leaf);
+            tree.layout_top(leaf).unwrap() + tree.first_baseline(leaf).unwrap().unwrap()
+        });
+        assert_eq!(shared_baselines, [35.0; 3]);
+        assert_eq!(tree.first_baseline(grid), Ok(Some(35.0)));
+    }
+
+    #[test]
+    fn only_the_first_baseline_aligned_item_of_the_first_line_participates() {
+        let tree = TaffyTree::new();
+        let (row, [absolute, start_aligned, first_aligned, second_aligned, wrapped]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = |align_self| Style {
+                size: Size { width: length(40.0), height: length(10.0) },
+                align_self: Some(align_self),
+                ..Default::default()
+            };
+            let absolute = Style { position: Position::Absolute, ..item(AlignItems::Baseline) };
+            let items = [
+                inner.new_leaf(absolute).unwrap(),
+                inner.new_leaf(item(AlignItems::Start)).unwrap(),
+                inner.new_leaf(item(AlignItems::Baseline)).unwrap(),
+                inner.new_leaf(item(AlignItems::Baseline)).unwrap(),
+                inner.new_leaf(item(AlignItems::Baseline)).unwrap(),
+            ];
+            // Three 40px items fit in the first line, and the last item wraps onto a second one
+            let row = Style {
+                flex_wrap: FlexWrap::Wrap,
+                size: Size { width: length(130.0), height: auto() },
+                ..Default::default()
+            };
+            let row = inner.new_with_children(row, &items).unwrap();
+            inner.compute_layout(row, Size::MAX_CONTENT).unwrap();
+            (js_node_id(row), items.map(js_node_id))
+        };
+
+        let participates = |node| tree.is_baseline_participant(node).unwrap();
+        assert!(participates(first_aligned));
+        assert!(!participates(absolute));
+        assert!(!participates(start_aligned));
+        assert!(!participates(second_aligned));
+        assert!(!participates(wrapped));
+        assert!(!participates(row));
+
+        // Without a baseline-aligned item, the first item of the first line sets the baseline
+        let mut inner = tree.inner.borrow_mut();
+        for item in [first_aligned, second_aligned] {
+            let style = Style { align_self: None, ..inner.style(node_from_js_id(item)).unwrap().clone() };
+            inner.set_style(node_from_js_id(item), style).unwrap();
+        }
+        inner.compute_layout(node_from_js_id(row), Size::MAX_CONTENT).unwrap();
+        drop(inner);
+        assert!(participates(start_aligned));
+        assert!(!participates(first_aligned));
+    }
+}
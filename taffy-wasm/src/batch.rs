@@ -0,0 +1,146 @@
+//! Batching bulk edits of a tree, so that nothing is laid out while it is only partly built
+use crate::{resolve_node, TaffyError, TaffyTree};
+use std::collections::BTreeSet;
+use wasm_bindgen::prelude::*;
+
+/// The state of the batch opened by `begin_batch`
+#[derive(Debug, Default)]
+pub(crate) struct Batch {
+    /// The number of `begin_batch` calls not yet matched by an `end_batch`
+    depth: u32,
+    /// The ids of the nodes passed to `mark_dirty` during the batch
+    dirty: BTreeSet<u32>,
+}
+
+impl TaffyTree {
+    /// Returns whether a batch is open
+    pub(crate) fn in_batch(&self) -> bool {
+        self.batch.borrow().is_some()
+    }
+
+    /// Fails with a `BATCH_IN_PROGRESS` error if a batch is open, for the methods that lay out the tree
+    pub(crate) fn check_no_batch(&self) -> Result<(), TaffyError> {
+        match self.in_batch() {
+            true => Err(TaffyError::BatchInProgress),
+            false => Ok(()),
+        }
+    }
+
+    /// Records that `mark_dirty` was called on a node during the open batch, returning `false` if no batch is open
+    pub(crate) fn defer_mark_dirty(&self, node_id: u32) -> bool {
+        match self.batch.borrow_mut().as_mut() {
+            Some(batch) => {
+                batch.dirty.insert(node_id);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Opens a batch of edits, during which the tree can't be laid out, e.g. to build or restructure a subtree in
+    /// many calls without a layout running on it half-way through
+    ///
+    /// Until the batch is closed with `end_batch`:
+    ///
+    /// - the methods that lay out the tree, such as `compute_layout`, `flush_relayouts` or `preview_insert`, throw
+    ///   a `BATCH_IN_PROGRESS` error without laying anything out.
+    /// - reading a layout, e.g. with `layout_width`, returns the layout from the last pass before the batch, which
+    ///   may be stale: `set_auto_compute` doesn't lay the tree out on reads during a batch.
+    /// - `mark_dirty` only records the node, and every node it was called on is marked dirty at once by
+    ///   `end_batch`, so `dirty` keeps returning what it did before the call. Other edits, such as
+    ///   `update_style` or `add_child`, mark their nodes dirty as they are made; marking stops at the first ancestor
+    ///   that is already dirty, so no ancestor is invalidated more than once per batch however many edits are made
+    ///   beneath it.
+    ///
+    /// Batches nest: calling `begin_batch` during a batch only closes with the matching `end_batch`, so that helpers
+    /// that batch their own edits can be called inside a larger batch.
+    #[wasm_bindgen]
+    pub fn begin_batch(&self) {
+        self.batch.borrow_mut().get_or_insert_with(Batch::default).depth += 1;
+    }
+
+    /// Closes the batch opened by the matching `begin_batch`, marking dirty every node passed to `mark_dirty` during
+    /// it if it is the outermost batch, after which the tree can be laid out again
+    ///
+    /// Nodes removed during the batch are skipped. Throws an `INVALID_ARGUMENT` error if no batch is open, and a
+    /// `BORROW_CONFLICT` error, leaving the batch open, if the tree is in use, e.g. when called from a measure
+    /// function.
+    #[wasm_bindgen]
+    pub fn end_batch(&self) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let mut batch = self.batch.borrow_mut();
+        let Some(open) = batch.as_mut() else {
+            return Err(TaffyError::InvalidArgument(String::from("end_batch was called without a batch open")));
+        };
+        open.depth -= 1;
+        if open.depth > 0 {
+            return Ok(());
+        }
+        for node_id in batch.take().map(|batch| batch.dirty).unwrap_or_default() {
+            if let Ok(node) = resolve_node(&tree, node_id) {
+                tree.mark_dirty(node)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Indicates whether a batch opened with `begin_batch` is still open
+    #[wasm_bindgen]
+    pub fn is_batching(&self) -> bool {
+        self.in_batch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_stats::ComputeStats;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use taffy::prelude::{length, AvailableSpace, Size, Style};
+
+    #[test]
+    fn edits_in_a_batch_are_laid_out_once_at_the_end() {
+        let tree = TaffyTree::new();
+        let (root, items) = {
+            let mut inner = tree.inner.borrow_mut();
+            let items: Vec<_> = (0..20).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            let root = inner.new_with_children(Style::default(), &items).unwrap();
+            (js_node_id(root), items.into_iter().map(js_node_id).collect::<Vec<_>>())
+        };
+        let available_space = Size { width: AvailableSpace::Definite(500.0), height: AvailableSpace::Definite(50.0) };
+        tree.compute_subtree(root, available_space).unwrap();
+        tree.set_auto_compute(true);
+        tree.last_compute_stats.set(ComputeStats::default());
+
+        tree.begin_batch();
+        tree.begin_batch();
+        for &item in &items {
+            tree.mark_dirty(item).unwrap();
+            assert_eq!((tree.dirty(item), tree.dirty(root)), (Ok(false), Ok(false)));
+        }
+        tree.end_batch().unwrap();
+        // The inner batch closing doesn't end the outer one
+        assert!(tree.is_batching());
+        for (index, &item) in items.iter().enumerate() {
+            let style = Style { size: length(index as f32), ..Default::default() };
+            tree.inner.borrow_mut().set_style(node_from_js_id(item), style).unwrap();
+            // Reads return the layout from before the batch rather than laying out the half-edited tree
+            assert_eq!(tree.layout_width(item), Ok(0.0));
+        }
+        assert_eq!(tree.compute_subtree(root, available_space), Err(TaffyError::BatchInProgress));
+        assert_eq!(tree.last_compute_stats.get(), ComputeStats::default());
+
+        tree.end_batch().unwrap();
+        assert!(!tree.is_batching());
+        assert!(items.iter().all(|&item| tree.dirty(item) == Ok(true)));
+        // The first read lays the tree out, once, with every edit
+        assert_eq!(tree.layout_width(items[19]), Ok(19.0));
+        assert_eq!(tree.last_compute_stats.get().nodes_laid_out, 21);
+        assert_eq!(tree.layout_width(root), Ok((0..20).sum::<u32>() as f32));
+
+        assert!(matches!(tree.end_batch(), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,83 @@
+//! Information about how this build of taffy was compiled, for attaching to bug reports
+use serde::Serialize;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+/// Returns whether each of taffy's optional Cargo features is enabled, along with `rounding`
+///
+/// Rounding is not a Cargo feature: it is a setting of the core tree, which is enabled by default.
+fn feature_flags() -> BTreeMap<&'static str, bool> {
+    let mut features: BTreeMap<_, _> = taffy::FEATURES.iter().copied().collect();
+    features.insert("rounding", true);
+    features.insert("trace", cfg!(feature = "trace"));
+    features
+}
+
+/// Returns the version of the taffy crate these bindings were built with, e.g. `"0.8.1"`
+#[wasm_bindgen]
+pub fn taffy_version() -> String {
+    taffy::VERSION.to_string()
+}
+
+/// Returns an object mapping the name of each of taffy's Cargo features to whether it was enabled in this build,
+/// e.g. `{ flexbox: true, grid: true, block_layout: false, debug: false, rounding: true, … }`
+///
+/// `rounding` is always `true`, since layouts returned by these bindings are rounded to whole pixels unless changed
+/// with `set_rounding_mode`.
+#[wasm_bindgen]
+pub fn enabled_features() -> JsValue {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    feature_flags().serialize(&serializer).unwrap_or(JsValue::NULL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_is_the_core_crate_version() {
+        assert_eq!(taffy_version(), taffy::VERSION);
+        assert_ne!(taffy_version(), env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn features_include_the_algorithms_the_bindings_require() {
+        let features = feature_flags();
+        assert_eq!(features.get("flexbox"), Some(&true));
+        assert_eq!(features.get("grid"), Some(&cfg!(feature = "grid")));
+        assert_eq!(features.get("block_layout"), Some(&cfg!(feature = "block")));
+        assert_eq!(features.get("serde"), Some(&true));
+        assert_eq!(features.get("rounding"), Some(&true));
+        assert!(features.contains_key("debug"));
+    }
+
+    /// Run with `cargo test -p taffy-wasm --no-default-features`
+    #[cfg(not(feature = "grid"))]
+    #[test]
+    fn flexbox_only_build_lays_out_a_flex_tree() {
+        use crate::node_ids::js_node_id;
+        use crate::{JsStyle, TaffyTree};
+        use taffy::prelude::*;
+
+        let tree = TaffyTree::new();
+        let (row, [fixed, growing], column) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fixed = inner.new_leaf(Style { size: length(40.0), ..Default::default() }).unwrap();
+            let growing = inner.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            let column = inner.new_with_children(column, &[growing]).unwrap();
+            let row = Style { size: Size { width: length(200.0), height: length(50.0) }, ..Default::default() };
+            let row = inner.new_with_children(row, &[fixed, column]).unwrap();
+            (js_node_id(row), [fixed, growing].map(js_node_id), js_node_id(column))
+        };
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!((tree.layout_left(column), tree.layout_width(column)), (Ok(40.0), Ok(0.0)));
+        assert_eq!((tree.layout_height(column), tree.layout_height(growing)), (Ok(50.0), Ok(50.0)));
+        assert_eq!((tree.layout_width(fixed), tree.layout_height(fixed)), (Ok(40.0), Ok(40.0)));
+
+        // Grid is compiled out, so a grid container can't be described at all
+        assert!(serde_json::from_value::<JsStyle>(serde_json::json!({ "display": "Grid" })).is_err());
+        assert_eq!(feature_flags().get("grid"), Some(&false));
+    }
+}
@@ -0,0 +1,180 @@
+//! Building a tree incrementally, e.g. from a description that arrives from the network in chunks
+use crate::length_scale::map_lengths;
+use crate::node_ids::{create_nodes, js_node_id, new_node_id};
+use crate::style_defaults::decode_style_with_box_sizing;
+use crate::{TaffyError, TaffyTree};
+use taffy::prelude::*;
+use taffy::style::{BoxSizing, Style};
+use wasm_bindgen::prelude::*;
+
+/// A tree being built node by node, created by `TaffyTree.begin_build`
+///
+/// Nodes added to the session are buffered in the module, already decoded, and are only inserted into the tree
+/// by `finish`. A session is a separate object from the tree it builds into, since a JS object can't hold a
+/// borrow of another: the tree is neither borrowed nor changed until `finish` is called, and can be used as
+/// normal in the meantime. `finish` then creates every node in a single borrow of the tree, and consumes the
+/// session, so calling any method of the session afterwards throws. A session that is never finished leaves the
+/// tree unchanged; call `free()` to release it.
+#[wasm_bindgen]
+pub struct BuildSession {
+    /// The box sizing of styles that don't specify one, from the tree when the session began
+    default_box_sizing: BoxSizing,
+    /// The factor the pixel lengths of styles are multiplied by, from the tree when the session began
+    length_scale: f32,
+    /// The style of each node, indexed by session id
+    styles: Vec<Style>,
+    /// The session ids of the children of each node, in the order they were added
+    children: Vec<Vec<u32>>,
+    /// The session id of the node that was added without a parent
+    root: Option<u32>,
+}
+
+impl BuildSession {
+    /// Adds a node with `style` as the last child of the node with session id `parent`, returning its session id
+    fn push_node(&mut self, style: Style, parent: Option<u32>) -> Result<u32, TaffyError> {
+        let id = self.styles.len() as u32;
+        match parent {
+            Some(parent) => {
+                let siblings = self.children.get_mut(parent as usize).ok_or_else(|| {
+                    TaffyError::InvalidArgument(format!("parentId {parent} is not a node added to this session"))
+                })?;
+                siblings.push(id);
+            }
+            None if self.root.is_some() => {
+                return Err(TaffyError::InvalidArgument(
+                    "the session already has a root: every node but the first must have a parentId".to_string(),
+                ))
+            }
+            None => self.root = Some(id),
+        }
+        self.styles.push(style);
+        self.children.push(Vec::new());
+        Ok(id)
+    }
+
+    /// Creates the nodes of the session in `tree`, returning the id of the root
+    fn build(self, tree: &TaffyTree) -> Result<u32, TaffyError> {
+        let root = self.root.ok_or_else(|| TaffyError::InvalidArgument("no nodes were added to the session".into()))?;
+        create_nodes(&mut *tree.tree_mut()?, |tree, nodes| {
+            for style in self.styles {
+                let node = tree.new_leaf(style)?;
+                new_node_id(tree, node)?;
+                nodes.push(node);
+            }
+            for (&node, children) in nodes.iter().zip(&self.children) {
+                if !children.is_empty() {
+                    let children: Vec<NodeId> = children.iter().map(|&child| nodes[child as usize]).collect();
+                    tree.set_children(node, &children)?;
+                }
+            }
+            Ok(js_node_id(nodes[root as usize]))
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl BuildSession {
+    /// Adds a node with the given style, returning its id within the session
+    ///
+    /// The first node is the root, and is added without a `parentId`. Every later node must be given the session
+    /// id of its parent, which must already have been added, and becomes its parent's last child. Session ids are
+    /// numbered from 0 in the order nodes are added, and are not node ids of the tree.
+    ///
+    /// Throws a `STYLE_DECODE` error if the object is not a valid style, or an `INVALID_ARGUMENT` error if
+    /// `parentId` is not a node of the session or is missing for a node other than the first. A node that fails
+    /// to be added is not added, and the session can still be used.
+    #[wasm_bindgen]
+    pub fn add_node(&mut self, style: JsValue, parent_id: Option<u32>) -> Result<u32, TaffyError> {
+        let style = decode_style_with_box_sizing(style, self.default_box_sizing)?;
+        let style = map_lengths(style, |px| px * self.length_scale);
+        self.push_node(style, parent_id)
+    }
+
+    /// The number of nodes added to the session so far
+    #[wasm_bindgen(getter)]
+    pub fn node_count(&self) -> usize {
+        self.styles.len()
+    }
+
+    /// Creates every node of the session in `tree`, returning the id of the root in the tree
+    ///
+    /// The nodes are created in the order they were added, so their tree ids can be read back from the root with
+    /// `children`. `tree` should be the tree the session was begun on: a node whose style omitted `box_sizing` has
+    /// the default box sizing that tree had when `begin_build` was called, and lengths are scaled by the length
+    /// scale it had then. The session is consumed.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if no nodes were added, a `BORROW_CONFLICT` error if the tree is in use,
+    /// e.g. when called from a measure function, and a `TOO_MANY_NODES` error, without creating any node, if the
+    /// tree can't hold them all.
+    #[wasm_bindgen]
+    pub fn finish(self, tree: &TaffyTree) -> Result<u32, TaffyError> {
+        self.build(tree)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Begins building a tree node by node, returning a `BuildSession` to add the nodes to
+    ///
+    /// This is meant for trees whose description arrives in chunks: each node is decoded as soon as it is added
+    /// with `session.add_node(style, parentId?)`, so the description never has to be held in full, and all the
+    /// nodes are inserted into this tree at once by `session.finish(tree)`. See `BuildSession` for how the session
+    /// relates to the tree.
+    #[wasm_bindgen]
+    pub fn begin_build(&self) -> BuildSession {
+        BuildSession {
+            default_box_sizing: self.default_box_sizing.get(),
+            length_scale: self.length_scale.get(),
+            styles: Vec::new(),
+            children: Vec::new(),
+            root: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::node_from_js_id;
+
+    #[test]
+    fn three_level_tree_is_built() {
+        let tree = TaffyTree::new();
+        let mut session = tree.begin_build();
+        let fixed = |width| Style { size: Size { width: length(width), height: length(10.0) }, ..Default::default() };
+        let root = session.push_node(Style { flex_direction: FlexDirection::Column, ..Default::default() }, None);
+        let first = session.push_node(Style::default(), Some(0)).unwrap();
+        let second = session.push_node(Style::default(), Some(0)).unwrap();
+        session.push_node(fixed(30.0), Some(first)).unwrap();
+        session.push_node(fixed(40.0), Some(second)).unwrap();
+        session.push_node(fixed(50.0), Some(first)).unwrap();
+        assert_eq!((root, first, second), (Ok(0), 1, 2));
+        assert_eq!(session.node_count(), 6);
+        // Nothing is created in the tree until the session is finished
+        assert_eq!(tree.inner.borrow().total_node_count(), 0);
+
+        let root = session.finish(&tree).unwrap();
+        let inner = tree.inner.borrow();
+        let children = inner.children(node_from_js_id(root)).unwrap();
+        assert_eq!(children.len(), 2);
+        let widths = |node| -> Vec<_> {
+            inner.children(node).unwrap().into_iter().map(|leaf| inner.style(leaf).unwrap().size.width).collect()
+        };
+        assert_eq!(widths(children[0]), [length(30.0), length(50.0)]);
+        assert_eq!(widths(children[1]), [length(40.0)]);
+        assert_eq!(inner.total_node_count(), 6);
+    }
+
+    #[test]
+    fn invalid_parents_are_rejected() {
+        let tree = TaffyTree::new();
+        let mut session = tree.begin_build();
+        assert!(matches!(session.push_node(Style::default(), Some(0)), Err(TaffyError::InvalidArgument(_))));
+        session.push_node(Style::default(), None).unwrap();
+        assert!(matches!(session.push_node(Style::default(), None), Err(TaffyError::InvalidArgument(_))));
+        assert!(matches!(session.push_node(Style::default(), Some(1)), Err(TaffyError::InvalidArgument(_))));
+        assert_eq!(session.node_count(), 1);
+
+        assert!(matches!(tree.begin_build().finish(&tree), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,113 @@
+//! Finding the subtrees whose layout changed in a relayout, to scope a repaint
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use std::collections::BTreeMap;
+use taffy::{prelude::*, Point, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The location and size of each node of a subtree, by node id
+type Boxes = BTreeMap<u32, (Point<f32>, Size<f32>)>;
+
+/// Returns the location and size of every node in the subtree rooted at `root`
+fn boxes(tree: &Taffy<JsContext>, root: NodeId) -> Result<Boxes, TaffyError> {
+    let mut boxes = BTreeMap::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let layout = tree.layout(node)?;
+        boxes.insert(js_node_id(node), (layout.location, layout.size));
+        stack.extend(tree.children(node)?);
+    }
+    Ok(boxes)
+}
+
+/// Returns the highest nodes in the subtree rooted at `root` whose location or size differs from `before`, in paint
+/// order
+///
+/// The subtree of a changed node is not searched further, so no returned node is a descendant of another. Nodes that
+/// are not in `before` count as changed.
+fn changed_roots(tree: &Taffy<JsContext>, root: NodeId, before: &Boxes) -> Result<Vec<u32>, TaffyError> {
+    let mut roots = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let layout = tree.layout(node)?;
+        if before.get(&js_node_id(node)) != Some(&(layout.location, layout.size)) {
+            roots.push(js_node_id(node));
+        } else {
+            stack.extend(tree.children(node)?.into_iter().rev());
+        }
+    }
+    Ok(roots)
+}
+
+impl TaffyTree {
+    /// Lays out the tree rooted at `root` in `available_space`, returning the roots of the subtrees whose layout
+    /// changed
+    fn compute_changed_roots(&self, root: u32, available_space: Size<AvailableSpace>) -> Result<Vec<u32>, TaffyError> {
+        let before = {
+            let tree = self.tree()?;
+            boxes(&tree, resolve_node(&tree, root)?)?
+        };
+        self.compute_subtree(root, available_space)?;
+        let tree = self.tree()?;
+        changed_roots(&tree, resolve_node(&tree, root)?, &before)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout like `compute_layout`, and returns the ids of the roots of the subtrees whose layout
+    /// changed, in paint order
+    ///
+    /// A node's layout has changed if its location or size differs from before the call. Each returned node is
+    /// the highest such node on its path from `root`: its descendants may have changed too, but none of its
+    /// ancestors did, so repainting the returned subtrees repaints every change. When a dirty node grows, for
+    /// instance, the highest ancestor that grows with it is returned, along with any following siblings that move
+    /// as a result. Nodes added since the last layout count as changed. The result is empty if nothing changed,
+    /// and is `[root]` on the first layout of a tree.
+    ///
+    /// Changes are detected by comparing the layouts of the whole subtree before and after, which costs a walk of
+    /// the subtree on top of the layout itself.
+    #[wasm_bindgen]
+    pub fn compute_layout_changed_roots(&self, root: u32, width: f32, height: f32) -> Result<Vec<u32>, TaffyError> {
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        self.compute_changed_roots(root, available_space)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::node_from_js_id;
+
+    #[test]
+    fn highest_resized_ancestor_is_returned() {
+        // root (fixed size) > panel (sized to content) > group (sized to content) > leaf, plus a fixed-size sibling
+        // of the panel placed before it, whose layout doesn't depend on the leaf
+        let tree = TaffyTree::new();
+        let (root, [sibling, panel, leaf]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let sized = |side: f32| Style { size: length(side), flex_shrink: 0.0, ..Default::default() };
+            let column = Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(AlignItems::Start),
+                ..Default::default()
+            };
+            let leaf = inner.new_leaf(sized(10.0)).unwrap();
+            let group = inner.new_with_children(column.clone(), &[leaf]).unwrap();
+            let panel = inner.new_with_children(column.clone(), &[group]).unwrap();
+            let sibling = inner.new_leaf(sized(20.0)).unwrap();
+            let root = inner.new_with_children(Style { size: length(200.0), ..column }, &[sibling, panel]).unwrap();
+            (js_node_id(root), [sibling, panel, leaf].map(js_node_id))
+        };
+        let available_space = Size { width: AvailableSpace::Definite(200.0), height: AvailableSpace::Definite(200.0) };
+        assert_eq!(tree.compute_changed_roots(root, available_space), Ok(vec![root]));
+        assert_eq!(tree.compute_changed_roots(root, available_space), Ok(vec![]));
+
+        // Growing the leaf grows the group and the panel, but not the fixed-size root
+        let grown = Style { size: length(30.0), flex_shrink: 0.0, ..Default::default() };
+        tree.inner.borrow_mut().set_style(node_from_js_id(leaf), grown).unwrap();
+        assert_eq!(tree.compute_changed_roots(root, available_space), Ok(vec![panel]));
+        assert_eq!(tree.layout_width(panel), Ok(30.0));
+        assert_eq!(tree.layout_width(sibling), Ok(20.0));
+    }
+}
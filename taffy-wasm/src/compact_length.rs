@@ -0,0 +1,79 @@
+//! Conversions to and from the 64-bit `CompactLength` encoding used in serialized styles
+//!
+//! These use taffy's own `CompactLength` implementation, so they are the reference that the `CompactLength` helpers
+//! generated into `pkg/CompactLength.ts` must match.
+use crate::TaffyError;
+use taffy::style::CompactLength;
+use wasm_bindgen::prelude::*;
+
+/// Decodes a serialized `CompactLength`, failing if it does not carry a valid tag
+fn decode(value: u64) -> Result<CompactLength, TaffyError> {
+    CompactLength::from_serialized(value)
+        .ok_or_else(|| TaffyError::InvalidArgument(format!("{value:#018x} is not a valid CompactLength encoding")))
+}
+
+/// Returns the serialized `CompactLength` encoding of a length of `value` pixels
+#[wasm_bindgen]
+pub fn compact_length_from_px(value: f32) -> u64 {
+    CompactLength::length(value).to_serialized()
+}
+
+/// Returns the number of pixels of a serialized `CompactLength` length
+///
+/// This is the inverse of `compact_length_from_px`. Throws an `INVALID_ARGUMENT` error if `value` is not a valid
+/// encoding, or encodes something other than a pixel length.
+#[wasm_bindgen]
+pub fn compact_length_to_px(value: u64) -> Result<f32, TaffyError> {
+    let length = decode(value)?;
+    if length.tag() == CompactLength::LENGTH_TAG {
+        Ok(length.value())
+    } else {
+        Err(TaffyError::InvalidArgument(format!("CompactLength with tag {:#04x} is not a pixel length", length.tag())))
+    }
+}
+
+/// Returns the tag of a serialized `CompactLength`, e.g. `CompactLength.LENGTH_TAG` for a pixel length
+///
+/// Throws an `INVALID_ARGUMENT` error if `value` is not a valid encoding.
+#[wasm_bindgen]
+pub fn compact_length_tag(value: u64) -> Result<u8, TaffyError> {
+    Ok(decode(value)?.tag() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn px_round_trips() {
+        for px in [0.0, 1.5, -20.0, 1e6] {
+            let encoded = compact_length_from_px(px);
+            assert_eq!(compact_length_tag(encoded), Ok(CompactLength::LENGTH_TAG as u8));
+            assert_eq!(compact_length_to_px(encoded), Ok(px));
+        }
+    }
+
+    #[test]
+    fn encoding_matches_generated_typescript_helpers() {
+        // The TypeScript helpers place the tag in the high 32 bits and the f32 bits in the low 32 bits
+        assert_eq!(compact_length_from_px(10.0), (CompactLength::LENGTH_TAG as u64) << 32 | 10.0f32.to_bits() as u64);
+        assert_eq!(CompactLength::auto().to_serialized(), (CompactLength::AUTO_TAG as u64) << 32);
+    }
+
+    #[test]
+    fn tag_of_keyword_values() {
+        assert_eq!(compact_length_tag(CompactLength::auto().to_serialized()), Ok(CompactLength::AUTO_TAG as u8));
+        assert_eq!(compact_length_tag(CompactLength::fr(1.0).to_serialized()), Ok(CompactLength::FR_TAG as u8));
+        assert!(matches!(
+            compact_length_to_px(CompactLength::percent(0.5).to_serialized()),
+            Err(TaffyError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_encodings_are_rejected() {
+        // A zero tag is the calc() tag, which can't be decoded
+        assert!(matches!(compact_length_tag(10.0f32.to_bits() as u64), Err(TaffyError::InvalidArgument(_))));
+        assert!(matches!(compact_length_to_px(0xff << 32), Err(TaffyError::InvalidArgument(_))));
+    }
+}
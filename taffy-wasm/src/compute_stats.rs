@@ -0,0 +1,240 @@
+//! Statistics about the most recent layout pass, for spotting performance regressions
+use crate::node_ids::node_from_js_id;
+use crate::{JsContext, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// How much work the most recent layout pass did, returned by `last_compute_stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputeStats {
+    /// The number of times the measure function was called
+    pub measure_calls: u32,
+    /// The number of nodes in the subtree that was laid out, including its root
+    pub nodes_laid_out: u32,
+    /// The wall-clock duration of the pass in milliseconds
+    pub duration_ms: f64,
+}
+
+/// The size a measure function returned for a node, and the size the node was laid out at, returned by
+/// `measured_vs_final_sizes`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MeasureDiscrepancy {
+    /// The size returned by the last call to the measure function for the node
+    pub measured: Size<f32>,
+    /// The size the node was laid out at
+    #[serde(rename = "final")]
+    pub final_size: Size<f32>,
+}
+
+/// Returns the current time in milliseconds
+///
+/// This uses `Date.now()` in the browser, and the system clock when running natively (e.g. in tests).
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    return js_sys::Date::now();
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0);
+}
+
+/// Returns the number of nodes in the subtree rooted at `root`, including `root`
+fn subtree_node_count(tree: &Taffy<JsContext>, root: NodeId) -> u32 {
+    let mut count = 0;
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        count += 1;
+        stack.extend(tree.children(node).unwrap_or_default());
+    }
+    count
+}
+
+impl TaffyTree {
+    /// Records the statistics of a layout pass of the subtree rooted at `root`, in which the nodes with the ids in
+    /// `measured_nodes` were measured, the last measurement of each returning the size it is mapped to
+    pub(crate) fn record_compute_stats(
+        &self,
+        tree: &Taffy<JsContext>,
+        root: NodeId,
+        measure_calls: u32,
+        measured_nodes: BTreeMap<u32, Size<f32>>,
+        duration_ms: f64,
+    ) {
+        let nodes_laid_out = subtree_node_count(tree, root);
+        self.last_compute_stats.set(ComputeStats { measure_calls, nodes_laid_out, duration_ms });
+        *self.last_measured_nodes.borrow_mut() = measured_nodes;
+    }
+
+    /// Returns the final size of each node measured during the most recent layout pass, keyed by node id
+    fn measured_sizes(&self) -> Result<BTreeMap<u32, Size<f32>>, TaffyError> {
+        let tree = self.tree()?;
+        let measured_nodes = self.last_measured_nodes.borrow();
+        let sizes =
+            measured_nodes.keys().filter_map(|&id| Some((id, tree.layout(node_from_js_id(id)).ok()?.size))).collect();
+        Ok(sizes)
+    }
+
+    /// Returns the last size returned by the measure function and the final size of each node measured during the
+    /// most recent layout pass, keyed by node id
+    fn measure_discrepancies(&self) -> Result<BTreeMap<u32, MeasureDiscrepancy>, TaffyError> {
+        let tree = self.tree()?;
+        let measured_nodes = self.last_measured_nodes.borrow();
+        let discrepancies = measured_nodes
+            .iter()
+            .filter_map(|(&id, &measured)| {
+                let final_size = tree.layout(node_from_js_id(id)).ok()?.size;
+                Some((id, MeasureDiscrepancy { measured, final_size }))
+            })
+            .collect();
+        Ok(discrepancies)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns statistics about the most recent layout pass as `{ measureCalls, nodesLaidOut, durationMs }`
+    ///
+    /// These are updated by every call that computes a layout: `compute_layout`, `compute_layout_with_measure`,
+    /// `compute_subtree_layout`, `compute_layout_into` and `compute_layouts`. `measureCalls` is the number of times
+    /// the measure function was called, and is always 0 for layouts computed without one. `nodesLaidOut` is the
+    /// number of nodes in the subtree that was laid out, whether or not their cached layout could be reused. All
+    /// values are 0 before the first layout, and the stats of a pass that failed with a `MEASURE_BUDGET_EXCEEDED`
+    /// error are kept.
+    #[wasm_bindgen]
+    pub fn last_compute_stats(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.last_compute_stats.get()).unwrap_or(JsValue::NULL)
+    }
+
+    /// Returns the final size of every node that was measured during the most recent layout pass, as an object
+    /// mapping node ids to `{ width, height }`
+    ///
+    /// The final size is the size the node was laid out at, after its `min_size`, `max_size`, `aspect_ratio` and
+    /// any stretching or flexing are applied, so it can differ from every size the measure function returned for
+    /// it. Text shaped at this size can be painted without being shaped again. The object is empty after a pass
+    /// without a measure function, and nodes removed since the pass are omitted.
+    #[wasm_bindgen]
+    pub fn measured_leaf_sizes(&self) -> Result<JsValue, TaffyError> {
+        let sizes = self.measured_sizes()?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        Ok(sizes.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns both the size the measure function returned and the final size of every node that was measured
+    /// during the most recent layout pass, as an object mapping node ids to
+    /// `{ measured: { width, height }, final: { width, height } }`
+    ///
+    /// `measured` is the size returned by the last measure call for the node, and `final` is the size given by
+    /// `measured_leaf_sizes`. They differ when the measured size was clamped by `min_size` or `max_size`, or changed
+    /// by stretching or flexing, in which case text shaped at the measured size has to be shaped again at the final
+    /// one. Nodes whose content size is fixed with `set_content_size` aren't measured and so aren't included.
+    #[wasm_bindgen]
+    pub fn measured_vs_final_sizes(&self) -> Result<JsValue, TaffyError> {
+        let discrepancies = self.measure_discrepancies()?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        Ok(discrepancies.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure_budget::MeasureBudget;
+    use crate::node_ids::js_node_id;
+    use taffy::{style::Style, Point};
+
+    /// Creates a row of two leaves inside a container, returning the tree and the id of the container
+    fn row() -> (TaffyTree, u32) {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaves: Vec<_> = (0..2).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            inner.new_with_children(Style::default(), &leaves).unwrap()
+        };
+        (tree, js_node_id(root))
+    }
+
+    #[test]
+    fn stats_are_empty_before_the_first_layout() {
+        assert_eq!(TaffyTree::new().last_compute_stats.get(), ComputeStats::default());
+    }
+
+    #[test]
+    fn layout_without_measure_function_makes_no_measure_calls() {
+        let (tree, root) = row();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        let stats = tree.last_compute_stats.get();
+        assert_eq!(stats.measure_calls, 0);
+        assert_eq!(stats.nodes_laid_out, 3);
+        assert!(stats.duration_ms >= 0.0);
+    }
+
+    #[test]
+    fn layout_with_measure_function_counts_measure_calls() {
+        let (tree, root) = row();
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, |_, _, _, _| {
+            (Size { width: 10.0, height: 10.0 }, Point::NONE)
+        })
+        .unwrap();
+        let stats = tree.last_compute_stats.get();
+        assert!(stats.measure_calls > 0);
+        assert_eq!(stats.nodes_laid_out, 3);
+    }
+
+    #[test]
+    fn measured_sizes_reflect_max_size_clamp() {
+        let tree = TaffyTree::new();
+        let (root, clamped, unclamped) = {
+            let mut inner = tree.inner.borrow_mut();
+            let clamped = Style { max_size: Size { width: length(30.0), height: auto() }, ..Default::default() };
+            let clamped = inner.new_leaf(clamped).unwrap();
+            let unclamped = inner.new_leaf(Style::default()).unwrap();
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            let root = inner.new_with_children(column, &[clamped, unclamped]).unwrap();
+            (js_node_id(root), js_node_id(clamped), js_node_id(unclamped))
+        };
+
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, |known, _, _, _| {
+            (known.unwrap_or(Size { width: 50.0, height: 10.0 }), Point::NONE)
+        })
+        .unwrap();
+        let sizes = tree.measured_sizes().unwrap();
+        assert_eq!(sizes.keys().copied().collect::<Vec<_>>(), vec![clamped, unclamped]);
+        assert_eq!(sizes[&clamped], Size { width: 30.0, height: 10.0 });
+        assert_eq!(sizes[&unclamped], Size { width: 50.0, height: 10.0 });
+
+        // A pass without a measure function measures nothing
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert!(tree.measured_sizes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn measure_discrepancies_show_min_size_clamp() {
+        let tree = TaffyTree::new();
+        let (root, clamped, unclamped) = {
+            let mut inner = tree.inner.borrow_mut();
+            let clamped = Style { min_size: Size { width: length(80.0), height: auto() }, ..Default::default() };
+            let clamped = inner.new_leaf(clamped).unwrap();
+            let unclamped = inner.new_leaf(Style::default()).unwrap();
+            let column = Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(AlignItems::Start),
+                ..Default::default()
+            };
+            let root = inner.new_with_children(column, &[clamped, unclamped]).unwrap();
+            (js_node_id(root), js_node_id(clamped), js_node_id(unclamped))
+        };
+
+        let text = Size { width: 50.0, height: 10.0 };
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, |_, _, _, _| {
+            (text, Point::NONE)
+        })
+        .unwrap();
+        let discrepancies = tree.measure_discrepancies().unwrap();
+        assert_eq!(discrepancies[&clamped].measured, text);
+        assert_eq!(discrepancies[&clamped].final_size, Size { width: 80.0, height: 10.0 });
+        assert_eq!(discrepancies[&unclamped], MeasureDiscrepancy { measured: text, final_size: text });
+    }
+}
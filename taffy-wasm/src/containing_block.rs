@@ -0,0 +1,158 @@
+//! Laying out a tree embedded in another layout engine, whose percentage base comes from the host
+use crate::available_space::{decode_available_space, BoundedAvailableSpace};
+use crate::node_ids::node_from_js_id;
+use crate::{compute_stats, resolve_node, TaffyError, TaffyTree};
+use std::collections::BTreeMap;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const CONTAINING_BLOCK_TS: &'static str = r#"
+/** The size that the percentages of a root resolve against, where `null` is an indefinite size */
+export interface ContainingBlock {
+  width: number | null;
+  height: number | null;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A `ContainingBlock` object passed from JavaScript
+    #[wasm_bindgen(typescript_type = "ContainingBlock")]
+    pub type JsContainingBlock;
+}
+
+/// Checks that the definite axes of a containing block are usable sizes
+fn validate(containing_block: Size<Option<f32>>) -> Result<Size<Option<f32>>, TaffyError> {
+    for (name, size) in [("width", containing_block.width), ("height", containing_block.height)] {
+        if let Some(pixels) = size.filter(|pixels| !pixels.is_finite() || *pixels < 0.0) {
+            return Err(TaffyError::InvalidArgument(format!(
+                "containingBlock.{name} must be null or a non-negative number of pixels, got {pixels}"
+            )));
+        }
+    }
+    Ok(containing_block)
+}
+
+/// Decodes a `ContainingBlock` object passed from JS
+fn decode_containing_block(containing_block: JsContainingBlock) -> Result<Size<Option<f32>>, TaffyError> {
+    let containing_block = serde_wasm_bindgen::from_value(containing_block.into())
+        .map_err(|e| TaffyError::InvalidArgument(format!("containingBlock: {e}")))?;
+    validate(containing_block)
+}
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `node_id` within `available_space`, resolving the percentages of `node_id`
+    /// against `containing_block`
+    fn compute_subtree_in_containing_block(
+        &self,
+        node_id: u32,
+        available_space: Size<AvailableSpace>,
+        containing_block: Size<Option<f32>>,
+    ) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        self.check_layout_depth(&tree, node)?;
+        self.keep_previous_layouts(&tree, node)?;
+        let start_ms = compute_stats::now_ms();
+        tree.compute_layout_with_measure_and_containing_block(
+            node,
+            available_space,
+            containing_block,
+            |known_dimensions, available, leaf, _, _| self.measure_content_size(known_dimensions, available, leaf),
+        )?;
+        self.record_compute_stats(&tree, node, 0, BTreeMap::new(), compute_stats::now_ms() - start_ms);
+        self.record_layout(node_id, available_space);
+        self.validate_layout(&tree, node)?;
+        Ok(())
+    }
+
+    /// Lays out the subtree rooted at `root` within `available` and `containing_block`, laying it out again within
+    /// the limits it exceeds
+    fn compute_bounded_subtree_in_containing_block(
+        &self,
+        root: u32,
+        available: BoundedAvailableSpace,
+        containing_block: Size<Option<f32>>,
+    ) -> Result<(), TaffyError> {
+        self.compute_subtree_in_containing_block(root, available.space, containing_block)?;
+        let size = self.tree()?.unrounded_layout(node_from_js_id(root)).size;
+        match available.relayout_space(size) {
+            Some(space) => self.compute_subtree_in_containing_block(root, space, containing_block),
+            None => Ok(()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `root` within `available`, resolving the percentages of `root`
+    /// against `containingBlock` instead
+    ///
+    /// `available` is a `LayoutAvailableSpace`, as taken by `compute_layout_v2`, and drives the intrinsic sizing
+    /// of `root`: how its content wraps, and how wide a block root stretches. `containingBlock` is
+    /// `{ width, height }` in pixels, and is what the percentage sizes, margins, padding, borders and insets of
+    /// `root` resolve against (horizontal padding and margins against its width), as they would against the
+    /// containing block supplied by a host layout engine. A `null` axis is indefinite, so percentages in it behave
+    /// like `auto`. `compute_layout_v2(root, available)` is this with a `containingBlock` equal to the definite
+    /// axes of `available`.
+    ///
+    /// Only `root` sees the containing block: its descendants resolve percentages against their parents as usual.
+    /// Its cached layout is always discarded, since the cache doesn't record the containing block it was computed
+    /// in, but the cached layouts of its descendants are reused when their inputs are unchanged.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available` is not a valid `LayoutAvailableSpace`, or if an axis of
+    /// `containingBlock` is neither `null` nor a non-negative number of pixels.
+    #[wasm_bindgen]
+    pub fn compute_layout_with_containing_block(
+        &self,
+        root: u32,
+        available: JsValue,
+        containing_block: JsContainingBlock,
+    ) -> Result<(), TaffyError> {
+        let available = decode_available_space(available)?;
+        let containing_block = decode_containing_block(containing_block)?;
+        self.compute_bounded_subtree_in_containing_block(root, available, containing_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn percentages_resolve_against_containing_block() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+            let root = Style { size: Size { width: percent(0.5), height: length(40.0) }, ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[child]).unwrap()), js_node_id(child))
+        };
+        let available = BoundedAvailableSpace {
+            space: Size { width: AvailableSpace::Definite(800.0), height: AvailableSpace::MaxContent },
+            limits: Size::NONE,
+        };
+
+        // The 50% root is half the 300px containing block, not half the 800px of available space
+        let containing_block = Size { width: Some(300.0), height: None };
+        tree.compute_bounded_subtree_in_containing_block(root, available, containing_block).unwrap();
+        assert_eq!(tree.layout_width(root), Ok(150.0));
+        assert_eq!((tree.layout_width(child), tree.layout_height(child)), (Ok(75.0), Ok(20.0)));
+
+        // The same tree laid out again with a different containing block isn't served from the cache
+        let containing_block = Size { width: Some(200.0), height: None };
+        tree.compute_bounded_subtree_in_containing_block(root, available, containing_block).unwrap();
+        assert_eq!(tree.layout_width(root), Ok(100.0));
+        assert_eq!(tree.layout_width(child), Ok(50.0));
+    }
+
+    #[test]
+    fn invalid_containing_block_is_rejected() {
+        for (width, height) in [(Some(-1.0), None), (None, Some(f32::NAN)), (Some(f32::INFINITY), Some(10.0))] {
+            assert!(matches!(validate(Size { width, height }), Err(TaffyError::InvalidArgument(_))));
+        }
+        assert_eq!(validate(Size { width: None, height: Some(0.0) }), Ok(Size { width: None, height: Some(0.0) }));
+    }
+}
@@ -0,0 +1,80 @@
+//! The content box of a node, inside its border and padding, for placing the content a node draws itself, e.g. text
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// The content box of a node relative to its own border box, returned by `content_box`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub(crate) struct ContentBox {
+    /// The distance from the left edge of the node's border box to the left edge of its content box
+    pub(crate) x: f32,
+    /// The distance from the top edge of the node's border box to the top edge of its content box
+    pub(crate) y: f32,
+    /// The width of the content box
+    pub(crate) width: f32,
+    /// The height of the content box
+    pub(crate) height: f32,
+}
+
+impl TaffyTree {
+    /// Returns the box inside the border, padding and scrollbars of a node, as last laid out
+    fn node_content_box(&self, node_id: u32) -> Result<ContentBox, TaffyError> {
+        let tree = self.tree()?;
+        let layout = tree.layout(resolve_node(&tree, node_id)?)?;
+        Ok(ContentBox {
+            x: layout.content_box_x(),
+            y: layout.content_box_y(),
+            width: (layout.content_box_width() - layout.scrollbar_size.width).max(0.0),
+            height: (layout.content_box_height() - layout.scrollbar_size.height).max(0.0),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the content box of a node, inside its border and padding, as `{ x, y, width, height }` relative to
+    /// the top left corner of the node's own border box
+    ///
+    /// `x` and `y` are the left and top border plus padding, and the size is the border box less the border and
+    /// padding on both sides, all as resolved by the last layout, so that percentage padding, which resolves
+    /// against the width of the parent, is accounted for. Space reserved for scrollbars by `scrollbar_width` is
+    /// also left out, as scrollbars sit on the right and bottom edges of the padding box. The size is never
+    /// negative: a node with more border and padding than size has an empty content box. Add `absolute_layout`
+    /// to `x` and `y` to get the content box relative to the root.
+    #[wasm_bindgen]
+    pub fn content_box(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let content_box = self.node_content_box(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&content_box).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::prelude::{auto, length, percent, Rect, Size, Style, TaffyMaxContent};
+
+    #[test]
+    fn content_box_is_the_border_box_less_border_and_padding() {
+        let tree = TaffyTree::new();
+        let (root, card) = {
+            let mut inner = tree.inner.borrow_mut();
+            let card = Style {
+                size: Size { width: length(100.0), height: length(60.0) },
+                border: Rect { left: length(2.0), right: length(4.0), top: length(1.0), bottom: length(3.0) },
+                // Resolves against the 200px width of the root, to 10px on every side
+                padding: percent(0.05),
+                ..Default::default()
+            };
+            let card = inner.new_leaf(card).unwrap();
+            let root = Style { size: Size { width: length(200.0), height: auto() }, ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[card]).unwrap()), js_node_id(card))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(tree.node_content_box(card), Ok(ContentBox { x: 12.0, y: 11.0, width: 74.0, height: 36.0 }));
+        // A node without border or padding has its border box as its content box
+        assert_eq!(tree.node_content_box(root), Ok(ContentBox { x: 0.0, y: 0.0, width: 200.0, height: 60.0 }));
+        assert_eq!(tree.node_content_box(100), Err(TaffyError::InvalidNode(100)));
+    }
+}
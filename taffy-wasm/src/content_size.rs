@@ -0,0 +1,213 @@
+//! Giving leaves a fixed content size, e.g. images with known intrinsic dimensions, without a measure function
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns the size of a leaf given a content size with `set_content_size` or an object fit with
+    /// `set_object_fit`, within its known dimensions
+    pub(crate) fn fixed_content_size(
+        &self,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        node: NodeId,
+    ) -> Option<Size<f32>> {
+        let node_id = js_node_id(node);
+        let node_states = self.node_states.borrow();
+        let state = node_states.get(node_id);
+        let content_size = match state.content_size {
+            Some(content_size) => content_size,
+            None => state.object_fit?.size_within(known_dimensions, available_space),
+        };
+        Some(known_dimensions.unwrap_or(content_size))
+    }
+
+    /// Sizes a leaf laid out without a measure function: by its content size or object fit if it has one, or as
+    /// empty otherwise
+    pub(crate) fn measure_content_size(
+        &self,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        node: NodeId,
+    ) -> Size<f32> {
+        self.fixed_content_size(known_dimensions, available_space, node).unwrap_or(Size::ZERO)
+    }
+
+    /// Sets or removes the content size of a node, removing its object fit, and marks it dirty
+    fn replace_content_size(&self, node_id: u32, content_size: Option<Size<f32>>) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        self.node_states.borrow_mut().update(node_id, |state| {
+            state.content_size = content_size;
+            state.object_fit = None;
+        });
+        tree.mark_dirty(node)?;
+        Ok(())
+    }
+}
+
+/// Checks that `width` and `height` are valid content sizes
+fn check_content_size(width: f32, height: f32) -> Result<Size<f32>, TaffyError> {
+    for (name, pixels) in [("width", width), ("height", height)] {
+        if !pixels.is_finite() || pixels < 0.0 {
+            return Err(TaffyError::InvalidArgument(format!(
+                "{name} must be a non-negative number of pixels, got {pixels}"
+            )));
+        }
+    }
+    Ok(Size { width, height })
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Gives a leaf a fixed content size of `width` by `height` pixels, which is used as the result of measuring it
+    ///
+    /// This is for leaves whose content has a known size, such as an image with intrinsic dimensions: the size is
+    /// used directly whenever the layout needs the size of the leaf's content, and the measure function passed to
+    /// `compute_layout_with_measure` (or any other layout method) is never called for the leaf. Layout methods
+    /// that take no measure function use it too, so a tree whose only measured leaves have fixed content sizes
+    /// doesn't need a measure function at all. The leaf's style still applies on top, like for a measured leaf:
+    /// a `size`, `min_size` or stretched axis overrides the content size in that axis.
+    ///
+    /// Content sizes only apply to leaves, since the size of the content of a node with children is that of its
+    /// children. Measurements served from a content size don't count towards `measureCalls` in
+    /// `last_compute_stats`, nor towards the budget of a layout. This replaces any object fit given with
+    /// `set_object_fit`. The node is marked dirty.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `width` or `height` is negative or not finite.
+    #[wasm_bindgen]
+    pub fn set_content_size(&self, node_id: u32, width: f32, height: f32) -> Result<(), TaffyError> {
+        self.replace_content_size(node_id, Some(check_content_size(width, height)?))
+    }
+
+    /// Changes the content size of a leaf, e.g. when an image finishes loading, returning whether it changed
+    ///
+    /// This is `set_content_size` for content whose size changes after it has been laid out: a new size is stored
+    /// and marks the leaf dirty, which marks its ancestors dirty too, up to its root, so that the next layout
+    /// recomputes only that chain and reuses the cached layouts of every other subtree. Updating a leaf to the
+    /// content size it already has changes nothing, marks nothing dirty and returns `false`, so it is cheap to
+    /// report sizes that might not have changed, e.g. on every load event. A leaf without a content size is given
+    /// one, as with `set_content_size`.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `width` or `height` is negative or not finite.
+    #[wasm_bindgen]
+    pub fn update_content_size(&self, node_id: u32, width: f32, height: f32) -> Result<bool, TaffyError> {
+        let content_size = check_content_size(width, height)?;
+        resolve_node(&*self.tree()?, node_id)?;
+        if self.node_states.borrow().get(node_id).content_size == Some(content_size) {
+            return Ok(false);
+        }
+        self.replace_content_size(node_id, Some(content_size))?;
+        Ok(true)
+    }
+
+    /// Removes the content size given to a node with `set_content_size`, so that it is measured by the measure
+    /// function again, and marks it dirty
+    #[wasm_bindgen]
+    pub fn remove_content_size(&self, node_id: u32) -> Result<(), TaffyError> {
+        self.replace_content_size(node_id, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_stats::now_ms;
+    use crate::measure_budget::MeasureBudget;
+    use crate::node_ids::node_from_js_id;
+    use std::cell::RefCell;
+    use taffy::Point;
+
+    /// Creates a row of three leaves, returning the tree, the root and the leaves
+    fn row() -> (TaffyTree, u32, [u32; 3]) {
+        let tree = TaffyTree::new();
+        let (root, leaves) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaves = [(); 3].map(|_| inner.new_leaf(Style::default()).unwrap());
+            let root = inner.new_with_children(Style::default(), &leaves).unwrap();
+            (js_node_id(root), leaves.map(js_node_id))
+        };
+        (tree, root, leaves)
+    }
+
+    #[test]
+    fn fixed_and_measured_leaves_in_one_layout() {
+        let (tree, root, [image, text, sized]) = row();
+        tree.set_content_size(image, 40.0, 30.0).unwrap();
+        tree.set_content_size(sized, 40.0, 30.0).unwrap();
+        let narrow = Style { size: Size { width: length(10.0), height: auto() }, ..Default::default() };
+        tree.inner.borrow_mut().set_style(node_from_js_id(sized), narrow).unwrap();
+
+        let measured = RefCell::new(Vec::new());
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, |_, _, node, _| {
+            measured.borrow_mut().push(js_node_id(node));
+            (Size { width: 25.0, height: 10.0 }, Point::NONE)
+        })
+        .unwrap();
+
+        // Only the leaf without a content size is passed to the measure function
+        assert!(!measured.borrow().is_empty());
+        assert!(measured.borrow().iter().all(|&node| node == text));
+        assert_eq!((tree.layout_width(image), tree.layout_height(image)), (Ok(40.0), Ok(30.0)));
+        assert_eq!((tree.layout_width(text), tree.layout_height(text)), (Ok(25.0), Ok(30.0)));
+        // A styled size overrides the content size in its axis
+        assert_eq!((tree.layout_width(sized), tree.layout_height(sized)), (Ok(10.0), Ok(30.0)));
+        assert_eq!(tree.layout_width(root), Ok(75.0));
+    }
+
+    #[test]
+    fn content_size_applies_without_measure_function() {
+        let (tree, root, [image, other, _]) = row();
+        tree.set_content_size(image, 40.0, 30.0).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!((tree.layout_width(image), tree.layout_height(image)), (Ok(40.0), Ok(30.0)));
+        assert_eq!(tree.layout_width(other), Ok(0.0));
+
+        // Removing the content size marks the leaf dirty and sizes it as empty again
+        tree.remove_content_size(image).unwrap();
+        assert_eq!(tree.dirty(image), Ok(true));
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(image), Ok(0.0));
+    }
+
+    #[test]
+    fn updating_a_content_size_dirties_only_its_ancestors() {
+        let tree = TaffyTree::new();
+        let [image, card, other, other_card, root] = {
+            let mut inner = tree.inner.borrow_mut();
+            let image = inner.new_leaf(Style::default()).unwrap();
+            let card = inner.new_with_children(Style::default(), &[image]).unwrap();
+            let other = inner.new_leaf(Style::default()).unwrap();
+            let other_card = inner.new_with_children(Style::default(), &[other]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[card, other_card]).unwrap();
+            [image, card, other, other_card, root].map(js_node_id)
+        };
+        tree.set_content_size(image, 10.0, 10.0).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // Reporting the same size again leaves the layout clean
+        assert_eq!(tree.update_content_size(image, 10.0, 10.0), Ok(false));
+        assert_eq!(tree.dirty(root), Ok(false));
+
+        assert_eq!(tree.update_content_size(image, 40.0, 30.0), Ok(true));
+        for node in [image, card, root] {
+            assert_eq!(tree.dirty(node), Ok(true));
+        }
+        for node in [other, other_card] {
+            assert_eq!(tree.dirty(node), Ok(false));
+        }
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!((tree.layout_width(image), tree.layout_height(image)), (Ok(40.0), Ok(30.0)));
+        assert_eq!(tree.layout_width(root), Ok(40.0));
+    }
+
+    #[test]
+    fn invalid_content_size_is_rejected() {
+        let (tree, _, [leaf, _, _]) = row();
+        assert!(matches!(tree.set_content_size(leaf, -1.0, 0.0), Err(TaffyError::InvalidArgument(_))));
+        assert!(matches!(tree.set_content_size(leaf, 0.0, f32::INFINITY), Err(TaffyError::InvalidArgument(_))));
+        assert_eq!(tree.set_content_size(100, 1.0, 1.0), Err(TaffyError::InvalidNode(100)));
+        assert_eq!(tree.update_content_size(100, 1.0, 1.0), Err(TaffyError::InvalidNode(100)));
+    }
+}
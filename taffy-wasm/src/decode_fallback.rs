@@ -0,0 +1,92 @@
+//! Substituting a chosen style for JS style objects that fail to decode, and counting the failures
+use crate::style_defaults::decode_style_with_box_sizing;
+use crate::{TaffyError, TaffyTree};
+use taffy::style::Style;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns `decoded` if a style was decoded, or the fallback style set by `set_decode_fallback_style` in place of
+    /// a style that failed to decode, if there is one, counting the failure either way
+    pub(crate) fn style_or_fallback(&self, decoded: Result<Style, TaffyError>) -> Result<Style, TaffyError> {
+        match decoded {
+            Err(TaffyError::StyleDecode(message)) => {
+                self.decode_failures.set(self.decode_failures.get().saturating_add(1));
+                match self.decode_fallback_style.borrow().clone() {
+                    Some(fallback) => Ok(fallback),
+                    None => Err(TaffyError::StyleDecode(message)),
+                }
+            }
+            decoded => decoded,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Makes style objects that fail to decode use `style` instead of throwing a `STYLE_DECODE` error, e.g. a
+    /// style with a visible size that makes broken nodes stand out
+    ///
+    /// By default `new_leaf`, `update_style` and the other methods taking a style object throw when it isn't a
+    /// valid style, including when it uses an unsupported value such as `position: "sticky"`. Once a fallback is
+    /// set they use it in its place and succeed, so a single malformed style can't interrupt building a tree; poll
+    /// `decode_failure_count` to find out that it happened. `style` is decoded like any other style object, with
+    /// the default box sizing at the time of the call, and its lengths are scaled by the length scale at the time
+    /// it is used. The fallback stays set until `clear_decode_fallback_style` is called.
+    ///
+    /// Throws a `STYLE_DECODE` error if `style` itself is not a valid style, leaving any previous fallback set.
+    #[wasm_bindgen]
+    pub fn set_decode_fallback_style(&self, style: JsValue) -> Result<(), TaffyError> {
+        let style = decode_style_with_box_sizing(style, self.default_box_sizing.get())?;
+        *self.decode_fallback_style.borrow_mut() = Some(style);
+        Ok(())
+    }
+
+    /// Removes the style set by `set_decode_fallback_style`, so that style objects that fail to decode throw a
+    /// `STYLE_DECODE` error again
+    #[wasm_bindgen]
+    pub fn clear_decode_fallback_style(&self) {
+        *self.decode_fallback_style.borrow_mut() = None;
+    }
+
+    /// Returns the number of style objects that failed to decode since the tree was created, whether they were
+    /// replaced by the style set with `set_decode_fallback_style` or threw a `STYLE_DECODE` error
+    #[wasm_bindgen]
+    pub fn decode_failure_count(&self) -> u32 {
+        self.decode_failures.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsStyle;
+    use taffy::prelude::{length, Size};
+
+    /// Decodes a JS style object given as JSON, as the JS bindings decode them
+    fn decode(style: serde_json::Value) -> Result<Style, TaffyError> {
+        let decoded = serde_json::from_value::<JsStyle>(style);
+        decoded.map(|style| style.0).map_err(|e| TaffyError::StyleDecode(e.to_string()))
+    }
+
+    #[test]
+    fn malformed_style_uses_the_fallback_and_is_counted() {
+        let tree = TaffyTree::new();
+        let malformed = || decode(serde_json::json!({ "display": 5 }));
+        assert!(matches!(tree.style_or_fallback(malformed()), Err(TaffyError::StyleDecode(_))));
+        assert_eq!(tree.decode_failure_count(), 1);
+
+        let error_box = Style { size: Size { width: length(100.0), height: length(20.0) }, ..Default::default() };
+        *tree.decode_fallback_style.borrow_mut() = Some(error_box.clone());
+        assert_eq!(tree.style_or_fallback(malformed()), Ok(error_box));
+        assert_eq!(tree.decode_failure_count(), 2);
+
+        // Valid styles are used as they are, and aren't counted
+        let valid = decode(serde_json::json!({ "flex_grow": 1.0 })).unwrap();
+        assert_eq!(tree.style_or_fallback(Ok(valid.clone())), Ok(valid));
+        assert_eq!(tree.decode_failure_count(), 2);
+
+        tree.clear_decode_fallback_style();
+        assert!(tree.style_or_fallback(malformed()).is_err());
+        assert_eq!(tree.decode_failure_count(), 3);
+    }
+}
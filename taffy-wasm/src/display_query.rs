@@ -0,0 +1,91 @@
+//! Finding the nodes of a subtree with a given `display`, e.g. to restyle every grid container at once
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// Parses a `display` value, accepting both the CSS spelling (`"grid"`) and the style object spelling (`"Grid"`)
+fn parse_display(display: &str) -> Result<Display, TaffyError> {
+    match display.to_ascii_lowercase().as_str() {
+        "flex" => Ok(Display::Flex),
+        #[cfg(feature = "grid")]
+        "grid" => Ok(Display::Grid),
+        #[cfg(feature = "block")]
+        "block" => Ok(Display::Block),
+        "none" => Ok(Display::None),
+        _ => Err(TaffyError::InvalidArgument(format!(
+            "{display:?} is not a display, expected \"flex\", \"grid\" or \"none\""
+        ))),
+    }
+}
+
+impl TaffyTree {
+    /// Returns the nodes of the subtree rooted at `root` whose style has `display`, in paint order, leaving out the
+    /// root unless `include_root` is set
+    fn nodes_with_display(&self, root: u32, display: Display, include_root: bool) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let mut nodes = Vec::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if (include_root || node != root) && tree.style(node)?.display == display {
+                nodes.push(js_node_id(node));
+            }
+            stack.extend(tree.children(node)?.into_iter().rev());
+        }
+        Ok(nodes)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the ids of the descendants of `root` whose `display` is `display`, one of `"flex"`, `"grid"` and
+    /// `"none"`, in paint order
+    ///
+    /// The root is only included if `include_root` is `true`. The match is on the `display` of each node's own
+    /// style, as returned by `get_style`, so a container is matched even without children, when it is laid out
+    /// as a leaf, and the descendants of a node with `display: "None"` are matched by their own `display` even
+    /// though they generate no box; use `formatting_context` to tell how a node is actually laid out. `display` can
+    /// also be spelled as in style objects, e.g. `"Grid"`.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `display` is not one of these values.
+    #[wasm_bindgen]
+    pub fn nodes_by_display(
+        &self,
+        root: u32,
+        display: &str,
+        include_root: Option<bool>,
+    ) -> Result<Vec<u32>, TaffyError> {
+        self.nodes_with_display(root, parse_display(display)?, include_root.unwrap_or(false))
+    }
+}
+
+#[cfg(all(test, feature = "grid"))]
+mod tests {
+    use super::*;
+    use taffy::style::Style;
+
+    #[test]
+    fn grid_and_flex_nodes_of_a_mixed_tree() {
+        let tree = TaffyTree::new();
+        let [root, outer_grid, inner_grid, row, leaf, hidden] = {
+            let mut inner = tree.inner.borrow_mut();
+            let grid = Style { display: Display::Grid, ..Default::default() };
+            let none = Style { display: Display::None, ..Default::default() };
+            let leaf = inner.new_leaf(Style::default()).unwrap();
+            let inner_grid = inner.new_leaf(grid.clone()).unwrap();
+            let row = inner.new_with_children(Style::default(), &[leaf, inner_grid]).unwrap();
+            let hidden = inner.new_leaf(none).unwrap();
+            let outer_grid = inner.new_with_children(grid, &[row, hidden]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[outer_grid]).unwrap();
+            [root, outer_grid, inner_grid, row, leaf, hidden].map(js_node_id)
+        };
+
+        assert_eq!(tree.nodes_by_display(root, "grid", None), Ok(vec![outer_grid, inner_grid]));
+        assert_eq!(tree.nodes_by_display(root, "flex", None), Ok(vec![row, leaf]));
+        assert_eq!(tree.nodes_by_display(root, "Flex", Some(true)), Ok(vec![root, row, leaf]));
+        assert_eq!(tree.nodes_by_display(root, "none", None), Ok(vec![hidden]));
+        assert_eq!(tree.nodes_by_display(row, "grid", None), Ok(vec![inner_grid]));
+        assert!(matches!(tree.nodes_by_display(root, "inline", None), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,77 @@
+//! Exporting a subtree as a Graphviz DOT graph, for seeing its structure and boxes at a glance when debugging
+use crate::layout_buffer::paint_order;
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns the subtree rooted at `root` as a DOT graph with a node statement per node and an edge per child
+    fn dot_graph(&self, root: u32) -> Result<String, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let mut dot = String::from("digraph taffy {\n  node [shape=box];\n");
+        for (node, _) in paint_order(&tree, root)? {
+            let id = js_node_id(node);
+            let layout = tree.layout(node)?;
+            let (location, size) = (layout.location, layout.size);
+            let display = tree.style(node)?.display;
+            let _ = writeln!(
+                dot,
+                "  n{id} [label=\"{id}\\n{display:?}\\n{},{} {}x{}\"];",
+                location.x, location.y, size.width, size.height
+            );
+            for child in tree.children(node)? {
+                let _ = writeln!(dot, "  n{id} -> n{};", js_node_id(child));
+            }
+        }
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the subtree rooted at `root` as a Graphviz DOT graph, for pasting into a DOT viewer
+    ///
+    /// Each node is a box labelled with its id, its `display`, and its box in the last layout as `x,y widthxheight`,
+    /// with `x` and `y` relative to its parent as in `layout_left` and `layout_top`. Each parent has an edge to each
+    /// of its children, in order. Nodes are listed in paint order, as in `layout_buffer`.
+    #[wasm_bindgen]
+    pub fn to_dot(&self, root: u32) -> Result<String, TaffyError> {
+        self.dot_graph(root)
+    }
+}
+
+#[cfg(all(test, feature = "grid"))]
+mod tests {
+    use super::*;
+    use taffy::prelude::{length, Display, Size, Style, TaffyMaxContent};
+
+    #[test]
+    fn dot_has_a_statement_per_node_and_an_edge_per_child() {
+        let tree = TaffyTree::new();
+        let (root, [grid, leaf]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let spacer = inner.new_leaf(Style { size: length(20.0), ..Default::default() }).unwrap();
+            let grid =
+                inner.new_with_children(Style { display: Display::Grid, ..Default::default() }, &[leaf]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[spacer, grid]).unwrap();
+            (js_node_id(root), [grid, leaf].map(js_node_id))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        let dot = tree.to_dot(root).unwrap();
+        assert!(dot.starts_with("digraph taffy {\n") && dot.ends_with("}\n"), "{dot}");
+        assert_eq!(dot.matches("[label=").count(), 4);
+        assert_eq!(dot.matches(" -> ").count(), 3);
+        assert!(dot.contains(&format!("  n{root} -> n{grid};\n")), "{dot}");
+        assert!(dot.contains(&format!("  n{grid} -> n{leaf};\n")), "{dot}");
+        assert!(dot.contains(&format!("  n{grid} [label=\"{grid}\\nGrid\\n20,0 10x20\"];\n")), "{dot}");
+
+        // A subtree is exported on its own
+        assert_eq!(tree.to_dot(grid).unwrap().matches("[label=").count(), 2);
+        assert_eq!(tree.to_dot(root + 1), Err(TaffyError::InvalidNode(root + 1)));
+    }
+}
@@ -0,0 +1,112 @@
+//! Laying out without reusing cached results, so that tests can observe every measurement
+use crate::measure_budget::{self, JsMeasureBudget, MeasureBudget};
+use crate::{compute_stats, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, Point};
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Lays out the tree rooted at `node_id` as `compute_layout_with_budget` does, but with caching disabled
+    fn compute_layout_uncached(
+        &self,
+        node_id: u32,
+        available_space: Size<AvailableSpace>,
+        budget: MeasureBudget,
+        measure: impl FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut JsContext>,
+        ) -> (Size<f32>, Point<Option<f32>>),
+    ) -> Result<(), TaffyError> {
+        self.tree_mut()?.disable_caching();
+        let result = self.compute_layout_with_budget(node_id, available_space, budget, compute_stats::now_ms, measure);
+        // The tree can't be in use once the layout has returned, so caching is always enabled again
+        if let Ok(mut tree) = self.tree_mut() {
+            tree.enable_caching();
+        }
+        result
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout like `compute_layout_with_measure`, but without reusing any cached result, so that
+    /// `measure_func` is called every time the layout algorithms ask for the size of a leaf
+    ///
+    /// Where `compute_layout_with_measure` measures a leaf once per distinct set of constraints and skips the
+    /// subtrees whose layout is unchanged, this measures every leaf of the tree on every call, once per visit,
+    /// even if nothing changed since the last layout. This makes the calls to `measure_func` deterministic, so a
+    /// test can assert on the constraints each leaf is measured with.
+    ///
+    /// This is much slower than `compute_layout_with_measure`, and is meant for tests and debugging rather than
+    /// production. The results are still cached afterwards, so a following `compute_layout_with_measure` of an
+    /// unchanged tree does no work. `budget` is as for `compute_layout_with_measure`.
+    #[wasm_bindgen]
+    pub fn compute_layout_eager_measure(
+        &self,
+        node_id: u32,
+        width: f32,
+        height: f32,
+        measure_func: &js_sys::Function,
+        budget: Option<JsMeasureBudget>,
+    ) -> Result<(), TaffyError> {
+        let budget = measure_budget::decode_budget(budget)?;
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        self.compute_layout_uncached(
+            node_id,
+            available_space,
+            budget,
+            |known_dimensions, available_space, node, context| {
+                self.call_js_measure_for(measure_func, &JsValue::NULL, known_dimensions, available_space, node, context)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use std::cell::Cell;
+
+    #[test]
+    fn every_visit_measures_the_leaf() {
+        // A shrinkable leaf in a flex row, which the flexbox algorithm sizes several times per pass
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style::default()).unwrap();
+            let row = Style { size: Size { width: length(50.0), height: auto() }, ..Default::default() };
+            js_node_id(inner.new_with_children(row, &[leaf]).unwrap())
+        };
+        let calls = Cell::new(0);
+        let measure = |known: Size<Option<f32>>, _, _, _: Option<&mut JsContext>| {
+            calls.set(calls.get() + 1);
+            (known.unwrap_or(Size { width: 100.0, height: 10.0 }), Point::NONE)
+        };
+        let count_calls = |eager: bool| {
+            calls.set(0);
+            let available_space = Size::MAX_CONTENT;
+            if eager {
+                tree.compute_layout_uncached(root, available_space, MeasureBudget::default(), measure).unwrap();
+            } else {
+                tree.compute_layout_with_budget(root, available_space, MeasureBudget::default(), || 0.0, measure)
+                    .unwrap();
+            }
+            calls.get()
+        };
+
+        let cached_calls = count_calls(false);
+        assert!(cached_calls > 0);
+        assert_eq!(count_calls(false), 0);
+
+        // Every pass measures the leaf once per visit, including the visits that a cold pass answers from the cache
+        let eager_calls = count_calls(true);
+        assert!(eager_calls > cached_calls);
+        assert_eq!(count_calls(true), eager_calls);
+        assert_eq!(tree.last_compute_stats.get().measure_calls, eager_calls);
+
+        // Caching is enabled again afterwards, and the results of the eager pass are reused
+        assert_eq!(count_calls(false), 0);
+    }
+}
@@ -0,0 +1,233 @@
+//! The error type returned to JavaScript by the [`TaffyTree`](crate::TaffyTree) bindings
+use crate::node_ids::{js_node_id, MAX_NODES};
+use serde::Serialize;
+use std::fmt;
+use taffy::TaffyError as CoreTaffyError;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TAFFY_ERROR_TS: &'static str = r#"
+/**
+ * The object thrown by TaffyTree methods when an operation fails.
+ * Branch on `code`: the codes are stable, whereas `message` is meant for humans and may change.
+ */
+export interface TaffyError {
+  code: "INVALID_NODE" | "CHILD_NOT_FOUND" | "CHILD_INDEX_OUT_OF_BOUNDS" | "STYLE_DECODE" | "INVALID_ARGUMENT" | "BORROW_CONFLICT" | "MEASURE_BUDGET_EXCEEDED" | "NON_FINITE_LAYOUT" | "LAYOUT_TOO_DEEP" | "BATCH_IN_PROGRESS" | "TOO_MANY_NODES";
+  message: string;
+  /** The node the error relates to, if any */
+  nodeId?: number;
+  /** Every node the error relates to, for errors raised by operations on many nodes at once */
+  nodeIds?: number[];
+}
+"#;
+
+/// An error that occurs when operating on a [`TaffyTree`](crate::TaffyTree)
+///
+/// Thrown to JavaScript as a `{ code, message, nodeId? }` object. Each variant documents its `code`: these are part
+/// of the public API and must not change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaffyError {
+    /// `INVALID_NODE`: the node id does not refer to a node in the tree
+    InvalidNode(u32),
+    /// `INVALID_NODE`: several node ids passed to a bulk operation do not refer to nodes in the tree
+    InvalidNodes(Vec<u32>),
+    /// `CHILD_NOT_FOUND`: the `child` node is not a child of the `parent` node
+    ChildNotFound {
+        /// The parent node whose children were searched
+        parent: u32,
+        /// The node that was looked for
+        child: u32,
+    },
+    /// `CHILD_INDEX_OUT_OF_BOUNDS`: the parent node does not have a child at `child_index`
+    ChildIndexOutOfBounds {
+        /// The parent node whose child was being looked up
+        parent: u32,
+        /// The index that was looked up
+        child_index: usize,
+        /// The total number of children the parent has
+        child_count: usize,
+    },
+    /// `STYLE_DECODE`: the style object could not be converted to a `Style`
+    StyleDecode(String),
+    /// `INVALID_ARGUMENT`: an argument other than a style could not be decoded
+    InvalidArgument(String),
+    /// `BORROW_CONFLICT`: the tree was accessed while already in use, e.g. from inside a measure function
+    BorrowConflict,
+    /// `MEASURE_BUDGET_EXCEEDED`: a layout made more measure calls, or took longer, than its budget allows
+    MeasureBudgetExceeded {
+        /// The node that was about to be measured when the budget ran out
+        node: u32,
+        /// The number of measure calls made before the budget ran out
+        measure_calls: u32,
+        /// The time taken by the layout before the budget ran out, if it had a time limit
+        elapsed_ms: f64,
+    },
+    /// `NON_FINITE_LAYOUT`: a node laid out with validation enabled has a NaN or infinite style value or layout
+    NonFiniteLayout {
+        /// The node whose layout is not finite
+        node: u32,
+        /// Which value is not finite, and what likely caused it
+        reason: String,
+    },
+    /// `LAYOUT_TOO_DEEP`: a subtree to be laid out is nested more deeply than the tree's maximum layout depth
+    LayoutTooDeep {
+        /// The first node, in paint order, nested more deeply than the limit
+        node: u32,
+        /// The maximum layout depth of the tree
+        max_depth: u32,
+    },
+    /// `BATCH_IN_PROGRESS`: the tree was to be laid out while a batch of edits opened with `begin_batch` is open
+    BatchInProgress,
+    /// `TOO_MANY_NODES`: a node was to be created in a tree that already holds as many nodes as node ids can refer to
+    TooManyNodes,
+}
+
+impl TaffyError {
+    /// The stable code identifying the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            TaffyError::InvalidNode(_) | TaffyError::InvalidNodes(_) => "INVALID_NODE",
+            TaffyError::ChildNotFound { .. } => "CHILD_NOT_FOUND",
+            TaffyError::ChildIndexOutOfBounds { .. } => "CHILD_INDEX_OUT_OF_BOUNDS",
+            TaffyError::StyleDecode(_) => "STYLE_DECODE",
+            TaffyError::InvalidArgument(_) => "INVALID_ARGUMENT",
+            TaffyError::BorrowConflict => "BORROW_CONFLICT",
+            TaffyError::MeasureBudgetExceeded { .. } => "MEASURE_BUDGET_EXCEEDED",
+            TaffyError::NonFiniteLayout { .. } => "NON_FINITE_LAYOUT",
+            TaffyError::LayoutTooDeep { .. } => "LAYOUT_TOO_DEEP",
+            TaffyError::BatchInProgress => "BATCH_IN_PROGRESS",
+            TaffyError::TooManyNodes => "TOO_MANY_NODES",
+        }
+    }
+
+    /// The node the error relates to, if any
+    pub fn node_id(&self) -> Option<u32> {
+        match self {
+            TaffyError::InvalidNode(node) => Some(*node),
+            TaffyError::InvalidNodes(nodes) => nodes.first().copied(),
+            TaffyError::ChildNotFound { child, .. } => Some(*child),
+            TaffyError::ChildIndexOutOfBounds { parent, .. } => Some(*parent),
+            TaffyError::MeasureBudgetExceeded { node, .. }
+            | TaffyError::NonFiniteLayout { node, .. }
+            | TaffyError::LayoutTooDeep { node, .. } => Some(*node),
+            TaffyError::StyleDecode(_)
+            | TaffyError::InvalidArgument(_)
+            | TaffyError::BorrowConflict
+            | TaffyError::BatchInProgress
+            | TaffyError::TooManyNodes => None,
+        }
+    }
+}
+
+impl fmt::Display for TaffyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaffyError::InvalidNode(node) => write!(f, "Node {node} is not in the TaffyTree instance"),
+            TaffyError::InvalidNodes(nodes) => {
+                let nodes = nodes.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+                write!(f, "Nodes {nodes} are not in the TaffyTree instance")
+            }
+            TaffyError::ChildNotFound { parent, child } => write!(f, "Node {child} is not a child of node {parent}"),
+            TaffyError::ChildIndexOutOfBounds { parent, child_index, child_count } => {
+                write!(f, "Index (is {child_index}) should be < child_count ({child_count}) for parent node {parent}")
+            }
+            TaffyError::StyleDecode(reason) => write!(f, "Failed to decode style: {reason}"),
+            TaffyError::InvalidArgument(reason) => write!(f, "Invalid argument: {reason}"),
+            TaffyError::BorrowConflict => {
+                write!(f, "The TaffyTree is already in use and cannot be accessed re-entrantly")
+            }
+            TaffyError::MeasureBudgetExceeded { node, measure_calls, elapsed_ms } => write!(
+                f,
+                "Layout exceeded its measure budget at node {node} after {measure_calls} measure calls ({elapsed_ms}ms), \
+                 which indicates a bug in the measure function"
+            ),
+            TaffyError::NonFiniteLayout { node, reason } => write!(f, "Layout of node {node} is not finite: {reason}"),
+            TaffyError::LayoutTooDeep { node, max_depth } => {
+                write!(f, "Node {node} is nested more than {max_depth} levels below the root being laid out")
+            }
+            TaffyError::BatchInProgress => {
+                write!(f, "The TaffyTree cannot be laid out while a batch of edits is open; call end_batch first")
+            }
+            TaffyError::TooManyNodes => write!(f, "The TaffyTree cannot hold more than {MAX_NODES} nodes"),
+        }
+    }
+}
+
+impl std::error::Error for TaffyError {}
+
+impl From<CoreTaffyError> for TaffyError {
+    fn from(error: CoreTaffyError) -> Self {
+        match error {
+            CoreTaffyError::ChildIndexOutOfBounds { parent, child_index, child_count } => {
+                TaffyError::ChildIndexOutOfBounds { parent: js_node_id(parent), child_index, child_count }
+            }
+            CoreTaffyError::InvalidParentNode(node)
+            | CoreTaffyError::InvalidChildNode(node)
+            | CoreTaffyError::InvalidInputNode(node) => TaffyError::InvalidNode(js_node_id(node)),
+        }
+    }
+}
+
+/// The shape of a [`TaffyError`] once thrown to JavaScript
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsTaffyError {
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node_ids: Option<Vec<u32>>,
+}
+
+impl From<TaffyError> for JsValue {
+    fn from(error: TaffyError) -> Self {
+        let node_ids = match &error {
+            TaffyError::InvalidNodes(nodes) => Some(nodes.clone()),
+            _ => None,
+        };
+        let js_error =
+            JsTaffyError { code: error.code(), message: error.to_string(), node_id: error.node_id(), node_ids };
+        serde_wasm_bindgen::to_value(&js_error).unwrap_or_else(|_| JsValue::from_str(&js_error.message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::NodeId;
+
+    #[test]
+    fn core_errors_map_to_invalid_node() {
+        let node = NodeId::from(7u64);
+        for error in [
+            CoreTaffyError::InvalidParentNode(node),
+            CoreTaffyError::InvalidChildNode(node),
+            CoreTaffyError::InvalidInputNode(node),
+        ] {
+            let error = TaffyError::from(error);
+            assert_eq!(error, TaffyError::InvalidNode(7));
+            assert_eq!(error.code(), "INVALID_NODE");
+            assert_eq!(error.node_id(), Some(7));
+        }
+    }
+
+    #[test]
+    fn child_index_out_of_bounds_reports_parent() {
+        let error = TaffyError::from(CoreTaffyError::ChildIndexOutOfBounds {
+            parent: NodeId::from(3u64),
+            child_index: 2,
+            child_count: 1,
+        });
+        assert_eq!(error.code(), "CHILD_INDEX_OUT_OF_BOUNDS");
+        assert_eq!(error.node_id(), Some(3));
+        assert_eq!(error.to_string(), "Index (is 2) should be < child_count (1) for parent node 3");
+    }
+
+    #[test]
+    fn errors_without_a_node() {
+        assert_eq!(TaffyError::StyleDecode("bad".into()).node_id(), None);
+        assert_eq!(TaffyError::BorrowConflict.node_id(), None);
+        assert_eq!(TaffyError::BorrowConflict.code(), "BORROW_CONFLICT");
+    }
+}
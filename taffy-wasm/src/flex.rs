@@ -0,0 +1,420 @@
+//! Accessors for the computed layout of flex containers, and setters for their most often toggled properties
+use crate::{resolve_node, set_style_if_changed, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::style::{Display, FlexDirection, FlexWrap, Position, Style};
+use taffy::{DetailedFlexLineInfo, DetailedLayoutInfo, NodeId};
+use wasm_bindgen::prelude::*;
+
+/// A flex line of a flex container, returned by `flex_lines`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlexLineBox {
+    /// The offset of the cross-start edge of the line from the container's border box
+    pub cross_offset: f32,
+    /// The size of the line in the cross axis
+    pub cross_size: f32,
+    /// The main-axis space left over once the line's items and gaps were sized, which is negative if they overflow
+    pub main_free_space: f32,
+    /// The main-axis space placed before each item in the line by `justify-content`, including the gap before it
+    pub main_offsets: Vec<f32>,
+}
+
+impl From<&DetailedFlexLineInfo> for FlexLineBox {
+    fn from(line: &DetailedFlexLineInfo) -> Self {
+        FlexLineBox {
+            cross_offset: line.cross_offset,
+            cross_size: line.cross_size,
+            main_free_space: line.main_free_space,
+            main_offsets: line.main_offsets.clone(),
+        }
+    }
+}
+
+/// Parses a `flex-wrap` value, accepting both its CSS and its style object spelling
+fn parse_flex_wrap(wrap: &str) -> Result<FlexWrap, TaffyError> {
+    match wrap {
+        "nowrap" | "NoWrap" => Ok(FlexWrap::NoWrap),
+        "wrap" | "Wrap" => Ok(FlexWrap::Wrap),
+        "wrap-reverse" | "WrapReverse" => Ok(FlexWrap::WrapReverse),
+        _ => Err(TaffyError::InvalidArgument(format!(
+            "{wrap:?} is not a flex-wrap value, expected \"nowrap\", \"wrap\" or \"wrap-reverse\""
+        ))),
+    }
+}
+
+impl TaffyTree {
+    /// Returns the flex lines of a flex container
+    fn line_boxes(&self, node_id: u32) -> Result<Vec<FlexLineBox>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Flexbox(info) => info.lines.iter().map(FlexLineBox::from).collect(),
+            _ => Vec::new(),
+        })
+    }
+
+    /// Returns the flex base size of a flex item from the last layout of its parent, if it is an in-flow flex item
+    fn item_flex_basis(&self, node_id: u32) -> Result<Option<f32>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let Some(parent) = tree.parent(node) else {
+            return Ok(None);
+        };
+        let DetailedLayoutInfo::Flexbox(info) = tree.detailed_layout_info(parent) else {
+            return Ok(None);
+        };
+        // The lines hold the in-flow children in order, as flex items are generated from them
+        let in_flow = |child: &NodeId| {
+            let style = tree.style(*child);
+            style.is_ok_and(|style| style.position != Position::Absolute && style.display != Display::None)
+        };
+        let Some(index) = tree.children(parent)?.iter().filter(|child| in_flow(child)).position(|&child| child == node)
+        else {
+            return Ok(None);
+        };
+        Ok(info.lines.iter().flat_map(|line| &line.flex_bases).nth(index).copied())
+    }
+
+    /// Returns the pixels by which a flex item was shrunk from its flex base size in the last layout of its parent
+    fn item_shrink(&self, node_id: u32) -> Result<f32, TaffyError> {
+        let Some(flex_basis) = self.item_flex_basis(node_id)? else {
+            return Ok(0.0);
+        };
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let parent = tree.parent(node).ok_or(TaffyError::InvalidNode(node_id))?;
+        let size = tree.unrounded_layout(node).size;
+        let main_size = match tree.style(parent)?.flex_direction {
+            FlexDirection::Row | FlexDirection::RowReverse => size.width,
+            FlexDirection::Column | FlexDirection::ColumnReverse => size.height,
+        };
+        Ok((flex_basis - main_size).max(0.0))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the flex lines of a flex container from the last layout, for debugging `justify-content` and
+    /// `align-content`
+    ///
+    /// Each line is `{ crossOffset, crossSize, mainFreeSpace, mainOffsets }`:
+    ///
+    /// - `crossOffset` and `crossSize` place the line in the cross axis (vertically for a row), after
+    ///   `align-content`, relative to the container's border box
+    /// - `mainFreeSpace` is the main-axis space left over once the line's items and gaps were sized, which
+    ///   `justify-content` (or `auto` margins) distributed. It is negative if the items overflow the line.
+    /// - `mainOffsets` is the space `justify-content` placed before each item of the line, including the gap before it
+    ///
+    /// Lines are listed in the order they are filled, so the first line is at the cross end under
+    /// `flex-wrap: wrap-reverse`. The array is empty if the node is not a flex container, has no children (childless
+    /// containers are sized as leaves), or has not been laid out yet.
+    #[wasm_bindgen]
+    pub fn flex_lines(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let lines = self.line_boxes(node_id)?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        Ok(lines.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the number of flex lines of a flex container from the last layout, e.g. to show "+N more" once a
+    /// row of chips wraps
+    ///
+    /// This is the length of `flex_lines(node_id)` without building the lines: 1 for a container with children
+    /// under `flex-wrap: nowrap`, and 0 if the node is not a flex container, has no children, or has not been laid
+    /// out yet.
+    #[wasm_bindgen]
+    pub fn flex_line_count(&self, node_id: u32) -> Result<u32, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Flexbox(info) => info.lines.len() as u32,
+            _ => 0,
+        })
+    }
+
+    /// Returns the flex base size of a flex item in pixels, as resolved in the last layout of its parent, or
+    /// `undefined` if the node is not an in-flow item of a laid out flex container
+    ///
+    /// The flex base size is the item's border-box main size before it is grown or shrunk: its `flex_basis`
+    /// resolved to pixels, or its main size or content size for an `auto` basis, floored at its padding and border
+    /// but not clamped by its min and max sizes. Comparing it with the item's final size shows how much
+    /// `flex_grow` or `flex_shrink` changed it, which is the first thing to check when free space is distributed
+    /// unexpectedly.
+    #[wasm_bindgen]
+    pub fn resolved_flex_basis(&self, node_id: u32) -> Result<Option<f32>, TaffyError> {
+        self.item_flex_basis(node_id)
+    }
+
+    /// Returns the pixels by which a flex item was shrunk from its flex base size in the last layout of its parent,
+    /// or 0 if it grew, kept its base size, or is not an in-flow item of a laid out flex container
+    ///
+    /// This is `resolved_flex_basis` minus the item's final main size, before rounding. When the items of a line
+    /// overflow it, `flex_shrink` takes the overflow, the negative `main` of `resolved_free_space`, out of them in
+    /// proportion to their `flex_shrink` times their base size, so the amounts of a line add up to the overflow
+    /// unless min sizes (including the automatic minimum size of items with content) stopped some from shrinking.
+    /// An item much smaller than expected usually has a large base size rather than a large `flex_shrink`.
+    #[wasm_bindgen]
+    pub fn resolved_shrink(&self, node_id: u32) -> Result<f32, TaffyError> {
+        self.item_shrink(node_id)
+    }
+
+    /// Sets the `flex_wrap` of a node to `"nowrap"`, `"wrap"` or `"wrap-reverse"`, leaving the rest of its style as is
+    ///
+    /// This is a shorthand for replacing the style with `update_style` with only `flex_wrap` changed, e.g. for a
+    /// toolbar that wraps once `flex_line_count` shows its items no longer fit, or stops wrapping when there is room
+    /// again. Like `update_style`, the node is only marked dirty if its `flex_wrap` changes.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `wrap` is not one of the three values.
+    #[wasm_bindgen]
+    pub fn set_flex_wrap(&self, node_id: u32, wrap: &str) -> Result<(), TaffyError> {
+        let flex_wrap = parse_flex_wrap(wrap)?;
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let style = Style { flex_wrap, ..tree.style(node)?.clone() };
+        set_style_if_changed(&mut tree, node, style)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_stats::now_ms;
+    use crate::measure_budget::MeasureBudget;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use crate::JsStyle;
+    use taffy::prelude::{
+        auto, length, percent, AlignContent, AvailableSpace, Dimension, FlexDirection, FlexWrap, JustifyContent, Size,
+        Style, TaffyMaxContent, TaffyMinContent,
+    };
+    use taffy::style::CompactLength;
+    use taffy::Point;
+
+    #[test]
+    fn wrapping_row_reports_line_boxes() {
+        let tree = TaffyTree::new();
+        let (container, item) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(60.0), height: length(10.0) }, ..Default::default() };
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item).unwrap()];
+            let row = Style {
+                flex_wrap: FlexWrap::Wrap,
+                align_content: Some(AlignContent::Center),
+                justify_content: Some(JustifyContent::Center),
+                size: Size { width: length(100.0), height: length(40.0) },
+                ..Default::default()
+            };
+            let container = inner.new_with_children(row, &items).unwrap();
+            inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+            (js_node_id(container), js_node_id(items[0]))
+        };
+
+        let lines = tree.line_boxes(container).unwrap();
+        let line = |cross_offset| FlexLineBox {
+            cross_offset,
+            cross_size: 10.0,
+            main_free_space: 40.0,
+            main_offsets: vec![20.0],
+        };
+        assert_eq!(lines, vec![line(10.0), line(20.0)]);
+
+        // Leaves have no flex lines
+        assert_eq!(tree.line_boxes(item), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn line_count_of_wrapping_and_nowrap_rows() {
+        let tree = TaffyTree::new();
+        let (wrapping, nowrap, item) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(40.0), height: length(10.0) }, ..Default::default() };
+            let items: Vec<_> = (0..5).map(|_| inner.new_leaf(item.clone()).unwrap()).collect();
+            // Two 40px items fit in each line of the 100px wide row, so five of them wrap onto three lines
+            let row = Style {
+                flex_wrap: FlexWrap::Wrap,
+                size: Size { width: length(100.0), height: auto() },
+                ..Default::default()
+            };
+            let wrapping = inner.new_with_children(row.clone(), &items).unwrap();
+            let nowrap_items: Vec<_> = (0..5).map(|_| inner.new_leaf(item.clone()).unwrap()).collect();
+            let nowrap = inner.new_with_children(Style { flex_wrap: FlexWrap::NoWrap, ..row }, &nowrap_items).unwrap();
+            inner.compute_layout(wrapping, Size::MAX_CONTENT).unwrap();
+            inner.compute_layout(nowrap, Size::MAX_CONTENT).unwrap();
+            (js_node_id(wrapping), js_node_id(nowrap), js_node_id(items[0]))
+        };
+
+        assert_eq!(tree.flex_line_count(wrapping), Ok(3));
+        assert_eq!(tree.flex_line_count(nowrap), Ok(1));
+        assert_eq!(tree.flex_line_count(item), Ok(0));
+    }
+
+    #[test]
+    fn resolved_flex_bases_of_items_with_differing_bases() {
+        let tree = TaffyTree::new();
+        let (row, [basis, sized, text, percentage, absolute]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let items = [
+                Style { flex_basis: length(50.0), flex_grow: 1.0, ..Default::default() },
+                Style { size: Size { width: length(80.0), height: auto() }, flex_shrink: 0.0, ..Default::default() },
+                Style { padding: length(10.0), ..Default::default() },
+                Style { flex_basis: percent(0.1), ..Default::default() },
+                Style { position: Position::Absolute, ..Default::default() },
+            ]
+            .map(|item| inner.new_leaf(item).unwrap());
+            let row = Style { size: Size { width: length(300.0), height: auto() }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        tree.set_content_size(text, 60.0, 20.0).unwrap();
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(tree.resolved_flex_basis(basis), Ok(Some(50.0)));
+        assert_eq!(tree.resolved_flex_basis(sized), Ok(Some(80.0)));
+        // An auto basis without a size uses the content size, plus the padding
+        assert_eq!(tree.resolved_flex_basis(text), Ok(Some(80.0)));
+        assert_eq!(tree.resolved_flex_basis(percentage), Ok(Some(30.0)));
+        // The first item grew from its basis to take the remaining 60px
+        assert_eq!(tree.layout_width(basis), Ok(110.0));
+
+        assert_eq!(tree.resolved_flex_basis(absolute), Ok(None));
+        assert_eq!(tree.resolved_flex_basis(row), Ok(None));
+    }
+
+    #[test]
+    fn shrink_amounts_of_an_overflowing_row_sum_to_its_overflow() {
+        let tree = TaffyTree::new();
+        let (row, items) = {
+            let mut inner = tree.inner.borrow_mut();
+            let items = [(200.0, 1.0), (100.0, 1.0), (100.0, 2.0), (50.0, 0.0)].map(|(basis, flex_shrink)| {
+                inner.new_leaf(Style { flex_basis: length(basis), flex_shrink, ..Default::default() }).unwrap()
+            });
+            let row = Style { size: Size { width: length(300.0), height: auto() }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+
+        // The 150px overflow is taken out in proportion to flex_shrink times the base size: 200, 100 and 200
+        let shrinks = items.map(|item| tree.resolved_shrink(item).unwrap());
+        assert_eq!(shrinks, [60.0, 30.0, 60.0, 0.0]);
+        let free_space = tree.free_space(row).unwrap().unwrap().main;
+        assert_eq!(shrinks.iter().sum::<f32>(), -free_space);
+
+        // Items that grow, and nodes that aren't flex items, weren't shrunk
+        let wide = Style { size: Size { width: length(600.0), height: auto() }, ..Default::default() };
+        tree.inner.borrow_mut().set_style(node_from_js_id(row), wide).unwrap();
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+        assert_eq!(items.map(|item| tree.resolved_shrink(item).unwrap()), [0.0; 4]);
+        assert_eq!(tree.resolved_shrink(row), Ok(0.0));
+    }
+
+    #[test]
+    fn toggling_wrap_changes_the_line_count() {
+        let tree = TaffyTree::new();
+        let row = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(40.0), height: length(10.0) }, ..Default::default() };
+            let items: Vec<_> = (0..5).map(|_| inner.new_leaf(item.clone()).unwrap()).collect();
+            let row = Style { size: Size { width: length(100.0), height: auto() }, ..Default::default() };
+            js_node_id(inner.new_with_children(row, &items).unwrap())
+        };
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.flex_line_count(row), Ok(1));
+
+        tree.set_flex_wrap(row, "wrap").unwrap();
+        assert!(tree.dirty(row).unwrap());
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.flex_line_count(row), Ok(3));
+
+        // Setting the current value leaves the row clean
+        tree.set_flex_wrap(row, "wrap").unwrap();
+        assert!(!tree.dirty(row).unwrap());
+
+        tree.set_flex_wrap(row, "nowrap").unwrap();
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.flex_line_count(row), Ok(1));
+
+        assert!(matches!(tree.set_flex_wrap(row, "wrap_reverse"), Err(TaffyError::InvalidArgument(_))));
+    }
+
+    /// Lays out an item decoded from `item` in a 300px wide row, measured as 80x20, and returns its width
+    fn measured_item_width(item: serde_json::Value) -> f32 {
+        let JsStyle(item) = serde_json::from_value(item).unwrap();
+        let tree = TaffyTree::new();
+        let (row, item) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = inner.new_leaf(item).unwrap();
+            let row = Style { size: Size { width: length(300.0), height: auto() }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &[item]).unwrap()), js_node_id(item))
+        };
+        let measure = |_, _, _, _: Option<&mut _>| (Size { width: 80.0, height: 20.0 }, Point::NONE);
+        tree.compute_layout_with_budget(row, Size::MAX_CONTENT, MeasureBudget::default(), now_ms, measure).unwrap();
+        tree.layout_width(item).unwrap()
+    }
+
+    #[test]
+    fn flex_basis_auto_sizes_the_item_to_its_content() {
+        let auto = CompactLength::auto().to_serialized();
+        let px = |value| CompactLength::length(value).to_serialized();
+        let key = if cfg!(feature = "camel-case-style") { "flexBasis" } else { "flex_basis" };
+        // An absent basis is `auto`, which uses the measured max-content size while the main size is `auto` too
+        assert_eq!(measured_item_width(serde_json::json!({})), 80.0);
+        assert_eq!(measured_item_width(serde_json::json!({ key: auto })), 80.0);
+        // Unlike CSS `content`, `auto` defers to a definite main size
+        let sized = serde_json::json!({ key: auto, "size": { "width": px(50.0), "height": auto } });
+        assert_eq!(measured_item_width(sized), 50.0);
+        let basis = serde_json::json!({ key: px(120.0), "size": { "width": px(50.0), "height": auto } });
+        assert_eq!(measured_item_width(basis), 120.0);
+
+        // `content` can't be expressed: intrinsic sizing keywords fail to decode as a basis
+        let max_content = CompactLength::max_content().to_serialized();
+        assert!(serde_json::from_value::<JsStyle>(serde_json::json!({ key: max_content })).is_err());
+    }
+
+    /// Lays out a row of width `row_width` holding a 100px item, an empty item with `min-width: 50%` and a 100px
+    /// item with `max-width: 25%`, returning the widths of the row and of each item
+    fn percentage_limited_row_widths(row_width: Dimension, available_space: Size<AvailableSpace>) -> [f32; 4] {
+        let tree = TaffyTree::new();
+        let (row, items) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fixed = Style { size: Size { width: length(100.0), height: length(10.0) }, ..Default::default() };
+            let min = Style { min_size: Size { width: percent(0.5), height: auto() }, ..Default::default() };
+            let max = Style { max_size: Size { width: percent(0.25), height: auto() }, ..fixed.clone() };
+            let items = [fixed, min, max].map(|item| inner.new_leaf(item).unwrap());
+            let row = Style { size: Size { width: row_width, height: auto() }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        tree.compute_subtree(row, available_space).unwrap();
+        let width = |node| tree.layout_width(node).unwrap();
+        [width(row), width(items[0]), width(items[1]), width(items[2])]
+    }
+
+    #[test]
+    fn percentage_min_and_max_widths_resolve_against_the_row() {
+        // In a 200px row, the min and max widths are 100px and 50px, and the first item shrinks to make room
+        let definite = percentage_limited_row_widths(length(200.0), Size::MAX_CONTENT);
+        assert_eq!(definite, [200.0, 50.0, 100.0, 50.0]);
+
+        // A content-sized row ignores the percentages while it is sized from its items' contributions, 100px each
+        // for the fixed items and nothing for the empty one, then resolves them against its resulting 200px width
+        let definite_space = Size { width: AvailableSpace::Definite(400.0), height: AvailableSpace::Definite(400.0) };
+        for available_space in [Size::MAX_CONTENT, Size::MIN_CONTENT, definite_space] {
+            assert_eq!(percentage_limited_row_widths(auto(), available_space), definite);
+        }
+    }
+
+    #[test]
+    fn percentage_min_height_is_ignored_in_a_content_sized_column() {
+        let tree = TaffyTree::new();
+        let (column, item) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fixed = inner.new_leaf(Style { size: length(50.0), ..Default::default() }).unwrap();
+            let min = Style { min_size: Size { width: auto(), height: percent(0.5) }, ..Default::default() };
+            let item = inner.new_leaf(min).unwrap();
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            (js_node_id(inner.new_with_children(column, &[fixed, item]).unwrap()), js_node_id(item))
+        };
+        tree.compute_subtree(column, Size::MAX_CONTENT).unwrap();
+
+        // An auto height is indefinite, so the percentage behaves as `auto` even once the column is laid out
+        assert_eq!(tree.layout_height(column), Ok(50.0));
+        assert_eq!(tree.layout_height(item), Ok(0.0));
+    }
+}
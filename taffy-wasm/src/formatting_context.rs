@@ -0,0 +1,84 @@
+//! Reporting which layout algorithm a node lays out its children with, i.e. the formatting context it establishes
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// Returns the name of the formatting context a node establishes, as for `formatting_context`
+pub(crate) fn formatting_context_of(tree: &Taffy<JsContext>, node: NodeId) -> Result<&'static str, TaffyError> {
+    for ancestor in std::iter::successors(Some(node), |&ancestor| tree.parent(ancestor)) {
+        if tree.style(ancestor)?.display == Display::None {
+            return Ok("none");
+        }
+    }
+    if tree.child_count(node) == 0 {
+        return Ok("leaf");
+    }
+    // `Display::Grid` and `Display::Block` only exist with the `grid` and `block` features
+    #[allow(unreachable_patterns)]
+    Ok(match tree.style(node)?.display {
+        Display::Flex => "flex",
+        #[cfg(feature = "grid")]
+        Display::Grid => "grid",
+        Display::None => "none",
+        _ => "block",
+    })
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the formatting context a node establishes for its children: `"flex"`, `"grid"`, `"block"`, `"leaf"`
+    /// or `"none"`
+    ///
+    /// This is the layout algorithm the node is laid out with, which sizes and positions its children and decides
+    /// what their percentages resolve against. It follows from the node's `display`, except that:
+    ///
+    /// - a node without children is `"leaf"` whatever its `display`: it is sized by its style and content size or
+    ///   measure function alone, like a replaced element
+    /// - a node with `display: "None"`, or with an ancestor that has it, is `"none"`: it generates no box, so it
+    ///   and its whole subtree are laid out with zero size
+    ///
+    /// Positioning doesn't affect the context a node establishes: an absolutely positioned flex container is taken
+    /// out of the flow of its parent, but still lays its own children out as a flex container. `"grid"` and `"block"`
+    /// only occur in builds with the `grid` and `block` features, the first of which is enabled by default.
+    #[wasm_bindgen]
+    pub fn formatting_context(&self, node_id: u32) -> Result<String, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(formatting_context_of(&tree, node)?.to_string())
+    }
+}
+
+#[cfg(all(test, feature = "grid"))]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn formatting_context_follows_display_children_and_hidden_ancestors() {
+        let tree = TaffyTree::new();
+        let [grid, empty_grid, absolute_flex, hidden, hidden_child, root] = {
+            let mut inner = tree.inner.borrow_mut();
+            let mut leaf = || inner.new_leaf(Style::default()).unwrap();
+            let [a, b, c, d] = [leaf(), leaf(), leaf(), leaf()];
+            let grid = inner.new_with_children(Style { display: Display::Grid, ..Default::default() }, &[a]).unwrap();
+            let empty_grid = inner.new_leaf(Style { display: Display::Grid, ..Default::default() }).unwrap();
+            let absolute = Style { position: Position::Absolute, ..Default::default() };
+            let absolute_flex = inner.new_with_children(absolute, &[b]).unwrap();
+            let hidden_child = inner.new_with_children(Style::default(), &[c]).unwrap();
+            let none = Style { display: Display::None, ..Default::default() };
+            let hidden = inner.new_with_children(none, &[hidden_child, d]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[grid, empty_grid, absolute_flex, hidden]).unwrap();
+            [grid, empty_grid, absolute_flex, hidden, hidden_child, root].map(js_node_id)
+        };
+
+        let context = |node| tree.formatting_context(node).unwrap();
+        assert_eq!(context(root), "flex");
+        assert_eq!(context(grid), "grid");
+        assert_eq!(context(empty_grid), "leaf");
+        assert_eq!(context(absolute_flex), "flex");
+        assert_eq!(context(hidden), "none");
+        // A flex container inside a hidden node generates no box either
+        assert_eq!(context(hidden_child), "none");
+        assert_eq!(tree.formatting_context(root + 1), Err(TaffyError::InvalidNode(root + 1)));
+    }
+}
@@ -0,0 +1,138 @@
+//! Reporting the free space flex and grid containers had to distribute, for debugging why items did or didn't grow
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+#[cfg(feature = "grid")]
+use taffy::DetailedGridTracksInfo;
+use taffy::DetailedLayoutInfo;
+use wasm_bindgen::prelude::*;
+
+/// The free space of a flex or grid container, returned by `resolved_free_space`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResolvedFreeSpace {
+    /// The free space in the main axis of a flex container, or in the inline (column) axis of a grid container
+    pub main: f32,
+    /// The free space in the cross axis of a flex container, or in the block (row) axis of a grid container
+    pub cross: f32,
+}
+
+/// Returns the space of `inner_size` left over once the tracks and gutters of an axis were sized
+#[cfg(feature = "grid")]
+fn track_free_space(tracks: &DetailedGridTracksInfo, inner_size: f32) -> f32 {
+    inner_size - tracks.sizes.iter().sum::<f32>() - tracks.gutters.iter().sum::<f32>()
+}
+
+impl TaffyTree {
+    /// Returns the free space of a flex or grid container from its last layout
+    pub(crate) fn free_space(&self, node_id: u32) -> Result<Option<ResolvedFreeSpace>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Flexbox(info) => info
+                .lines
+                .first()
+                .map(|line| ResolvedFreeSpace { main: line.initial_main_free_space, cross: info.cross_free_space }),
+            #[cfg(feature = "grid")]
+            DetailedLayoutInfo::Grid(info) => {
+                let layout = tree.unrounded_layout(node);
+                let inner_width = layout.content_box_width() - layout.scrollbar_size.width;
+                let inner_height = layout.content_box_height() - layout.scrollbar_size.height;
+                Some(ResolvedFreeSpace {
+                    main: track_free_space(&info.columns, inner_width),
+                    cross: track_free_space(&info.rows, inner_height),
+                })
+            }
+            _ => None,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the free space of a flex or grid container from its last layout as `{ main, cross }` in pixels, or
+    /// `undefined` if the node was not laid out as one
+    ///
+    /// For a flex container, `main` is the space left in its first line once the items were given their
+    /// hypothetical main sizes, before `flex_grow` or `flex_shrink` were applied: the items grow into it if it is
+    /// positive and shrink by it if it is negative, so a positive value with items that didn't grow points at their
+    /// `flex_grow`, or at max sizes that froze them. `cross` is the space left once the lines were sized, which
+    /// `align-content` distributes. For a grid container, `main` and `cross` are the space left in the column and
+    /// row axes once the tracks and gaps were sized, which `justify-content` and `align-content` distribute.
+    ///
+    /// Either value is negative if the content overflows the container. A flex container without in-flow children
+    /// is sized as a leaf and has no free space.
+    #[wasm_bindgen]
+    pub fn resolved_free_space(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        Ok(match self.free_space(node_id)? {
+            Some(free_space) => serde_wasm_bindgen::to_value(&free_space).unwrap_or(JsValue::NULL),
+            None => JsValue::UNDEFINED,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    #[cfg(feature = "grid")]
+    use taffy::prelude::Display;
+    use taffy::prelude::{length, Size, Style, TaffyMaxContent};
+
+    /// Builds a laid out 200x100 flex row with a 10px gap, holding growable 30px tall items of the given widths
+    fn row(tree: &TaffyTree, widths: &[f32]) -> u32 {
+        let mut inner = tree.inner.borrow_mut();
+        let items: Vec<_> = widths
+            .iter()
+            .map(|&width| {
+                let item = Style { size: Size { width: length(width), height: length(30.0) }, ..Default::default() };
+                inner.new_leaf(Style { flex_grow: 1.0, ..item }).unwrap()
+            })
+            .collect();
+        let row = Style {
+            gap: Size { width: length(10.0), height: length(0.0) },
+            size: Size { width: length(200.0), height: length(100.0) },
+            ..Default::default()
+        };
+        let row = inner.new_with_children(row, &items).unwrap();
+        inner.compute_layout(row, Size::MAX_CONTENT).unwrap();
+        js_node_id(row)
+    }
+
+    #[test]
+    fn under_filled_and_overflowing_rows() {
+        let tree = TaffyTree::new();
+
+        // 50 + 10 + 60 leaves 80px for the items to grow into, while the single line of a nowrap row takes its
+        // whole height
+        let under_filled = row(&tree, &[50.0, 60.0]);
+        assert_eq!(tree.free_space(under_filled), Ok(Some(ResolvedFreeSpace { main: 80.0, cross: 0.0 })));
+
+        // 120 + 10 + 150 is 80px more than the row, which the items shrink by
+        let overflowing = row(&tree, &[120.0, 150.0]);
+        assert_eq!(tree.free_space(overflowing), Ok(Some(ResolvedFreeSpace { main: -80.0, cross: 0.0 })));
+
+        let leaf = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        assert_eq!(tree.free_space(leaf), Ok(None));
+    }
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn grid_reports_space_left_by_its_tracks() {
+        let tree = TaffyTree::new();
+        let grid = {
+            let mut inner = tree.inner.borrow_mut();
+            let grid = Style {
+                display: Display::Grid,
+                grid_template_columns: vec![length(50.0), length(60.0)],
+                grid_template_rows: vec![length(30.0)],
+                gap: Size { width: length(10.0), height: length(0.0) },
+                size: Size { width: length(200.0), height: length(100.0) },
+                ..Default::default()
+            };
+            let item = inner.new_leaf(Style::default()).unwrap();
+            let grid = inner.new_with_children(grid, &[item]).unwrap();
+            inner.compute_layout(grid, Size::MAX_CONTENT).unwrap();
+            js_node_id(grid)
+        };
+        assert_eq!(tree.free_space(grid), Ok(Some(ResolvedFreeSpace { main: 80.0, cross: 70.0 })));
+    }
+}
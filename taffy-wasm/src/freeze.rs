@@ -0,0 +1,151 @@
+//! Freezing static subtrees so that cache clears elsewhere in the tree don't force them to be laid out again
+use crate::{resolve_node, TaffyError, TaffyTree};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Freezes the subtree rooted at `node_id`, so that its cached layouts survive `clear_cache` on its ancestors
+    ///
+    /// Freezing is narrower than making the subtree immune to changes: it only changes what `clear_cache` discards.
+    /// Layout treats a frozen subtree like any other, and reuses its cached layouts exactly when it would without
+    /// freezing, and edits inside it dirty it as usual. This is because taffy already skips a subtree whose cached
+    /// layout matches the constraints it is laid out with, and changes to the rest of the tree never invalidate
+    /// it: `mark_dirty` and `update_style` only dirty a node and its ancestors. What freezing can't safely do is
+    /// go further: reusing a layout cached for other constraints would give the subtree a stale size, and
+    /// ignoring edits inside it would leave the tree out of date with its styles. A frozen subtree is therefore
+    /// still laid out again (and its leaves measured again):
+    ///
+    /// - when the space available to it, or any other constraint from its parent, differs from every cached pass.
+    ///   A parent that changes size can still cause it to be laid out from scratch.
+    /// - when a node inside it is changed, e.g. with `update_style`, `add_child` or `mark_dirty`, which dirties the
+    ///   nodes between it and the root of the subtree, and the ancestors of the subtree, as usual.
+    /// - when `clear_cache` is called on a node inside it, including its root.
+    /// - when a layout pass is aborted with a `MEASURE_BUDGET_EXCEEDED` error, which discards all cached layouts.
+    ///
+    /// Freezing a node that has not been laid out yet is allowed, and takes effect once it has been.
+    #[wasm_bindgen]
+    pub fn freeze(&self, node_id: u32) -> Result<(), TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        self.node_states.borrow_mut().update(node_id, |state| state.frozen = true);
+        Ok(())
+    }
+
+    /// Undoes `freeze`, so that `clear_cache` on an ancestor clears the subtree rooted at `node_id` again
+    ///
+    /// Unfreezing a node that is not frozen does nothing.
+    #[wasm_bindgen]
+    pub fn unfreeze(&self, node_id: u32) -> Result<(), TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        self.node_states.borrow_mut().update(node_id, |state| state.frozen = false);
+        Ok(())
+    }
+
+    /// Indicates whether the subtree rooted at `node_id` was frozen with `freeze`
+    ///
+    /// This is `false` for the descendants of a frozen node, which are frozen along with it but not by themselves.
+    #[wasm_bindgen]
+    pub fn is_frozen(&self, node_id: u32) -> Result<bool, TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        Ok(self.node_states.borrow().get(node_id).frozen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::measure_budget::MeasureBudget;
+    use crate::node_ids::js_node_id;
+    use crate::{TaffyError, TaffyTree};
+    use std::cell::RefCell;
+    use taffy::{prelude::*, Point};
+
+    /// A full-width row with a static container holding a measured leaf, next to a dynamic measured leaf. Both leaves
+    /// prefer to be 100px wide, but can shrink to nothing.
+    struct Fixture {
+        tree: TaffyTree,
+        root: u32,
+        frozen: u32,
+        frozen_leaf: u32,
+        dynamic_leaf: u32,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let tree = TaffyTree::new();
+            let (root, frozen, frozen_leaf, dynamic_leaf) = {
+                let mut inner = tree.inner.borrow_mut();
+                let frozen_leaf = inner.new_leaf(Style::default()).unwrap();
+                let frozen = inner.new_with_children(Style::default(), &[frozen_leaf]).unwrap();
+                let dynamic_leaf = inner.new_leaf(Style::default()).unwrap();
+                let root_style = Style { size: Size { width: percent(1.0), height: auto() }, ..Default::default() };
+                let root = inner.new_with_children(root_style, &[frozen, dynamic_leaf]).unwrap();
+                (js_node_id(root), js_node_id(frozen), js_node_id(frozen_leaf), js_node_id(dynamic_leaf))
+            };
+            Self { tree, root, frozen, frozen_leaf, dynamic_leaf }
+        }
+
+        /// Lays out the tree with `width` of available space, returning the ids of the measured nodes in order
+        fn layout(&self, width: f32) -> Vec<u32> {
+            let measured = RefCell::new(Vec::new());
+            let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::MaxContent };
+            let budget = MeasureBudget::default();
+            let result = self.tree.compute_layout_with_budget(
+                self.root,
+                available_space,
+                budget,
+                || 0.0,
+                |known, available, node, _| {
+                    measured.borrow_mut().push(js_node_id(node));
+                    let width = known.width.unwrap_or(match available.width {
+                        AvailableSpace::Definite(width) => width.min(100.0),
+                        AvailableSpace::MinContent => 0.0,
+                        AvailableSpace::MaxContent => 100.0,
+                    });
+                    (Size { width, height: 10.0 }, Point::NONE)
+                },
+            );
+            result.unwrap();
+            measured.into_inner()
+        }
+    }
+
+    #[test]
+    fn frozen_subtree_is_not_measured_after_ancestor_cache_clear() {
+        let fixture = Fixture::new();
+        assert!(fixture.layout(300.0).contains(&fixture.frozen_leaf));
+
+        fixture.tree.freeze(fixture.frozen).unwrap();
+        assert_eq!(fixture.tree.is_frozen(fixture.frozen), Ok(true));
+        fixture.tree.clear_cache(fixture.root).unwrap();
+        let measured = fixture.layout(300.0);
+        assert!(!measured.contains(&fixture.frozen_leaf));
+        assert!(measured.contains(&fixture.dynamic_leaf));
+
+        // Once unfrozen, clearing the cache reaches the subtree again
+        fixture.tree.unfreeze(fixture.frozen).unwrap();
+        fixture.tree.clear_cache(fixture.root).unwrap();
+        assert!(fixture.layout(300.0).contains(&fixture.frozen_leaf));
+    }
+
+    #[test]
+    fn frozen_subtree_is_laid_out_again_when_its_constraints_change() {
+        let fixture = Fixture::new();
+        fixture.layout(300.0);
+        fixture.tree.freeze(fixture.frozen).unwrap();
+
+        // Narrower than the leaves' preferred width, so the frozen leaf is measured with a different available width
+        assert!(fixture.layout(120.0).contains(&fixture.frozen_leaf));
+        assert_eq!(fixture.tree.layout_width(fixture.frozen_leaf), Ok(60.0));
+
+        // Edits inside a frozen subtree dirty it as they would any other
+        fixture.tree.mark_dirty(fixture.frozen_leaf).unwrap();
+        assert_eq!(fixture.tree.dirty(fixture.root), Ok(true));
+        assert!(fixture.layout(120.0).contains(&fixture.frozen_leaf));
+    }
+
+    #[test]
+    fn freeze_invalid_node() {
+        let tree = TaffyTree::new();
+        assert_eq!(tree.freeze(1), Err(TaffyError::InvalidNode(1)));
+        assert_eq!(tree.unfreeze(1), Err(TaffyError::InvalidNode(1)));
+    }
+}
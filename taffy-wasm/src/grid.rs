@@ -0,0 +1,583 @@
+//! Accessors for the computed layout of CSS Grid containers
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::{prelude::*, DetailedGridItemsInfo, DetailedGridTracksInfo, DetailedLayoutInfo};
+use wasm_bindgen::prelude::*;
+
+/// The positions of the grid lines of a grid container, returned by `grid_track_positions`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GridTrackPositions {
+    /// The offset of each horizontal grid line from the top edge of the container's border box
+    pub rows: Vec<f32>,
+    /// The offset of each vertical grid line from the left edge of the container's border box
+    pub columns: Vec<f32>,
+}
+
+/// The sizes of the tracks of a grid container, returned by `grid_resolved_tracks`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GridTrackSizes {
+    /// The height of each row
+    pub rows: Vec<f32>,
+    /// The width of each column
+    pub columns: Vec<f32>,
+}
+
+/// The number of implicit tracks of a grid container, returned by `grid_implicit_track_count`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GridImplicitTrackCount {
+    /// The number of rows created outside the explicit grid
+    pub rows: u32,
+    /// The number of columns created outside the explicit grid
+    pub columns: u32,
+}
+
+/// The extent of a gutter between two adjacent tracks of a grid container
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GridGutter {
+    /// The offset of the far edge of the track before the gutter from the container's border box
+    pub start: f32,
+    /// The offset of the near edge of the track after the gutter from the container's border box
+    pub end: f32,
+}
+
+/// The gutters between the tracks of a grid container, returned by `grid_gutters`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GridGutters {
+    /// The vertical extent of each gutter between two rows
+    pub rows: Vec<GridGutter>,
+    /// The horizontal extent of each gutter between two columns
+    pub columns: Vec<GridGutter>,
+}
+
+/// The grid lines between which a grid item was placed, returned by `grid_item_placement`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridItemPlacement {
+    /// The 1-based index of the line at the top edge of the item
+    pub row_start: u16,
+    /// The 1-based index of the line at the bottom edge of the item
+    pub row_end: u16,
+    /// The 1-based index of the line at the left edge of the item
+    pub column_start: u16,
+    /// The 1-based index of the line at the right edge of the item
+    pub column_end: u16,
+}
+
+impl From<&DetailedGridItemsInfo> for GridItemPlacement {
+    fn from(item: &DetailedGridItemsInfo) -> Self {
+        GridItemPlacement {
+            row_start: item.row_start,
+            row_end: item.row_end,
+            column_start: item.column_start,
+            column_end: item.column_end,
+        }
+    }
+}
+
+/// Returns the position of each grid line in an axis
+///
+/// The outer lines lie on the outer edges of the first and last tracks, and inner lines lie in the middle of the
+/// gutter between the tracks on either side of them.
+fn line_positions(tracks: &DetailedGridTracksInfo) -> Vec<f32> {
+    let track_count = tracks.sizes.len();
+    if track_count == 0 {
+        return Vec::new();
+    }
+
+    let end = |index: usize| tracks.offsets[index] + tracks.sizes[index];
+    let mut lines = Vec::with_capacity(track_count + 1);
+    lines.push(tracks.offsets[0]);
+    lines.extend((1..track_count).map(|index| (end(index - 1) + tracks.offsets[index]) / 2.0));
+    lines.push(end(track_count - 1));
+    lines
+}
+
+/// Returns the extent of the gutter between each pair of adjacent tracks in an axis
+fn gutters(tracks: &DetailedGridTracksInfo) -> Vec<GridGutter> {
+    (1..tracks.sizes.len())
+        .map(|index| GridGutter {
+            start: tracks.offsets[index - 1] + tracks.sizes[index - 1],
+            end: tracks.offsets[index],
+        })
+        .collect()
+}
+
+impl TaffyTree {
+    /// Returns the grid line positions of a grid container
+    fn track_positions(&self, node_id: u32) -> Result<GridTrackPositions, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Grid(info) => {
+                GridTrackPositions { rows: line_positions(&info.rows), columns: line_positions(&info.columns) }
+            }
+            _ => GridTrackPositions { rows: Vec::new(), columns: Vec::new() },
+        })
+    }
+
+    /// Returns the gutters between the tracks of a grid container
+    fn track_gutters(&self, node_id: u32) -> Result<GridGutters, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Grid(info) => {
+                GridGutters { rows: gutters(&info.rows), columns: gutters(&info.columns) }
+            }
+            _ => GridGutters { rows: Vec::new(), columns: Vec::new() },
+        })
+    }
+
+    /// Returns the track sizes of a grid container
+    fn track_sizes(&self, node_id: u32) -> Result<GridTrackSizes, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Grid(info) => {
+                GridTrackSizes { rows: info.rows.sizes.clone(), columns: info.columns.sizes.clone() }
+            }
+            _ => GridTrackSizes { rows: Vec::new(), columns: Vec::new() },
+        })
+    }
+
+    /// Returns the number of columns of a grid container, or 0 if it isn't one
+    fn column_count(&self, node_id: u32) -> Result<u32, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Grid(info) => info.columns.sizes.len() as u32,
+            _ => 0,
+        })
+    }
+
+    /// Returns the number of implicit tracks of a grid container, or 0 in each axis if it isn't one
+    fn implicit_track_count(&self, node_id: u32) -> Result<GridImplicitTrackCount, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let implicit = |tracks: &DetailedGridTracksInfo| {
+            u32::from(tracks.negative_implicit_tracks) + u32::from(tracks.positive_implicit_tracks)
+        };
+        Ok(match tree.detailed_layout_info(node) {
+            DetailedLayoutInfo::Grid(info) => {
+                GridImplicitTrackCount { rows: implicit(&info.rows), columns: implicit(&info.columns) }
+            }
+            _ => GridImplicitTrackCount { rows: 0, columns: 0 },
+        })
+    }
+
+    /// Returns the placement of a grid item within its parent grid container
+    fn item_placement(&self, node_id: u32) -> Result<Option<GridItemPlacement>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let Some(parent) = tree.parent(node) else {
+            return Ok(None);
+        };
+        let DetailedLayoutInfo::Grid(info) = tree.detailed_layout_info(parent) else {
+            return Ok(None);
+        };
+        // The container records its items in order, leaving out the children that are not laid out in the grid
+        let mut items = Vec::new();
+        for child in tree.children(parent)? {
+            let style = tree.style(child)?;
+            if style.display != Display::None && style.position != Position::Absolute {
+                items.push(child);
+            }
+        }
+        let index = items.iter().position(|&item| item == node);
+        Ok(index.and_then(|index| info.items.get(index)).map(GridItemPlacement::from))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the positions of the grid lines of a grid container as `{ rows: number[], columns: number[] }`
+    ///
+    /// Entry `i` of each array is the position of grid line `i + 1`, relative to the container's border box, so
+    /// there is one more entry than there are tracks (implicit tracks included). Where the tracks are separated by
+    /// a gap, the line is drawn through the middle of the gap. Both arrays are empty if the node is not a grid
+    /// container, has no children (childless containers are sized as leaves), or has not been laid out yet.
+    #[wasm_bindgen]
+    pub fn grid_track_positions(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let positions = self.track_positions(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&positions).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the sizes of the tracks of a grid container as `{ rows: number[], columns: number[] }`
+    ///
+    /// These are the used sizes from the last layout, in order, including implicit tracks (those before the
+    /// explicit grid first) and excluding gaps. Fixed and `fr` tracks have the size their sizing function resolved
+    /// to. Content-sized tracks (`auto`, `min-content`, `max-content` and `fit-content()`) are sized to the items
+    /// placed in them, and `auto` tracks may then grow to fill the container when `align-content` or
+    /// `justify-content` is `normal` or `stretch`. Both arrays are empty in the same cases as for
+    /// `grid_track_positions`.
+    #[wasm_bindgen]
+    pub fn grid_resolved_tracks(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let sizes = self.track_sizes(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&sizes).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the gutters between the tracks of a grid container in the last layout as
+    /// `{ rows: { start, end }[], columns: { start, end }[] }`
+    ///
+    /// Each entry is the range of pixels, relative to the container's border box, between the end of a track and
+    /// the start of the next one, so there is one entry fewer than there are tracks (implicit tracks included).
+    /// A point whose offset lies within a gutter is between the tracks rather than on them, e.g. to offer to insert
+    /// a track there when a click in a grid editor hits the container rather than one of its items. A gutter spans
+    /// the gap between the tracks, plus any space `align-content` or `justify-content` distributed between them,
+    /// so it is empty, with `start` equal to `end`, between tracks without either. Both arrays are empty in the
+    /// same cases as for `grid_track_positions`, and when the container has a single track in that axis.
+    #[wasm_bindgen]
+    pub fn grid_gutters(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let gutters = self.track_gutters(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&gutters).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the number of columns of a grid container in the last layout, implicit columns included
+    ///
+    /// With an auto-repeated template such as `repeat(auto-fill, minmax(200px, 1fr))`, i.e. a `Repeat` of `"AutoFill"`
+    /// or `"AutoFit"` over `{ min, max }` track sizes, this is the number of columns taffy generated to fit the
+    /// container's width, e.g. to size a page of items to fill whole rows of a responsive grid. As in CSS, only a
+    /// definite `size` or `max_size` width makes more than one repetition fit, so a container that should fill the
+    /// available width needs a width of `100%`. Columns collapsed by `"AutoFit"` because no item was placed in them
+    /// still count, with a size of 0 in `grid_resolved_tracks`. Returns 0 in the same cases as `grid_track_positions`
+    /// returns empty arrays.
+    #[wasm_bindgen]
+    pub fn grid_column_count(&self, node_id: u32) -> Result<u32, TaffyError> {
+        self.column_count(node_id)
+    }
+
+    /// Returns the number of implicit tracks of a grid container in the last layout as `{ rows, columns }`
+    ///
+    /// Implicit tracks are those created beyond the explicit `grid_template_rows` and `grid_template_columns`
+    /// to hold the items that don't fit in the explicit grid: the rows added by auto-placement once the explicit
+    /// cells are filled (or the columns, with a column `grid_auto_flow`), and the tracks reaching the lines items
+    /// are placed at outside the explicit grid, on either side of it. They are sized by `grid_auto_rows` and
+    /// `grid_auto_columns`. Adding these counts to the lengths of the templates gives the track counts of
+    /// `grid_resolved_tracks`. Both counts are 0 in the same cases as `grid_track_positions` returns empty arrays.
+    #[wasm_bindgen]
+    pub fn grid_implicit_track_count(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let count = self.implicit_track_count(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&count).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the grid lines between which a grid item was placed by the last layout of its container, as
+    /// `{ rowStart, rowEnd, columnStart, columnEnd }`, or `undefined` if the node is not a grid item
+    ///
+    /// This is where the item ended up, whether it was placed explicitly or by the auto-placement algorithm, e.g.
+    /// to show which cells an item occupies in a grid editor. The item spans the tracks from line `rowStart` to
+    /// line `rowEnd`, and from line `columnStart` to line `columnEnd`. Lines are numbered from 1, starting at the
+    /// first track including implicit tracks before the explicit grid, like the lines of `grid_track_positions`;
+    /// they only match CSS line numbers when no item is placed before the explicit grid.
+    ///
+    /// Returns `undefined` if the node's parent is not a grid container or has not been laid out yet, or if the
+    /// node is not laid out in the grid, because it is absolutely positioned or has `display: none`.
+    #[wasm_bindgen]
+    pub fn grid_item_placement(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let placement = self.item_placement(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&placement).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use crate::{JsStyle, TaffyTree};
+    use taffy::style::GridTrackRepetition;
+    use taffy::CompactLength;
+
+    /// Creates a 2x2 grid container with four auto-placed children, decoding its style through the same serde path as the JS bindings
+    fn gap_grid(gap: Size<LengthPercentage>) -> (TaffyTree, u32, Vec<u32>) {
+        let container_style = Style {
+            display: Display::Grid,
+            size: Size { width: length(220.0), height: length(120.0) },
+            grid_template_columns: vec![fr(1.0), fr(1.0)],
+            grid_template_rows: vec![fr(1.0), fr(1.0)],
+            gap,
+            ..Default::default()
+        };
+        let json = serde_json::to_value(JsStyle(container_style)).unwrap();
+        let JsStyle(container_style) = serde_json::from_value(json).unwrap();
+
+        let tree = TaffyTree::new();
+        let mut inner = tree.inner.borrow_mut();
+        let children: Vec<_> = (0..4).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+        let container = inner.new_with_children(container_style, &children).unwrap();
+        inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        drop(inner);
+
+        (tree, js_node_id(container), children.into_iter().map(js_node_id).collect())
+    }
+
+    #[test]
+    fn grid_length_gap_reduces_track_space() {
+        let (tree, container, children) = gap_grid(Size { width: length(20.0), height: length(10.0) });
+
+        assert_eq!(tree.layout_width(children[0]), Ok(100.0));
+        assert_eq!(tree.layout_height(children[0]), Ok(55.0));
+        assert_eq!(tree.layout_left(children[3]), Ok(120.0));
+        assert_eq!(tree.layout_top(children[3]), Ok(65.0));
+
+        let positions = tree.track_positions(container).unwrap();
+        assert_eq!(positions.columns, vec![0.0, 110.0, 220.0]);
+        assert_eq!(positions.rows, vec![0.0, 60.0, 120.0]);
+    }
+
+    #[test]
+    fn grid_percentage_gap_resolves_against_container() {
+        // 10% of the 220px wide container, and 25% of the 120px high container
+        let (tree, container, children) = gap_grid(Size { width: percent(0.1), height: percent(0.25) });
+
+        assert_eq!(tree.layout_width(children[0]), Ok(99.0));
+        assert_eq!(tree.layout_height(children[0]), Ok(45.0));
+        assert_eq!(tree.layout_left(children[3]), Ok(121.0));
+        assert_eq!(tree.layout_top(children[3]), Ok(75.0));
+
+        let positions = tree.track_positions(container).unwrap();
+        assert_eq!(positions.columns, vec![0.0, 110.0, 220.0]);
+        assert_eq!(positions.rows, vec![0.0, 60.0, 120.0]);
+    }
+
+    #[test]
+    fn gutters_lie_between_the_tracks() {
+        let (tree, container, children) = gap_grid(Size { width: length(20.0), height: length(10.0) });
+
+        let gutters = tree.track_gutters(container).unwrap();
+        assert_eq!(gutters.columns, vec![GridGutter { start: 100.0, end: 120.0 }]);
+        assert_eq!(gutters.rows, vec![GridGutter { start: 55.0, end: 65.0 }]);
+        // Each gutter runs from the far edge of an item in the track before it to the near edge of one after it,
+        // and the grid line between the tracks runs through it
+        let positions = tree.track_positions(container).unwrap();
+        let right = tree.layout_left(children[0]).unwrap() + tree.layout_width(children[0]).unwrap();
+        let bottom = tree.layout_top(children[0]).unwrap() + tree.layout_height(children[0]).unwrap();
+        assert_eq!((right, tree.layout_left(children[3])), (100.0, Ok(120.0)));
+        assert_eq!((bottom, tree.layout_top(children[3])), (55.0, Ok(65.0)));
+        assert!(gutters.columns[0].start < positions.columns[1] && positions.columns[1] < gutters.columns[0].end);
+        assert!(gutters.rows[0].start < positions.rows[1] && positions.rows[1] < gutters.rows[0].end);
+
+        assert_eq!(tree.track_gutters(children[0]), Ok(GridGutters { rows: Vec::new(), columns: Vec::new() }));
+    }
+
+    #[test]
+    fn track_positions_of_non_grid_node() {
+        let tree = TaffyTree::new();
+        let node = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        assert_eq!(tree.track_positions(node), Ok(GridTrackPositions { rows: Vec::new(), columns: Vec::new() }));
+    }
+
+    #[test]
+    fn resolved_tracks_of_fr_columns() {
+        let tree = TaffyTree::new();
+        let container = {
+            let mut inner = tree.inner.borrow_mut();
+            let style = Style {
+                display: Display::Grid,
+                size: Size { width: length(300.0), height: length(50.0) },
+                grid_template_columns: vec![fr(1.0), fr(2.0)],
+                ..Default::default()
+            };
+            let children = [inner.new_leaf(Style::default()).unwrap(), inner.new_leaf(Style::default()).unwrap()];
+            let container = inner.new_with_children(style, &children).unwrap();
+            inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+            js_node_id(container)
+        };
+
+        let sizes = tree.track_sizes(container).unwrap();
+        assert_eq!(sizes.columns, vec![100.0, 200.0]);
+        // The implicit row holding the items stretches to fill the container
+        assert_eq!(sizes.rows, vec![50.0]);
+    }
+
+    #[test]
+    fn resolved_tracks_of_content_sized_and_implicit_tracks() {
+        let tree = TaffyTree::new();
+        let container = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = |width, height| Style {
+                size: Size { width: length(width), height: length(height) },
+                ..Default::default()
+            };
+            let children = [
+                inner.new_leaf(item(30.0, 10.0)).unwrap(),
+                inner.new_leaf(item(70.0, 20.0)).unwrap(),
+                inner.new_leaf(item(10.0, 40.0)).unwrap(),
+            ];
+            let style = Style {
+                display: Display::Grid,
+                grid_template_columns: vec![min_content(), length(50.0)],
+                grid_auto_rows: vec![auto()],
+                ..Default::default()
+            };
+            let container = inner.new_with_children(style, &children).unwrap();
+            inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+            js_node_id(container)
+        };
+
+        let sizes = tree.track_sizes(container).unwrap();
+        // The min-content column fits its widest item, and the implicit rows fit their tallest item
+        assert_eq!(sizes.columns, vec![30.0, 50.0]);
+        assert_eq!(sizes.rows, vec![20.0, 40.0]);
+    }
+
+    /// Lays out a grid with three 10px tracks in the flow direction, whose first two items span two of those tracks
+    /// and leave a hole after the first, returning the location of a third, single-track item
+    ///
+    /// The auto-flow is decoded from its JS string form, e.g. `"RowDense"`.
+    fn spanning_grid_third_item(grid_auto_flow: &str) -> (f32, f32) {
+        let key = if cfg!(feature = "camel-case-style") { "gridAutoFlow" } else { "grid_auto_flow" };
+        let json = serde_json::json!({ "display": "Grid", key: grid_auto_flow });
+        let JsStyle(style) = serde_json::from_value(json).unwrap();
+        let is_row_flow = matches!(style.grid_auto_flow, GridAutoFlow::Row | GridAutoFlow::RowDense);
+        let container_style =
+            Style { grid_template_columns: vec![length(10.0); 3], grid_template_rows: vec![length(10.0); 3], ..style };
+        let spanning = if is_row_flow {
+            Style { grid_column: Line { start: span(2), end: auto() }, ..Default::default() }
+        } else {
+            Style { grid_row: Line { start: span(2), end: auto() }, ..Default::default() }
+        };
+
+        let tree = TaffyTree::new();
+        let item = {
+            let mut inner = tree.inner.borrow_mut();
+            let first = inner.new_leaf(spanning.clone()).unwrap();
+            let second = inner.new_leaf(spanning).unwrap();
+            let item = inner.new_leaf(Style::default()).unwrap();
+            let container = inner.new_with_children(container_style, &[first, second, item]).unwrap();
+            inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+            js_node_id(item)
+        };
+        (tree.layout_left(item).unwrap(), tree.layout_top(item).unwrap())
+    }
+
+    #[test]
+    fn auto_fill_minmax_columns_follow_the_container_width() {
+        // repeat(auto-fill, minmax(200px, 1fr)), decoded from its JS form
+        let min = CompactLength::length(200.0).to_serialized();
+        let max = CompactLength::fr(1.0).to_serialized();
+        let key = if cfg!(feature = "camel-case-style") { "gridTemplateColumns" } else { "grid_template_columns" };
+        let json = serde_json::json!({
+            "display": "Grid",
+            key: [{ "Repeat": ["AutoFill", [{ "min": min, "max": max }]] }],
+        });
+        let JsStyle(style) = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            style.grid_template_columns,
+            vec![repeat(GridTrackRepetition::AutoFill, vec![minmax(length(200.0), fr(1.0))])]
+        );
+
+        let tree = TaffyTree::new();
+        let container = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = inner.new_leaf(Style::default()).unwrap();
+            let style = Style { size: Size { width: percent(1.0), height: auto() }, ..style };
+            js_node_id(inner.new_with_children(style, &[item]).unwrap())
+        };
+        let columns_at = |width| {
+            let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::MaxContent };
+            tree.compute_subtree(container, available_space).unwrap();
+            tree.column_count(container).unwrap()
+        };
+        assert_eq!(columns_at(650.0), 3);
+        assert_eq!(columns_at(450.0), 2);
+        assert_eq!(tree.track_sizes(container).unwrap().columns, vec![225.0, 225.0]);
+        assert_eq!(columns_at(150.0), 1);
+    }
+
+    #[test]
+    fn dense_auto_flow_backfills_holes() {
+        // The second item doesn't fit after the first, so it leaves a hole in the first row (or column)
+        assert_eq!(spanning_grid_third_item("RowDense"), (20.0, 0.0));
+        assert_eq!(spanning_grid_third_item("ColumnDense"), (0.0, 20.0));
+    }
+
+    #[test]
+    fn sparse_auto_flow_leaves_holes() {
+        assert_eq!(spanning_grid_third_item("Row"), (20.0, 10.0));
+        assert_eq!(spanning_grid_third_item("Column"), (10.0, 20.0));
+    }
+
+    /// Lays out a three-column grid of two items spanning two columns and a single-column item, returning the
+    /// placement of each
+    fn auto_placed_items(grid_auto_flow: GridAutoFlow) -> Vec<Option<GridItemPlacement>> {
+        let tree = TaffyTree::new();
+        let items = {
+            let mut inner = tree.inner.borrow_mut();
+            let spanning = Style { grid_column: Line { start: span(2), end: auto() }, ..Default::default() };
+            let hidden = Style { display: Display::None, ..Default::default() };
+            let items = [
+                inner.new_leaf(spanning.clone()).unwrap(),
+                inner.new_leaf(hidden).unwrap(),
+                inner.new_leaf(spanning).unwrap(),
+                inner.new_leaf(Style::default()).unwrap(),
+            ];
+            let style = Style {
+                display: Display::Grid,
+                grid_auto_flow,
+                grid_template_columns: vec![length(10.0); 3],
+                ..Default::default()
+            };
+            let container = inner.new_with_children(style, &items).unwrap();
+            inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+            items.map(js_node_id)
+        };
+        items.iter().map(|&item| tree.item_placement(item).unwrap()).collect()
+    }
+
+    #[test]
+    fn placement_of_auto_placed_items() {
+        let placement = |row_start, column_start, column_end| {
+            Some(GridItemPlacement { row_start, row_end: row_start + 1, column_start, column_end })
+        };
+        // The second spanning item doesn't fit in the first row, which the last item backfills only when dense
+        let sparse = vec![placement(1, 1, 3), None, placement(2, 1, 3), placement(2, 3, 4)];
+        assert_eq!(auto_placed_items(GridAutoFlow::Row), sparse);
+        let dense = vec![placement(1, 1, 3), None, placement(2, 1, 3), placement(1, 3, 4)];
+        assert_eq!(auto_placed_items(GridAutoFlow::RowDense), dense);
+    }
+
+    #[test]
+    fn placement_of_non_grid_items() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style::default()).unwrap();
+            let root = inner.new_with_children(Style::default(), &[child]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (js_node_id(root), js_node_id(child))
+        };
+        assert_eq!(tree.item_placement(root), Ok(None));
+        assert_eq!(tree.item_placement(child), Ok(None));
+    }
+
+    #[test]
+    fn items_beyond_the_explicit_cells_create_implicit_tracks() {
+        let tree = TaffyTree::new();
+        let (container, item) = {
+            let mut inner = tree.inner.borrow_mut();
+            let mut items: Vec<_> = (0..5).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            let beyond_the_columns = Style {
+                grid_row: Line { start: line(1), end: auto() },
+                grid_column: Line { start: line(4), end: auto() },
+                ..Default::default()
+            };
+            items.push(inner.new_leaf(beyond_the_columns).unwrap());
+            let grid = Style {
+                display: Display::Grid,
+                grid_template_columns: vec![length(10.0); 2],
+                grid_template_rows: vec![length(10.0)],
+                ..Default::default()
+            };
+            let container = inner.new_with_children(grid, &items).unwrap();
+            inner.compute_layout(container, Size::MAX_CONTENT).unwrap();
+            (js_node_id(container), js_node_id(items[0]))
+        };
+
+        // The item starting at line 4 adds two columns after the explicit two, leaving three cells of the first row
+        // to the five auto-placed items, which need one more row for the other two
+        let count = tree.implicit_track_count(container).unwrap();
+        assert_eq!(count, GridImplicitTrackCount { rows: 1, columns: 2 });
+        let sizes = tree.track_sizes(container).unwrap();
+        assert_eq!((sizes.rows.len(), sizes.columns.len()), (2, 4));
+
+        assert_eq!(tree.implicit_track_count(item), Ok(GridImplicitTrackCount { rows: 0, columns: 0 }));
+    }
+}
@@ -0,0 +1,84 @@
+//! Measuring the space between two laid out sibling boxes, e.g. to draw an insertion indicator between them
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::Layout;
+use wasm_bindgen::prelude::*;
+
+/// The space between two sibling boxes, returned by `gap_between`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Gutter {
+    /// The space between the boxes along the x axis, or 0 if they overlap horizontally
+    pub horizontal: f32,
+    /// The space between the boxes along the y axis, or 0 if they overlap vertically
+    pub vertical: f32,
+}
+
+/// Returns the space between the ranges `[a_start, a_start + a_length]` and `[b_start, b_start + b_length]`
+fn space_between(a_start: f32, a_length: f32, b_start: f32, b_length: f32) -> f32 {
+    (b_start - (a_start + a_length)).max(a_start - (b_start + b_length)).max(0.0)
+}
+
+impl Gutter {
+    /// Returns the space between the border boxes of two layouts
+    fn between(a: &Layout, b: &Layout) -> Self {
+        Gutter {
+            horizontal: space_between(a.location.x, a.size.width, b.location.x, b.size.width),
+            vertical: space_between(a.location.y, a.size.height, b.location.y, b.size.height),
+        }
+    }
+}
+
+impl TaffyTree {
+    /// Returns the space between the boxes of two siblings
+    fn sibling_gutter(&self, a: u32, b: u32) -> Result<Gutter, TaffyError> {
+        let tree = self.tree()?;
+        let (a_node, b_node) = (resolve_node(&tree, a)?, resolve_node(&tree, b)?);
+        if tree.parent(a_node).is_none() || tree.parent(a_node) != tree.parent(b_node) {
+            return Err(TaffyError::InvalidArgument(format!("nodes {a} and {b} are not siblings")));
+        }
+        Ok(Gutter::between(tree.layout(a_node)?, tree.layout(b_node)?))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the space between the border boxes of two siblings in the last layout, as `{ horizontal, vertical }`
+    ///
+    /// Each component is the distance between the facing edges of the boxes along that axis, or 0 where the boxes
+    /// overlap along it: for two items next to each other in a flex row, `horizontal` is the gutter between them
+    /// and `vertical` is 0. Unlike the container's `gap`, this is the space as laid out and rounded, including any
+    /// margins and free space distributed between the items. The boxes can be in either order.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `a` and `b` don't have the same parent.
+    #[wasm_bindgen]
+    pub fn gap_between(&self, a: u32, b: u32) -> Result<JsValue, TaffyError> {
+        let gutter = self.sibling_gutter(a, b)?;
+        Ok(serde_wasm_bindgen::to_value(&gutter).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::prelude::{length, zero, Size, Style, TaffyMaxContent};
+
+    #[test]
+    fn gutter_between_items_of_a_gapped_row() {
+        let tree = TaffyTree::new();
+        let (row, [first, second, third]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(20.3), height: length(10.0) }, ..Default::default() };
+            let items = [(); 3].map(|_| inner.new_leaf(item.clone()).unwrap());
+            let row = Style { gap: Size { width: length(7.5), height: zero() }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+
+        // The items span [0, 20.3], [27.8, 48.1] and [55.6, 75.9], which round to [0, 20], [28, 48] and [56, 76]
+        assert_eq!(tree.sibling_gutter(first, second), Ok(Gutter { horizontal: 8.0, vertical: 0.0 }));
+        assert_eq!(tree.sibling_gutter(second, first), Ok(Gutter { horizontal: 8.0, vertical: 0.0 }));
+        assert_eq!(tree.sibling_gutter(first, third), Ok(Gutter { horizontal: 36.0, vertical: 0.0 }));
+        assert!(matches!(tree.sibling_gutter(first, row), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,320 @@
+//! A spatial index of the boxes of a laid out tree, for answering many point queries without walking the tree
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use std::cmp::Reverse;
+use std::ops::Range;
+use taffy::{prelude::*, style::Overflow, Point, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The maximum number of entries in each node of a [`HitIndex`]
+const NODE_CAPACITY: usize = 16;
+
+/// An axis-aligned box in absolute coordinates, including its top and left edges but excluding its bottom and right
+/// edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bounds {
+    pub(crate) min: Point<f32>,
+    pub(crate) max: Point<f32>,
+}
+
+impl Bounds {
+    /// The bounds that contain every point
+    const EVERYWHERE: Self = Self {
+        min: Point { x: f32::NEG_INFINITY, y: f32::NEG_INFINITY },
+        max: Point { x: f32::INFINITY, y: f32::INFINITY },
+    };
+
+    fn contains(&self, point: Point<f32>) -> bool {
+        (self.min.x..self.max.x).contains(&point.x) && (self.min.y..self.max.y).contains(&point.y)
+    }
+
+    /// Returns whether no point lies within the bounds
+    pub(crate) fn is_empty(&self) -> bool {
+        !(self.min.x < self.max.x && self.min.y < self.max.y)
+    }
+
+    fn center(&self) -> Point<f32> {
+        Point { x: (self.min.x + self.max.x) / 2.0, y: (self.min.y + self.max.y) / 2.0 }
+    }
+
+    /// Returns the smallest bounds containing both `self` and `other`
+    pub(crate) fn union(self, other: Self) -> Self {
+        Self {
+            min: Point { x: self.min.x.min(other.min.x), y: self.min.y.min(other.min.y) },
+            max: Point { x: self.max.x.max(other.max.x), y: self.max.y.max(other.max.y) },
+        }
+    }
+
+    /// Returns the bounds of the points within both `self` and `other`
+    pub(crate) fn intersection(self, other: Self) -> Self {
+        Self {
+            min: Point { x: self.min.x.max(other.min.x), y: self.min.y.max(other.min.y) },
+            max: Point { x: self.max.x.min(other.max.x), y: self.max.y.min(other.max.y) },
+        }
+    }
+}
+
+/// The area of a node that can be hit
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HitArea {
+    /// The border box of the node, clipped by its ancestors
+    pub(crate) bounds: Bounds,
+    /// The position of the node in paint order, where later nodes are painted on top of earlier ones
+    pub(crate) paint_position: u32,
+    /// The id of the node
+    pub(crate) node: u32,
+}
+
+/// Returns the hit areas of the nodes in the subtree rooted at `root`, in paint order
+///
+/// Nodes are clipped as by `node_at_point`: the children of a node whose `overflow` is not `visible` along an axis
+/// can only be hit within its padding box along that axis. Nodes that are clipped away entirely are left out.
+pub(crate) fn hit_areas(tree: &Taffy<JsContext>, root: NodeId) -> Result<Vec<HitArea>, TaffyError> {
+    let mut areas = Vec::new();
+    let mut paint_position = 0;
+    let mut stack = vec![(root, Point::ZERO, Bounds::EVERYWHERE)];
+    while let Some((node, parent_origin, clip)) = stack.pop() {
+        let layout = tree.layout(node)?;
+        let origin = parent_origin + layout.location;
+        let border_box =
+            Bounds { min: origin, max: Point { x: origin.x + layout.size.width, y: origin.y + layout.size.height } };
+        let bounds = border_box.intersection(clip);
+        if !bounds.is_empty() {
+            areas.push(HitArea { bounds, paint_position, node: js_node_id(node) });
+        }
+        paint_position += 1;
+
+        let overflow = tree.style(node)?.overflow;
+        let padding_box = Bounds {
+            min: Point { x: border_box.min.x + layout.border.left, y: border_box.min.y + layout.border.top },
+            max: Point { x: border_box.max.x - layout.border.right, y: border_box.max.y - layout.border.bottom },
+        };
+        let child_clip = Bounds {
+            min: Point {
+                x: if overflow.x == Overflow::Visible { clip.min.x } else { clip.min.x.max(padding_box.min.x) },
+                y: if overflow.y == Overflow::Visible { clip.min.y } else { clip.min.y.max(padding_box.min.y) },
+            },
+            max: Point {
+                x: if overflow.x == Overflow::Visible { clip.max.x } else { clip.max.x.min(padding_box.max.x) },
+                y: if overflow.y == Overflow::Visible { clip.max.y } else { clip.max.y.min(padding_box.max.y) },
+            },
+        };
+        stack.extend(tree.children(node)?.into_iter().rev().map(|child| (child, origin, child_clip)));
+    }
+    Ok(areas)
+}
+
+/// Groups `items` into nodes of up to [`NODE_CAPACITY`] neighbouring items with the sort-tile-recursive algorithm
+///
+/// The items are reordered so that each node covers a contiguous range of them, and the bounds and range of each
+/// node are returned.
+fn pack<T>(items: &mut [T], bounds: impl Fn(&T) -> Bounds) -> Vec<(Bounds, Range<usize>)> {
+    let node_count = items.len().div_ceil(NODE_CAPACITY);
+    let strip_len = (node_count as f64).sqrt().ceil() as usize * NODE_CAPACITY;
+    items.sort_by(|a, b| bounds(a).center().x.total_cmp(&bounds(b).center().x));
+
+    let mut nodes = Vec::with_capacity(node_count);
+    for (strip_index, strip) in items.chunks_mut(strip_len.max(1)).enumerate() {
+        strip.sort_by(|a, b| bounds(a).center().y.total_cmp(&bounds(b).center().y));
+        for (chunk_index, chunk) in strip.chunks(NODE_CAPACITY).enumerate() {
+            let start = strip_index * strip_len + chunk_index * NODE_CAPACITY;
+            let chunk_bounds = chunk.iter().map(&bounds).reduce(Bounds::union).unwrap_or(Bounds::EVERYWHERE);
+            nodes.push((chunk_bounds, start..start + chunk.len()));
+        }
+    }
+    nodes
+}
+
+/// An R-tree of the hit areas of the nodes of a laid out subtree, built by `build_hit_index`
+#[derive(Debug)]
+pub(crate) struct HitIndex {
+    /// The hit areas, ordered so that each node of the lowest level covers a contiguous range of them
+    areas: Vec<HitArea>,
+    /// The levels of the tree, from the lowest up to the single root. Each node is its bounds and the range of the
+    /// nodes of the level below it, or of `areas` for the lowest level, that it contains.
+    levels: Vec<Vec<(Bounds, Range<usize>)>>,
+}
+
+impl HitIndex {
+    /// Builds an index of `areas`
+    fn new(mut areas: Vec<HitArea>) -> Self {
+        let mut levels = Vec::new();
+        if !areas.is_empty() {
+            let mut level = pack(&mut areas, |area| area.bounds);
+            while level.len() > 1 {
+                let parents = pack(&mut level, |(bounds, _)| *bounds);
+                levels.push(level);
+                level = parents;
+            }
+            levels.push(level);
+        }
+        Self { areas, levels }
+    }
+
+    /// Returns the ids of the nodes whose hit area contains `point`, topmost first
+    fn query(&self, point: Point<f32>) -> Vec<u32> {
+        let mut hits: Vec<&HitArea> = Vec::new();
+        let mut stack: Vec<_> =
+            self.levels.last().map(|root| (self.levels.len() - 1, 0..root.len())).into_iter().collect();
+        while let Some((level, range)) = stack.pop() {
+            for (bounds, children) in &self.levels[level][range] {
+                if !bounds.contains(point) {
+                    continue;
+                }
+                match level {
+                    0 => hits.extend(self.areas[children.clone()].iter().filter(|area| area.bounds.contains(point))),
+                    _ => stack.push((level - 1, children.clone())),
+                }
+            }
+        }
+        hits.sort_unstable_by_key(|area| Reverse(area.paint_position));
+        hits.into_iter().map(|area| area.node).collect()
+    }
+}
+
+impl TaffyTree {
+    /// Builds a hit index of the subtree rooted at `root`, returning its handle
+    fn build_hit_index_handle(&self, root: u32) -> Result<u32, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let index = HitIndex::new(hit_areas(&tree, root)?);
+        let handle = self.next_hit_index.get();
+        self.next_hit_index.set(handle.wrapping_add(1));
+        self.hit_indices.borrow_mut().insert(handle, index);
+        Ok(handle)
+    }
+
+    /// Returns the ids of the nodes in the hit index `handle` that contain the point `(x, y)`, topmost first
+    fn query_hit_index(&self, handle: u32, x: f32, y: f32) -> Result<Vec<u32>, TaffyError> {
+        let indices = self.hit_indices.borrow();
+        let index = indices.get(&handle).ok_or_else(|| {
+            TaffyError::InvalidArgument(format!(
+                "{handle} is not the handle of a hit index built since the last layout; call build_hit_index again"
+            ))
+        })?;
+        Ok(index.query(Point { x, y }))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Builds a spatial index of the boxes of the subtree rooted at `root`, as last laid out, returning a handle to
+    /// pass to `hit_index_query`
+    ///
+    /// The index is an R-tree of the absolute border boxes of the nodes, in the same coordinates as `layout_buffer`
+    /// and clipped as by `node_at_point`. Building it walks the subtree once; after that each query only looks at
+    /// the boxes near the point, which makes it worthwhile when a large tree is queried many times between
+    /// layouts, e.g. on every pointer move.
+    ///
+    /// The index is a snapshot of the current layout: it is discarded by the next call that computes a layout, of
+    /// this root or any other, after which its handle is no longer valid. Handles are never reused.
+    #[wasm_bindgen]
+    pub fn build_hit_index(&self, root: u32) -> Result<u32, TaffyError> {
+        self.build_hit_index_handle(root)
+    }
+
+    /// Returns the ids of every node in the hit index `handle` whose box contains the point `(x, y)`, as a
+    /// `Uint32Array` ordered from the topmost node down
+    ///
+    /// The order is the reverse of paint order: children come before their parent, and later siblings before
+    /// earlier ones, so the first node is the one `node_at_point` returns. The array is empty if no node contains
+    /// the point. Throws an `INVALID_ARGUMENT` error if `handle` was not returned by `build_hit_index`, or if a
+    /// layout has been computed since.
+    #[wasm_bindgen]
+    pub fn hit_index_query(&self, handle: u32, x: f32, y: f32) -> Result<Vec<u32>, TaffyError> {
+        self.query_hit_index(handle, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::node_from_js_id;
+    use taffy::style::Style;
+
+    fn absolute(left: f32, top: f32, width: f32, height: f32) -> Style {
+        Style {
+            position: Position::Absolute,
+            inset: Rect { left: length(left), right: auto(), top: length(top), bottom: auto() },
+            size: Size { width: length(width), height: length(height) },
+            ..Default::default()
+        }
+    }
+
+    /// Builds a 200x200 root holding `count` overlapping absolute children, some of which clip a child of their own
+    fn dense_tree(count: usize) -> (TaffyTree, u32) {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let children: Vec<_> = (0..count)
+                .map(|i| {
+                    let (left, top) = ((i * 37 % 170) as f32, (i * 53 % 170) as f32);
+                    let style = absolute(left, top, 20.0 + (i % 4) as f32 * 5.0, 25.0);
+                    if i % 5 == 0 {
+                        let clipped = inner.new_leaf(absolute(10.0, 10.0, 40.0, 40.0)).unwrap();
+                        let style = Style { overflow: Point { x: Overflow::Hidden, y: Overflow::Hidden }, ..style };
+                        inner.new_with_children(style, &[clipped]).unwrap()
+                    } else {
+                        inner.new_leaf(style).unwrap()
+                    }
+                })
+                .collect();
+            let root = absolute(0.0, 0.0, 200.0, 200.0);
+            js_node_id(inner.new_with_children(Style { position: Position::Relative, ..root }, &children).unwrap())
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        (tree, root)
+    }
+
+    #[test]
+    fn dense_overlapping_boxes_match_a_tree_walk() {
+        let (tree, root) = dense_tree(300);
+        let handle = tree.build_hit_index(root).unwrap();
+        let areas = hit_areas(&tree.inner.borrow(), node_from_js_id(root)).unwrap();
+        for y in (0..210).step_by(7) {
+            for x in (0..210).step_by(7) {
+                let point = Point { x: x as f32 + 0.5, y: y as f32 + 0.5 };
+                let hits = tree.hit_index_query(handle, point.x, point.y).unwrap();
+                let mut expected: Vec<_> = areas.iter().filter(|area| area.bounds.contains(point)).collect();
+                expected.reverse();
+                assert_eq!(hits, expected.iter().map(|area| area.node).collect::<Vec<_>>());
+                assert_eq!(hits.first().copied(), tree.node_at_point(root, point.x, point.y).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn hits_are_ordered_topmost_first() {
+        let tree = TaffyTree::new();
+        let (root, below, above, nested) = {
+            let mut inner = tree.inner.borrow_mut();
+            let nested = inner.new_leaf(absolute(5.0, 5.0, 10.0, 10.0)).unwrap();
+            let below = inner.new_with_children(absolute(10.0, 10.0, 50.0, 50.0), &[nested]).unwrap();
+            let above = inner.new_leaf(absolute(12.0, 12.0, 50.0, 50.0)).unwrap();
+            let root = Style { size: length(100.0), ..Default::default() };
+            let root = inner.new_with_children(root, &[below, above]).unwrap();
+            (js_node_id(root), js_node_id(below), js_node_id(above), js_node_id(nested))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        let handle = tree.build_hit_index(root).unwrap();
+
+        // The later sibling is on top of the earlier one and its child, which are on top of the root
+        assert_eq!(tree.hit_index_query(handle, 20.0, 20.0), Ok(vec![above, nested, below, root]));
+        assert_eq!(tree.hit_index_query(handle, 11.0, 11.0), Ok(vec![below, root]));
+        assert_eq!(tree.hit_index_query(handle, 150.0, 20.0), Ok(vec![]));
+    }
+
+    #[test]
+    fn layout_invalidates_hit_indices() {
+        let (tree, root) = dense_tree(3);
+        let handle = tree.build_hit_index(root).unwrap();
+        assert!(tree.hit_index_query(handle, 190.0, 190.0).is_ok());
+
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert!(matches!(tree.hit_index_query(handle, 190.0, 190.0), Err(TaffyError::InvalidArgument(_))));
+        let rebuilt = tree.build_hit_index(root).unwrap();
+        assert_ne!(rebuilt, handle);
+        assert_eq!(tree.hit_index_query(rebuilt, 190.0, 190.0), Ok(vec![root]));
+        assert_eq!(tree.build_hit_index(1000), Err(TaffyError::InvalidNode(1000)));
+    }
+}
@@ -0,0 +1,164 @@
+//! Finding the node under a point, e.g. to dispatch pointer events to the node that was clicked
+use crate::node_ids::js_node_id;
+use crate::node_state::NodeStates;
+use crate::scroll_offset::scrolled_origin;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, style::Overflow, Point, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// Returns whether `point` lies within the box starting at `origin` of size `size`, including its top and left
+/// edges but excluding its bottom and right edges
+fn box_contains(origin: Point<f32>, size: Size<f32>, point: Point<f32>) -> bool {
+    (origin.x..origin.x + size.width).contains(&point.x) && (origin.y..origin.y + size.height).contains(&point.y)
+}
+
+/// Returns the deepest, topmost node in the subtree rooted at `node` that contains `point`
+///
+/// `parent_origin` is the absolute location of the parent of `node`. Children are tested before their parent, and
+/// later siblings before earlier ones since they are painted on top. A child of a node whose `overflow` is not
+/// `visible` can only be hit within the padding box of that node. The children of a node with a scroll offset in
+/// `node_states` are moved up and left by it.
+fn hit_test(
+    tree: &Taffy<JsContext>,
+    node_states: &NodeStates,
+    node: NodeId,
+    parent_origin: Point<f32>,
+    point: Point<f32>,
+) -> Result<Option<NodeId>, TaffyError> {
+    let layout = tree.layout(node)?;
+    let origin = parent_origin + layout.location;
+
+    let overflow = tree.style(node)?.overflow;
+    let clips_x = overflow.x != Overflow::Visible;
+    let clips_y = overflow.y != Overflow::Visible;
+    let children_can_be_hit = (!clips_x
+        || (origin.x + layout.border.left..origin.x + layout.size.width - layout.border.right).contains(&point.x))
+        && (!clips_y
+            || (origin.y + layout.border.top..origin.y + layout.size.height - layout.border.bottom).contains(&point.y));
+
+    if children_can_be_hit {
+        let children_origin = scrolled_origin(node_states, js_node_id(node), origin);
+        for child in tree.children(node)?.into_iter().rev() {
+            if let Some(hit) = hit_test(tree, node_states, child, children_origin, point)? {
+                return Ok(Some(hit));
+            }
+        }
+    }
+
+    Ok(box_contains(origin, layout.size, point).then_some(node))
+}
+
+impl TaffyTree {
+    /// Returns the deepest node in the subtree rooted at `root` whose border box contains the point `(x, y)`
+    fn hit_test_node(&self, root: u32, x: f32, y: f32) -> Result<Option<NodeId>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        hit_test(&tree, &self.node_states.borrow(), root, Point::ZERO, Point { x, y })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the id of the deepest node in the subtree rooted at `root` whose border box contains the point
+    /// `(x, y)`, or `undefined` if no node does
+    ///
+    /// The point is in the same coordinates as `layout_buffer`, i.e. relative to the parent of `root`. When nodes
+    /// overlap, the one painted on top wins: children are on top of their parent and later siblings are on top of
+    /// earlier ones. Children may be hit outside the box of an ancestor whose `overflow` is `visible`, but not
+    /// outside the padding box of one whose `overflow` is `clip`, `hidden` or `scroll` along that axis. The
+    /// descendants of a node scrolled with `set_scroll_offset` are hit where they are scrolled to.
+    ///
+    /// The layout must have been computed beforehand.
+    #[wasm_bindgen]
+    pub fn node_at_point(&self, root: u32, x: f32, y: f32) -> Result<Option<u32>, TaffyError> {
+        Ok(self.hit_test_node(root, x, y)?.map(js_node_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaffyTree;
+    use taffy::style::Style;
+
+    fn fixed(width: f32, height: f32) -> Style {
+        Style { size: Size { width: length(width), height: length(height) }, ..Default::default() }
+    }
+
+    fn absolute(left: f32, top: f32, width: f32, height: f32) -> Style {
+        Style {
+            position: Position::Absolute,
+            inset: Rect { left: length(left), right: auto(), top: length(top), bottom: auto() },
+            ..fixed(width, height)
+        }
+    }
+
+    #[test]
+    fn topmost_overlapping_absolute_node_wins() {
+        let tree = TaffyTree::new();
+        let (root, below, above) = {
+            let mut inner = tree.inner.borrow_mut();
+            let below = inner.new_leaf(absolute(10.0, 10.0, 50.0, 50.0)).unwrap();
+            let above = inner.new_leaf(absolute(30.0, 30.0, 50.0, 50.0)).unwrap();
+            let root = inner.new_with_children(fixed(100.0, 100.0), &[below, above]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (root, below, above)
+        };
+        let hit = |x, y| tree.node_at_point(js_node_id(root), x, y).unwrap();
+
+        assert_eq!(hit(20.0, 20.0), Some(js_node_id(below)));
+        assert_eq!(hit(40.0, 40.0), Some(js_node_id(above)));
+        assert_eq!(hit(90.0, 10.0), Some(js_node_id(root)));
+        assert_eq!(hit(100.0, 50.0), None);
+        assert_eq!(hit(-1.0, 50.0), None);
+    }
+
+    #[test]
+    fn deepest_node_is_returned() {
+        let tree = TaffyTree::new();
+        let (root, grandchild) = {
+            let mut inner = tree.inner.borrow_mut();
+            let grandchild = inner.new_leaf(fixed(10.0, 10.0)).unwrap();
+            let child = inner.new_with_children(Style { padding: length(5.0), ..fixed(40.0, 40.0) }, &[grandchild]);
+            let root = inner.new_with_children(fixed(100.0, 100.0), &[child.unwrap()]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (root, grandchild)
+        };
+        assert_eq!(tree.node_at_point(js_node_id(root), 5.0, 5.0), Ok(Some(js_node_id(grandchild))));
+    }
+
+    #[test]
+    fn clipped_overflow_is_not_hit() {
+        let tree = TaffyTree::new();
+        let (root, clip, overflowing) = {
+            let mut inner = tree.inner.borrow_mut();
+            let overflowing = inner.new_leaf(absolute(40.0, 0.0, 50.0, 10.0)).unwrap();
+            let clip = Style {
+                overflow: Point { x: Overflow::Hidden, y: Overflow::Visible },
+                border: length(2.0),
+                ..fixed(50.0, 50.0)
+            };
+            let clip = inner.new_with_children(clip, &[overflowing]).unwrap();
+            let root = inner.new_with_children(fixed(100.0, 100.0), &[clip]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (root, clip, overflowing)
+        };
+        let hit = |x, y| tree.node_at_point(js_node_id(root), x, y).unwrap();
+
+        // The child starts at x = 42 inside the 2px border, and the clip's padding box ends at x = 48
+        assert_eq!(hit(45.0, 5.0), Some(js_node_id(overflowing)));
+        assert_eq!(hit(49.0, 5.0), Some(js_node_id(clip)));
+        assert_eq!(hit(60.0, 5.0), Some(js_node_id(root)));
+
+        // Without clipping the overflowing part of the child can be hit
+        tree.inner.borrow_mut().set_style(clip, fixed(50.0, 50.0)).unwrap();
+        tree.inner.borrow_mut().compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(hit(60.0, 5.0), Some(js_node_id(overflowing)));
+    }
+
+    #[test]
+    fn node_at_point_of_invalid_root() {
+        let tree = TaffyTree::new();
+        assert_eq!(tree.node_at_point(1, 0.0, 0.0), Err(TaffyError::InvalidNode(1)));
+    }
+}
@@ -0,0 +1,142 @@
+//! Finding the style properties that have no effect on the nodes they are set on, e.g. grid placements copied onto
+//! flex items while migrating CSS
+use crate::formatting_context::formatting_context_of;
+use crate::node_ids::js_node_id;
+use crate::style_defaults::differing_fields;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// Returns the snake case spelling of a style property name, whether or not it is spelled in camel case
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 2);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+/// Returns whether the property `name` (in snake case) has no effect on a node establishing the formatting context
+/// `context`, laid out by a parent establishing `parent_context`, if it has a parent
+fn is_inert(name: &str, context: &str, parent_context: Option<&str>, absolute: bool) -> bool {
+    match name {
+        "flex_grow" | "flex_shrink" | "flex_basis" => parent_context != Some("flex") || absolute,
+        "grid_row" | "grid_column" | "justify_self" => parent_context != Some("grid"),
+        "align_self" => !matches!(parent_context, Some("flex" | "grid")),
+        "flex_direction" | "flex_wrap" => context != "flex",
+        "grid_template_rows"
+        | "grid_template_columns"
+        | "grid_auto_rows"
+        | "grid_auto_columns"
+        | "grid_auto_flow"
+        | "justify_items" => context != "grid",
+        "align_items" | "align_content" | "justify_content" | "gap" => !matches!(context, "flex" | "grid"),
+        _ => false,
+    }
+}
+
+impl TaffyTree {
+    /// Returns the set style properties that have no effect on each node of the subtree rooted at `root`, leaving
+    /// out the nodes without any
+    fn ignored_style_fields(&self, root: u32) -> Result<BTreeMap<u32, Vec<String>>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let default = self.default_style();
+        let mut ignored = BTreeMap::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            stack.extend(tree.children(node)?);
+            let context = formatting_context_of(&tree, node)?;
+            if context == "none" {
+                continue;
+            }
+            let parent_context = tree.parent(node).map(|parent| formatting_context_of(&tree, parent)).transpose()?;
+            let style = self.unscale_style(tree.style(node)?.clone());
+            let absolute = style.position == Position::Absolute;
+            let fields: Vec<String> = differing_fields(&style, &default)
+                .into_iter()
+                .filter(|name| is_inert(&snake_case(name), context, parent_context, absolute))
+                .collect();
+            if !fields.is_empty() {
+                ignored.insert(js_node_id(node), fields);
+            }
+        }
+        Ok(ignored)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the style properties set on the nodes of the subtree rooted at `root` that have no effect where the
+    /// nodes are, as an object mapping node ids to arrays of property names, e.g. `{ 12: ["grid_row"] }`
+    ///
+    /// A property is reported if it differs from the default style, as for `non_default_fields`, and the formatting
+    /// context of the node or of its parent (see `formatting_context`) doesn't use it:
+    ///
+    /// - `flex_grow`, `flex_shrink` and `flex_basis` on a node that is not an in-flow item of a flex container
+    /// - `grid_row`, `grid_column` and `justify_self` on a node that is not an item of a grid container
+    /// - `align_self` on a node that is not an item of a flex or grid container
+    /// - `flex_direction` and `flex_wrap` on a node that is not a flex container, and the grid templates, auto
+    ///   tracks, `grid_auto_flow` and `justify_items` on a node that is not a grid container
+    /// - `align_items`, `align_content`, `justify_content` and `gap` on a node that is neither
+    ///
+    /// A container without children is a leaf, so its container properties are reported until children are added.
+    /// Nodes with `display: "None"` or inside one are skipped, since none of their properties have an effect. The
+    /// check only reads styles and the shape of the tree, so it gives the same result before and after a layout,
+    /// and costs nothing unless it is called. Names follow `non_default_fields`, so they are in camel case with the
+    /// `camel-case-style` feature, and nodes without ignored properties are left out.
+    #[wasm_bindgen]
+    pub fn ignored_styles(&self, root: u32) -> Result<JsValue, TaffyError> {
+        let ignored = self.ignored_style_fields(root)?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        Ok(ignored.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::style::Style;
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn grid_placement_on_a_flex_item_is_ignored() {
+        let tree = TaffyTree::new();
+        let (row, [flex_item, grid_item]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let placed = Style { grid_row: Line { start: line(2), end: auto() }, flex_grow: 1.0, ..Default::default() };
+            let flex_item = inner.new_leaf(placed.clone()).unwrap();
+            let grid_item = inner.new_leaf(placed).unwrap();
+            let grid = Style { display: Display::Grid, ..Default::default() };
+            let grid = inner.new_with_children(grid, &[grid_item]).unwrap();
+            let row = Style { flex_wrap: FlexWrap::Wrap, ..Default::default() };
+            (inner.new_with_children(row, &[flex_item, grid]).unwrap(), [flex_item, grid_item])
+        };
+        let names = |name: &str, camel_case: &str| {
+            vec![String::from(if cfg!(feature = "camel-case-style") { camel_case } else { name })]
+        };
+
+        let ignored = tree.ignored_style_fields(js_node_id(row)).unwrap();
+        assert_eq!(
+            ignored,
+            BTreeMap::from([
+                (js_node_id(flex_item), names("grid_row", "gridRow")),
+                (js_node_id(grid_item), names("flex_grow", "flexGrow")),
+            ])
+        );
+    }
+
+    #[test]
+    fn container_properties_of_a_leaf_are_ignored() {
+        let tree = TaffyTree::new();
+        let leaf = Style { flex_direction: FlexDirection::Column, gap: length(4.0), ..Default::default() };
+        let leaf = js_node_id(tree.inner.borrow_mut().new_leaf(leaf).unwrap());
+        assert_eq!(tree.ignored_style_fields(leaf).unwrap()[&leaf].len(), 2);
+        assert_eq!(tree.ignored_style_fields(leaf + 1), Err(TaffyError::InvalidNode(leaf + 1)));
+    }
+}
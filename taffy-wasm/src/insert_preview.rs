@@ -0,0 +1,169 @@
+//! Previewing the reflow that inserting a child would cause, without inserting it, e.g. to show where a dragged item
+//! would land
+use crate::available_space::{decode_available_space, BoundedAvailableSpace};
+use crate::layout_json::{node_boxes, NodeBox};
+use crate::node_ids::{js_node_id, new_node_id};
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// The box a node that doesn't exist yet would have, relative to the same origin as the boxes of [`NodeBox`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub(crate) struct PreviewBox {
+    /// The distance from the left edge of the root's parent to the left edge of the node's border box
+    pub(crate) x: f32,
+    /// The distance from the top edge of the root's parent to the top edge of the node's border box
+    pub(crate) y: f32,
+    /// The width of the node's border box
+    pub(crate) w: f32,
+    /// The height of the node's border box
+    pub(crate) h: f32,
+}
+
+/// The layout an insertion would cause, returned by `preview_insert`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct InsertPreview {
+    /// The boxes the existing children of the parent would have, for those whose box would change
+    siblings: Vec<NodeBox>,
+    /// The box the inserted node would have
+    inserted: PreviewBox,
+}
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `root` within `available` on a scratch copy of the tree in which a node with
+    /// `style` is inserted as the child of `parent` at `index`, returning the boxes that differ from the tree's own
+    fn preview_insertion(
+        &self,
+        parent: u32,
+        style: Style,
+        index: u32,
+        root: u32,
+        available: BoundedAvailableSpace,
+    ) -> Result<InsertPreview, TaffyError> {
+        let (mut scratch, current_boxes) = {
+            let tree = self.tree()?;
+            let root_node = resolve_node(&tree, root)?;
+            let mut ancestor = Some(resolve_node(&tree, parent)?);
+            while ancestor.is_some_and(|node| node != root_node) {
+                ancestor = ancestor.and_then(|node| tree.parent(node));
+            }
+            if ancestor.is_none() {
+                return Err(TaffyError::InvalidArgument(format!("node {parent} is not in the subtree of node {root}")));
+            }
+            self.check_layout_depth(&tree, root_node)?;
+            let current_boxes: BTreeMap<u32, NodeBox> =
+                node_boxes(&tree, root_node)?.into_iter().map(|node_box| (node_box.id, node_box)).collect();
+            (tree.clone(), current_boxes)
+        };
+        let (parent, root) = (resolve_node(&scratch, parent)?, resolve_node(&scratch, root)?);
+        let siblings = scratch.children(parent)?;
+        let inserted = scratch.new_leaf(style)?;
+        new_node_id(&mut scratch, inserted)?;
+        scratch.insert_child_at_index(parent, index as usize, inserted)?;
+
+        let mut layout = |space| {
+            scratch.compute_layout_with_measure(root, space, |known, available, leaf, _, _| {
+                self.measure_content_size(known, available, leaf)
+            })?;
+            Ok::<_, TaffyError>(scratch.unrounded_layout(root).size)
+        };
+        let size = layout(available.space)?;
+        if let Some(space) = available.relayout_space(size) {
+            layout(space)?;
+        }
+
+        let boxes: BTreeMap<u32, NodeBox> =
+            node_boxes(&scratch, root)?.into_iter().map(|node_box| (node_box.id, node_box)).collect();
+        let inserted = boxes[&js_node_id(inserted)];
+        Ok(InsertPreview {
+            siblings: siblings
+                .into_iter()
+                .map(|sibling| boxes[&js_node_id(sibling)])
+                .filter(|node_box| current_boxes.get(&node_box.id) != Some(node_box))
+                .collect(),
+            inserted: PreviewBox { x: inserted.x, y: inserted.y, w: inserted.w, h: inserted.h },
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the reflow that inserting a node with `child_style` as the child of `parent` at `index` would cause
+    /// in the subtree rooted at `root`, laid out within `available`, without inserting it, as
+    /// `{ siblings: [{ id, x, y, w, h }], inserted: { x, y, w, h } }`
+    ///
+    /// This is meant for drag and drop: `inserted` is where the dragged item would land, and `siblings` are the
+    /// boxes the existing children of `parent` would move or resize to, so that they can be animated out of its
+    /// way. Only the siblings whose box would differ from their box in the last layout are listed, in child order.
+    /// Boxes have `x` and `y` relative to the parent of `root`, as from `compute_layout_to_json`.
+    ///
+    /// The insertion is made in a copy of the tree that is discarded afterwards, so no node is created, and the
+    /// children, layouts, caches and dirty flags of every node are exactly as they were before the call. The
+    /// inserted node is a leaf sized by its style, and `available` is a `LayoutAvailableSpace`, as for
+    /// `compute_layout_v2`. The copy takes time proportional to the size of the whole tree.
+    ///
+    /// Throws a `STYLE_DECODE` error if `child_style` is not a valid style, a `CHILD_INDEX_OUT_OF_BOUNDS` error if
+    /// `index` is greater than the number of children of `parent`, and an `INVALID_ARGUMENT` error if `parent` is
+    /// not in the subtree of `root` or if `available` is not a valid `LayoutAvailableSpace`.
+    #[wasm_bindgen]
+    pub fn preview_insert(
+        &self,
+        parent: u32,
+        child_style: JsValue,
+        index: u32,
+        root: u32,
+        available: JsValue,
+    ) -> Result<JsValue, TaffyError> {
+        let style = self.decode_style_with_defaults(child_style)?;
+        let preview = self.preview_insertion(parent, style, index, root, decode_available_space(available)?)?;
+        Ok(serde_wasm_bindgen::to_value(&preview).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::style::Style;
+
+    #[test]
+    fn siblings_make_room_and_the_tree_is_unchanged() {
+        let tree = TaffyTree::new();
+        let item = Style { flex_grow: 1.0, flex_basis: length(0.0), ..Default::default() };
+        let (root, row, [first, second]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item.clone()).unwrap()];
+            let row = Style { size: Size { width: length(300.0), height: length(40.0) }, ..Default::default() };
+            let row = inner.new_with_children(row, &items).unwrap();
+            let root = inner.new_with_children(Style { padding: length(10.0), ..Default::default() }, &[row]).unwrap();
+            (js_node_id(root), js_node_id(row), items.map(js_node_id))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        let node_count = tree.inner.borrow().total_node_count();
+
+        let available = BoundedAvailableSpace { space: Size::MAX_CONTENT, limits: Size::NONE };
+        let preview = tree.preview_insertion(row, item, 1, root, available).unwrap();
+        let node_box = |id, x, w| NodeBox { id, x, y: 10.0, w, h: 40.0 };
+        assert_eq!(preview.siblings, vec![node_box(first, 10.0, 100.0), node_box(second, 210.0, 100.0)]);
+        assert_eq!(preview.inserted, PreviewBox { x: 110.0, y: 10.0, w: 100.0, h: 40.0 });
+
+        // No node was added, and the tree keeps its layout without needing a relayout
+        assert_eq!(tree.inner.borrow().total_node_count(), node_count);
+        assert_eq!(tree.descendants(row, None), Ok(vec![first, second]));
+        assert_eq!((tree.layout_left(second), tree.layout_width(second)), (Ok(150.0), Ok(150.0)));
+        assert_eq!(tree.dirty(root), Ok(false));
+        assert_eq!(tree.dirty(row), Ok(false));
+
+        // Appending a fixed-size item that fits leaves the siblings where they are
+        let fixed = Style { size: Size { width: length(0.0), height: length(40.0) }, ..Default::default() };
+        let preview = tree.preview_insertion(row, fixed.clone(), 2, root, available).unwrap();
+        assert_eq!(preview.siblings, vec![]);
+        assert_eq!(preview.inserted, PreviewBox { x: 310.0, y: 10.0, w: 0.0, h: 40.0 });
+
+        let out_of_bounds = tree.preview_insertion(row, fixed.clone(), 3, root, available);
+        assert!(matches!(out_of_bounds, Err(TaffyError::ChildIndexOutOfBounds { .. })), "{out_of_bounds:?}");
+        let outside = tree.preview_insertion(root, fixed, 0, row, available);
+        assert!(matches!(outside, Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,115 @@
+//! Reporting the alignment a flex or grid item is laid out with, once `auto` is resolved against its container
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// The alignment of an item in each axis, returned by `resolved_item_alignment`, or `None` in an axis the item is
+/// not aligned in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemAlignment {
+    /// The alignment in the cross axis of a flex container, or the block axis of a grid container
+    pub(crate) align_self: Option<AlignSelf>,
+    /// The alignment in the inline axis of a grid container
+    pub(crate) justify_self: Option<AlignSelf>,
+}
+
+impl TaffyTree {
+    /// Returns the alignment of a node within its parent, resolving `auto` against the parent's default alignment
+    pub(crate) fn item_alignment(&self, node_id: u32) -> Result<ItemAlignment, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let unaligned = ItemAlignment { align_self: None, justify_self: None };
+        let Some(parent) = tree.parent(node) else {
+            return Ok(unaligned);
+        };
+        let (style, container) = (tree.style(node)?, tree.style(parent)?);
+        if style.display == Display::None {
+            return Ok(unaligned);
+        }
+        // `Display::Grid` and `Display::Block` only exist with the `grid` and `block` features
+        #[allow(unreachable_patterns)]
+        Ok(match container.display {
+            Display::Flex => ItemAlignment {
+                align_self: Some(style.align_self.or(container.align_items).unwrap_or(AlignSelf::Stretch)),
+                justify_self: None,
+            },
+            #[cfg(feature = "grid")]
+            // As in CSS grid, an item with a definite size or an aspect ratio is not stretched to fill its area
+            Display::Grid => ItemAlignment {
+                align_self: Some(style.align_self.or(container.align_items).unwrap_or(
+                    if style.size.height.is_auto() && style.aspect_ratio.is_none() {
+                        AlignSelf::Stretch
+                    } else {
+                        AlignSelf::Start
+                    },
+                )),
+                justify_self: Some(
+                    style.justify_self.or(container.justify_items).unwrap_or(if style.size.width.is_auto() {
+                        AlignSelf::Stretch
+                    } else {
+                        AlignSelf::Start
+                    }),
+                ),
+            },
+            _ => unaligned,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the alignment a node is laid out with within its parent, as `{ alignSelf, justifySelf }`
+    ///
+    /// Each is an `AlignSelf` value such as `"Center"`, with `auto` (an unset `align_self` or `justify_self`)
+    /// resolved as the layout resolves it: to the parent's `align_items` or `justify_items`, or if that is unset too,
+    /// to `"Stretch"`. In a grid, an item with a non-`auto` size in the axis, or, for `alignSelf`, an
+    /// `aspect_ratio`, falls back to `"Start"` instead, as it is not stretched. Flex items have no `justifySelf`, as
+    /// the main axis is aligned by the container's `justify_content`, so it is `null` for them. Both are `null` for a
+    /// root, a node with `display: "None"`, and the children of a block container.
+    ///
+    /// The values are resolved from the current styles of the node and its parent, which are those of the last
+    /// layout unless either has changed since. `"Start"` and `"End"` are as written, and refer to the inline
+    /// direction even in a right-to-left column, where the layout flips them.
+    #[wasm_bindgen]
+    pub fn resolved_item_alignment(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let alignment = self.item_alignment(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&alignment).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(all(test, feature = "grid"))]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn auto_alignment_resolves_against_the_container() {
+        let tree = TaffyTree::new();
+        let [grid_item, overriding_item, sized_item, flex_item, root] = {
+            let mut inner = tree.inner.borrow_mut();
+            let grid_item = inner.new_leaf(Style::default()).unwrap();
+            let overriding = Style { align_self: Some(AlignSelf::End), ..Default::default() };
+            let overriding_item = inner.new_leaf(overriding).unwrap();
+            let centered =
+                Style { display: Display::Grid, align_items: Some(AlignItems::Center), ..Default::default() };
+            let grid = inner.new_with_children(centered, &[grid_item, overriding_item]).unwrap();
+            let sized_item = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let plain_grid = Style { display: Display::Grid, ..Default::default() };
+            let sized_grid = inner.new_with_children(plain_grid, &[sized_item]).unwrap();
+            let flex_item = inner.new_leaf(Style::default()).unwrap();
+            let root = inner.new_with_children(Style::default(), &[grid, sized_grid, flex_item]).unwrap();
+            [grid_item, overriding_item, sized_item, flex_item, root].map(js_node_id)
+        };
+
+        let alignment = |node| tree.item_alignment(node).unwrap();
+        let aligned = |align_self, justify_self| ItemAlignment { align_self, justify_self };
+        // The grid item's `auto` align-self is the container's `align-items`, and its justify-self stretches
+        assert_eq!(alignment(grid_item), aligned(Some(AlignSelf::Center), Some(AlignSelf::Stretch)));
+        assert_eq!(alignment(overriding_item), aligned(Some(AlignSelf::End), Some(AlignSelf::Stretch)));
+        assert_eq!(alignment(sized_item), aligned(Some(AlignSelf::Start), Some(AlignSelf::Start)));
+        assert_eq!(alignment(flex_item), aligned(Some(AlignSelf::Stretch), None));
+        assert_eq!(alignment(root), aligned(None, None));
+    }
+}
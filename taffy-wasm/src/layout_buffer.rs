@@ -0,0 +1,352 @@
+//! Bulk export of computed layouts into typed arrays, for renderers that upload node boxes directly
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use js_sys::{Float32Array, SharedArrayBuffer, Uint32Array};
+use serde::Serialize;
+use taffy::{prelude::*, Point, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The number of values written per node: `[x, y, width, height]`
+pub const LAYOUT_STRIDE: usize = 4;
+
+/// The number of values written per node when the content size is included:
+/// `[x, y, width, height, content_width, content_height]`
+pub const LAYOUT_STRIDE_WITH_CONTENT_SIZE: usize = 6;
+
+/// The result of `compute_layout_into_shared`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedLayoutWrite {
+    /// The number of nodes whose layout was written
+    pub count: u32,
+    /// The ids of the nodes whose layout was written, in the order they were written
+    pub node_ids: Vec<u32>,
+}
+
+/// Returns the nodes of the subtree rooted at `root` in paint order, along with their absolute location
+///
+/// Paint order is a pre-order traversal: each node comes before its children, and siblings are in document order.
+/// Locations are relative to the origin of `root`'s parent, i.e. `root` is at its own layout location.
+pub(crate) fn paint_order(tree: &Taffy<JsContext>, root: NodeId) -> Result<Vec<(NodeId, Point<f32>)>, TaffyError> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![(root, Point::ZERO)];
+    while let Some((node, parent_location)) = stack.pop() {
+        let location = parent_location + tree.layout(node)?.location;
+        nodes.push((node, location));
+        stack.extend(tree.children(node)?.into_iter().rev().map(|child| (child, location)));
+    }
+    Ok(nodes)
+}
+
+impl TaffyTree {
+    /// Returns the layout of every node in the subtree rooted at `root`, in paint order, packed into a single buffer
+    fn layout_values(&self, root: u32, include_content_size: bool) -> Result<Vec<f32>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+
+        let nodes = paint_order(&tree, root)?;
+        let stride = if include_content_size { LAYOUT_STRIDE_WITH_CONTENT_SIZE } else { LAYOUT_STRIDE };
+        let mut values = Vec::with_capacity(nodes.len() * stride);
+        for (node, location) in nodes {
+            let layout = tree.layout(node)?;
+            values.extend([location.x, location.y, layout.size.width, layout.size.height]);
+            if include_content_size {
+                values.extend([layout.content_size.width, layout.content_size.height]);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Returns the `[x, y, width, height]` of every node in the subtree rooted at `root`, in paint order, multiplied by
+    /// `scale`, with the edges of each box rounded to whole device pixels if `round` is `true`
+    fn scaled_layout_values(&self, root: u32, scale: f32, round: bool) -> Result<Vec<f32>, TaffyError> {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(TaffyError::InvalidArgument(format!("scale must be a positive number, got {scale}")));
+        }
+        let mut values = self.layout_values(root, false)?;
+        for node in values.chunks_exact_mut(LAYOUT_STRIDE) {
+            let [x, y, width, height] = [node[0], node[1], node[2], node[3]].map(|value| value * scale);
+            if round {
+                // As taffy does, round the edges rather than the size, so that adjacent boxes meet without seams
+                let (left, top) = (x.round(), y.round());
+                node.copy_from_slice(&[left, top, (x + width).round() - left, (y + height).round() - top]);
+            } else {
+                node.copy_from_slice(&[x, y, width, height]);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Writes the `[x, y, width, height]` of every node in the subtree rooted at `root`, in paint order, to the start
+    /// of `out`, returning the number of nodes written
+    fn write_layout_values(&self, root: u32, out: &mut [f32]) -> Result<usize, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+
+        let nodes = paint_order(&tree, root)?;
+        let required = nodes.len() * LAYOUT_STRIDE;
+        if out.len() < required {
+            return Err(TaffyError::InvalidArgument(format!(
+                "out has length {} but {required} values are needed to hold the layout of {} nodes",
+                out.len(),
+                nodes.len()
+            )));
+        }
+        for ((node, location), values) in nodes.iter().zip(out.chunks_exact_mut(LAYOUT_STRIDE)) {
+            let size = tree.layout(*node)?.size;
+            values.copy_from_slice(&[location.x, location.y, size.width, size.height]);
+        }
+        Ok(nodes.len())
+    }
+
+    /// Computes the layout of the tree rooted at `root`, returning its `[x, y, width, height]` values as for
+    /// `write_layout_values` and the ids of their nodes, once checked to fit at byte `offset` of a buffer of
+    /// `byte_length` bytes
+    fn shared_layout_values(
+        &self,
+        root: u32,
+        available_space: Size<AvailableSpace>,
+        offset: u32,
+        byte_length: u32,
+    ) -> Result<(Vec<f32>, Vec<u32>), TaffyError> {
+        if !offset.is_multiple_of(4) {
+            return Err(TaffyError::InvalidArgument(format!("offset must be a multiple of 4 bytes, got {offset}")));
+        }
+        self.compute_subtree(root, available_space)?;
+        let values = self.layout_values(root, false)?;
+        let required = offset as usize + values.len() * std::mem::size_of::<f32>();
+        if (byte_length as usize) < required {
+            return Err(TaffyError::InvalidArgument(format!(
+                "the shared buffer has {byte_length} bytes but {required} are needed to hold the layout of {} nodes \
+                 at offset {offset}",
+                values.len() / LAYOUT_STRIDE
+            )));
+        }
+        Ok((values, self.node_order_ids(root)?))
+    }
+
+    /// Returns the ids of the nodes in the subtree rooted at `root`, in paint order
+    fn node_order_ids(&self, root: u32) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        Ok(paint_order(&tree, root)?.into_iter().map(|(node, _)| js_node_id(node)).collect())
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Writes the computed layout of every node in the subtree rooted at `root` into a single `Float32Array`
+    ///
+    /// Each node takes up 4 consecutive values, `[x, y, width, height]`, or 6 values,
+    /// `[x, y, width, height, contentWidth, contentHeight]`, if `include_content_size` is `true`. Positions are
+    /// absolute: they are relative to the parent of `root` rather than to each node's own parent. Nodes are in
+    /// paint order (parents before their children, siblings in document order): use `node_order` to map buffer
+    /// entries back to node ids.
+    #[wasm_bindgen]
+    pub fn layout_buffer(&self, root: u32, include_content_size: Option<bool>) -> Result<Float32Array, TaffyError> {
+        let values = self.layout_values(root, include_content_size.unwrap_or(false))?;
+        Ok(Float32Array::from(values.as_slice()))
+    }
+
+    /// Returns `layout_buffer(root)` with every value multiplied by `scale`, e.g. a canvas's zoom factor
+    ///
+    /// Each node takes up 4 consecutive values, `[x, y, width, height]`, in the same order and with the same
+    /// absolute positions as `layout_buffer`, scaled from layout units to device pixels so that a renderer doesn't
+    /// have to multiply them in JS. If `round` is `true`, each box is rounded to whole device pixels at that scale
+    /// the way taffy rounds layouts: its left and top edges are rounded, and its width and height are the distance
+    /// to its rounded right and bottom edges, so that adjacent boxes still meet without gaps. Rounding applies to the
+    /// layout as returned, so to round only once, disable taffy's own rounding with `set_rounding_mode("none")`.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `scale` is not a positive finite number.
+    #[wasm_bindgen]
+    pub fn layout_buffer_scaled(&self, root: u32, scale: f32, round: Option<bool>) -> Result<Float32Array, TaffyError> {
+        let values = self.scaled_layout_values(root, scale, round.unwrap_or(false))?;
+        Ok(Float32Array::from(values.as_slice()))
+    }
+
+    /// Computes the layout of the tree rooted at `root` and writes it into `out`, returning the number of nodes written
+    ///
+    /// This is `compute_layout` followed by `layout_buffer`, except that the layout is written in place into an
+    /// existing `Float32Array` so that a render loop can reuse the same buffer every frame. Each node takes up 4
+    /// consecutive values, `[x, y, width, height]`, in the same order and with the same absolute positions as
+    /// `layout_buffer`. Values past the last node are left untouched.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error naming the required length if `out` has fewer than 4 values per node in
+    /// the subtree. The layout is still computed in that case, so `layout_buffer` can be used instead.
+    #[wasm_bindgen]
+    pub fn compute_layout_into(&self, root: u32, width: f32, height: f32, out: &mut [f32]) -> Result<u32, TaffyError> {
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        self.compute_subtree(root, available_space)?;
+        Ok(self.write_layout_values(root, out)? as u32)
+    }
+
+    /// Computes the layout of the tree rooted at `root` and writes it into `shared` starting at byte `offset`,
+    /// returning `{ count, nodeIds }`
+    ///
+    /// This is `compute_layout_into` for a layout computed in a worker and read on the main thread: the values are
+    /// written straight into a `SharedArrayBuffer` the main thread also has a view of, so that boxes can be read
+    /// without a `postMessage`. Each node takes up 4 consecutive `f32` values, `[x, y, width, height]`, in the same
+    /// order and with the same absolute positions as `layout_buffer`, i.e. `count * 16` bytes from `offset`.
+    /// `nodeIds` maps the entries back to node ids, as `node_order` does. Bytes outside the written range are left
+    /// untouched.
+    ///
+    /// The writes are plain, non-atomic stores. The caller must ensure the main thread reads the buffer only after
+    /// this call has returned, e.g. by publishing a frame counter with `Atomics.store` after the call and checking
+    /// it with `Atomics.load` (or waiting on it with `Atomics.wait`) before reading, and must not let two layouts
+    /// write the same range concurrently. A read that races the write may see a mix of old and new boxes.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `offset` is not a multiple of 4, or if the layout doesn't fit between
+    /// `offset` and the end of `shared`. The layout is still computed in the latter case.
+    #[wasm_bindgen]
+    pub fn compute_layout_into_shared(
+        &self,
+        root: u32,
+        width: f32,
+        height: f32,
+        shared: &SharedArrayBuffer,
+        offset: u32,
+    ) -> Result<JsValue, TaffyError> {
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        let (values, node_ids) = self.shared_layout_values(root, available_space, offset, shared.byte_length())?;
+        Float32Array::new_with_byte_offset_and_length(shared, offset, values.len() as u32).copy_from(&values);
+        let written = SharedLayoutWrite { count: node_ids.len() as u32, node_ids };
+        Ok(serde_wasm_bindgen::to_value(&written).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the ids of the nodes in the subtree rooted at `root`, in the order used by `layout_buffer`
+    #[wasm_bindgen]
+    pub fn node_order(&self, root: u32) -> Result<Uint32Array, TaffyError> {
+        let ids = self.node_order_ids(root)?;
+        Ok(Uint32Array::from(ids.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::style::Style;
+
+    #[test]
+    fn layout_values_are_absolute_and_in_paint_order() {
+        let tree = TaffyTree::new();
+        let (root, child, grandchild, sibling) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fixed = |width, height| Style {
+                size: Size { width: length(width), height: length(height) },
+                ..Default::default()
+            };
+            let grandchild = inner.new_leaf(fixed(5.0, 5.0)).unwrap();
+            let child =
+                inner.new_with_children(Style { padding: length(10.0), ..fixed(40.0, 40.0) }, &[grandchild]).unwrap();
+            let sibling = inner.new_leaf(fixed(20.0, 20.0)).unwrap();
+            let root = inner.new_with_children(fixed(100.0, 100.0), &[child, sibling]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (root, child, grandchild, sibling)
+        };
+
+        assert_eq!(
+            tree.node_order_ids(js_node_id(root)).unwrap(),
+            vec![js_node_id(root), js_node_id(child), js_node_id(grandchild), js_node_id(sibling)]
+        );
+        assert_eq!(
+            tree.layout_values(js_node_id(root), false).unwrap(),
+            vec![
+                0.0, 0.0, 100.0, 100.0, // root
+                0.0, 0.0, 40.0, 40.0, // child
+                10.0, 10.0, 5.0, 5.0, // grandchild, inside the child's padding
+                40.0, 0.0, 20.0, 20.0, // sibling
+            ]
+        );
+
+        let with_content_size = tree.layout_values(js_node_id(root), true).unwrap();
+        assert_eq!(with_content_size.len(), 4 * LAYOUT_STRIDE_WITH_CONTENT_SIZE);
+        assert_eq!(&with_content_size[..LAYOUT_STRIDE_WITH_CONTENT_SIZE], &[0.0, 0.0, 100.0, 100.0, 60.0, 40.0]);
+    }
+
+    #[test]
+    fn scaled_layout_values_are_the_unscaled_values_multiplied() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let children: Vec<_> =
+                (0..3).map(|_| inner.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap()).collect();
+            let root = inner.new_with_children(Style { size: length(10.0), ..Default::default() }, &children).unwrap();
+            inner.disable_rounding();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            js_node_id(root)
+        };
+
+        let unscaled = tree.layout_values(root, false).unwrap();
+        let multiplied: Vec<f32> = unscaled.iter().map(|value| value * 1.5).collect();
+        assert_eq!(tree.scaled_layout_values(root, 1.5, false).unwrap(), multiplied);
+
+        // The children are 20/3 device pixels wide: rounding their edges gives whole widths that still add up to the
+        // root's 20 pixels, where rounding each width on its own would give 7 pixels each
+        let rounded = tree.scaled_layout_values(root, 2.0, true).unwrap();
+        assert_eq!(&rounded[4..], &[0.0, 0.0, 7.0, 20.0, 7.0, 0.0, 6.0, 20.0, 13.0, 0.0, 7.0, 20.0]);
+        assert!(matches!(tree.scaled_layout_values(root, 0.0, false), Err(TaffyError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn compute_layout_into_reuses_the_buffer() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let children: Vec<_> =
+                (0..2).map(|_| inner.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap()).collect();
+            let root = inner.new_with_children(Style { size: percent(1.0), ..Default::default() }, &children).unwrap();
+            js_node_id(root)
+        };
+
+        let mut out = [-1.0; 16];
+        assert_eq!(tree.compute_layout_into(root, 100.0, 20.0, &mut out), Ok(3));
+        assert_eq!(&out[..12], &[0.0, 0.0, 100.0, 20.0, 0.0, 0.0, 50.0, 20.0, 50.0, 0.0, 50.0, 20.0]);
+        assert_eq!(&out[12..], &[-1.0; 4]);
+
+        // The next frame overwrites the same buffer
+        assert_eq!(tree.compute_layout_into(root, 60.0, 20.0, &mut out), Ok(3));
+        assert_eq!(&out[..12], &[0.0, 0.0, 60.0, 20.0, 0.0, 0.0, 30.0, 20.0, 30.0, 0.0, 30.0, 20.0]);
+    }
+
+    #[test]
+    fn compute_layout_into_too_small_buffer() {
+        let tree = TaffyTree::new();
+        let root = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        let mut out = [0.0; 3];
+        match tree.compute_layout_into(root, 100.0, 100.0, &mut out) {
+            Err(TaffyError::InvalidArgument(message)) => assert!(message.contains("4 values are needed")),
+            other => panic!("expected an INVALID_ARGUMENT error, got {other:?}"),
+        }
+        assert_eq!(out, [0.0; 3]);
+    }
+
+    #[test]
+    fn shared_layout_values_fit_after_the_offset() {
+        let tree = TaffyTree::new();
+        let (root, children) = {
+            let mut inner = tree.inner.borrow_mut();
+            let children: Vec<_> =
+                (0..2).map(|_| inner.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap()).collect();
+            let root = inner.new_with_children(Style { size: percent(1.0), ..Default::default() }, &children).unwrap();
+            (js_node_id(root), children.into_iter().map(js_node_id).collect::<Vec<_>>())
+        };
+        let available_space = Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::Definite(20.0) };
+
+        // Three nodes take 48 bytes, which fit exactly after a 16 byte header
+        let (values, node_ids) = tree.shared_layout_values(root, available_space, 16, 64).unwrap();
+        assert_eq!(values, vec![0.0, 0.0, 100.0, 20.0, 0.0, 0.0, 50.0, 20.0, 50.0, 0.0, 50.0, 20.0]);
+        assert_eq!(node_ids, vec![root, children[0], children[1]]);
+
+        // Past the end of the buffer, and misaligned for a `Float32Array` view
+        for (offset, byte_length) in [(20, 64), (18, 100)] {
+            let result = tree.shared_layout_values(root, available_space, offset, byte_length);
+            assert!(matches!(result, Err(TaffyError::InvalidArgument(_))), "offset {offset}");
+        }
+    }
+
+    #[test]
+    fn layout_values_of_invalid_root() {
+        let tree = TaffyTree::new();
+        assert_eq!(tree.layout_values(1, false), Err(TaffyError::InvalidNode(1)));
+    }
+}
@@ -0,0 +1,255 @@
+//! Comparing serialized layouts within a tolerance, e.g. in golden-file tests that trip on floating-point noise, and
+//! comparing computed layouts with boxes measured in a browser
+use crate::layout_buffer::paint_order;
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+/// The box a node is expected to have, relative to the root of the subtree, as passed to `compare_with_boxes`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct ExpectedBox {
+    /// The node id
+    id: u32,
+    /// The expected distance from the left edge of the root to the left edge of the node's border box
+    x: f32,
+    /// The expected distance from the top edge of the root to the top edge of the node's border box
+    y: f32,
+    /// The expected width of the node's border box
+    width: f32,
+    /// The expected height of the node's border box
+    height: f32,
+}
+
+/// How far the computed box of a node is from its expected box, returned by `compare_with_boxes`
+///
+/// Each difference is the computed value minus the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BoxDelta {
+    /// The node id
+    id: u32,
+    /// The difference in the distance from the left edge of the root
+    dx: f32,
+    /// The difference in the distance from the top edge of the root
+    dy: f32,
+    /// The difference in width
+    d_width: f32,
+    /// The difference in height
+    d_height: f32,
+}
+
+/// Returns the path to the first value at which `a` and `b` differ, or `None` if they are equal within `epsilon`
+///
+/// Paths are written as JSONPath, e.g. `$.children[1].size.width`, and `path` is the path to `a` and `b`.
+fn first_difference(a: &Value, b: &Value, epsilon: f64, path: &str) -> Option<String> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or(f64::NAN), b.as_f64().unwrap_or(f64::NAN));
+            ((a - b).abs() > epsilon).then(|| path.to_string())
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            if a.len() != b.len() {
+                return Some(path.to_string());
+            }
+            a.iter().zip(b).enumerate().find_map(|(i, (a, b))| first_difference(a, b, epsilon, &format!("{path}[{i}]")))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            if let Some(key) = a.keys().chain(b.keys()).find(|key| !(a.contains_key(*key) && b.contains_key(*key))) {
+                return Some(format!("{path}.{key}"));
+            }
+            a.iter().find_map(|(key, a)| first_difference(a, &b[key], epsilon, &format!("{path}.{key}")))
+        }
+        (a, b) => (a != b).then(|| path.to_string()),
+    }
+}
+
+/// Decodes the serialized layouts and tolerance passed to `layouts_equal` and `layout_difference`
+fn decode(a: JsValue, b: JsValue, epsilon: f32) -> Result<(Value, Value, f64), TaffyError> {
+    if epsilon.is_nan() || epsilon < 0.0 {
+        return Err(TaffyError::InvalidArgument(format!("epsilon must be a non-negative number, got {epsilon}")));
+    }
+    let decode = |value, name| {
+        serde_wasm_bindgen::from_value(value).map_err(|e| TaffyError::InvalidArgument(format!("{name}: {e}")))
+    };
+    Ok((decode(a, "a")?, decode(b, "b")?, f64::from(epsilon)))
+}
+
+/// Returns whether two serialized layouts are equal, treating numbers that differ by at most `epsilon` as equal
+///
+/// `a` and `b` are any JSON-like values built from the layouts returned by these bindings, e.g. a golden file
+/// holding a tree of `{ x, y, width, height, children }` objects. They are compared field by field: objects must
+/// have the same keys, arrays the same length, and strings, booleans and `null`s must be identical. Numbers are
+/// compared within `epsilon`. `NaN`, which JSON can't represent, compares like `null`.
+///
+/// Throws an `INVALID_ARGUMENT` error if `epsilon` is negative or `NaN`, or if `a` or `b` holds a value that
+/// can't be represented in JSON, such as a function.
+#[wasm_bindgen]
+pub fn layouts_equal(a: JsValue, b: JsValue, epsilon: f32) -> Result<bool, TaffyError> {
+    let (a, b, epsilon) = decode(a, b, epsilon)?;
+    Ok(first_difference(&a, &b, epsilon, "$").is_none())
+}
+
+/// Returns the path to the first value at which two serialized layouts differ, e.g. `"$.children[1].width"`, or
+/// `undefined` if they are equal within `epsilon`
+///
+/// Values are compared as by `layouts_equal`, with object keys in alphabetical order, so this is meant to report
+/// which node of a failing snapshot test first differs. The path of a key that is missing on one side ends with
+/// that key, and the path of arrays of different lengths is the path of the arrays themselves.
+#[wasm_bindgen]
+pub fn layout_difference(a: JsValue, b: JsValue, epsilon: f32) -> Result<Option<String>, TaffyError> {
+    let (a, b, epsilon) = decode(a, b, epsilon)?;
+    Ok(first_difference(&a, &b, epsilon, "$"))
+}
+
+impl TaffyTree {
+    /// Returns the differences between the computed boxes of nodes in the subtree rooted at `root` and their
+    /// expected boxes, for the nodes that differ by more than `epsilon` in any value
+    fn box_deltas(&self, root: u32, expected: &[ExpectedBox], epsilon: f32) -> Result<Vec<BoxDelta>, TaffyError> {
+        let tree = self.tree()?;
+        let root_id = root;
+        let root = resolve_node(&tree, root)?;
+        // Boxes relative to the root's border box, rather than to the origin it was laid out in
+        let root_location = tree.layout(root)?.location;
+        let mut boxes = BTreeMap::new();
+        for (node, location) in paint_order(&tree, root)? {
+            let location = taffy::Point { x: location.x - root_location.x, y: location.y - root_location.y };
+            boxes.insert(js_node_id(node), (location, tree.layout(node)?.size));
+        }
+        let mut deltas = Vec::new();
+        for expected in expected {
+            let Some((location, size)) = boxes.get(&expected.id) else {
+                return Err(TaffyError::InvalidArgument(format!(
+                    "node {} is not in the subtree rooted at node {root_id}",
+                    expected.id
+                )));
+            };
+            let delta = BoxDelta {
+                id: expected.id,
+                dx: location.x - expected.x,
+                dy: location.y - expected.y,
+                d_width: size.width - expected.width,
+                d_height: size.height - expected.height,
+            };
+            // A NaN difference is never within epsilon
+            let differences = [delta.dx, delta.dy, delta.d_width, delta.d_height];
+            if !differences.iter().all(|difference| difference.abs() <= epsilon) {
+                deltas.push(delta);
+            }
+        }
+        Ok(deltas)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Compares the last layout of the subtree rooted at `root` with the boxes it is expected to have, returning
+    /// an array `[{ id, dx, dy, dWidth, dHeight }]` with an entry for each node that is off by more than `epsilon`
+    ///
+    /// `expected` is an array of `{ id, x, y, width, height }` border boxes with `x` and `y` relative to the
+    /// top left corner of the root's border box. This is meant for checking a layout against the one a browser
+    /// computes for the same styles: harvest each element's `getBoundingClientRect()` and subtract the `x` and `y`
+    /// of the root element's rect. The browser must not round the boxes differently: compare against unrounded
+    /// layouts (`set_rounding_mode("none")`), or use an `epsilon` of at least 1.
+    ///
+    /// Each difference is the computed value minus the expected one, so a positive `dWidth` means taffy made the
+    /// node wider than expected. Entries are in the order of `expected`, and nodes of the subtree missing from
+    /// `expected` are not compared. An empty array means the layout matches.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `epsilon` is negative or `NaN`, if `expected` is not an array of
+    /// boxes, or if one of its ids is not a node in the subtree rooted at `root`.
+    #[wasm_bindgen]
+    pub fn compare_with_boxes(&self, root: u32, expected: JsValue, epsilon: f32) -> Result<JsValue, TaffyError> {
+        if epsilon.is_nan() || epsilon < 0.0 {
+            return Err(TaffyError::InvalidArgument(format!("epsilon must be a non-negative number, got {epsilon}")));
+        }
+        let expected: Vec<ExpectedBox> = serde_wasm_bindgen::from_value(expected)
+            .map_err(|e| TaffyError::InvalidArgument(format!("expected: {e}")))?;
+        let deltas = self.box_deltas(root, &expected, epsilon)?;
+        Ok(serde_wasm_bindgen::to_value(&deltas).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use taffy::prelude::{length, Size, Style, TaffyMaxContent};
+
+    fn layout(child_width: f64) -> Value {
+        json!({
+            "x": 0, "y": 0, "width": 100, "height": 20,
+            "children": [
+                { "x": 0, "y": 0, "width": 50, "height": 20, "children": [] },
+                { "x": 50, "y": 0, "width": child_width, "height": 20, "children": [] },
+            ],
+        })
+    }
+
+    #[test]
+    fn identical_layouts_are_equal() {
+        assert_eq!(first_difference(&layout(50.0), &layout(50.0), 0.0, "$"), None);
+    }
+
+    #[test]
+    fn layouts_differing_within_epsilon_are_equal() {
+        assert_eq!(first_difference(&layout(50.0), &layout(50.004), 0.01, "$"), None);
+    }
+
+    #[test]
+    fn layouts_differing_beyond_epsilon_are_not_equal() {
+        let difference = first_difference(&layout(50.0), &layout(50.1), 0.01, "$");
+        assert_eq!(difference.as_deref(), Some("$.children[1].width"));
+    }
+
+    #[test]
+    fn structural_differences_are_reported() {
+        let fewer_children = json!({ "x": 0, "y": 0, "width": 100, "height": 20, "children": [] });
+        let difference = first_difference(&layout(50.0), &fewer_children, 1.0, "$");
+        assert_eq!(difference.as_deref(), Some("$.children"));
+
+        let missing_key = json!({ "x": 0, "width": 100 });
+        assert_eq!(first_difference(&json!({ "x": 0 }), &missing_key, 1.0, "$").as_deref(), Some("$.width"));
+        assert_eq!(first_difference(&json!("a"), &json!(0), 1.0, "$").as_deref(), Some("$"));
+    }
+
+    #[test]
+    fn boxes_beyond_epsilon_of_the_expected_ones_are_reported() {
+        let tree = TaffyTree::new();
+        let (root, [first, second], outside) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(40.0), height: length(20.0) }, ..Default::default() };
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item).unwrap()];
+            let root = Style { padding: length(10.0), ..Default::default() };
+            let root = inner.new_with_children(root, &items).unwrap();
+            let outside = inner.new_leaf(Style::default()).unwrap();
+            (js_node_id(root), items.map(js_node_id), js_node_id(outside))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        let boxed = |id, x, y, width, height| ExpectedBox { id, x, y, width, height };
+        let expected = [
+            boxed(root, 0.0, 0.0, 100.0, 40.0),
+            // The browser put the first item 0.5px lower, and made the second one 3px narrower
+            boxed(first, 10.0, 10.5, 40.0, 20.0),
+            boxed(second, 50.0, 10.0, 37.0, 20.0),
+        ];
+        let deltas = tree.box_deltas(root, &expected, 0.1).unwrap();
+        let delta = |id, dx, dy, d_width, d_height| BoxDelta { id, dx, dy, d_width, d_height };
+        assert_eq!(deltas, [delta(first, 0.0, -0.5, 0.0, 0.0), delta(second, 0.0, 0.0, 3.0, 0.0)]);
+        assert_eq!(tree.box_deltas(root, &expected, 5.0), Ok(vec![]));
+
+        let result = tree.box_deltas(root, &[boxed(outside, 0.0, 0.0, 0.0, 0.0)], 0.1);
+        assert!(matches!(result, Err(TaffyError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn invalid_epsilon_is_rejected() {
+        for epsilon in [-1.0, f32::NAN] {
+            let result = decode(JsValue::NULL, JsValue::NULL, epsilon);
+            assert!(matches!(result, Err(TaffyError::InvalidArgument(_))));
+        }
+    }
+}
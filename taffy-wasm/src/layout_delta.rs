@@ -0,0 +1,132 @@
+//! Comparing the layouts of a tree at two available sizes, e.g. to show how a layout moves between breakpoints
+use crate::layout_buffer::paint_order;
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// How the box of a node changes between two layouts, returned by `layout_delta`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutDelta {
+    /// The id of the node
+    pub id: u32,
+    /// How far the node moves right, in pixels
+    pub delta_x: f32,
+    /// How far the node moves down, in pixels
+    pub delta_y: f32,
+    /// How much wider the node gets, in pixels
+    pub delta_w: f32,
+    /// How much taller the node gets, in pixels
+    pub delta_h: f32,
+}
+
+impl TaffyTree {
+    /// Returns the id and absolute `[x, y, width, height]` of every node in the subtree rooted at `root`, in paint
+    /// order, laid out within `size` on a scratch copy of the tree
+    fn scratch_boxes(&self, root: u32, size: Size<f32>) -> Result<Vec<(u32, [f32; 4])>, TaffyError> {
+        let mut scratch = {
+            let tree = self.tree()?;
+            let node = resolve_node(&tree, root)?;
+            self.check_layout_depth(&tree, node)?;
+            tree.clone()
+        };
+        let root = resolve_node(&scratch, root)?;
+        scratch.compute_layout_with_measure(
+            root,
+            size.map(AvailableSpace::Definite),
+            |known, available, leaf, _, _| self.measure_content_size(known, available, leaf),
+        )?;
+        let nodes = paint_order(&scratch, root)?;
+        let boxes = nodes.into_iter().map(|(node, location)| {
+            let size = scratch.layout(node)?.size;
+            Ok((js_node_id(node), [location.x, location.y, size.width, size.height]))
+        });
+        boxes.collect()
+    }
+
+    /// Returns how the box of every node in the subtree rooted at `root` changes from its layout within `size_a` to
+    /// its layout within `size_b`, in paint order
+    fn layout_deltas(&self, root: u32, size_a: Size<f32>, size_b: Size<f32>) -> Result<Vec<LayoutDelta>, TaffyError> {
+        let before = self.scratch_boxes(root, size_a)?;
+        let after = self.scratch_boxes(root, size_b)?;
+        let deltas = before.into_iter().zip(after).map(|((id, [x_a, y_a, w_a, h_a]), (_, [x_b, y_b, w_b, h_b]))| {
+            LayoutDelta { id, delta_x: x_b - x_a, delta_y: y_b - y_a, delta_w: w_b - w_a, delta_h: h_b - h_a }
+        });
+        Ok(deltas.collect())
+    }
+}
+
+/// Decodes a `{ width, height }` size in pixels, naming `argument` if it is invalid
+fn decode_size(size: JsValue, argument: &str) -> Result<Size<f32>, TaffyError> {
+    serde_wasm_bindgen::from_value(size).map_err(|e| TaffyError::InvalidArgument(format!("{argument}: {e}")))
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns how the box of every node in the subtree rooted at `root` changes between a layout within `size_a`
+    /// and a layout within `size_b`, as an array of `{ id, deltaX, deltaY, deltaW, deltaH }`
+    ///
+    /// `size_a` and `size_b` are `{ width, height }` in pixels, e.g. two viewport sizes on either side of a
+    /// breakpoint. Each delta is the value at `size_b` minus the value at `size_a`, with positions relative to the
+    /// parent of `root` as in `layout_buffer`, and the nodes are in the same paint order. Every node is included,
+    /// with zero deltas if its box doesn't change.
+    ///
+    /// Both layouts are computed on copies of the tree that are discarded afterwards, so the layouts, caches and
+    /// dirty flags of the tree are exactly as they were before the call, and `last_compute_stats` is not updated.
+    /// Leaves are sized by their content sizes and object fits, as by `compute_layout`. Each copy takes time
+    /// proportional to the size of the whole tree.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if either size is not a `{ width, height }` object of numbers.
+    #[wasm_bindgen]
+    pub fn layout_delta(&self, root: u32, size_a: JsValue, size_b: JsValue) -> Result<JsValue, TaffyError> {
+        let deltas = self.layout_deltas(root, decode_size(size_a, "sizeA")?, decode_size(size_b, "sizeB")?)?;
+        Ok(serde_wasm_bindgen::to_value(&deltas).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::style::Style;
+
+    #[test]
+    fn wrapping_row_reflows_between_widths() {
+        let tree = TaffyTree::new();
+        let (root, cards) = {
+            let mut inner = tree.inner.borrow_mut();
+            let card = Style { size: length(100.0), flex_shrink: 0.0, ..Default::default() };
+            let cards: Vec<_> = (0..3).map(|_| inner.new_leaf(card.clone()).unwrap()).collect();
+            let row = Style {
+                flex_wrap: FlexWrap::Wrap,
+                align_content: Some(AlignContent::Start),
+                size: percent(1.0),
+                ..Default::default()
+            };
+            let root = inner.new_with_children(row, &cards).unwrap();
+            (js_node_id(root), cards.into_iter().map(js_node_id).collect::<Vec<_>>())
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // At 300px the cards fit on one line, and at 200px the last one wraps onto a second line
+        let deltas = tree
+            .layout_deltas(root, Size { width: 300.0, height: 400.0 }, Size { width: 200.0, height: 400.0 })
+            .unwrap();
+        let delta = |id, delta_x, delta_y, delta_w| LayoutDelta { id, delta_x, delta_y, delta_w, delta_h: 0.0 };
+        assert_eq!(
+            deltas,
+            vec![
+                delta(root, 0.0, 0.0, -100.0),
+                delta(cards[0], 0.0, 0.0, 0.0),
+                delta(cards[1], 0.0, 0.0, 0.0),
+                delta(cards[2], -200.0, 100.0, 0.0),
+            ]
+        );
+
+        // The tree keeps its own layout
+        assert_eq!(tree.layout_width(root), Ok(300.0));
+        assert_eq!(tree.dirty(root), Ok(false));
+        assert_eq!(tree.layout_deltas(99, Size::ZERO, Size::ZERO), Err(TaffyError::InvalidNode(99)));
+    }
+}
@@ -0,0 +1,143 @@
+//! Refusing to lay out subtrees deep enough to overflow the stack, e.g. when laying out trees built from untrusted input
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{NodeId, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The default maximum depth of a subtree that can be laid out, as for `set_max_layout_depth`
+///
+/// This matches the maximum depth of the DOM built by browsers' HTML parsers, which real documents stay well within.
+pub const DEFAULT_MAX_LAYOUT_DEPTH: u32 = 512;
+
+/// Returns the first node, in paint order, nested more than `max_depth` levels below `root`, if any
+fn node_below_depth(tree: &Taffy<JsContext>, root: NodeId, max_depth: u32) -> Result<Option<NodeId>, TaffyError> {
+    let mut stack = vec![(root, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        if depth > max_depth {
+            return Ok(Some(node));
+        }
+        stack.extend(tree.children(node)?.into_iter().rev().map(|child| (child, depth + 1)));
+    }
+    Ok(None)
+}
+
+/// Returns the number of nodes on the longest path from `root` down to a leaf, counting both ends
+fn subtree_depth(tree: &Taffy<JsContext>, root: NodeId) -> Result<u32, TaffyError> {
+    let mut deepest = 0;
+    let mut stack = vec![(root, 1)];
+    while let Some((node, depth)) = stack.pop() {
+        deepest = deepest.max(depth);
+        stack.extend(tree.children(node)?.into_iter().map(|child| (child, depth + 1)));
+    }
+    Ok(deepest)
+}
+
+impl TaffyTree {
+    /// Checks that the subtree rooted at `root` can be laid out: that no batch of edits is open, and that the subtree
+    /// is shallow enough, if the depth is limited
+    pub(crate) fn check_layout_depth(&self, tree: &Taffy<JsContext>, root: NodeId) -> Result<(), TaffyError> {
+        self.check_no_batch()?;
+        let Some(max_depth) = self.max_layout_depth.get() else {
+            return Ok(());
+        };
+        match node_below_depth(tree, root, max_depth)? {
+            Some(node) => Err(TaffyError::LayoutTooDeep { node: js_node_id(node), max_depth }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sets the maximum depth of a subtree that can be laid out, or removes the limit if `max_depth` is `undefined`
+    ///
+    /// Layout recurses once per level of the tree, so laying out a pathologically deep tree, e.g. one built from
+    /// malicious input, would overflow the stack and crash the wasm module. Instead, every layout method first
+    /// checks the subtree it lays out and throws a `LAYOUT_TOO_DEEP` error, leaving every layout untouched, if any
+    /// node is nested more than `max_depth` levels below the root: the root's children are at depth 1. The check
+    /// walks the subtree, which costs little next to the layout itself.
+    ///
+    /// The limit defaults to `DEFAULT_MAX_LAYOUT_DEPTH` (512). Raise it only for trees known to be trusted, and after
+    /// checking that layouts of that depth fit within the stack size the module was built with.
+    #[wasm_bindgen]
+    pub fn set_max_layout_depth(&self, max_depth: Option<u32>) {
+        self.max_layout_depth.set(max_depth);
+    }
+
+    /// Returns the number of nodes on the longest path from `root` down to a leaf, counting both, so a tree of a
+    /// single node has a depth of 1
+    ///
+    /// This is a cheap walk of the subtree that doesn't lay it out, e.g. to warn that a generated tree is
+    /// pathologically deep before laying it out. It counts nodes where `set_max_layout_depth` counts levels below
+    /// the root, so a subtree can be laid out under a limit of `n` if its depth is at most `n + 1`. Nodes with
+    /// `display: "None"` are counted like any other.
+    #[wasm_bindgen]
+    pub fn max_depth(&self, root: u32) -> Result<u32, TaffyError> {
+        let tree = self.tree()?;
+        subtree_depth(&tree, resolve_node(&tree, root)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::node_from_js_id;
+    use taffy::prelude::{Size, Style, TaffyMaxContent};
+
+    /// Builds a chain of `depth + 1` nested nodes, returning the ids of the outermost and innermost nodes
+    fn chain(tree: &TaffyTree, depth: usize) -> (u32, u32) {
+        let mut inner = tree.inner.borrow_mut();
+        let leaf = inner.new_leaf(Style::default()).unwrap();
+        let root = (0..depth).fold(leaf, |child, _| inner.new_with_children(Style::default(), &[child]).unwrap());
+        (js_node_id(root), js_node_id(leaf))
+    }
+
+    #[test]
+    fn very_deep_chain_is_rejected_instead_of_overflowing_the_stack() {
+        let tree = TaffyTree::new();
+        let (root, _) = chain(&tree, 100_000);
+        let result = tree.compute_subtree(root, Size::MAX_CONTENT);
+        assert!(matches!(result, Err(TaffyError::LayoutTooDeep { max_depth: DEFAULT_MAX_LAYOUT_DEPTH, .. })));
+        assert_eq!(tree.layout_width(root), Ok(0.0));
+    }
+
+    #[test]
+    fn chain_at_the_limit_is_laid_out() {
+        let tree = TaffyTree::new();
+        let (root, leaf) = chain(&tree, 3);
+        tree.set_max_layout_depth(Some(3));
+        assert_eq!(tree.compute_subtree(root, Size::MAX_CONTENT), Ok(()));
+
+        tree.set_max_layout_depth(Some(2));
+        let error = tree.compute_subtree(root, Size::MAX_CONTENT).unwrap_err();
+        assert_eq!(error.code(), "LAYOUT_TOO_DEEP");
+        assert_eq!(error.node_id(), Some(leaf));
+
+        tree.set_max_layout_depth(None);
+        assert_eq!(tree.compute_subtree(root, Size::MAX_CONTENT), Ok(()));
+    }
+
+    #[test]
+    fn depth_counts_the_nodes_of_the_longest_branch() {
+        let tree = TaffyTree::new();
+        let (single, _) = chain(&tree, 0);
+        assert_eq!(tree.max_depth(single), Ok(1));
+
+        let (deep, _) = chain(&tree, 4);
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let shallow = inner.new_leaf(Style::default()).unwrap();
+            let branches = [shallow, node_from_js_id(deep)];
+            js_node_id(inner.new_with_children(Style::default(), &branches).unwrap())
+        };
+        assert_eq!(tree.max_depth(deep), Ok(5));
+        assert_eq!(tree.max_depth(root), Ok(6));
+
+        // A limit of one less than the depth is the smallest the tree can be laid out under
+        tree.set_max_layout_depth(Some(5));
+        assert_eq!(tree.compute_subtree(root, Size::MAX_CONTENT), Ok(()));
+        tree.set_max_layout_depth(Some(4));
+        assert!(tree.compute_subtree(root, Size::MAX_CONTENT).is_err());
+        assert_eq!(tree.max_depth(root + 1), Err(TaffyError::InvalidNode(root + 1)));
+    }
+}
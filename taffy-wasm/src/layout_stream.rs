@@ -0,0 +1,116 @@
+//! Handing the boxes of a laid out tree to the host one node at a time, so that it can start painting before it has
+//! seen every node
+use crate::layout_json::{node_boxes, NodeBox};
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `root` within `available_space`, then calls `on_node` with the box of each of
+    /// its nodes in paint order, stopping at the first error it returns
+    fn stream_layout(
+        &self,
+        root: u32,
+        available_space: Size<AvailableSpace>,
+        mut on_node: impl FnMut(NodeBox) -> Result<(), TaffyError>,
+    ) -> Result<(), TaffyError> {
+        self.compute_subtree(root, available_space)?;
+        // The tree stays borrowed while the boxes are handed out, so that they can't be invalidated in between
+        let tree = self.tree()?;
+        for node_box in node_boxes(&tree, resolve_node(&tree, root)?)? {
+            on_node(node_box)?;
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `root` like `compute_layout`, then calls `on_node(nodeId, box)`
+    /// once for each node of the tree, with `box` the `{ id, x, y, w, h }` of the node as from
+    /// `compute_layout_to_json`
+    ///
+    /// The nodes are visited in paint order: each node before its descendants, and siblings in order, so a
+    /// renderer can paint each node as it arrives and have it covered by the ones that follow. A box is only final
+    /// once every node has been laid out and rounded, since the size of a node can change until its last ancestor
+    /// is laid out, so the calls start once the layout pass ends; streaming them saves building and copying the
+    /// layout of the whole tree at once, and lets the renderer work while the boxes are being read.
+    ///
+    /// The tree is borrowed for the duration of the calls: `on_node` can read layouts, e.g. with `layout_padding`,
+    /// but changing the tree from it fails with a `BORROW_CONFLICT` error. Throws an `INVALID_ARGUMENT` error,
+    /// without calling `on_node` for the remaining nodes, if `on_node` throws. The layout itself is complete either
+    /// way.
+    #[wasm_bindgen]
+    pub fn compute_layout_streaming(
+        &self,
+        root: u32,
+        width: f32,
+        height: f32,
+        on_node: &js_sys::Function,
+    ) -> Result<(), TaffyError> {
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        self.stream_layout(root, available_space, |node_box| {
+            let js_box = serde_wasm_bindgen::to_value(&node_box).unwrap_or(JsValue::NULL);
+            match on_node.call2(&JsValue::NULL, &JsValue::from(node_box.id), &js_box) {
+                Ok(_) => Ok(()),
+                Err(error) => {
+                    Err(TaffyError::InvalidArgument(format!("onNode threw for node {}: {error:?}", node_box.id)))
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::style::Style;
+
+    #[test]
+    fn each_node_is_reported_once_with_its_final_box() {
+        let tree = TaffyTree::new();
+        let (root, [column, first, second, last]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(40.0), height: length(10.0) }, ..Default::default() };
+            let [first, second, last] = [(); 3].map(|_| inner.new_leaf(item.clone()).unwrap());
+            let column = Style { flex_direction: FlexDirection::Column, padding: length(5.0), ..Default::default() };
+            let column = inner.new_with_children(column, &[first, second]).unwrap();
+            let root = Style { size: percent(1.0), ..Default::default() };
+            let root = inner.new_with_children(root, &[column, last]).unwrap();
+            (js_node_id(root), [column, first, second, last].map(js_node_id))
+        };
+
+        let mut streamed = Vec::new();
+        let viewport = Size { width: AvailableSpace::Definite(200.0), height: AvailableSpace::Definite(100.0) };
+        tree.stream_layout(root, viewport, |node_box| {
+            // The layout can be read while the boxes are handed out, but not changed
+            assert_eq!(tree.layout_width(node_box.id), Ok(node_box.w));
+            assert_eq!(tree.mark_dirty(node_box.id), Err(TaffyError::BorrowConflict));
+            streamed.push(node_box);
+            Ok(())
+        })
+        .unwrap();
+
+        let node_box = |id, x, y, w, h| NodeBox { id, x, y, w, h };
+        assert_eq!(
+            streamed,
+            vec![
+                node_box(root, 0.0, 0.0, 200.0, 100.0),
+                node_box(column, 0.0, 0.0, 50.0, 100.0),
+                node_box(first, 5.0, 5.0, 40.0, 10.0),
+                node_box(second, 5.0, 15.0, 40.0, 10.0),
+                node_box(last, 50.0, 0.0, 40.0, 10.0),
+            ]
+        );
+
+        // An error from the callback stops the stream after the layout
+        let mut calls = 0;
+        let failing = tree.stream_layout(root, Size::MAX_CONTENT, |_| {
+            calls += 1;
+            Err(TaffyError::InvalidArgument(String::from("stop")))
+        });
+        assert_eq!(failing, Err(TaffyError::InvalidArgument(String::from("stop"))));
+        assert_eq!((calls, tree.layout_width(root)), (1, Ok(90.0)));
+    }
+}
@@ -0,0 +1,168 @@
+//! Checking computed layouts for NaN and infinite values, and pointing at the style value that likely caused them
+use crate::node_ids::js_node_id;
+use crate::{JsContext, TaffyError, TaffyTree};
+use taffy::style::{CompactLength, Style};
+use taffy::{prelude::*, Layout, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// Returns the name and value of the first NaN or infinite value in the box of a layout, if any
+fn non_finite_box_value(layout: &Layout) -> Option<(&'static str, f32)> {
+    [
+        ("x", layout.location.x),
+        ("y", layout.location.y),
+        ("width", layout.size.width),
+        ("height", layout.size.height),
+        ("content width", layout.content_size.width),
+        ("content height", layout.content_size.height),
+    ]
+    .into_iter()
+    .find(|(_, value)| !value.is_finite())
+}
+
+/// Returns the name and value of the first NaN or infinite length or number in a style, if any
+///
+/// Only pixel lengths and percentages are checked: keywords such as `auto` carry no value.
+fn non_finite_style_value(style: &Style) -> Option<(&'static str, f32)> {
+    let lengths = [
+        ("size.width", style.size.width.into_raw()),
+        ("size.height", style.size.height.into_raw()),
+        ("min_size.width", style.min_size.width.into_raw()),
+        ("min_size.height", style.min_size.height.into_raw()),
+        ("max_size.width", style.max_size.width.into_raw()),
+        ("max_size.height", style.max_size.height.into_raw()),
+        ("flex_basis", style.flex_basis.into_raw()),
+        ("margin.left", style.margin.left.into_raw()),
+        ("margin.right", style.margin.right.into_raw()),
+        ("margin.top", style.margin.top.into_raw()),
+        ("margin.bottom", style.margin.bottom.into_raw()),
+        ("padding.left", style.padding.left.into_raw()),
+        ("padding.right", style.padding.right.into_raw()),
+        ("padding.top", style.padding.top.into_raw()),
+        ("padding.bottom", style.padding.bottom.into_raw()),
+        ("border.left", style.border.left.into_raw()),
+        ("border.right", style.border.right.into_raw()),
+        ("border.top", style.border.top.into_raw()),
+        ("border.bottom", style.border.bottom.into_raw()),
+        ("inset.left", style.inset.left.into_raw()),
+        ("inset.right", style.inset.right.into_raw()),
+        ("inset.top", style.inset.top.into_raw()),
+        ("inset.bottom", style.inset.bottom.into_raw()),
+        ("gap.width", style.gap.width.into_raw()),
+        ("gap.height", style.gap.height.into_raw()),
+    ]
+    .into_iter()
+    .filter(|(_, length)| matches!(length.tag(), CompactLength::LENGTH_TAG | CompactLength::PERCENT_TAG))
+    .map(|(name, length)| (name, length.value()));
+    let numbers = [
+        ("flex_grow", Some(style.flex_grow)),
+        ("flex_shrink", Some(style.flex_shrink)),
+        ("aspect_ratio", style.aspect_ratio),
+        ("scrollbar_width", Some(style.scrollbar_width)),
+    ]
+    .into_iter()
+    .filter_map(|(name, number)| Some((name, number?)));
+    lengths.chain(numbers).find(|(_, value)| !value.is_finite())
+}
+
+/// Checks the styles and layouts of the subtree rooted at `root` for NaN and infinite values
+///
+/// Non-finite style values are reported first, wherever they are: taffy mostly treats them like zero, so they make a
+/// node vanish without making its layout non-finite, and a non-finite layout is usually caused by one anyway.
+fn validate_subtree(tree: &Taffy<JsContext>, root: NodeId) -> Result<(), TaffyError> {
+    let mut first_invalid_box = None;
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if let Some((property, value)) = non_finite_style_value(tree.style(node)?) {
+            let reason = format!("its style has {property} = {value}");
+            return Err(TaffyError::NonFiniteLayout { node: js_node_id(node), reason });
+        }
+        if let Some(invalid_value) = non_finite_box_value(tree.unrounded_layout(node)) {
+            first_invalid_box.get_or_insert((node, invalid_value));
+        }
+        stack.extend(tree.children(node)?.into_iter().rev());
+    }
+    match first_invalid_box {
+        Some((node, (field, value))) => {
+            let reason = format!(
+                "its {field} is {value}, but its style values are finite: check the sizes returned by the measure \
+                 function and the available space"
+            );
+            Err(TaffyError::NonFiniteLayout { node: js_node_id(node), reason })
+        }
+        None => Ok(()),
+    }
+}
+
+impl TaffyTree {
+    /// Checks the subtree rooted at `root` for NaN and infinite layout values, if validation is enabled
+    pub(crate) fn validate_layout(&self, tree: &Taffy<JsContext>, root: NodeId) -> Result<(), TaffyError> {
+        match self.validate_layouts.get() {
+            true => validate_subtree(tree, root),
+            false => Ok(()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Enables or disables checking every computed layout for NaN and infinite values
+    ///
+    /// A NaN in a style, e.g. a width computed in JS by dividing by zero, makes nodes silently vanish or spreads
+    /// NaN through the layout. With validation enabled, every layout method scans the laid out subtree afterwards
+    /// and throws a `NON_FINITE_LAYOUT` error naming the node at fault: the first node, in paint order, with a NaN
+    /// or infinite style value, along with the style property, or else the first node whose computed box is not
+    /// finite, e.g. because its measure function returned NaN. The layout is still stored. Validation is disabled
+    /// by default, since it costs a walk of the subtree on every layout: enable it in development builds.
+    #[wasm_bindgen]
+    pub fn set_layout_validation(&self, enabled: bool) {
+        self.validate_layouts.set(enabled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::style::Dimension;
+
+    /// Lays out a row of a 10px leaf and a leaf with `style`, returning the result and the id of the second leaf
+    fn layout_row(validate: bool, style: Style) -> (Result<(), TaffyError>, u32) {
+        let tree = TaffyTree::new();
+        tree.set_layout_validation(validate);
+        let (row, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let sized = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let leaf = inner.new_leaf(style).unwrap();
+            (js_node_id(inner.new_with_children(Style::default(), &[sized, leaf]).unwrap()), js_node_id(leaf))
+        };
+        (tree.compute_subtree(row, Size::MAX_CONTENT), leaf)
+    }
+
+    #[test]
+    fn nan_width_is_reported_with_its_node() {
+        let nan_width =
+            Style { size: Size { width: Dimension::length(f32::NAN), height: auto() }, ..Default::default() };
+        let (result, leaf) = layout_row(true, nan_width.clone());
+        let error = result.unwrap_err();
+        assert_eq!(error.code(), "NON_FINITE_LAYOUT");
+        assert_eq!(error.node_id(), Some(leaf));
+        assert!(error.to_string().contains("size.width = NaN"), "{error}");
+
+        // Without validation the NaN goes unnoticed
+        assert_eq!(layout_row(false, nan_width).0, Ok(()));
+    }
+
+    #[test]
+    fn nan_margin_is_reported_for_its_nan_location() {
+        let margin = Rect { left: LengthPercentageAuto::length(f32::NAN), ..Rect::zero() };
+        let (result, leaf) = layout_row(true, Style { margin, ..Default::default() });
+        let error = result.unwrap_err();
+        assert_eq!(error.node_id(), Some(leaf));
+        assert!(error.to_string().contains("margin.left = NaN"), "{error}");
+    }
+
+    #[test]
+    fn finite_layouts_pass() {
+        let (result, _) = layout_row(true, Style { flex_grow: 1.0, ..Default::default() });
+        assert_eq!(result, Ok(()));
+    }
+}
@@ -0,0 +1,202 @@
+//! Scaling the pixel lengths of styles by a tree-wide factor, e.g. to author styles in `rem` or to zoom a whole UI
+use crate::node_ids::js_node_id;
+use crate::{set_style_if_changed, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use taffy::style::CompactLength;
+#[cfg(feature = "grid")]
+use taffy::{MaxTrackSizingFunction, MinMax, MinTrackSizingFunction};
+use wasm_bindgen::prelude::*;
+
+/// Returns the pixels of `length` mapped by `map`, or `None` if it is not a pixel length
+fn mapped_px(length: CompactLength, map: &impl Fn(f32) -> f32) -> Option<f32> {
+    (length.tag() == CompactLength::LENGTH_TAG).then(|| map(length.value()))
+}
+
+fn map_dimension(dimension: Dimension, map: &impl Fn(f32) -> f32) -> Dimension {
+    mapped_px(dimension.into_raw(), map).map_or(dimension, Dimension::length)
+}
+
+fn map_length_percentage(length: LengthPercentage, map: &impl Fn(f32) -> f32) -> LengthPercentage {
+    mapped_px(length.into_raw(), map).map_or(length, LengthPercentage::length)
+}
+
+fn map_length_percentage_auto(length: LengthPercentageAuto, map: &impl Fn(f32) -> f32) -> LengthPercentageAuto {
+    mapped_px(length.into_raw(), map).map_or(length, LengthPercentageAuto::length)
+}
+
+/// Maps the pixel lengths of a track, including the limit of a `fit-content()` track given in pixels
+#[cfg(feature = "grid")]
+fn map_track(track: NonRepeatedTrackSizingFunction, map: &impl Fn(f32) -> f32) -> NonRepeatedTrackSizingFunction {
+    let (min, max) = (track.min.into_raw(), track.max.into_raw());
+    let max = match max.tag() {
+        CompactLength::FIT_CONTENT_PX_TAG => MaxTrackSizingFunction::fit_content_px(map(max.value())),
+        _ => mapped_px(max, map).map_or(track.max, MaxTrackSizingFunction::length),
+    };
+    MinMax { min: mapped_px(min, map).map_or(track.min, MinTrackSizingFunction::length), max }
+}
+
+#[cfg(feature = "grid")]
+fn map_template_track(track: TrackSizingFunction, map: &impl Fn(f32) -> f32) -> TrackSizingFunction {
+    match track {
+        TrackSizingFunction::Single(track) => TrackSizingFunction::Single(map_track(track, map)),
+        TrackSizingFunction::Repeat(repetition, tracks) => {
+            TrackSizingFunction::Repeat(repetition, tracks.into_iter().map(|track| map_track(track, map)).collect())
+        }
+    }
+}
+
+/// Returns `style` with every length given in pixels mapped by `map`, leaving percentages, `fr`, `auto`, keywords,
+/// `calc()` values and unitless numbers as they are
+pub(crate) fn map_lengths(style: Style, map: impl Fn(f32) -> f32) -> Style {
+    let map = &map;
+    Style {
+        scrollbar_width: map(style.scrollbar_width),
+        inset: style.inset.map(|length| map_length_percentage_auto(length, map)),
+        size: style.size.map(|dimension| map_dimension(dimension, map)),
+        min_size: style.min_size.map(|dimension| map_dimension(dimension, map)),
+        max_size: style.max_size.map(|dimension| map_dimension(dimension, map)),
+        margin: style.margin.map(|length| map_length_percentage_auto(length, map)),
+        padding: style.padding.map(|length| map_length_percentage(length, map)),
+        border: style.border.map(|length| map_length_percentage(length, map)),
+        gap: style.gap.map(|length| map_length_percentage(length, map)),
+        flex_basis: map_dimension(style.flex_basis, map),
+        ..map_track_lengths(style, map)
+    }
+}
+
+/// Returns `style` with the pixel lengths of its grid tracks mapped by `map`
+#[cfg(feature = "grid")]
+fn map_track_lengths(style: Style, map: &impl Fn(f32) -> f32) -> Style {
+    Style {
+        grid_template_rows: style.grid_template_rows.into_iter().map(|track| map_template_track(track, map)).collect(),
+        grid_template_columns: style
+            .grid_template_columns
+            .into_iter()
+            .map(|track| map_template_track(track, map))
+            .collect(),
+        grid_auto_rows: style.grid_auto_rows.into_iter().map(|track| map_track(track, map)).collect(),
+        grid_auto_columns: style.grid_auto_columns.into_iter().map(|track| map_track(track, map)).collect(),
+        ..style
+    }
+}
+
+/// Returns `style` as it is, since it has no grid tracks without the `grid` feature
+#[cfg(not(feature = "grid"))]
+fn map_track_lengths(style: Style, _map: &impl Fn(f32) -> f32) -> Style {
+    style
+}
+
+impl TaffyTree {
+    /// Returns `style`, decoded from a JS style object, with its pixel lengths multiplied by the length scale
+    pub(crate) fn scale_style(&self, style: Style) -> Style {
+        let scale = self.length_scale.get();
+        if scale == 1.0 {
+            return style;
+        }
+        map_lengths(style, |px| px * scale)
+    }
+
+    /// Returns `style`, as stored in the tree, with its pixel lengths divided by the length scale, as given in JS
+    pub(crate) fn unscale_style(&self, style: Style) -> Style {
+        let scale = self.length_scale.get();
+        if scale == 1.0 {
+            return style;
+        }
+        map_lengths(style, |px| px / scale)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sets the factor that the pixel lengths of style objects are multiplied by, e.g. `16` to write styles in `rem`
+    ///
+    /// The lengths that are scaled are those given in pixels: `size`, `min_size`, `max_size`, `flex_basis`,
+    /// `inset`, `margin`, `padding`, `border`, `gap` and `scrollbar_width` when they are lengths, and lengths in
+    /// grid tracks, including the limit of a `fit-content()` track in pixels. Percentages, `fr`, `auto`,
+    /// `min-content` and `max-content`, `calc()` values, and unitless numbers such as `flex_grow` or
+    /// `aspect_ratio` are not, and neither are values that are not style properties: the available space passed to
+    /// `compute_layout`, content sizes, measured sizes and returned layouts are all in pixels.
+    ///
+    /// Changing the scale rescales the styles of every node already in the tree, marking those it changes dirty,
+    /// so that e.g. a zoom level applies without setting every style again. Style objects passed in later, such as
+    /// to `new_leaf` or `update_style`, are scaled as they are decoded, and `get_styles` divides the lengths it
+    /// returns by the scale, so styles read back can be passed in again unchanged. The initial scale is `1`.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `factor` is not a positive finite number.
+    #[wasm_bindgen]
+    pub fn set_length_scale(&self, factor: f32) -> Result<(), TaffyError> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(TaffyError::InvalidArgument(format!("factor must be a positive number, got {factor}")));
+        }
+        let previous = self.length_scale.get();
+        if factor == previous {
+            return Ok(());
+        }
+        let mut tree = self.tree_mut()?;
+        let nodes: Vec<NodeId> = tree.node_ids().collect();
+        for node in nodes {
+            // Pinned sizes and tap targets are in layout pixels, so they are reapplied to the rescaled style as they are
+            let node_id = js_node_id(node);
+            let authored = self.authored_style(node_id, tree.style(node)?.clone());
+            let style = map_lengths(authored, |px| px / previous * factor);
+            let style = self.apply_pinned_size(node_id, self.apply_tap_target(node_id, style));
+            set_style_if_changed(&mut tree, node, style)?;
+        }
+        self.length_scale.set(factor);
+        Ok(())
+    }
+
+    /// Returns the factor that the pixel lengths of style objects are multiplied by, set with `set_length_scale`
+    #[wasm_bindgen]
+    pub fn length_scale(&self) -> f32 {
+        self.length_scale.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn scaling_doubles_pixel_lengths_but_not_percentages() {
+        let tree = TaffyTree::new();
+        let (root, [fixed, relative]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fixed = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let relative = inner.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+            let root = Style { size: percent(1.0), flex_direction: FlexDirection::Column, ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[fixed, relative]).unwrap()), [fixed, relative].map(js_node_id))
+        };
+        let available_space = Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::Definite(100.0) };
+        let widths = || {
+            tree.compute_subtree(root, available_space).unwrap();
+            [fixed, relative].map(|node| tree.layout_width(node).unwrap())
+        };
+        assert_eq!(widths(), [10.0, 50.0]);
+
+        tree.set_length_scale(2.0).unwrap();
+        assert_eq!(tree.length_scale(), 2.0);
+        assert_eq!(widths(), [20.0, 50.0]);
+        // Styles read back are in the units they were written in
+        let styles = tree.styles(&[fixed]).unwrap();
+        assert_eq!(styles[0].as_ref().unwrap().0.size, length(10.0));
+
+        tree.set_length_scale(1.0).unwrap();
+        assert_eq!(widths(), [10.0, 50.0]);
+        assert!(matches!(tree.set_length_scale(0.0), Err(TaffyError::InvalidArgument(_))));
+    }
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn only_pixel_track_lengths_are_scaled() {
+        let style = Style {
+            grid_template_columns: vec![length(10.0), fr(1.0), percent(0.5), fit_content(length(20.0))],
+            grid_auto_rows: vec![minmax(length(5.0), auto())],
+            ..Default::default()
+        };
+        let scaled = map_lengths(style, |px| px * 2.0);
+        assert_eq!(scaled.grid_template_columns, vec![length(20.0), fr(1.0), percent(0.5), fit_content(length(40.0))]);
+        assert_eq!(scaled.grid_auto_rows, vec![minmax(length(10.0), auto())]);
+    }
+}
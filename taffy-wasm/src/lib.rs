@@ -1,9 +1,127 @@
+use batch::Batch;
+use hit_index::HitIndex;
+use lod_index::LodIndex;
+use node_ids::{js_node_id, new_node_id, node_from_js_id};
+use node_state::NodeStates;
+use previous_layout::PreviousLayouts;
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
-use taffy::{prelude::*, style::Style, TaffyTree as Taffy};
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::{BTreeMap, BTreeSet};
+use taffy::{prelude::*, style::Style, CacheTree, Point, TaffyTree as Taffy};
 use wasm_bindgen::prelude::*;
 
+mod absolute_layout;
+mod auto_compute;
+mod available_space;
+mod baseline;
+mod batch;
+mod build_info;
+mod build_session;
+mod changed_roots;
+mod compact_length;
+mod compute_stats;
+mod containing_block;
+mod content_box;
+mod content_size;
+mod decode_fallback;
+mod display_query;
+mod dot;
+mod eager_measure;
+mod edges;
+mod error;
+mod flex;
+mod formatting_context;
+mod free_space;
+mod freeze;
+#[cfg(feature = "grid")]
+mod grid;
+mod gutter;
+mod hit_index;
+mod hit_test;
+mod ignored_styles;
+mod insert_preview;
+mod item_alignment;
+mod layout_buffer;
+mod layout_compare;
+mod layout_delta;
+mod layout_depth;
+mod layout_json;
+mod layout_stream;
+mod layout_validation;
+mod length_scale;
+mod lod_index;
+mod max_lines;
+mod measure_budget;
+mod measure_fallback;
+mod measure_subtree;
+mod memory_usage;
+mod multi_root;
+mod node_contexts;
+mod node_ids;
+mod node_state;
+mod node_tags;
+mod object_fit;
+mod overflow;
+mod paint_order;
+mod pinned_size;
+mod previous_layout;
+mod profiling;
+mod random_tree;
+mod recompute;
+mod rect_selection;
+mod relayout_requests;
+mod rounding;
+mod scroll_offset;
+mod shorthand;
+mod size_constraints;
+mod size_origin;
+mod snapshot;
+mod snapshot_diff;
+mod structure;
+mod style_defaults;
+mod style_preview;
+mod tap_target;
+#[cfg(feature = "trace")]
+mod trace;
+mod tree_builder;
+mod tree_json;
+mod user_data;
+mod visibility;
+mod windowed_layout;
+mod writing_mode;
+
+pub use absolute_layout::{AbsoluteLayout, NormalizedLayout};
+pub use build_info::{enabled_features, taffy_version};
+pub use build_session::BuildSession;
+pub use compact_length::{compact_length_from_px, compact_length_tag, compact_length_to_px};
+pub use compute_stats::{ComputeStats, MeasureDiscrepancy};
+pub use containing_block::JsContainingBlock;
+pub use error::TaffyError;
+pub use flex::FlexLineBox;
+pub use free_space::ResolvedFreeSpace;
+#[cfg(feature = "grid")]
+pub use grid::{
+    GridGutter, GridGutters, GridImplicitTrackCount, GridItemPlacement, GridTrackPositions, GridTrackSizes,
+};
+pub use gutter::Gutter;
+pub use layout_buffer::{SharedLayoutWrite, LAYOUT_STRIDE, LAYOUT_STRIDE_WITH_CONTENT_SIZE};
+pub use layout_compare::{layout_difference, layouts_equal};
+pub use layout_delta::LayoutDelta;
+pub use layout_depth::DEFAULT_MAX_LAYOUT_DEPTH;
+pub use measure_budget::{JsMeasureBudget, MeasureBudget, DEFAULT_MAX_MEASURE_CALLS};
+pub use memory_usage::MemoryUsage;
+pub use multi_root::JsLayoutRequests;
+pub use node_ids::{JsTaffyTreeOptions, TaffyTreeOptions};
+pub use overflow::{ResolvedOverflow, RootOverflow};
+pub use rounding::round_layout_value;
+pub use shorthand::gap;
+pub use size_constraints::ResolvedSizeConstraints;
+pub use snapshot::TreeSnapshot;
+pub use snapshot_diff::{diff_snapshots, NodeChanges, Reparenting, SnapshotDiff};
+pub use structure::NodeStructure;
+
 // Re-export grid types for TypeScript generation
+#[cfg(feature = "grid")]
 pub use taffy::style::{
     ConcreteGridPlacement, GridTrackRepetition, SimpleMaxTrackSizingFunction, SimpleMinTrackSizingFunction,
     SimpleNonRepeatedTrackSizingFunction, SimpleTrackSizingFunction,
@@ -64,10 +182,11 @@ macro_rules! wasm_log {
 }
 
 /// Thin, easily‑serialised copy of `Style`
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct JsStyle(pub Style);
 
 /// Context data for JavaScript - can hold any JS value
+#[derive(Clone)]
 pub struct JsContext {
     data: JsValue,
 }
@@ -75,6 +194,47 @@ pub struct JsContext {
 #[wasm_bindgen]
 pub struct TaffyTree {
     inner: RefCell<Taffy<JsContext>>,
+    /// The statistics of the most recent layout pass
+    last_compute_stats: Cell<ComputeStats>,
+    /// The last size returned by the measure function for each node measured during the most recent layout pass
+    last_measured_nodes: RefCell<BTreeMap<u32, Size<f32>>>,
+    /// What was set on each node outside its style, and what was recorded about it by earlier layouts
+    node_states: RefCell<NodeStates>,
+    /// The box sizing of styles that don't specify one
+    default_box_sizing: Cell<BoxSizing>,
+    /// The factor the pixel lengths of style objects are multiplied by, set with `set_length_scale`
+    length_scale: Cell<f32>,
+    /// The options the tree was created with
+    options: TaffyTreeOptions,
+    /// The ids of the placeholder nodes that keep the slots of removed nodes occupied under `stableIds`
+    retired: RefCell<BTreeSet<u32>>,
+    /// The size passed to JS measure functions in place of intrinsic constraints, if set
+    measure_fallback_size: Cell<Option<Size<f32>>>,
+    /// The style used in place of style objects that fail to decode, set with `set_decode_fallback_style`
+    decode_fallback_style: RefCell<Option<Style>>,
+    /// The number of style objects that failed to decode, returned by `decode_failure_count`
+    decode_failures: Cell<u32>,
+    /// The hit indices built with `build_hit_index` since the last layout, by handle
+    hit_indices: RefCell<BTreeMap<u32, HitIndex>>,
+    /// The handle of the next hit index to be built
+    next_hit_index: Cell<u32>,
+    /// The level-of-detail indices built with `build_lod_index` since the last layout, by handle
+    lod_indices: RefCell<BTreeMap<u32, LodIndex>>,
+    /// The handle of the next level-of-detail index to be built
+    next_lod_index: Cell<u32>,
+    /// Whether layouts are checked for NaN and infinite values, enabled with `set_layout_validation`
+    validate_layouts: Cell<bool>,
+    /// The maximum depth of a subtree that can be laid out, set with `set_max_layout_depth`
+    max_layout_depth: Cell<Option<u32>>,
+    /// Whether reading a layout first lays out its tree if it changed, enabled with `set_auto_compute`
+    auto_compute: Cell<bool>,
+    /// The layouts nodes had before their most recent layout, if kept with `set_keep_previous_layouts`
+    previous_layouts: RefCell<Option<PreviousLayouts>>,
+    /// The cumulative time in microseconds of each node in the last profiled layout, if profiled with
+    /// `set_profiling`
+    node_timings: RefCell<Option<BTreeMap<u32, f64>>>,
+    /// The batch of edits opened with `begin_batch`, if one is open
+    batch: RefCell<Option<Batch>>,
 }
 
 impl Default for TaffyTree {
@@ -83,180 +243,758 @@ impl Default for TaffyTree {
     }
 }
 
+impl TaffyTree {
+    /// Borrows the underlying tree, failing if it is currently borrowed mutably
+    fn tree(&self) -> Result<Ref<'_, Taffy<JsContext>>, TaffyError> {
+        self.inner.try_borrow().map_err(|_| TaffyError::BorrowConflict)
+    }
+
+    /// Mutably borrows the underlying tree, failing if it is currently borrowed
+    fn tree_mut(&self) -> Result<RefMut<'_, Taffy<JsContext>>, TaffyError> {
+        self.inner.try_borrow_mut().map_err(|_| TaffyError::BorrowConflict)
+    }
+
+    /// Creates a childless node with a prebuilt style
+    fn new_container(&self, style: Style) -> Result<u32, TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = tree.new_leaf(style)?;
+        new_node_id(&mut tree, node)
+    }
+
+    /// Returns the style of a grid container with the given explicit tracks
+    #[cfg(feature = "grid")]
+    fn grid_style(&self, columns: Vec<TrackSizingFunction>, rows: Vec<TrackSizingFunction>) -> Style {
+        Style {
+            display: Display::Grid,
+            grid_template_columns: columns,
+            grid_template_rows: rows,
+            ..self.default_style()
+        }
+    }
+
+    /// Returns the styles of the given nodes in order, with `None` for each id that is not a node in the tree
+    fn styles(&self, ids: &[u32]) -> Result<Vec<Option<JsStyle>>, TaffyError> {
+        let tree = self.tree()?;
+        let style = |id| {
+            let style = resolve_node(&tree, id).ok().and_then(|node| tree.style(node).ok())?;
+            Some(JsStyle(self.unscale_style(self.authored_style(id, style.clone()))))
+        };
+        Ok(ids.iter().map(|&id| style(id)).collect())
+    }
+
+    /// Records that the subtree rooted at `root` was laid out in `available_space`, for `recompute` to reuse, and
+    /// discards the hit and level-of-detail indices built from the previous layout
+    fn record_layout(&self, root: u32, available_space: Size<AvailableSpace>) {
+        self.node_states.borrow_mut().update(root, |state| state.last_available_space = Some(available_space));
+        self.hit_indices.borrow_mut().clear();
+        self.lod_indices.borrow_mut().clear();
+    }
+
+    /// Lays out the subtree rooted at `node_id` as though `node_id` had no parent
+    pub(crate) fn compute_subtree(
+        &self,
+        node_id: u32,
+        available_space: Size<AvailableSpace>,
+    ) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        self.check_layout_depth(&tree, node)?;
+        self.keep_previous_layouts(&tree, node)?;
+        let start_ms = compute_stats::now_ms();
+        tree.compute_layout_with_measure(node, available_space, |known_dimensions, available, leaf, _, _| {
+            self.measure_content_size(known_dimensions, available, leaf)
+        })?;
+        self.record_compute_stats(&tree, node, 0, BTreeMap::new(), compute_stats::now_ms() - start_ms);
+        self.record_layout(node_id, available_space);
+        self.validate_layout(&tree, node)?;
+        Ok(())
+    }
+}
+
+/// Sets the style of a node and marks it dirty, unless it already has an equal style, returning whether it changed
+fn set_style_if_changed(tree: &mut Taffy<JsContext>, node: NodeId, style: Style) -> Result<bool, TaffyError> {
+    if *tree.style(node)? == style {
+        return Ok(false);
+    }
+    tree.set_style(node, style)?;
+    Ok(true)
+}
+
+/// Converts a JS node id to a `NodeId`, checking that the node is in the tree
+fn resolve_node(tree: &Taffy<JsContext>, node_id: u32) -> Result<NodeId, TaffyError> {
+    let node = node_from_js_id(node_id);
+    if tree.contains(node) {
+        Ok(node)
+    } else {
+        Err(TaffyError::InvalidNode(node_id))
+    }
+}
+
+/// Discards the cached layout of `node` and of every node in its subtree, and marks its ancestors dirty
+///
+/// The subtrees of descendants for which `keep` returns `true` are skipped and keep their cached layouts.
+fn clear_subtree_cache(
+    tree: &mut Taffy<JsContext>,
+    node: NodeId,
+    keep: impl Fn(NodeId) -> bool,
+) -> Result<(), TaffyError> {
+    let mut stack = vec![node];
+    while let Some(current) = stack.pop() {
+        tree.cache_clear(current);
+        stack.extend(tree.child_ids(current).filter(|&child| !keep(child)));
+    }
+
+    // The node's own cache is already empty, so `mark_dirty` would stop there: start from the parent instead
+    if let Some(parent) = tree.parent(node) {
+        tree.mark_dirty(parent)?;
+    }
+    Ok(())
+}
+
+/// The constraints object passed to JS measure functions
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MeasureConstraints {
+    /// The space available in the x axis
+    width: AvailableSpace,
+    /// The space available in the y axis
+    height: AvailableSpace,
+    /// The maximum number of lines the leaf may wrap to, set with `set_max_lines`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_lines: Option<u32>,
+}
+
+/// Sizes a leaf by calling the JS function `measure_func(contextData, constraints)` with `this` as its receiver,
+/// where `constraints` is the `Size<AvailableSpace>` of the leaf, with its known dimensions as definite space, and
+/// the `maxLines` of the leaf if it has one
+///
+/// The result is parsed as `{ width, height, firstBaseline?, truncated? }` or `[width, height]`, returning the size,
+/// the baselines and whether the content was truncated. Anything else, or a measure function that throws, gives the
+/// leaf a zero size.
+fn call_js_measure(
+    measure_func: &js_sys::Function,
+    this: &JsValue,
+    known_dimensions: Size<Option<f32>>,
+    available_space: Size<AvailableSpace>,
+    max_lines: Option<u32>,
+    node_context: Option<&mut JsContext>,
+) -> (Size<f32>, Point<Option<f32>>, bool) {
+    // Get the context data (or null if no context)
+    let null_value = JsValue::NULL;
+    let context_data = node_context.map(|ctx| &ctx.data).unwrap_or(&null_value);
+
+    // Calculate the effective available space for measure function
+    // If we have a known dimension, use Definite with that value
+    // Otherwise, use the available space as provided by the parent
+    let effective_width = known_dimensions.width.map(AvailableSpace::Definite).unwrap_or(available_space.width);
+    let effective_height = known_dimensions.height.map(AvailableSpace::Definite).unwrap_or(available_space.height);
+
+    let constraints = MeasureConstraints { width: effective_width, height: effective_height, max_lines };
+
+    wasm_log!("🚀 WASM: Measuring with constraints: width={:?}, height={:?}", constraints.width, constraints.height);
+
+    // Serialize constraints to JsValue for passing to JavaScript
+    let constraints_js = match serde_wasm_bindgen::to_value(&constraints) {
+        Ok(value) => value,
+        Err(e) => {
+            wasm_log!("🚀 WASM: Failed to serialize constraints: {}", e);
+            return (Size::ZERO, Point::NONE, false);
+        }
+    };
+
+    // Call the JavaScript function with (contextData, constraints)
+    match measure_func.call2(this, context_data, &constraints_js) {
+        Ok(result) => {
+            // Try to parse the result as {width: number, height: number}
+            if result.is_object() {
+                let width_prop = js_sys::Reflect::get(&result, &"width".into()).unwrap_or(JsValue::from(0.0));
+                let height_prop = js_sys::Reflect::get(&result, &"height".into()).unwrap_or(JsValue::from(0.0));
+
+                let width = width_prop.as_f64().unwrap_or(0.0) as f32;
+                let height = height_prop.as_f64().unwrap_or(0.0) as f32;
+
+                // The optional first baseline is the offset of the first line's baseline from the top
+                let baseline_prop = js_sys::Reflect::get(&result, &"firstBaseline".into()).ok();
+                let first_baseline = baseline_prop.and_then(|baseline| baseline.as_f64()).map(|b| b as f32);
+
+                let truncated_prop = js_sys::Reflect::get(&result, &"truncated".into()).ok();
+                let truncated = truncated_prop.and_then(|truncated| truncated.as_bool()).unwrap_or(false);
+
+                (Size { width, height }, Point { x: None, y: first_baseline }, truncated)
+            } else {
+                // Fallback: try to parse as array [width, height]
+                if js_sys::Array::is_array(&result) {
+                    let array = js_sys::Array::from(&result);
+                    let width = array.get(0).as_f64().unwrap_or(0.0) as f32;
+                    let height = array.get(1).as_f64().unwrap_or(0.0) as f32;
+                    (Size { width, height }, Point::NONE, false)
+                } else {
+                    (Size { width: 0.0, height: 0.0 }, Point::NONE, false)
+                }
+            }
+        }
+        Err(_) => (Size { width: 0.0, height: 0.0 }, Point::NONE, false),
+    }
+}
+
+/// The value of a style property that is only inspected when it is a keyword, or a list containing keywords
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StylePropertyValue {
+    Keyword(String),
+    List(Vec<StylePropertyValue>),
+    Other(serde::de::IgnoredAny),
+}
+
+impl StylePropertyValue {
+    /// Returns whether the value is `keyword`, or a list with `keyword` as an item, ignoring case
+    fn contains_keyword(&self, keyword: &str) -> bool {
+        match self {
+            StylePropertyValue::Keyword(value) => value.eq_ignore_ascii_case(keyword),
+            StylePropertyValue::List(items) => items.iter().any(|item| item.contains_keyword(keyword)),
+            StylePropertyValue::Other(_) => false,
+        }
+    }
+}
+
+/// The properties of a JS style object that can hold CSS keywords taffy doesn't support
+#[derive(Deserialize)]
+#[cfg_attr(feature = "camel-case-style", serde(rename_all = "camelCase"))]
+struct UnsupportedKeywords {
+    position: Option<StylePropertyValue>,
+    grid_template_columns: Option<StylePropertyValue>,
+    grid_template_rows: Option<StylePropertyValue>,
+}
+
+/// Returns the error for a grid track list given as `subgrid`, or containing it, which taffy doesn't support
+fn unsupported_subgrid(property: &str) -> String {
+    format!("unsupported: subgrid in {property}, taffy has no subgrid support: give the tracks explicitly instead")
+}
+
+/// Rejects the CSS keywords that taffy doesn't support with an error naming them, rather than the generic error
+/// for an unknown value
+fn check_supported_keywords(keywords: UnsupportedKeywords) -> Result<(), TaffyError> {
+    if let Some(StylePropertyValue::Keyword(position)) = &keywords.position {
+        if position.eq_ignore_ascii_case("sticky") {
+            return Err(TaffyError::StyleDecode(format!(
+                "unsupported: sticky position {position:?}, expected \"Relative\" or \"Absolute\""
+            )));
+        }
+    }
+    let (columns, rows) = match cfg!(feature = "camel-case-style") {
+        true => ("gridTemplateColumns", "gridTemplateRows"),
+        false => ("grid_template_columns", "grid_template_rows"),
+    };
+    let tracks = [(columns, &keywords.grid_template_columns), (rows, &keywords.grid_template_rows)];
+    for (property, value) in tracks {
+        if value.as_ref().is_some_and(|value| value.contains_keyword("subgrid")) {
+            return Err(TaffyError::StyleDecode(unsupported_subgrid(property)));
+        }
+    }
+    Ok(())
+}
+
+/// Decodes a JS style object
+///
+/// `position: "sticky"` is rejected with an `unsupported: sticky` error, since taffy has no sticky positioning, and
+/// a `grid_template_columns` or `grid_template_rows` of `"subgrid"` with an `unsupported: subgrid` error, since
+/// taffy has no subgrids.
+fn decode_style(style: JsValue) -> Result<Style, TaffyError> {
+    // A value that isn't an object fails to decode as a style below, with the usual error
+    if let Ok(keywords) = serde_wasm_bindgen::from_value(style.clone()) {
+        check_supported_keywords(keywords)?;
+    }
+    serde_wasm_bindgen::from_value::<JsStyle>(style).map(|style| style.0).map_err(|e| {
+        wasm_log!("🚀 WASM: Style decode error: {}", e);
+        TaffyError::StyleDecode(e.to_string())
+    })
+}
+
+impl TaffyTree {
+    /// Creates an empty tree with the default options
+    pub fn new() -> Self {
+        Self::with_options(TaffyTreeOptions::default())
+    }
+
+    /// Creates an empty tree with the given options
+    pub fn with_options(options: TaffyTreeOptions) -> Self {
+        Self {
+            inner: RefCell::new(Taffy::new()),
+            last_compute_stats: Cell::default(),
+            last_measured_nodes: RefCell::default(),
+            node_states: RefCell::default(),
+            default_box_sizing: Cell::new(BoxSizing::BorderBox),
+            length_scale: Cell::new(1.0),
+            options,
+            retired: RefCell::default(),
+            measure_fallback_size: Cell::default(),
+            decode_fallback_style: RefCell::default(),
+            decode_failures: Cell::default(),
+            hit_indices: RefCell::default(),
+            next_hit_index: Cell::default(),
+            lod_indices: RefCell::default(),
+            next_lod_index: Cell::default(),
+            validate_layouts: Cell::default(),
+            max_layout_depth: Cell::new(Some(DEFAULT_MAX_LAYOUT_DEPTH)),
+            auto_compute: Cell::new(false),
+            previous_layouts: RefCell::default(),
+            node_timings: RefCell::default(),
+            batch: RefCell::default(),
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl TaffyTree {
+    /// Creates an empty tree, configured by `options` (`{ stableIds?: boolean }`) if given
+    ///
+    /// With `stableIds: true` the id of a removed node is never given to another node; see `remove`. Throws an
+    /// `INVALID_ARGUMENT` error if `options` is not a valid `TaffyTreeOptions`.
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        Self { inner: RefCell::new(Taffy::new()) }
+    pub fn new_with_options(options: Option<JsTaffyTreeOptions>) -> Result<TaffyTree, TaffyError> {
+        Ok(Self::with_options(node_ids::decode_options(options)?))
     }
 
     /// Create a leaf from a JS object `{display:"flex", flexDirection:"row", …}`
+    ///
+    /// Throws a `STYLE_DECODE` error if the object is not a valid style, unless a style to use instead was set with
+    /// `set_decode_fallback_style`. Sticky positioning is not supported:
+    /// `position: "sticky"` throws a `STYLE_DECODE` error starting with `unsupported: sticky` rather than falling
+    /// back to another position.
+    ///
+    /// **Subgrids are not supported.** A `grid_template_columns` or `grid_template_rows` of `"subgrid"`, or a track
+    /// list containing it, throws a `STYLE_DECODE` error starting with `unsupported: subgrid`, rather than the grid
+    /// being laid out with other tracks. A nested grid has to repeat its parent's tracks explicitly instead.
+    ///
+    /// A tree can hold at most 16,777,216 (2^24) nodes, counting those retired by `remove`, as node ids keep the
+    /// index of the node in their low 24 bits. Past that, this and every other function that creates nodes throws a
+    /// `TOO_MANY_NODES` error without creating any.
+    #[wasm_bindgen]
+    pub fn new_leaf(&self, style: JsValue) -> Result<u32, TaffyError> {
+        let style = self.decode_style_with_defaults(style)?;
+        self.new_container(style)
+    }
+
+    /// Creates a flex container that lays out its children in a row, left to right
+    ///
+    /// This is a shorthand for `new_leaf({ display: "Flex", flex_direction: "Row" })`. Use `update_style` to
+    /// change any other property.
     #[wasm_bindgen]
-    pub fn new_leaf(&self, style: JsValue) -> u32 {
-        let rs: JsStyle = match serde_wasm_bindgen::from_value(style) {
-            Ok(style) => style,
-            Err(e) => {
-                wasm_log!("🚀 WASM: Style decode error in new_leaf: {}", e);
-                JsStyle(Style::default())
+    pub fn new_flex_row(&self) -> Result<u32, TaffyError> {
+        self.new_container(Style { display: Display::Flex, flex_direction: FlexDirection::Row, ..self.default_style() })
+    }
+
+    /// Creates a flex container that lays out its children in a column, top to bottom
+    ///
+    /// This is a shorthand for `new_leaf({ display: "Flex", flex_direction: "Column" })`.
+    #[wasm_bindgen]
+    pub fn new_flex_column(&self) -> Result<u32, TaffyError> {
+        self.new_container(Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            ..self.default_style()
+        })
+    }
+
+    /// Creates a grid container with the given column and row tracks, each an `Array<TrackSizingFunction>`
+    ///
+    /// This is a shorthand for `new_leaf({ display: "Grid", grid_template_columns: columns, grid_template_rows: rows })`.
+    /// Throws an `INVALID_ARGUMENT` error if `columns` or `rows` is not a valid array of tracks, or if either is
+    /// `"subgrid"` or contains it, with a message containing `unsupported: subgrid`: subgrids are not supported.
+    ///
+    /// Only available with the `grid` feature, which is enabled by default.
+    #[cfg(feature = "grid")]
+    #[wasm_bindgen]
+    pub fn new_grid(&self, columns: JsValue, rows: JsValue) -> Result<u32, TaffyError> {
+        let decode_tracks = |tracks: JsValue, name: &str| {
+            let value = serde_wasm_bindgen::from_value::<StylePropertyValue>(tracks.clone());
+            if value.is_ok_and(|value| value.contains_keyword("subgrid")) {
+                return Err(TaffyError::InvalidArgument(unsupported_subgrid(name)));
             }
+            serde_wasm_bindgen::from_value::<Vec<TrackSizingFunction>>(tracks)
+                .map_err(|e| TaffyError::InvalidArgument(format!("{name}: {e}")))
         };
-        let node = self.inner.borrow_mut().new_leaf(rs.0).unwrap();
-        u64::from(node) as u32
+        let columns = decode_tracks(columns, "columns")?;
+        let rows = decode_tracks(rows, "rows")?;
+        self.new_container(self.grid_style(columns, rows))
+    }
+
+    #[wasm_bindgen]
+    pub fn add_child(&self, parent: u32, child: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let parent = resolve_node(&tree, parent)?;
+        let child = resolve_node(&tree, child)?;
+        tree.add_child(parent, child)?;
+        Ok(())
     }
 
+    /// Detaches `child` from `parent`, marking `parent` dirty
+    ///
+    /// The child is not destroyed: it keeps its style, context and own children, and becomes a root that can be
+    /// added to another parent. Throws a `CHILD_NOT_FOUND` error, leaving the tree as it is, if `child` is not a
+    /// child of `parent`.
     #[wasm_bindgen]
-    pub fn add_child(&self, parent: u32, child: u32) {
-        let parent = NodeId::from(parent as u64);
-        let child = NodeId::from(child as u64);
-        self.inner.borrow_mut().add_child(parent, child).unwrap();
+    pub fn remove_child(&self, parent: u32, child: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let parent_node = resolve_node(&tree, parent)?;
+        let child_node = resolve_node(&tree, child)?;
+        if tree.parent(child_node) != Some(parent_node) {
+            return Err(TaffyError::ChildNotFound { parent, child });
+        }
+        tree.remove_child(parent_node, child_node)?;
+        Ok(())
+    }
+
+    /// Detaches every child of `parent` in one call, returning their ids in their former order as a `Uint32Array`
+    ///
+    /// The children are not destroyed: they keep their styles, contexts and own children, and can be added to
+    /// another parent (or back to this one). `parent` is marked dirty once, however many children it had.
+    #[wasm_bindgen]
+    pub fn clear_children(&self, parent: u32) -> Result<Vec<u32>, TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let parent = resolve_node(&tree, parent)?;
+        let children = tree.children(parent)?;
+        tree.set_children(parent, &[])?;
+        Ok(children.into_iter().map(js_node_id).collect())
+    }
+
+    /// Swaps the children of `parent` at `index_a` and `index_b`, e.g. to reorder a list after a drag
+    ///
+    /// The two children keep their styles and subtrees and only change places, and `parent` is marked dirty once.
+    /// Swapping a child with itself leaves the tree as it is, without marking `parent` dirty.
+    ///
+    /// Throws a `CHILD_INDEX_OUT_OF_BOUNDS` error, leaving the children as they are, if either index is not less
+    /// than the number of children of `parent`.
+    #[wasm_bindgen]
+    pub fn swap_children(&self, parent: u32, index_a: u32, index_b: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let parent_node = resolve_node(&tree, parent)?;
+        let mut children = tree.children(parent_node)?;
+        let child_count = children.len();
+        for child_index in [index_a as usize, index_b as usize] {
+            if child_index >= child_count {
+                return Err(TaffyError::ChildIndexOutOfBounds { parent, child_index, child_count });
+            }
+        }
+        if index_a != index_b {
+            children.swap(index_a as usize, index_b as usize);
+            tree.set_children(parent_node, &children)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the ancestors of a node as a `Uint32Array`, nearest first: its parent, then its parent's parent, and
+    /// so on up to the root
+    ///
+    /// The node itself is not included, so the result is empty for a root. This is the order in which an event
+    /// dispatched at the node bubbles up through the tree.
+    #[wasm_bindgen]
+    pub fn ancestors(&self, node_id: u32) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(std::iter::successors(tree.parent(node), |&ancestor| tree.parent(ancestor)).map(js_node_id).collect())
+    }
+
+    /// Returns the descendants of a node as a `Uint32Array`, in pre-order: each node before its own descendants, and
+    /// siblings in order
+    ///
+    /// The node itself is excluded by default, like in `ancestors`; pass `include_self: true` to list it first. This
+    /// is the order in which the nodes are painted, and makes it possible to act on a whole subtree, e.g. to select
+    /// or remove it, without walking it with a call to `children` per node.
+    #[wasm_bindgen]
+    pub fn descendants(&self, node_id: u32, include_self: Option<bool>) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let mut descendants = Vec::new();
+        let mut stack = match include_self.unwrap_or(false) {
+            true => vec![node],
+            false => tree.children(node)?.into_iter().rev().collect(),
+        };
+        while let Some(node) = stack.pop() {
+            descendants.push(js_node_id(node));
+            stack.extend(tree.children(node)?.into_iter().rev());
+        }
+        Ok(descendants)
     }
 
+    /// Replaces the style of a node. Throws a `STYLE_DECODE` error, leaving the style unchanged, if the object is
+    /// not a valid style.
+    ///
+    /// The node and its ancestors are only marked dirty if the new style differs from the current one, so
+    /// re-applying an equal style, e.g. when a framework reconciles an unchanged element, doesn't cause a relayout.
+    /// Every style property can affect layout, so any other change marks the node dirty.
     #[wasm_bindgen]
-    pub fn update_style(&self, node_id: u32, style: JsValue) {
+    pub fn update_style(&self, node_id: u32, style: JsValue) -> Result<(), TaffyError> {
         // Add explicit console logging for debugging
         #[cfg(feature = "node-console")]
         web_sys::console::log_1(&format!("🚀 WASM: update_style called for node {}", node_id).into());
 
-        let rs: JsStyle = match serde_wasm_bindgen::from_value(style) {
-            Ok(style) => {
-                #[cfg(feature = "node-console")]
-                web_sys::console::log_1(&"✅ WASM: Style deserialized successfully".into());
-                style
-            }
-            Err(e) => {
-                // Use both wasm_log! and direct console logging
-                wasm_log!("🚀 WASM: Style decode error in update_style: {}", e);
-                #[cfg(feature = "node-console")]
-                web_sys::console::error_1(&format!("❌ WASM: Style decode error in update_style: {}", e).into());
-                return;
-            }
-        };
-        let node = NodeId::from(node_id as u64);
+        // Check the node first, so that a stale id is reported as such rather than decoding the style for nothing
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let style = self.decode_style_with_defaults(style)?;
+        let style = self.apply_pinned_size(node_id, self.apply_tap_target(node_id, style));
 
         #[cfg(feature = "node-console")]
         web_sys::console::log_1(&format!("🚀 WASM: About to call set_style for node {}", node_id).into());
 
-        if let Err(e) = self.inner.borrow_mut().set_style(node, rs.0) {
-            wasm_log!("🚀 WASM: Set style error: {}", e);
-            #[cfg(feature = "node-console")]
-            web_sys::console::error_1(&format!("❌ WASM: Set style error: {}", e).into());
-        } else {
-            #[cfg(feature = "node-console")]
-            web_sys::console::log_1(&"✅ WASM: set_style completed successfully".into());
+        set_style_if_changed(&mut tree, node, style)?;
+        Ok(())
+    }
+
+    /// Returns the styles of the nodes in `ids` as an array in the same order, with `null` for each id that is not
+    /// a node in the tree
+    ///
+    /// Each style is an object with every property set, using the same keys and values as the style objects passed
+    /// to `new_leaf`, so it can be passed back to `update_style` unchanged. Reading the styles of many nodes this
+    /// way takes a single call into the module.
+    #[wasm_bindgen]
+    pub fn get_styles(&self, ids: Vec<u32>) -> Result<JsValue, TaffyError> {
+        let styles = self.styles(&ids)?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_missing_as_null(true);
+        Ok(styles.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+
+    /// Marks the layout of this node and its ancestors as outdated
+    ///
+    /// Only the node and its ancestors are invalidated: cached results for its descendants are kept and are
+    /// reused on the next pass wherever their inputs are unchanged. Use `clear_cache` to discard those as well.
+    ///
+    /// During a batch opened with `begin_batch`, the node is only marked dirty when the batch ends.
+    #[wasm_bindgen]
+    pub fn mark_dirty(&self, node_id: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        if !self.defer_mark_dirty(node_id) {
+            tree.mark_dirty(node)?;
         }
+        Ok(())
+    }
+
+    /// Indicates whether the layout of this node needs to be recomputed
+    #[wasm_bindgen]
+    pub fn dirty(&self, node_id: u32) -> Result<bool, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(tree.dirty(node)?)
+    }
+
+    /// Indicates whether the layout of any node in the tree needs to be recomputed, e.g. to skip `compute_layout`
+    /// in a frame where nothing changed
+    ///
+    /// This is cheap: the tree tracks a flag which is set when a node is created or changed, including by
+    /// `mark_dirty`, and recomputed after each layout. It stays set after laying out one root while another is
+    /// still dirty, and after removing a dirty node that had no parent until the next layout.
+    #[wasm_bindgen]
+    pub fn has_dirty(&self) -> Result<bool, TaffyError> {
+        Ok(self.tree()?.has_dirty())
+    }
+
+    /// Discards the cached layout of this node and of every node in its subtree, and marks its ancestors dirty
+    ///
+    /// Where `mark_dirty` leaves the caches of descendants intact, this forces the whole subtree to be laid out
+    /// (and its leaves measured) from scratch on the next pass. This makes it possible to time a "cold" layout
+    /// of the same tree repeatedly. Subtrees frozen with `freeze` below this node keep their cached layouts.
+    #[wasm_bindgen]
+    pub fn clear_cache(&self, node_id: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let node_states = self.node_states.borrow();
+        clear_subtree_cache(&mut tree, node, |child| node_states.get(js_node_id(child)).frozen)
     }
 
     #[wasm_bindgen]
-    pub fn compute_layout(&self, node_id: u32, width: f32, height: f32) {
+    pub fn compute_layout(&self, node_id: u32, width: f32, height: f32) -> Result<(), TaffyError> {
         wasm_log!("🚀 WASM: Starting compute_layout for node {} with size {}x{}", node_id, width, height);
-        let node = NodeId::from(node_id as u64);
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        self.check_layout_depth(&tree, node)?;
+        self.keep_previous_layouts(&tree, node)?;
         let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
-        self.inner.borrow_mut().compute_layout(node, available_space).unwrap();
+        let start_ms = compute_stats::now_ms();
+        tree.compute_layout_with_measure(node, available_space, |known_dimensions, available, leaf, _, _| {
+            self.measure_content_size(known_dimensions, available, leaf)
+        })?;
+        self.record_compute_stats(&tree, node, 0, BTreeMap::new(), compute_stats::now_ms() - start_ms);
+        self.record_layout(node_id, available_space);
+        self.validate_layout(&tree, node)?;
         wasm_log!("✅ WASM: Finished compute_layout for node {}", node_id);
+        Ok(())
     }
 
+    /// Computes the layout of the subtree rooted at `node_id`, which need not be attached to the rest of the tree
+    ///
+    /// `available_space` is a `Size<AvailableSpace>`, e.g. `{ width: { Definite: 300 }, height: "MaxContent" }`. The
+    /// subtree is laid out as if `node_id` were a root: percentage sizes, margins, padding and insets of `node_id`
+    /// resolve against the definite components of `available_space` (horizontal padding and margins against its
+    /// width) and behave like `auto` in axes where it is `MinContent` or `MaxContent`. Descendants resolve
+    /// percentages against their parents as usual. `node_id` is placed at `(0, 0)`.
+    ///
+    /// This is meant for measuring detached subtrees, such as a popover before it is inserted. If `node_id` does
+    /// have a parent, the parent is ignored, and the node's position within it is lost until the parent is next
+    /// laid out after being marked dirty.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available_space` is not a valid `Size<AvailableSpace>`.
     #[wasm_bindgen]
-    pub fn compute_layout_with_measure(&self, node_id: u32, width: f32, height: f32, measure_func: &js_sys::Function) {
-        let node = NodeId::from(node_id as u64);
-        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+    pub fn compute_subtree_layout(&self, node_id: u32, available_space: JsValue) -> Result<(), TaffyError> {
+        let available_space: Size<AvailableSpace> = serde_wasm_bindgen::from_value(available_space)
+            .map_err(|e| TaffyError::InvalidArgument(format!("availableSpace: {e}")))?;
+        self.compute_subtree(node_id, available_space)
+    }
 
-        let measure_function = |known_dimensions: Size<Option<f32>>,
-                                available_space: Size<AvailableSpace>,
-                                _node_id: NodeId,
-                                node_context: Option<&mut JsContext>,
-                                _style: &Style|
-         -> Size<f32> {
-            // Get the context data (or null if no context)
-            let null_value = JsValue::NULL;
-            let context_data = node_context.map(|ctx| &ctx.data).unwrap_or(&null_value);
-
-            // Calculate the effective available space for measure function
-            // If we have a known dimension, use Definite with that value
-            // Otherwise, use the available space as provided by the parent
-            let effective_width = known_dimensions.width.map(AvailableSpace::Definite).unwrap_or(available_space.width);
-            let effective_height =
-                known_dimensions.height.map(AvailableSpace::Definite).unwrap_or(available_space.height);
-
-            // Create the constraints using Size<AvailableSpace>
-            let constraints = Size { width: effective_width, height: effective_height };
-
-            wasm_log!(
-                "🚀 WASM: Measuring with constraints: width={:?}, height={:?}",
-                constraints.width,
-                constraints.height
-            );
-
-            // Serialize constraints to JsValue for passing to JavaScript
-            let constraints_js = match serde_wasm_bindgen::to_value(&constraints) {
-                Ok(value) => value,
-                Err(e) => {
-                    wasm_log!("🚀 WASM: Failed to serialize constraints: {}", e);
-                    return Size::ZERO;
-                }
-            };
+    /// Computes the layout, calling `measure_func(contextData, constraints)` to size leaf nodes
+    ///
+    /// `measure_func` returns `{ width, height, firstBaseline? }`. `firstBaseline` is the offset of the baseline of the
+    /// node's first line of text from the top of its content box, and is used to align the node with
+    /// `alignItems: "Baseline"`. Nodes measured without a `firstBaseline` are aligned by the bottom of their box.
+    ///
+    /// The tree is in use for the duration of the call: a measure function that calls back into it fails with a
+    /// `BORROW_CONFLICT` error, and the node being measured is given a zero size.
+    ///
+    /// `budget` (`{ maxMeasureCalls?: number, timeoutMs?: number }`) limits the work done in one call: by default
+    /// up to 1,000,000 measure calls and no time limit. The time limit is checked before each measure call, so a
+    /// single call that never returns can't be interrupted. Once a limit is reached the remaining leaves are not
+    /// measured, the cached layouts of the subtree are discarded, and a `MEASURE_BUDGET_EXCEEDED` error naming the
+    /// node that was about to be measured is thrown. A well-behaved measure function never triggers this: it
+    /// indicates a bug such as a measure function that blocks, or returns a different size each time it is called.
+    /// Throws an `INVALID_ARGUMENT` error if `budget` is not a valid budget.
+    #[wasm_bindgen]
+    pub fn compute_layout_with_measure(
+        &self,
+        node_id: u32,
+        width: f32,
+        height: f32,
+        measure_func: &js_sys::Function,
+        budget: Option<JsMeasureBudget>,
+    ) -> Result<(), TaffyError> {
+        self.compute_layout_with_measure_this(node_id, width, height, JsValue::NULL, measure_func, budget)
+    }
 
-            // Call the JavaScript function with (contextData, constraints)
-            match measure_func.call2(&JsValue::NULL, context_data, &constraints_js) {
-                Ok(result) => {
-                    // Try to parse the result as {width: number, height: number}
-                    if result.is_object() {
-                        let width_prop = js_sys::Reflect::get(&result, &"width".into()).unwrap_or(JsValue::from(0.0));
-                        let height_prop = js_sys::Reflect::get(&result, &"height".into()).unwrap_or(JsValue::from(0.0));
-
-                        let width = width_prop.as_f64().unwrap_or(0.0) as f32;
-                        let height = height_prop.as_f64().unwrap_or(0.0) as f32;
-
-                        Size { width, height }
-                    } else {
-                        // Fallback: try to parse as array [width, height]
-                        if js_sys::Array::is_array(&result) {
-                            let array = js_sys::Array::from(&result);
-                            let width = array.get(0).as_f64().unwrap_or(0.0) as f32;
-                            let height = array.get(1).as_f64().unwrap_or(0.0) as f32;
-                            Size { width, height }
-                        } else {
-                            Size { width: 0.0, height: 0.0 }
-                        }
-                    }
-                }
-                Err(_) => Size { width: 0.0, height: 0.0 },
-            }
-        };
+    /// Computes the layout like `compute_layout_with_measure`, calling `measure_func` with `this_arg` as `this`
+    ///
+    /// `measure_func` is called as `measure_func.call(thisArg, contextData, constraints)`, so it can be a method
+    /// that uses the object it belongs to, e.g. `compute_layout_with_measure_this(root, w, h, measurer,
+    /// measurer.measure)`, without binding it first. `compute_layout_with_measure` calls `measure_func` with a
+    /// `this` of `null`, which fails or silently reads `undefined` properties in a method that expects its object.
+    #[wasm_bindgen]
+    pub fn compute_layout_with_measure_this(
+        &self,
+        node_id: u32,
+        width: f32,
+        height: f32,
+        this_arg: JsValue,
+        measure_func: &js_sys::Function,
+        budget: Option<JsMeasureBudget>,
+    ) -> Result<(), TaffyError> {
+        let budget = measure_budget::decode_budget(budget)?;
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
 
-        self.inner.borrow_mut().compute_layout_with_measure(node, available_space, measure_function).unwrap();
+        self.compute_layout_with_budget(
+            node_id,
+            available_space,
+            budget,
+            compute_stats::now_ms,
+            |known_dimensions, available_space, node, node_context| {
+                self.call_js_measure_for(measure_func, &this_arg, known_dimensions, available_space, node, node_context)
+            },
+        )
     }
 
     #[wasm_bindgen]
-    pub fn set_node_context(&self, node_id: u32, data: &JsValue) {
-        let node = NodeId::from(node_id as u64);
+    pub fn set_node_context(&self, node_id: u32, data: &JsValue) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
         let context = JsContext { data: data.clone() };
-        self.inner.borrow_mut().set_node_context(node, Some(context)).unwrap();
+        tree.set_node_context(node, Some(context))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn remove_node_context(&self, node_id: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        tree.set_node_context(node, None)?;
+        Ok(())
+    }
+
+    /// Returns the layout of a node
+    fn layout(&self, node_id: u32) -> Result<Layout, TaffyError> {
+        self.compute_if_stale(node_id)?;
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        Ok(*tree.layout(node)?)
+    }
+
+    /// Returns the layout of a node if it is in the tree and has been laid out since it was last marked dirty
+    fn current_layout(&self, node_id: u32) -> Result<Option<Layout>, TaffyError> {
+        self.compute_if_stale(node_id)?;
+        let tree = self.tree()?;
+        let Ok(node) = resolve_node(&tree, node_id) else {
+            return Ok(None);
+        };
+        if tree.dirty(node)? {
+            return Ok(None);
+        }
+        Ok(Some(*tree.layout(node)?))
     }
 
+    /// Returns the x position of a node relative to its parent in the last layout, or 0 before it is first laid out
+    ///
+    /// Throws an `INVALID_NODE` error if the node is not in the tree. Use `try_layout` to tell a node that hasn't
+    /// been laid out yet apart from one at 0.
     #[wasm_bindgen]
-    pub fn remove_node_context(&self, node_id: u32) {
-        let node = NodeId::from(node_id as u64);
-        self.inner.borrow_mut().set_node_context(node, None).unwrap();
+    pub fn layout_left(&self, node_id: u32) -> Result<f32, TaffyError> {
+        Ok(self.layout(node_id)?.location.x)
     }
 
+    /// Returns the y position of a node relative to its parent in the last layout, or 0 before it is first laid out
+    ///
+    /// Throws an `INVALID_NODE` error if the node is not in the tree.
     #[wasm_bindgen]
-    pub fn layout_left(&self, node_id: u32) -> f32 {
-        self.inner.borrow().layout(NodeId::from(node_id as u64)).unwrap().location.x
+    pub fn layout_top(&self, node_id: u32) -> Result<f32, TaffyError> {
+        Ok(self.layout(node_id)?.location.y)
     }
 
+    /// Returns the width of a node in the last layout, or 0 before it is first laid out
+    ///
+    /// Throws an `INVALID_NODE` error if the node is not in the tree.
     #[wasm_bindgen]
-    pub fn layout_top(&self, node_id: u32) -> f32 {
-        self.inner.borrow().layout(NodeId::from(node_id as u64)).unwrap().location.y
+    pub fn layout_width(&self, node_id: u32) -> Result<f32, TaffyError> {
+        Ok(self.layout(node_id)?.size.width)
     }
 
+    /// Returns the height of a node in the last layout, or 0 before it is first laid out
+    ///
+    /// Throws an `INVALID_NODE` error if the node is not in the tree.
     #[wasm_bindgen]
-    pub fn layout_width(&self, node_id: u32) -> f32 {
-        self.inner.borrow().layout(NodeId::from(node_id as u64)).unwrap().size.width
+    pub fn layout_height(&self, node_id: u32) -> Result<f32, TaffyError> {
+        Ok(self.layout(node_id)?.size.height)
     }
 
+    /// Returns the full layout of a node, or `undefined` if the node is not in the tree or its layout is not up to
+    /// date
+    ///
+    /// The layout is `{ order, location: { x, y }, size: { width, height }, content_size, scrollbar_size, border,
+    /// padding, margin }`, with rounded values as returned by `layout_left` and friends. It is `undefined` where those
+    /// would throw or return a placeholder: for an id that is not (or no longer) a node, and for a node that is
+    /// dirty, i.e. that hasn't been laid out yet or has changed since its last layout. Nodes inside a
+    /// `display: "None"` subtree are not laid out, so they have no layout either. Use this to render a UI that
+    /// may be read before its first layout without guarding every call.
     #[wasm_bindgen]
-    pub fn layout_height(&self, node_id: u32) -> f32 {
-        self.inner.borrow().layout(NodeId::from(node_id as u64)).unwrap().size.height
+    pub fn try_layout(&self, node_id: u32) -> Result<Option<JsValue>, TaffyError> {
+        let layout = self.current_layout(node_id)?;
+        Ok(layout.map(|layout| serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL)))
+    }
+
+    /// Returns the margins of a node in the last layout, in pixels, as `{ top, right, bottom, left }`
+    ///
+    /// These are the margins the node was actually laid out with: percentages are resolved against the width of
+    /// its parent, and `auto` margins are the space they absorbed, e.g. the equal left and right margins that
+    /// center a flex item with `margin: auto`. All edges are 0 before the node is first laid out.
+    #[wasm_bindgen]
+    pub fn resolved_margin(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let margin = self.layout(node_id)?.margin;
+        Ok(serde_wasm_bindgen::to_value(&margin).unwrap_or(JsValue::NULL))
     }
 
     // …add other helpers you need (top, width, height, etc.)
@@ -266,6 +1004,7 @@ impl TaffyTree {
 // These functions are never called but ensure the types get exported
 
 /// Get the default TrackSizingFunction for TypeScript export
+#[cfg(feature = "grid")]
 #[wasm_bindgen]
 pub fn get_default_track_sizing_function() -> JsValue {
     use taffy::style::TrackSizingFunction;
@@ -274,6 +1013,7 @@ pub fn get_default_track_sizing_function() -> JsValue {
 }
 
 /// Get the default NonRepeatedTrackSizingFunction for TypeScript export  
+#[cfg(feature = "grid")]
 #[wasm_bindgen]
 pub fn get_default_non_repeated_track_sizing_function() -> JsValue {
     use taffy::style::NonRepeatedTrackSizingFunction;
@@ -282,6 +1022,7 @@ pub fn get_default_non_repeated_track_sizing_function() -> JsValue {
 }
 
 /// Get the default GridPlacement for TypeScript export
+#[cfg(feature = "grid")]
 #[wasm_bindgen]
 pub fn get_default_grid_placement() -> JsValue {
     use taffy::style::GridPlacement;
@@ -290,6 +1031,7 @@ pub fn get_default_grid_placement() -> JsValue {
 }
 
 /// Get the default MinTrackSizingFunction for TypeScript export
+#[cfg(feature = "grid")]
 #[wasm_bindgen]
 pub fn get_default_min_track_sizing_function() -> JsValue {
     use taffy::style::MinTrackSizingFunction;
@@ -298,6 +1040,7 @@ pub fn get_default_min_track_sizing_function() -> JsValue {
 }
 
 /// Get the default MaxTrackSizingFunction for TypeScript export
+#[cfg(feature = "grid")]
 #[wasm_bindgen]
 pub fn get_default_max_track_sizing_function() -> JsValue {
     use taffy::style::MaxTrackSizingFunction;
@@ -306,9 +1049,636 @@ pub fn get_default_max_track_sizing_function() -> JsValue {
 }
 
 /// Get the default GridTrackRepetition for TypeScript export
+#[cfg(feature = "grid")]
 #[wasm_bindgen]
 pub fn get_default_grid_track_repetition() -> JsValue {
     use taffy::style::GridTrackRepetition;
     let default_repetition = GridTrackRepetition::AutoFill;
     serde_wasm_bindgen::to_value(&default_repetition).unwrap_or(JsValue::NULL)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out `root`, returning the number of times the measure function was called
+    fn measure_count(tree: &TaffyTree, root: u32) -> usize {
+        let mut count = 0;
+        tree.inner
+            .borrow_mut()
+            .compute_layout_with_measure(node_from_js_id(root), Size::MAX_CONTENT, |known, _, _, _, _| {
+                count += 1;
+                known.unwrap_or(Size { width: 10.0, height: 10.0 })
+            })
+            .unwrap();
+        count
+    }
+
+    #[test]
+    fn clear_cache_forces_subtree_to_be_recomputed() {
+        let tree = TaffyTree::new();
+        let (root, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style::default()).unwrap();
+            let root = inner.new_with_children(Style::default(), &[leaf]).unwrap();
+            (js_node_id(root), js_node_id(leaf))
+        };
+
+        assert!(measure_count(&tree, root) > 0);
+        assert_eq!(measure_count(&tree, root), 0);
+
+        // Marking the root dirty relays out the root, but the leaf's cached measurement is reused
+        tree.mark_dirty(root).unwrap();
+        assert!(tree.dirty(root).unwrap());
+        assert_eq!(measure_count(&tree, root), 0);
+
+        // Clearing the cache of the root discards the leaf's cache too
+        tree.clear_cache(root).unwrap();
+        assert!(tree.dirty(root).unwrap());
+        assert!(tree.dirty(leaf).unwrap());
+        assert!(measure_count(&tree, root) > 0);
+        assert!(!tree.dirty(leaf).unwrap());
+
+        // Clearing the cache of the leaf invalidates its ancestors
+        tree.clear_cache(leaf).unwrap();
+        assert!(tree.dirty(root).unwrap());
+        assert!(measure_count(&tree, root) > 0);
+    }
+
+    #[test]
+    fn compute_subtree_layout_of_detached_subtree() {
+        let tree = TaffyTree::new();
+        let (main_root, popover, content) = {
+            let mut inner = tree.inner.borrow_mut();
+            let main_root = inner.new_leaf(Style { size: length(500.0), ..Default::default() }).unwrap();
+            let content = inner
+                .new_leaf(Style { size: Size { width: percent(0.5), height: length(20.0) }, ..Default::default() })
+                .unwrap();
+            let popover = inner
+                .new_with_children(
+                    Style {
+                        size: Size { width: percent(0.5), height: auto() },
+                        padding: Rect { left: percent(0.1), right: zero(), top: zero(), bottom: zero() },
+                        ..Default::default()
+                    },
+                    &[content],
+                )
+                .unwrap();
+            inner.compute_layout(main_root, Size::MAX_CONTENT).unwrap();
+            (js_node_id(main_root), js_node_id(popover), js_node_id(content))
+        };
+
+        let available_space = Size { width: AvailableSpace::Definite(400.0), height: AvailableSpace::MaxContent };
+        tree.compute_subtree(popover, available_space).unwrap();
+
+        // The detached root resolves its percentages against the available space...
+        assert_eq!(tree.layout_width(popover), Ok(200.0));
+        assert_eq!(tree.layout_height(popover), Ok(20.0));
+        // ...and its children resolve theirs against its content box as usual
+        assert_eq!(tree.layout_left(content), Ok(40.0));
+        assert_eq!(tree.layout_width(content), Ok(80.0));
+
+        // Laying out the detached subtree leaves the main tree alone
+        assert_eq!(tree.layout_width(main_root), Ok(500.0));
+        assert_eq!(tree.dirty(main_root), Ok(false));
+    }
+
+    #[test]
+    fn compute_subtree_layout_with_indefinite_space() {
+        let tree = TaffyTree::new();
+        let node = js_node_id(
+            tree.inner
+                .borrow_mut()
+                .new_leaf(Style { size: Size { width: percent(0.5), height: length(10.0) }, ..Default::default() })
+                .unwrap(),
+        );
+
+        // Percentages can't be resolved against an indefinite size, so the width behaves like `auto`
+        tree.compute_subtree(node, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(node), Ok(0.0));
+    }
+
+    #[test]
+    fn rtl_direction_decodes_and_lays_out_right_to_left() {
+        let JsStyle(style) = serde_json::from_value(serde_json::json!({ "direction": "Rtl" })).unwrap();
+        assert_eq!(style.direction, Direction::Rtl);
+        let style = Style { size: Size { width: length(100.0), height: auto() }, ..style };
+
+        let tree = TaffyTree::new();
+        let (first, second) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = || Style { size: Size { width: length(20.0), height: length(10.0) }, ..Default::default() };
+            let (first, second) = (inner.new_leaf(item()).unwrap(), inner.new_leaf(item()).unwrap());
+            let root = inner.new_with_children(style, &[first, second]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (js_node_id(first), js_node_id(second))
+        };
+
+        assert_eq!(tree.layout_left(first), Ok(80.0));
+        assert_eq!(tree.layout_left(second), Ok(60.0));
+    }
+
+    #[test]
+    fn stretch_only_applies_to_items_with_auto_cross_size() {
+        let key = if cfg!(feature = "camel-case-style") { "alignItems" } else { "align_items" };
+        for direction in [FlexDirection::Row, FlexDirection::Column] {
+            let JsStyle(style) = serde_json::from_value(serde_json::json!({ key: "Stretch" })).unwrap();
+            assert_eq!(style.align_items, Some(AlignItems::Stretch));
+            let style = Style { flex_direction: direction, size: length(100.0), ..style };
+
+            let tree = TaffyTree::new();
+            let (sized, stretched) = {
+                let mut inner = tree.inner.borrow_mut();
+                let sized = inner.new_leaf(Style { size: length(20.0), ..Default::default() }).unwrap();
+                let stretched = inner.new_leaf(Style::default()).unwrap();
+                let root = inner.new_with_children(style, &[sized, stretched]).unwrap();
+                inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+                (js_node_id(sized), js_node_id(stretched))
+            };
+
+            // Only the item whose cross size is auto fills the cross axis of the container
+            let cross_size = |node| match direction {
+                FlexDirection::Column => tree.layout_width(node),
+                _ => tree.layout_height(node),
+            };
+            assert_eq!(cross_size(sized), Ok(20.0), "{direction:?}");
+            assert_eq!(cross_size(stretched), Ok(100.0), "{direction:?}");
+        }
+    }
+
+    /// Lays out an absolutely positioned child of a 200x100 parent with a 5px border and 10px padding, decoding the
+    /// child's inset from a JS-style `{ top, right, bottom, left }` object
+    fn absolute_child_location(inset: serde_json::Value) -> (f32, f32) {
+        let json = serde_json::json!({ "position": "Absolute", "inset": inset });
+        let JsStyle(style) = serde_json::from_value(json).unwrap();
+        let style = Style { size: Size { width: length(20.0), height: length(10.0) }, ..style };
+
+        let tree = TaffyTree::new();
+        let child = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(style).unwrap();
+            let parent_style = Style {
+                size: Size { width: length(200.0), height: length(100.0) },
+                border: length(5.0),
+                padding: length(10.0),
+                ..Default::default()
+            };
+            let parent = inner.new_with_children(parent_style, &[child]).unwrap();
+            inner.compute_layout(parent, Size::MAX_CONTENT).unwrap();
+            js_node_id(child)
+        };
+        (tree.layout_left(child).unwrap(), tree.layout_top(child).unwrap())
+    }
+
+    #[test]
+    fn absolute_pixel_insets_are_offsets_from_padding_box() {
+        let auto = CompactLength::auto().to_serialized();
+        let px = compact_length_from_px;
+
+        let inset = serde_json::json!({ "top": px(10.0), "right": auto, "bottom": auto, "left": px(20.0) });
+        assert_eq!(absolute_child_location(inset), (25.0, 15.0));
+
+        let inset = serde_json::json!({ "top": auto, "right": px(20.0), "bottom": px(10.0), "left": auto });
+        assert_eq!(absolute_child_location(inset), (155.0, 75.0));
+    }
+
+    #[test]
+    fn absolute_percentage_insets_resolve_against_padding_box() {
+        let auto = CompactLength::auto().to_serialized();
+        let percent = |value: f32| CompactLength::percent(value).to_serialized();
+
+        // The padding box is 190x90: the parent's border box minus its border, but including its padding
+        let inset = serde_json::json!({ "top": percent(0.5), "right": auto, "bottom": auto, "left": percent(0.1) });
+        assert_eq!(absolute_child_location(inset), (24.0, 50.0));
+
+        let inset = serde_json::json!({ "top": auto, "right": percent(0.1), "bottom": percent(0.5), "left": auto });
+        assert_eq!(absolute_child_location(inset), (156.0, 40.0));
+    }
+
+    #[test]
+    fn measured_baselines_align_text_of_different_sizes() {
+        let tree = TaffyTree::new();
+        let (root, small, large) = {
+            let mut inner = tree.inner.borrow_mut();
+            let small = inner.new_leaf(Style::default()).unwrap();
+            let large = inner.new_leaf(Style::default()).unwrap();
+            let style = Style { align_items: Some(AlignItems::Baseline), ..Default::default() };
+            let root = inner.new_with_children(style, &[small, large]).unwrap();
+            (js_node_id(root), small, large)
+        };
+        // A line of 12px text with its baseline 10px from the top, and a line of 24px text with its baseline at 20px
+        let text = |node: NodeId, with_baseline: bool| {
+            let (height, baseline) = if node == small { (12.0, 10.0) } else { (24.0, 20.0) };
+            (Size { width: 50.0, height }, Point { x: None, y: with_baseline.then_some(baseline) })
+        };
+
+        let budget = MeasureBudget::default();
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, budget, || 0.0, |_, _, node, _| text(node, true))
+            .unwrap();
+        assert_eq!(tree.layout_top(js_node_id(large)), Ok(0.0));
+        assert_eq!(tree.layout_top(js_node_id(small)), Ok(10.0));
+
+        // Without baselines, the bottom edges of the nodes are aligned instead
+        tree.clear_cache(root).unwrap();
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, budget, || 0.0, |_, _, node, _| text(node, false))
+            .unwrap();
+        assert_eq!(tree.layout_top(js_node_id(large)), Ok(0.0));
+        assert_eq!(tree.layout_top(js_node_id(small)), Ok(12.0));
+    }
+
+    #[test]
+    fn ancestors_are_nearest_first() {
+        let tree = TaffyTree::new();
+        let (root, middle, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style::default()).unwrap();
+            let middle = inner.new_with_children(Style::default(), &[leaf]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[middle]).unwrap();
+            (js_node_id(root), js_node_id(middle), js_node_id(leaf))
+        };
+
+        assert_eq!(tree.ancestors(leaf), Ok(vec![middle, root]));
+        assert_eq!(tree.ancestors(middle), Ok(vec![root]));
+        assert_eq!(tree.ancestors(root), Ok(vec![]));
+        assert_eq!(tree.ancestors(root + 1), Err(TaffyError::InvalidNode(root + 1)));
+    }
+
+    #[test]
+    fn layout_is_missing_before_the_first_layout_and_for_invalid_ids() {
+        let tree = TaffyTree::new();
+        let (root, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            (js_node_id(inner.new_with_children(Style::default(), &[leaf]).unwrap()), js_node_id(leaf))
+        };
+        assert_eq!(tree.current_layout(leaf), Ok(None));
+        assert_eq!(tree.layout_width(leaf), Ok(0.0));
+
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(
+            tree.current_layout(leaf).unwrap().map(|layout| layout.size),
+            Some(Size { width: 10.0, height: 10.0 })
+        );
+
+        // A change makes the layout stale until the next layout
+        tree.mark_dirty(leaf).unwrap();
+        assert_eq!(tree.current_layout(leaf), Ok(None));
+        assert_eq!(tree.layout_width(leaf), Ok(10.0));
+
+        let invalid = leaf + 100;
+        assert_eq!(tree.current_layout(invalid), Ok(None));
+        assert_eq!(tree.layout_width(invalid), Err(TaffyError::InvalidNode(invalid)));
+    }
+
+    #[test]
+    fn descendants_are_in_pre_order() {
+        let tree = TaffyTree::new();
+        let (root, [a, a1, a2, b, b1]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let [a1, a2, b1] = [(); 3].map(|_| inner.new_leaf(Style::default()).unwrap());
+            let a = inner.new_with_children(Style::default(), &[a1, a2]).unwrap();
+            let b = inner.new_with_children(Style::default(), &[b1]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[a, b]).unwrap();
+            (js_node_id(root), [a, a1, a2, b, b1].map(js_node_id))
+        };
+
+        assert_eq!(tree.descendants(root, None), Ok(vec![a, a1, a2, b, b1]));
+        assert_eq!(tree.descendants(root, Some(true)), Ok(vec![root, a, a1, a2, b, b1]));
+        assert_eq!(tree.descendants(a, Some(false)), Ok(vec![a1, a2]));
+        assert_eq!(tree.descendants(b1, None), Ok(vec![]));
+        assert_eq!(tree.descendants(b1, Some(true)), Ok(vec![b1]));
+    }
+
+    #[test]
+    fn invalid_node_is_reported() {
+        let tree = TaffyTree::new();
+        let node = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+
+        assert_eq!(tree.layout_width(node + 1), Err(TaffyError::InvalidNode(node + 1)));
+        assert_eq!(tree.add_child(node, node + 1), Err(TaffyError::InvalidNode(node + 1)));
+        assert_eq!(tree.mark_dirty(node + 1), Err(TaffyError::InvalidNode(node + 1)));
+        assert_eq!(tree.mark_dirty(node), Ok(()));
+    }
+
+    #[test]
+    fn auto_margins_resolve_to_the_space_they_center_an_item_in() {
+        let tree = TaffyTree::new();
+        let item = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = inner
+                .new_leaf(Style {
+                    size: Size { width: length(40.0), height: length(10.0) },
+                    margin: Rect { left: auto(), right: auto(), top: length(5.0), bottom: percent(0.1) },
+                    ..Default::default()
+                })
+                .unwrap();
+            let row = Style { size: Size { width: length(200.0), height: length(50.0) }, ..Default::default() };
+            let root = inner.new_with_children(row, &[item]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            js_node_id(item)
+        };
+
+        assert_eq!(tree.layout(item).unwrap().margin, Rect { left: 80.0, right: 80.0, top: 5.0, bottom: 20.0 });
+        assert_eq!(tree.layout_left(item), Ok(80.0));
+    }
+
+    #[test]
+    fn removed_node_is_reported() {
+        let tree = TaffyTree::new();
+        let (parent, removed) = {
+            let mut inner = tree.inner.borrow_mut();
+            let parent = inner.new_leaf(Style::default()).unwrap();
+            let removed = inner.new_leaf(Style::default()).unwrap();
+            inner.remove(removed).unwrap();
+            (js_node_id(parent), js_node_id(removed))
+        };
+
+        assert_eq!(tree.add_child(parent, removed), Err(TaffyError::InvalidNode(removed)));
+        assert_eq!(tree.add_child(removed, parent), Err(TaffyError::InvalidNode(removed)));
+        assert_eq!(tree.layout(removed), Err(TaffyError::InvalidNode(removed)));
+        assert_eq!(tree.update_style(removed, JsValue::NULL), Err(TaffyError::InvalidNode(removed)));
+        assert_eq!(tree.inner.borrow().child_count(node_from_js_id(parent)), 0);
+    }
+
+    #[test]
+    fn reentrant_access_is_a_borrow_conflict() {
+        let tree = TaffyTree::new();
+        let node = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+
+        let _guard = tree.inner.borrow_mut();
+        assert_eq!(tree.dirty(node), Err(TaffyError::BorrowConflict));
+        assert_eq!(tree.mark_dirty(node), Err(TaffyError::BorrowConflict));
+    }
+
+    #[test]
+    fn flex_row_and_column_constructors() {
+        let tree = TaffyTree::new();
+        let row = tree.new_flex_row().unwrap();
+        let column = tree.new_flex_column().unwrap();
+        let fixed = Style { size: Size { width: length(20.0), height: length(10.0) }, ..Default::default() };
+        {
+            let mut inner = tree.inner.borrow_mut();
+            for parent in [row, column] {
+                for _ in 0..2 {
+                    let child = inner.new_leaf(fixed.clone()).unwrap();
+                    inner.add_child(node_from_js_id(parent), child).unwrap();
+                }
+                inner.compute_layout(node_from_js_id(parent), Size::MAX_CONTENT).unwrap();
+            }
+        }
+
+        assert_eq!((tree.layout_width(row), tree.layout_height(row)), (Ok(40.0), Ok(10.0)));
+        assert_eq!((tree.layout_width(column), tree.layout_height(column)), (Ok(20.0), Ok(20.0)));
+    }
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn grid_style_places_children_in_the_template() {
+        let tree = TaffyTree::new();
+        let style = tree.grid_style(vec![length(30.0), fr(1.0), fr(1.0)], vec![length(10.0)]);
+        let grid = tree.new_container(Style { size: Size { width: length(130.0), height: auto() }, ..style }).unwrap();
+        let children: Vec<_> = {
+            let mut inner = tree.inner.borrow_mut();
+            let grid_node = node_from_js_id(grid);
+            let children: Vec<_> = (0..3).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            inner.set_children(grid_node, &children).unwrap();
+            inner.compute_layout(grid_node, Size::MAX_CONTENT).unwrap();
+            children.into_iter().map(js_node_id).collect()
+        };
+
+        let lefts: Vec<_> = children.iter().map(|&child| tree.layout_left(child).unwrap()).collect();
+        assert_eq!(lefts, vec![0.0, 30.0, 80.0]);
+        assert_eq!(tree.layout_height(grid), Ok(10.0));
+    }
+
+    #[test]
+    fn clear_children_detaches_without_destroying() {
+        let tree = TaffyTree::new();
+        let (parent, children) = {
+            let mut inner = tree.inner.borrow_mut();
+            let grandchild = inner.new_leaf(Style::default()).unwrap();
+            let children = [
+                inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap(),
+                inner.new_with_children(Style::default(), &[grandchild]).unwrap(),
+            ];
+            let parent = inner.new_with_children(Style::default(), &children).unwrap();
+            inner.compute_layout(parent, Size::MAX_CONTENT).unwrap();
+            (parent, children)
+        };
+
+        assert_eq!(tree.clear_children(js_node_id(parent)), Ok(children.map(js_node_id).to_vec()));
+        assert_eq!(tree.dirty(js_node_id(parent)), Ok(true));
+        let mut inner = tree.inner.borrow_mut();
+        assert_eq!(inner.child_count(parent), 0);
+        for child in children {
+            assert_eq!(inner.parent(child), None);
+        }
+        assert_eq!(inner.style(children[0]).unwrap().size, length(10.0));
+        assert_eq!(inner.child_count(children[1]), 1);
+
+        // The detached children can be re-parented
+        let other = inner.new_with_children(Style::default(), &children).unwrap();
+        assert_eq!(inner.parent(children[0]), Some(other));
+    }
+
+    #[test]
+    fn removing_a_node_that_is_not_a_child_is_child_not_found() {
+        let tree = TaffyTree::new();
+        let (parent, child, stranger) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style::default()).unwrap();
+            let parent = inner.new_with_children(Style::default(), &[child]).unwrap();
+            (js_node_id(parent), js_node_id(child), js_node_id(inner.new_leaf(Style::default()).unwrap()))
+        };
+
+        let error = tree.remove_child(parent, stranger).unwrap_err();
+        assert_eq!(error, TaffyError::ChildNotFound { parent, child: stranger });
+        assert_eq!((error.code(), error.node_id()), ("CHILD_NOT_FOUND", Some(stranger)));
+
+        tree.remove_child(parent, child).unwrap();
+        assert_eq!(tree.inner.borrow().parent(node_from_js_id(child)), None);
+        assert_eq!(tree.remove_child(parent, child), Err(TaffyError::ChildNotFound { parent, child }));
+    }
+
+    #[test]
+    fn swapping_the_first_and_last_children() {
+        let tree = TaffyTree::new();
+        let (parent, [first, middle, last]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let children = [(); 3].map(|_| inner.new_leaf(Style::default()).unwrap());
+            let parent = inner.new_with_children(Style::default(), &children).unwrap();
+            inner.compute_layout(parent, Size::MAX_CONTENT).unwrap();
+            (js_node_id(parent), children.map(js_node_id))
+        };
+
+        let children = || tree.inner.borrow().children(node_from_js_id(parent)).unwrap();
+        tree.swap_children(parent, 0, 2).unwrap();
+        assert_eq!(children(), [last, middle, first].map(node_from_js_id));
+        assert_eq!(tree.dirty(parent), Ok(true));
+
+        assert_eq!(
+            tree.swap_children(parent, 1, 3),
+            Err(TaffyError::ChildIndexOutOfBounds { parent, child_index: 3, child_count: 3 })
+        );
+        assert_eq!(children(), [last, middle, first].map(node_from_js_id));
+    }
+
+    #[test]
+    fn styles_are_read_in_order() {
+        let tree = TaffyTree::new();
+        let ids = {
+            let mut inner = tree.inner.borrow_mut();
+            [10.0, 20.0, 30.0].map(|width| {
+                let style = Style { size: Size { width: length(width), height: auto() }, ..Default::default() };
+                js_node_id(inner.new_leaf(style).unwrap())
+            })
+        };
+
+        let styles = tree.styles(&[ids[2], 100, ids[0], ids[1]]).unwrap();
+        let widths: Vec<_> = styles.iter().map(|style| style.as_ref().map(|JsStyle(style)| style.size.width)).collect();
+        assert_eq!(widths, [Some(length(30.0)), None, Some(length(10.0)), Some(length(20.0))]);
+
+        // Each style round-trips through the style objects passed to `update_style`
+        let json = serde_json::to_value(&styles[0]).unwrap();
+        let JsStyle(style) = serde_json::from_value(json).unwrap();
+        assert_eq!(style.size.width, length(30.0));
+        assert_eq!(serde_json::to_value(&styles[1]).unwrap(), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn sticky_position_is_rejected_as_unsupported() {
+        for position in ["sticky", "Sticky"] {
+            let keywords = serde_json::from_value(serde_json::json!({ "position": position })).unwrap();
+            let Err(TaffyError::StyleDecode(reason)) = check_supported_keywords(keywords) else {
+                panic!("position {position:?} was accepted");
+            };
+            assert!(reason.starts_with("unsupported: sticky"), "{reason}");
+        }
+
+        for style in
+            [serde_json::json!({ "position": "Absolute" }), serde_json::json!({ "position": 1 }), serde_json::json!({})]
+        {
+            assert_eq!(check_supported_keywords(serde_json::from_value(style).unwrap()), Ok(()));
+        }
+    }
+
+    #[cfg(all(feature = "grid", not(feature = "camel-case-style")))]
+    #[test]
+    fn subgrid_tracks_are_rejected_as_unsupported() {
+        let styles = [
+            serde_json::json!({ "display": "Grid", "grid_template_columns": "subgrid" }),
+            serde_json::json!({ "grid_template_rows": "Subgrid" }),
+            serde_json::json!({ "grid_template_rows": ["subgrid", "auto"] }),
+        ];
+        for style in styles {
+            let keywords = serde_json::from_value(style.clone()).unwrap();
+            let Err(TaffyError::StyleDecode(reason)) = check_supported_keywords(keywords) else {
+                panic!("{style} was accepted");
+            };
+            assert!(reason.starts_with("unsupported: subgrid in grid_template_"), "{reason}");
+        }
+
+        // Explicit tracks are still accepted
+        let tracks =
+            serde_json::to_value(JsStyle(Style { grid_template_columns: vec![fr(1.0)], ..Default::default() }));
+        let keywords = serde_json::from_value(tracks.unwrap()).unwrap();
+        assert_eq!(check_supported_keywords(keywords), Ok(()));
+    }
+
+    #[cfg(all(feature = "grid", feature = "camel-case-style"))]
+    #[test]
+    fn camel_case_subgrid_tracks_are_rejected_as_unsupported() {
+        let styles = [
+            serde_json::json!({ "display": "Grid", "gridTemplateColumns": "subgrid" }),
+            serde_json::json!({ "gridTemplateRows": ["subgrid", "auto"] }),
+        ];
+        for style in styles {
+            let keywords = serde_json::from_value(style.clone()).unwrap();
+            let Err(TaffyError::StyleDecode(reason)) = check_supported_keywords(keywords) else {
+                panic!("{style} was accepted");
+            };
+            assert!(reason.starts_with("unsupported: subgrid in gridTemplate"), "{reason}");
+        }
+    }
+
+    /// Returns the size of a leaf with `style`, round-tripped through a JS style object, laid out as the only child
+    /// of a node with `parent` style in an 800x600 space, and laid out on its own at max-content
+    #[cfg(feature = "grid")]
+    fn aspect_ratio_leaf_sizes(style: Style, parent: Style) -> [(f32, f32); 2] {
+        let JsStyle(style) = serde_json::from_value(serde_json::to_value(JsStyle(style)).unwrap()).unwrap();
+        let tree = TaffyTree::new();
+        let (root, leaf, lone) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(style.clone()).unwrap();
+            let root = inner.new_with_children(parent, &[leaf]).unwrap();
+            (js_node_id(root), js_node_id(leaf), js_node_id(inner.new_leaf(style).unwrap()))
+        };
+        tree.compute_subtree(
+            root,
+            Size { width: AvailableSpace::Definite(800.0), height: AvailableSpace::Definite(600.0) },
+        )
+        .unwrap();
+        tree.compute_subtree(lone, Size::MAX_CONTENT).unwrap();
+        [leaf, lone].map(|node| (tree.layout_width(node).unwrap(), tree.layout_height(node).unwrap()))
+    }
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn aspect_ratio_derives_the_missing_dimension() {
+        let width = |width| Size { width: length(width), height: auto() };
+        let height = |height| Size { width: auto(), height: length(height) };
+        let image = Style { aspect_ratio: Some(1.5), ..Default::default() };
+        let cases = [
+            (Style { size: width(300.0), ..image.clone() }, (300.0, 200.0)),
+            (Style { size: height(200.0), ..image.clone() }, (300.0, 200.0)),
+            // Padding is added around the content box, whose size follows the ratio
+            (
+                Style { size: width(300.0), padding: length(10.0), box_sizing: BoxSizing::ContentBox, ..image.clone() },
+                (320.0, 220.0),
+            ),
+            (Style { size: width(300.0), padding: length(10.0), ..image.clone() }, (300.0, 200.0)),
+            // A max size clamps the size before the ratio is applied
+            (Style { size: width(300.0), max_size: width(150.0), ..image.clone() }, (150.0, 100.0)),
+            (Style { size: height(200.0), max_size: height(100.0), ..image.clone() }, (150.0, 100.0)),
+            // A min size in the other axis doesn't override the definite width
+            (Style { size: width(300.0), min_size: height(300.0), ..image.clone() }, (300.0, 300.0)),
+        ];
+        let parents = [
+            Style::default(),
+            Style { flex_direction: FlexDirection::Column, ..Default::default() },
+            Style { display: Display::Grid, ..Default::default() },
+        ];
+        for (style, expected) in cases {
+            for parent in parents.clone() {
+                assert_eq!(
+                    aspect_ratio_leaf_sizes(style.clone(), parent.clone()),
+                    [expected; 2],
+                    "{style:?} in {parent:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn setting_an_equal_style_leaves_the_node_clean() {
+        let tree = TaffyTree::new();
+        let style = Style { size: Size { width: length(10.0), height: auto() }, ..Default::default() };
+        let (root, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(style.clone()).unwrap();
+            (js_node_id(inner.new_with_children(Style::default(), &[leaf]).unwrap()), leaf)
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        let mut inner = tree.inner.borrow_mut();
+        assert_eq!(set_style_if_changed(&mut inner, leaf, style.clone()), Ok(false));
+        assert!(!inner.dirty(leaf).unwrap());
+        assert!(!inner.has_dirty());
+
+        let changed = Style { flex_grow: 1.0, ..style };
+        assert_eq!(set_style_if_changed(&mut inner, leaf, changed.clone()), Ok(true));
+        assert!(inner.dirty(leaf).unwrap());
+        assert!(inner.dirty(node_from_js_id(root)).unwrap());
+        assert_eq!(inner.style(leaf).unwrap(), &changed);
+    }
+}
@@ -0,0 +1,301 @@
+//! A quadtree of the boxes of a laid out tree that also indexes their sizes, for culling the nodes that are off
+//! screen or too small to see when rendering a large tree at any zoom level
+use crate::hit_index::{hit_areas, Bounds, HitArea};
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Deserialize;
+use taffy::Point;
+use wasm_bindgen::prelude::*;
+
+/// The largest number of levels a [`LodIndex`] can have
+const MAX_LEVELS: u32 = 16;
+
+/// The part of the layout shown on screen, and its zoom, passed to `lod_query`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LodViewport {
+    /// The left edge of the visible area, in layout pixels
+    x: f32,
+    /// The top edge of the visible area, in layout pixels
+    y: f32,
+    /// The width of the visible area, in layout pixels
+    width: f32,
+    /// The height of the visible area, in layout pixels
+    height: f32,
+    /// The number of screen pixels per layout pixel, e.g. 2 when zoomed in to 200%
+    scale: f32,
+}
+
+/// Returns whether `a` and `b` share any point
+fn overlaps(a: &Bounds, b: &Bounds) -> bool {
+    a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y
+}
+
+/// Returns whether every point of `inner` is within `outer`
+fn encloses(outer: &Bounds, inner: &Bounds) -> bool {
+    outer.min.x <= inner.min.x && inner.max.x <= outer.max.x && outer.min.y <= inner.min.y && inner.max.y <= outer.max.y
+}
+
+/// The length of the longer side of a box, which decides whether it is large enough to be drawn
+fn extent(bounds: &Bounds) -> f32 {
+    (bounds.max.x - bounds.min.x).max(bounds.max.y - bounds.min.y)
+}
+
+/// A square of the quadtree, holding the boxes that fit in it but in none of its quadrants
+#[derive(Debug)]
+struct Cell {
+    bounds: Bounds,
+    /// The indices in [`LodIndex::cells`] of the quadrants of the cell, once a box has been put in one of them
+    quadrants: Option<[usize; 4]>,
+    /// The boxes held by the cell itself
+    areas: Vec<HitArea>,
+    /// The longest side of any box held by the cell or its quadrants, so that a cell whose boxes are all too small to
+    /// draw is skipped without visiting them
+    largest: f32,
+}
+
+impl Cell {
+    fn new(bounds: Bounds) -> Self {
+        Self { bounds, quadrants: None, areas: Vec::new(), largest: 0.0 }
+    }
+}
+
+/// A quadtree of the boxes of the nodes of a laid out subtree, built by `build_lod_index`
+///
+/// Boxes are held by the smallest cell that encloses them, so the large boxes sit in the coarse levels near the root
+/// and the small ones in the fine levels, and a query at a low zoom level stops at the coarse levels.
+#[derive(Debug)]
+pub(crate) struct LodIndex {
+    /// The cells of the quadtree, the first of which is the root, if any box was indexed
+    cells: Vec<Cell>,
+}
+
+impl LodIndex {
+    /// Builds an index of `areas` with at most `levels` levels of cells
+    fn new(areas: Vec<HitArea>, levels: u32) -> Self {
+        let Some(root_bounds) = areas.iter().map(|area| area.bounds).reduce(Bounds::union) else {
+            return Self { cells: Vec::new() };
+        };
+        let mut index = Self { cells: vec![Cell::new(root_bounds)] };
+        for area in areas {
+            index.insert(area, levels);
+        }
+        index
+    }
+
+    /// Puts `area` in the smallest cell that encloses it, splitting cells into quadrants down to `levels` levels
+    fn insert(&mut self, area: HitArea, levels: u32) {
+        let area_extent = extent(&area.bounds);
+        let mut cell = 0;
+        for _ in 1..levels {
+            self.cells[cell].largest = self.cells[cell].largest.max(area_extent);
+            let quadrants = match self.cells[cell].quadrants {
+                Some(quadrants) => quadrants,
+                None => self.split(cell),
+            };
+            match quadrants.into_iter().find(|&quadrant| encloses(&self.cells[quadrant].bounds, &area.bounds)) {
+                Some(quadrant) => cell = quadrant,
+                None => break,
+            }
+        }
+        self.cells[cell].largest = self.cells[cell].largest.max(area_extent);
+        self.cells[cell].areas.push(area);
+    }
+
+    /// Creates the quadrants of `cell`, returning their indices
+    fn split(&mut self, cell: usize) -> [usize; 4] {
+        let Bounds { min, max } = self.cells[cell].bounds;
+        let mid = Point { x: (min.x + max.x) / 2.0, y: (min.y + max.y) / 2.0 };
+        let first = self.cells.len();
+        for (x, y) in [(min.x..mid.x, min.y..mid.y), (mid.x..max.x, min.y..mid.y), (min.x..mid.x, mid.y..max.y)]
+            .into_iter()
+            .chain([(mid.x..max.x, mid.y..max.y)])
+        {
+            let bounds = Bounds { min: Point { x: x.start, y: y.start }, max: Point { x: x.end, y: y.end } };
+            self.cells.push(Cell::new(bounds));
+        }
+        let quadrants = [first, first + 1, first + 2, first + 3];
+        self.cells[cell].quadrants = Some(quadrants);
+        quadrants
+    }
+
+    /// Returns the ids of the nodes whose box overlaps `viewport` and is longer than `min_size` screen pixels along
+    /// its longer side, in paint order
+    fn query(&self, viewport: LodViewport, min_size: f32) -> Vec<u32> {
+        let visible = Bounds {
+            min: Point { x: viewport.x, y: viewport.y },
+            max: Point { x: viewport.x + viewport.width, y: viewport.y + viewport.height },
+        };
+        let min_extent = min_size / viewport.scale;
+        let mut found: Vec<&HitArea> = Vec::new();
+        let mut stack: Vec<usize> = if self.cells.is_empty() { Vec::new() } else { vec![0] };
+        while let Some(cell) = stack.pop() {
+            let cell = &self.cells[cell];
+            if cell.largest <= min_extent || !overlaps(&cell.bounds, &visible) {
+                continue;
+            }
+            found.extend(
+                cell.areas.iter().filter(|area| extent(&area.bounds) > min_extent && overlaps(&area.bounds, &visible)),
+            );
+            stack.extend(cell.quadrants.into_iter().flatten());
+        }
+        found.sort_unstable_by_key(|area| area.paint_position);
+        found.into_iter().map(|area| area.node).collect()
+    }
+}
+
+impl TaffyTree {
+    /// Builds a level-of-detail index of the subtree rooted at `root` with `levels` levels, returning its handle
+    fn build_lod_index_handle(&self, root: u32, levels: u32) -> Result<u32, TaffyError> {
+        if !(1..=MAX_LEVELS).contains(&levels) {
+            return Err(TaffyError::InvalidArgument(format!("levels must be from 1 to {MAX_LEVELS}, got {levels}")));
+        }
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let index = LodIndex::new(hit_areas(&tree, root)?, levels);
+        let handle = self.next_lod_index.get();
+        self.next_lod_index.set(handle.wrapping_add(1));
+        self.lod_indices.borrow_mut().insert(handle, index);
+        Ok(handle)
+    }
+
+    /// Returns the ids of the nodes in the level-of-detail index `handle` that are visible in `viewport` and larger
+    /// than `min_size` on screen, in paint order
+    fn query_lod_index(&self, handle: u32, viewport: LodViewport, min_size: f32) -> Result<Vec<u32>, TaffyError> {
+        if !(viewport.scale.is_finite() && viewport.scale > 0.0) {
+            return Err(TaffyError::InvalidArgument(format!(
+                "viewport.scale must be a positive number, got {}",
+                viewport.scale
+            )));
+        }
+        let indices = self.lod_indices.borrow();
+        let index = indices.get(&handle).ok_or_else(|| {
+            TaffyError::InvalidArgument(format!(
+                "{handle} is not the handle of a level-of-detail index built since the last layout; call \
+                 build_lod_index again"
+            ))
+        })?;
+        Ok(index.query(viewport, min_size))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Builds a quadtree of the boxes of the subtree rooted at `root`, as last laid out, returning a handle to pass
+    /// to `lod_query`
+    ///
+    /// This is meant for renderers of trees too large to draw in full: each frame, `lod_query` returns only the
+    /// nodes that are both in view and large enough to see at the current zoom, without walking the tree. The
+    /// quadtree divides the area covered by the subtree into quadrants, `levels` times over (from 1, a single cell,
+    /// to 16), and keeps each box in the smallest cell that encloses it, along with the size of the largest box
+    /// of each cell, so that zoomed out, the cells holding only small boxes are skipped as a whole. More levels make
+    /// queries of small viewports faster, at the cost of memory; 8 is a good start for tens of thousands of nodes.
+    ///
+    /// The boxes are the absolute border boxes of the nodes, in the same coordinates as `layout_buffer`, clipped as
+    /// by `node_at_point`. As with `build_hit_index`, the index is a snapshot of the current layout, discarded by
+    /// the next call that computes a layout, after which its handle is no longer valid. Handles are never reused.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `levels` is not from 1 to 16.
+    #[wasm_bindgen]
+    pub fn build_lod_index(&self, root: u32, levels: u32) -> Result<u32, TaffyError> {
+        self.build_lod_index_handle(root, levels)
+    }
+
+    /// Returns the ids of the nodes in the index `handle` worth drawing in `viewport`, as a `Uint32Array` in paint
+    /// order
+    ///
+    /// `viewport` is `{ x, y, width, height, scale }`: the visible area in layout pixels, and the number of screen
+    /// pixels per layout pixel. A node is returned if its box overlaps the visible area and the longer side of its
+    /// box is more than `min_size` screen pixels long once scaled, so that zoomed out, small nodes are left out and
+    /// can e.g. be drawn as part of their parent, and zoomed in, they are returned once they are large enough. The
+    /// nodes are in paint order, parents before their children, so drawing them in order draws each on top of the
+    /// ones it covers.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `handle` was not returned by `build_lod_index`, or if a layout has been
+    /// computed since, or if `viewport` is not a valid viewport with a positive `scale`.
+    #[wasm_bindgen]
+    pub fn lod_query(&self, handle: u32, viewport: JsValue, min_size: f32) -> Result<Vec<u32>, TaffyError> {
+        let viewport = serde_wasm_bindgen::from_value(viewport)
+            .map_err(|e| TaffyError::InvalidArgument(format!("viewport: {e}")))?;
+        self.query_lod_index(handle, viewport, min_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use taffy::prelude::{auto, length, Position, Rect, Size, Style, TaffyMaxContent};
+
+    fn absolute(left: f32, top: f32, width: f32, height: f32) -> Style {
+        Style {
+            position: Position::Absolute,
+            inset: Rect { left: length(left), right: auto(), top: length(top), bottom: auto() },
+            size: Size { width: length(width), height: length(height) },
+            ..Default::default()
+        }
+    }
+
+    /// Builds a 1000x1000 root holding a 400x400 panel, and a grid of 2x2 dots every 50px
+    fn map_tree() -> (TaffyTree, u32, u32, Vec<u32>) {
+        let tree = TaffyTree::new();
+        let (root, panel, dots) = {
+            let mut inner = tree.inner.borrow_mut();
+            let panel = inner.new_leaf(absolute(100.0, 100.0, 400.0, 400.0)).unwrap();
+            let dots: Vec<_> = (0..400)
+                .map(|i| inner.new_leaf(absolute((i % 20 * 50) as f32, (i / 20 * 50) as f32, 2.0, 2.0)).unwrap())
+                .collect();
+            let root = Style { size: length(1000.0), ..Default::default() };
+            let root = inner.new_with_children(root, &[&[panel][..], &dots].concat()).unwrap();
+            (js_node_id(root), js_node_id(panel), dots.into_iter().map(js_node_id).collect())
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        (tree, root, panel, dots)
+    }
+
+    fn viewport(x: f32, y: f32, width: f32, height: f32, scale: f32) -> LodViewport {
+        LodViewport { x, y, width, height, scale }
+    }
+
+    #[test]
+    fn small_nodes_are_left_out_until_zoomed_in() {
+        let (tree, root, panel, dots) = map_tree();
+        let handle = tree.build_lod_index(root, 6).unwrap();
+
+        // Zoomed out to 10%, the dots are 0.2px wide on screen, so only the root and panel are worth drawing
+        let zoomed_out = viewport(0.0, 0.0, 1000.0, 1000.0, 0.1);
+        assert_eq!(tree.query_lod_index(handle, zoomed_out, 1.0), Ok(vec![root, panel]));
+
+        // Zoomed in to 1000% on the top left corner, the dots in view are 20px wide, and those out of view are culled
+        let zoomed_in = viewport(0.0, 0.0, 120.0, 60.0, 10.0);
+        let expected = [root].into_iter().chain([dots[0], dots[1], dots[2], dots[20], dots[21], dots[22]]).collect();
+        assert_eq!(tree.query_lod_index(handle, zoomed_in, 1.0), Ok(expected));
+    }
+
+    #[test]
+    fn queries_match_a_scan_of_every_box() {
+        let (tree, root, _, _) = map_tree();
+        let areas = hit_areas(&tree.inner.borrow(), node_from_js_id(root)).unwrap();
+        for levels in [1, 4, 9] {
+            let handle = tree.build_lod_index(root, levels).unwrap();
+            for (i, scale) in (0..30).zip([0.05, 0.5, 1.0, 4.0].into_iter().cycle()) {
+                let view = viewport((i * 97 % 900) as f32, (i * 61 % 900) as f32, 150.0 + i as f32 * 10.0, 90.0, scale);
+                let visible = Bounds {
+                    min: Point { x: view.x, y: view.y },
+                    max: Point { x: view.x + view.width, y: view.y + view.height },
+                };
+                let expected: Vec<u32> = areas
+                    .iter()
+                    .filter(|area| extent(&area.bounds) * scale > 4.0 && overlaps(&area.bounds, &visible))
+                    .map(|area| area.node)
+                    .collect();
+                assert_eq!(tree.query_lod_index(handle, view, 4.0), Ok(expected), "levels {levels}, {view:?}");
+            }
+        }
+
+        assert!(matches!(tree.build_lod_index(root, 0), Err(TaffyError::InvalidArgument(_))));
+        let handle = tree.build_lod_index(root, 4).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        let stale = tree.query_lod_index(handle, viewport(0.0, 0.0, 10.0, 10.0, 1.0), 0.0);
+        assert!(matches!(stale, Err(TaffyError::InvalidArgument(_))));
+    }
+}
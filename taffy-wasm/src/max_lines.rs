@@ -0,0 +1,175 @@
+//! Clamping measured text to a maximum number of lines, e.g. to truncate it with an ellipsis
+use crate::node_ids::js_node_id;
+use crate::{call_js_measure, resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, Point};
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Measures `node` with `measure`, passing it the maximum number of lines of the node, and records whether
+    /// `measure` reported its content as truncated to that many lines
+    pub(crate) fn measure_with_max_lines(
+        &self,
+        node: NodeId,
+        measure: impl FnOnce(Option<u32>) -> (Size<f32>, Point<Option<f32>>, bool),
+    ) -> (Size<f32>, Point<Option<f32>>) {
+        let node_id = js_node_id(node);
+        let max_lines = self.node_states.borrow().get(node_id).max_lines;
+        let (size, baselines, truncated) = measure(max_lines);
+        if max_lines.is_some() {
+            self.node_states.borrow_mut().update(node_id, |state| state.truncated = truncated);
+        }
+        (size, baselines)
+    }
+
+    /// Sizes `node` by calling the JS function `measure_func` with `this` as its receiver, passing it the
+    /// constraints for `available_space` and the maximum number of lines of the node
+    pub(crate) fn call_js_measure_for(
+        &self,
+        measure_func: &js_sys::Function,
+        this: &JsValue,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        node: NodeId,
+        node_context: Option<&mut JsContext>,
+    ) -> (Size<f32>, Point<Option<f32>>) {
+        let constraints = self.measure_constraints(available_space);
+        self.measure_with_max_lines(node, |max_lines| {
+            call_js_measure(measure_func, this, known_dimensions, constraints, max_lines, node_context)
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Limits the text of a measured leaf to `max_lines` lines, e.g. to clamp it and end it with an ellipsis
+    ///
+    /// The limit is passed to the measure function as the `maxLines` field of the `constraints` object, which is
+    /// left out for leaves without a limit. The measure function should wrap the text to at most that many lines
+    /// and return the size of those lines, along with `truncated: true` if the text didn't fit in them:
+    /// `{ width, height, truncated }`. Whether it did is reported by `is_truncated`. Taffy doesn't know how text
+    /// wraps, so the limit has no effect on a measure function that ignores it, nor on leaves sized without one,
+    /// e.g. by `set_content_size`, nor on nodes with children.
+    ///
+    /// The leaf is marked dirty if this changes its limit, so that it is measured again by the next layout.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `max_lines` is 0.
+    #[wasm_bindgen]
+    pub fn set_max_lines(&self, node_id: u32, max_lines: u32) -> Result<(), TaffyError> {
+        if max_lines == 0 {
+            return Err(TaffyError::InvalidArgument(String::from("max_lines must be at least 1")));
+        }
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let previous = self.node_states.borrow_mut().update(node_id, |state| state.max_lines.replace(max_lines));
+        if previous != Some(max_lines) {
+            tree.mark_dirty(node)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the limit set with `set_max_lines`, so that the text of the leaf is measured without one from the
+    /// next layout, and marks the leaf dirty if it had one
+    #[wasm_bindgen]
+    pub fn clear_max_lines(&self, node_id: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let previous = self.node_states.borrow_mut().update(node_id, |state| {
+            state.truncated = false;
+            state.max_lines.take()
+        });
+        if previous.is_some() {
+            tree.mark_dirty(node)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the text of a leaf limited with `set_max_lines` was truncated to fit that many lines
+    ///
+    /// This is the `truncated` flag the measure function returned the last time it measured the leaf, which is the
+    /// measurement of the last layout unless the leaf's size was served from the layout cache. It is `false` for
+    /// leaves without a limit, and until the leaf is first measured with one.
+    #[wasm_bindgen]
+    pub fn is_truncated(&self, node_id: u32) -> Result<bool, TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        Ok(self.node_states.borrow().get(node_id).truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure_budget::MeasureBudget;
+    use crate::MeasureConstraints;
+    use taffy::style::Style;
+
+    /// The width of each word of the text, and the height of each of its lines
+    const WORD_WIDTH: f32 = 50.0;
+    const LINE_HEIGHT: f32 = 20.0;
+
+    /// Wraps `words` words to the available width, clamped to `max_lines`, returning the size of the text and
+    /// whether it was truncated
+    fn wrap_text(
+        words: u32,
+        known: Size<Option<f32>>,
+        available: Size<AvailableSpace>,
+        max_lines: Option<u32>,
+    ) -> (Size<f32>, Point<Option<f32>>, bool) {
+        let width = known.width.or(available.width.into_option()).unwrap_or(f32::INFINITY);
+        let words_per_line = ((width / WORD_WIDTH).floor() as u32).clamp(1, words);
+        let lines = words.div_ceil(words_per_line);
+        let shown_lines = max_lines.map_or(lines, |max_lines| lines.min(max_lines));
+        let size = Size { width: words_per_line as f32 * WORD_WIDTH, height: shown_lines as f32 * LINE_HEIGHT };
+        (size, Point::NONE, shown_lines < lines)
+    }
+
+    #[test]
+    fn long_text_is_clamped_to_two_lines() {
+        let tree = TaffyTree::new();
+        let (column, text) = {
+            let mut inner = tree.inner.borrow_mut();
+            let text = inner.new_leaf(Style::default()).unwrap();
+            let column = Style {
+                flex_direction: FlexDirection::Column,
+                size: Size { width: length(200.0), height: auto() },
+                ..Default::default()
+            };
+            (js_node_id(inner.new_with_children(column, &[text]).unwrap()), js_node_id(text))
+        };
+        let layout = || {
+            let measure = |known, available, node, _: Option<&mut JsContext>| {
+                tree.measure_with_max_lines(node, |max_lines| wrap_text(10, known, available, max_lines))
+            };
+            tree.compute_layout_with_budget(column, Size::MAX_CONTENT, MeasureBudget::default(), || 0.0, measure)
+                .unwrap();
+        };
+
+        // Four 50px words fit on each 200px line, so the ten words take three lines
+        layout();
+        assert_eq!(tree.layout_height(text), Ok(60.0));
+        assert_eq!(tree.is_truncated(text), Ok(false));
+
+        tree.set_max_lines(text, 2).unwrap();
+        assert_eq!(tree.dirty(column), Ok(true));
+        layout();
+        assert_eq!(tree.layout_height(text), Ok(40.0));
+        assert_eq!(tree.layout_height(column), Ok(40.0));
+        assert_eq!(tree.is_truncated(text), Ok(true));
+
+        tree.clear_max_lines(text).unwrap();
+        layout();
+        assert_eq!((tree.layout_height(text), tree.is_truncated(text)), (Ok(60.0), Ok(false)));
+        assert!(matches!(tree.set_max_lines(text, 0), Err(TaffyError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn max_lines_is_passed_in_the_constraints() {
+        let width = AvailableSpace::Definite(200.0);
+        let clamped = MeasureConstraints { width, height: AvailableSpace::MaxContent, max_lines: Some(2) };
+        let expected = serde_json::json!({ "width": { "Definite": 200.0 }, "height": "MaxContent", "maxLines": 2 });
+        assert_eq!(serde_json::to_value(clamped).unwrap(), expected);
+
+        let unclamped = MeasureConstraints { width, height: AvailableSpace::MaxContent, max_lines: None };
+        let expected = serde_json::json!({ "width": { "Definite": 200.0 }, "height": "MaxContent" });
+        assert_eq!(serde_json::to_value(unclamped).unwrap(), expected);
+    }
+}
@@ -0,0 +1,255 @@
+//! A limit on the work done by measure functions during a single layout pass
+//!
+//! A measure function that is very slow, or that keeps returning sizes which cause its node to be measured again
+//! with new constraints, can otherwise block the page for as long as the layout takes. Once the budget is exceeded
+//! the remaining measurements are skipped and the layout fails with a `MEASURE_BUDGET_EXCEEDED` error.
+use crate::node_ids::js_node_id;
+use crate::{clear_subtree_cache, resolve_node, JsContext, TaffyError, TaffyTree};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use taffy::{prelude::*, Point};
+use wasm_bindgen::prelude::*;
+
+/// The number of measure calls allowed per layout pass when no budget is given
+///
+/// Each leaf is usually measured a handful of times per pass, so this only trips for trees with hundreds of
+/// thousands of measured leaves, or for a measure function whose results keep invalidating its node.
+pub const DEFAULT_MAX_MEASURE_CALLS: u32 = 1_000_000;
+
+#[wasm_bindgen(typescript_custom_section)]
+const MEASURE_BUDGET_TS: &'static str = r#"
+/** Limits on the work done by the measure function during one `compute_layout_with_measure` call */
+export interface MeasureBudget {
+  /** The maximum number of measure calls. Defaults to 1,000,000. */
+  maxMeasureCalls?: number;
+  /** The maximum duration of the layout in milliseconds, checked before each measure call. Defaults to no limit. */
+  timeoutMs?: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A `MeasureBudget` object passed from JavaScript
+    #[wasm_bindgen(typescript_type = "MeasureBudget")]
+    pub type JsMeasureBudget;
+}
+
+/// Limits on the measure calls of one layout pass, decoded from `{ maxMeasureCalls?: number, timeoutMs?: number }`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MeasureBudget {
+    /// The maximum number of times the measure function may be called in one layout pass
+    pub max_measure_calls: u32,
+    /// The maximum time in milliseconds the layout pass may take, measured whenever a node is about to be measured
+    pub timeout_ms: Option<f64>,
+}
+
+impl Default for MeasureBudget {
+    fn default() -> Self {
+        Self { max_measure_calls: DEFAULT_MAX_MEASURE_CALLS, timeout_ms: None }
+    }
+}
+
+/// Tracks the measure calls made during a layout pass against a [`MeasureBudget`]
+struct BudgetTracker<Clock: Fn() -> f64> {
+    /// The limits being enforced
+    budget: MeasureBudget,
+    /// Returns the current time in milliseconds
+    clock: Clock,
+    /// The time at which the layout pass started
+    start_ms: f64,
+    /// The number of measure calls made so far
+    measure_calls: u32,
+    /// The error to report once the budget has been exceeded
+    exceeded: Option<TaffyError>,
+}
+
+impl<Clock: Fn() -> f64> BudgetTracker<Clock> {
+    fn new(budget: MeasureBudget, clock: Clock) -> Self {
+        let start_ms = clock();
+        Self { budget, clock, start_ms, measure_calls: 0, exceeded: None }
+    }
+
+    /// Returns whether `node` may be measured, recording it as the culprit if the budget has just run out
+    fn admit(&mut self, node: NodeId) -> bool {
+        if self.exceeded.is_some() {
+            return false;
+        }
+
+        let elapsed_ms = if self.budget.timeout_ms.is_some() { (self.clock)() - self.start_ms } else { 0.0 };
+        let out_of_calls = self.measure_calls >= self.budget.max_measure_calls;
+        let out_of_time = self.budget.timeout_ms.is_some_and(|timeout_ms| elapsed_ms > timeout_ms);
+        if out_of_calls || out_of_time {
+            self.exceeded = Some(TaffyError::MeasureBudgetExceeded {
+                node: js_node_id(node),
+                measure_calls: self.measure_calls,
+                elapsed_ms,
+            });
+            return false;
+        }
+
+        self.measure_calls += 1;
+        true
+    }
+}
+
+impl TaffyTree {
+    /// Lays out the tree rooted at `node_id`, sizing leaves with `measure` until `budget` runs out
+    ///
+    /// If the budget is exceeded the remaining leaves are given a zero size without calling `measure`, and the
+    /// cached layouts of the subtree (frozen subtrees included) are discarded so that the incorrect results are not
+    /// reused by the next pass.
+    pub(crate) fn compute_layout_with_budget(
+        &self,
+        node_id: u32,
+        available_space: Size<AvailableSpace>,
+        budget: MeasureBudget,
+        clock: impl Fn() -> f64,
+        mut measure: impl FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut JsContext>,
+        ) -> (Size<f32>, Point<Option<f32>>),
+    ) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        self.check_layout_depth(&tree, node)?;
+        self.keep_previous_layouts(&tree, node)?;
+
+        let mut tracker = BudgetTracker::new(budget, clock);
+        let mut measured_nodes = BTreeMap::new();
+        let profiling = self.is_profiling();
+        let mut measure_ms = BTreeMap::new();
+        tree.compute_layout_with_measure_and_baselines(
+            node,
+            available_space,
+            |known_dimensions, available_space, measured_node, node_context, _style| {
+                if let Some(size) = self.fixed_content_size(known_dimensions, available_space, measured_node) {
+                    (size, Point::NONE)
+                } else if tracker.admit(measured_node) {
+                    let started_ms = profiling.then(|| (tracker.clock)());
+                    let (size, baselines) = measure(known_dimensions, available_space, measured_node, node_context);
+                    if let Some(started_ms) = started_ms {
+                        *measure_ms.entry(js_node_id(measured_node)).or_insert(0.0) += (tracker.clock)() - started_ms;
+                    }
+                    measured_nodes.insert(js_node_id(measured_node), size);
+                    (size, baselines)
+                } else {
+                    (Size::ZERO, Point::NONE)
+                }
+            },
+        )?;
+        let duration_ms = (tracker.clock)() - tracker.start_ms;
+        self.record_compute_stats(&tree, node, tracker.measure_calls, measured_nodes, duration_ms);
+        self.record_node_timings(&tree, node, duration_ms, &measure_ms);
+        self.record_layout(node_id, available_space);
+
+        match tracker.exceeded {
+            Some(error) => {
+                clear_subtree_cache(&mut tree, node, |_| false)?;
+                Err(error)
+            }
+            None => self.validate_layout(&tree, node),
+        }
+    }
+}
+
+/// Decodes the optional `budget` argument of `compute_layout_with_measure`
+pub(crate) fn decode_budget(budget: Option<JsMeasureBudget>) -> Result<MeasureBudget, TaffyError> {
+    let Some(budget) = budget else {
+        return Ok(MeasureBudget::default());
+    };
+    serde_wasm_bindgen::from_value(budget.into()).map_err(|e| TaffyError::InvalidArgument(format!("budget: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaffyTree;
+    use std::cell::Cell;
+    use taffy::style::Style;
+
+    /// Creates a row of `count` measured leaves, returning the tree, the root and the leaves
+    fn measured_row(count: usize) -> (TaffyTree, u32, Vec<u32>) {
+        let tree = TaffyTree::new();
+        let (root, leaves) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaves: Vec<_> = (0..count).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            let root = inner.new_with_children(Style::default(), &leaves).unwrap();
+            (root, leaves)
+        };
+        (tree, js_node_id(root), leaves.into_iter().map(js_node_id).collect())
+    }
+
+    #[test]
+    fn well_behaved_measure_stays_within_default_budget() {
+        let (tree, root, leaves) = measured_row(3);
+        let result = tree.compute_layout_with_budget(
+            root,
+            Size::MAX_CONTENT,
+            MeasureBudget::default(),
+            || 0.0,
+            |_, _, _, _| (Size { width: 10.0, height: 10.0 }, Point::NONE),
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(tree.layout_left(leaves[2]), Ok(20.0));
+    }
+
+    #[test]
+    fn exceeding_measure_calls_names_the_node() {
+        let (tree, root, leaves) = measured_row(3);
+        let budget = MeasureBudget { max_measure_calls: 2, timeout_ms: None };
+        // A pathological measure function whose result changes on every call
+        let calls = Cell::new(0);
+        let result = tree.compute_layout_with_budget(
+            root,
+            Size::MAX_CONTENT,
+            budget,
+            || 0.0,
+            |known, _, _, _| {
+                calls.set(calls.get() + 1);
+                let size = calls.get() as f32 * 10.0;
+                (known.unwrap_or(Size { width: size, height: size }), Point::NONE)
+            },
+        );
+
+        match result {
+            Err(TaffyError::MeasureBudgetExceeded { node, measure_calls, .. }) => {
+                assert!(leaves.contains(&node));
+                assert_eq!(measure_calls, 2);
+            }
+            other => panic!("expected the budget to be exceeded, got {other:?}"),
+        }
+        assert_eq!(calls.get(), 2);
+
+        // The results of the aborted pass are not cached
+        assert_eq!(tree.dirty(root), Ok(true));
+    }
+
+    #[test]
+    fn exceeding_timeout_aborts_layout() {
+        let (tree, root, leaves) = measured_row(3);
+        let budget = MeasureBudget { timeout_ms: Some(50.0), ..Default::default() };
+        // Each measurement takes 40ms on this fake clock, so the third leaf is never measured
+        let now = Cell::new(0.0);
+        let result = tree.compute_layout_with_budget(
+            root,
+            Size::MAX_CONTENT,
+            budget,
+            || now.get(),
+            |_, _, _, _| {
+                now.set(now.get() + 40.0);
+                (Size { width: 10.0, height: 10.0 }, Point::NONE)
+            },
+        );
+
+        match result {
+            Err(TaffyError::MeasureBudgetExceeded { node, elapsed_ms, .. }) => {
+                assert!(leaves.contains(&node));
+                assert!(elapsed_ms > 50.0);
+            }
+            other => panic!("expected the budget to be exceeded, got {other:?}"),
+        }
+    }
+}
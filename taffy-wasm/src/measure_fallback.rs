@@ -0,0 +1,92 @@
+//! Substituting a fallback size for the intrinsic constraints passed to JS measure functions
+use crate::{TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns the constraints to pass to a JS measure function for `available_space`, replacing its
+    /// `MinContent` and `MaxContent` axes with the fallback size set by `set_measure_fallback_size`, if any
+    pub(crate) fn measure_constraints(&self, available_space: Size<AvailableSpace>) -> Size<AvailableSpace> {
+        let Some(fallback) = self.measure_fallback_size.get() else {
+            return available_space;
+        };
+        let substitute = |space: AvailableSpace, fallback: f32| match space {
+            AvailableSpace::Definite(_) => space,
+            AvailableSpace::MinContent | AvailableSpace::MaxContent => AvailableSpace::Definite(fallback),
+        };
+        Size {
+            width: substitute(available_space.width, fallback.width),
+            height: substitute(available_space.height, fallback.height),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Makes JS measure functions receive `width` and `height` pixels, e.g. the viewport size, in place of
+    /// intrinsic constraints
+    ///
+    /// By default the `constraints` passed to a measure function hold `"MinContent"` or `"MaxContent"` in the axes
+    /// where a leaf is being sized to its content. Once a fallback size is set, those axes are passed as
+    /// `{ Definite: width }` and `{ Definite: height }` instead, so every axis of the constraints is a number. The
+    /// layout algorithms still size the leaf intrinsically; only the constraints seen by the measure function
+    /// change, so a leaf that fills its constraints grows to the fallback size. Definite constraints and known
+    /// dimensions are passed unchanged. The fallback applies to every method taking a measure function, and stays
+    /// set until `clear_measure_fallback_size` is called. Cached layouts are not discarded: call `mark_dirty` on
+    /// the measured leaves to measure them again with the new constraints.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `width` or `height` is negative or not finite.
+    #[wasm_bindgen]
+    pub fn set_measure_fallback_size(&self, width: f32, height: f32) -> Result<(), TaffyError> {
+        for (name, pixels) in [("width", width), ("height", height)] {
+            if !pixels.is_finite() || pixels < 0.0 {
+                return Err(TaffyError::InvalidArgument(format!(
+                    "{name} must be a non-negative number of pixels, got {pixels}"
+                )));
+            }
+        }
+        self.measure_fallback_size.set(Some(Size { width, height }));
+        Ok(())
+    }
+
+    /// Removes the fallback size set by `set_measure_fallback_size`, so that measure functions receive intrinsic
+    /// constraints as `"MinContent"` and `"MaxContent"` again
+    #[wasm_bindgen]
+    pub fn clear_measure_fallback_size(&self) {
+        self.measure_fallback_size.set(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_replaces_intrinsic_constraints() {
+        let tree = TaffyTree::new();
+        let intrinsic = Size { width: AvailableSpace::MinContent, height: AvailableSpace::MaxContent };
+        assert_eq!(tree.measure_constraints(intrinsic), intrinsic);
+
+        tree.set_measure_fallback_size(1024.0, 768.0).unwrap();
+        assert_eq!(
+            tree.measure_constraints(intrinsic),
+            Size { width: AvailableSpace::Definite(1024.0), height: AvailableSpace::Definite(768.0) }
+        );
+        let mixed = Size { width: AvailableSpace::Definite(300.0), height: AvailableSpace::MinContent };
+        assert_eq!(
+            tree.measure_constraints(mixed),
+            Size { width: AvailableSpace::Definite(300.0), height: AvailableSpace::Definite(768.0) }
+        );
+
+        tree.clear_measure_fallback_size();
+        assert_eq!(tree.measure_constraints(intrinsic), intrinsic);
+    }
+
+    #[test]
+    fn invalid_fallback_size_is_rejected() {
+        let tree = TaffyTree::new();
+        assert!(matches!(tree.set_measure_fallback_size(-1.0, 0.0), Err(TaffyError::InvalidArgument(_))));
+        assert!(matches!(tree.set_measure_fallback_size(0.0, f32::NAN), Err(TaffyError::InvalidArgument(_))));
+        assert_eq!(tree.measure_fallback_size.get(), None);
+    }
+}
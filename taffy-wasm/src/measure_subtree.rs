@@ -0,0 +1,299 @@
+//! Measuring a subtree without committing its layout, e.g. to size a tooltip preview or to ask for the intrinsic
+//! size of a node from another layout engine
+use crate::available_space::decode_available_space;
+use crate::node_ids::node_from_js_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, Point};
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `root` on a scratch copy of the tree, returning the size of `root`
+    fn measure_subtree_size(
+        &self,
+        root: u32,
+        available_space: Size<AvailableSpace>,
+        mut measure: impl FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut JsContext>,
+        ) -> (Size<f32>, Point<Option<f32>>),
+    ) -> Result<Size<f32>, TaffyError> {
+        let mut scratch = {
+            let tree = self.tree()?;
+            let node = resolve_node(&tree, root)?;
+            self.check_layout_depth(&tree, node)?;
+            tree.clone()
+        };
+        // A node taken out of flow is measured with the display it had in flow
+        let out_of_flow_display = self.node_states.borrow().get(root).out_of_flow_display;
+        if let Some(display) = out_of_flow_display {
+            let node = node_from_js_id(root);
+            if scratch.style(node)?.display == Display::None {
+                let style = Style { display, ..scratch.style(node)?.clone() };
+                scratch.set_style(node, style)?;
+            }
+        }
+        let root = node_from_js_id(root);
+        scratch.compute_layout_with_measure_and_baselines(
+            root,
+            available_space,
+            |known_dimensions, available_space, node, node_context, _style| match self.fixed_content_size(
+                known_dimensions,
+                available_space,
+                node,
+            ) {
+                Some(size) => (size, Point::NONE),
+                None => measure(known_dimensions, available_space, node, node_context),
+            },
+        )?;
+        Ok(scratch.layout(root)?.size)
+    }
+
+    /// Returns the min-content or max-content size of the subtree rooted at `node_id`, as named by `mode`
+    pub(crate) fn intrinsic_size(
+        &self,
+        node_id: u32,
+        mode: &str,
+        measure: impl FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut JsContext>,
+        ) -> (Size<f32>, Point<Option<f32>>),
+    ) -> Result<Size<f32>, TaffyError> {
+        let available_space = match mode {
+            "min-content" => Size::MIN_CONTENT,
+            "max-content" => Size::MAX_CONTENT,
+            mode => {
+                return Err(TaffyError::InvalidArgument(format!(
+                    "unknown intrinsic size mode {mode:?}, expected \"min-content\" or \"max-content\""
+                )))
+            }
+        };
+        self.measure_subtree_size(node_id, available_space, measure)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the size `{ width, height }` that the subtree rooted at `root` would have if it were laid out
+    /// within `available`, without changing the tree
+    ///
+    /// `available` is a `LayoutAvailableSpace`, as for `compute_layout_v2`, including its max-content limits. The
+    /// layout is computed on a copy of the tree that is discarded afterwards, so the layouts, caches and dirty flags
+    /// of every node are exactly as they were before the call, and the next `compute_layout` is unaffected.
+    /// `last_compute_stats` and `measured_leaf_sizes` are not updated either. The copy takes time proportional to the
+    /// size of the whole tree.
+    ///
+    /// If `measure_func` is given it is called to size leaves, exactly as by `compute_layout_with_measure`,
+    /// including for leaves whose size was cached by a previous layout but whose constraints differ. Without it,
+    /// leaves are sized by their styles alone. The tree can be read but not changed from within `measure_func`
+    /// while the subtree is being measured; changes made there are not reflected in the returned size.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available` is not a valid `LayoutAvailableSpace`.
+    #[wasm_bindgen]
+    pub fn measure_subtree(
+        &self,
+        root: u32,
+        available: JsValue,
+        measure_func: Option<js_sys::Function>,
+    ) -> Result<JsValue, TaffyError> {
+        let available = decode_available_space(available)?;
+        let mut measure = |known_dimensions, available_space, node, context: Option<&mut JsContext>| match &measure_func
+        {
+            Some(measure_func) => {
+                self.call_js_measure_for(measure_func, &JsValue::NULL, known_dimensions, available_space, node, context)
+            }
+            None => (Size::ZERO, Point::NONE),
+        };
+        let mut size = self.measure_subtree_size(root, available.space, &mut measure)?;
+        if let Some(space) = available.relayout_space(size) {
+            size = self.measure_subtree_size(root, space, &mut measure)?;
+        }
+        Ok(serde_wasm_bindgen::to_value(&size).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the size `{ width, height }` of a node laid out as if it were the root of the tree, ignoring its
+    /// actual parent, within `available`, without changing the tree
+    ///
+    /// This lays the node out as `measure_subtree` does, e.g. to render a dragged element as a standalone preview.
+    /// The node stays attached to its parent, and the layouts, caches and dirty flags of the real tree, including
+    /// those of the node and its descendants, are exactly as they were before the call. As for any root, the
+    /// percentage sizes, margins and paddings of the node resolve against the definite sizes of `available` rather
+    /// than against its parent, and behave like `auto` (or zero) in axes whose available space is min-content or
+    /// max-content. Its descendants resolve their percentages against the node as usual.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available` is not a valid `LayoutAvailableSpace`.
+    #[wasm_bindgen]
+    pub fn compute_isolated(
+        &self,
+        node_id: u32,
+        available: JsValue,
+        measure_func: Option<js_sys::Function>,
+    ) -> Result<JsValue, TaffyError> {
+        self.measure_subtree(node_id, available, measure_func)
+    }
+
+    /// Returns the intrinsic size `{ width, height }` of a node given its current subtree, without changing the tree
+    ///
+    /// With `mode` `"min-content"` this is the size of the node laid out under a min-content constraint in both
+    /// axes, i.e. as narrow as its content allows, and with `"max-content"` under a max-content constraint, i.e. with
+    /// no line wrapped. This is the size `measure_subtree` returns for those constraints: the node is laid out as a
+    /// root on a copy of the tree, ignoring its parent, so its own percentage sizes behave like `auto`. It lets
+    /// another layout engine that embeds taffy nodes size them as it would its own boxes. A node taken out of flow
+    /// with `set_out_of_flow` is measured with the `display` it had in flow.
+    ///
+    /// If `measure_func` is given it is called to size leaves, exactly as by `compute_layout_with_measure`. Without
+    /// it, leaves are sized by their styles, content sizes and object fits alone.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `mode` is neither `"min-content"` nor `"max-content"`.
+    #[wasm_bindgen]
+    pub fn node_intrinsic_size(
+        &self,
+        node_id: u32,
+        mode: &str,
+        measure_func: Option<js_sys::Function>,
+    ) -> Result<JsValue, TaffyError> {
+        let measure = |known_dimensions, available_space, node, context: Option<&mut JsContext>| match &measure_func {
+            Some(measure_func) => {
+                self.call_js_measure_for(measure_func, &JsValue::NULL, known_dimensions, available_space, node, context)
+            }
+            None => (Size::ZERO, Point::NONE),
+        };
+        let size = self.intrinsic_size(node_id, mode, measure)?;
+        Ok(serde_wasm_bindgen::to_value(&size).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::measure_budget::MeasureBudget;
+    use crate::node_ids::js_node_id;
+    use crate::TaffyTree;
+    use std::cell::Cell;
+    use taffy::{prelude::*, Point};
+
+    /// Measures text that is 100px wide on one line, and wraps onto 10px tall lines when narrower
+    fn measure_text(known: Size<Option<f32>>, available: Size<AvailableSpace>) -> (Size<f32>, Point<Option<f32>>) {
+        let width = known.width.unwrap_or(match available.width {
+            AvailableSpace::Definite(width) => width.min(100.0),
+            AvailableSpace::MinContent => 25.0,
+            AvailableSpace::MaxContent => 100.0,
+        });
+        (Size { width, height: (100.0 / width).ceil() * 10.0 }, Point::NONE)
+    }
+
+    #[test]
+    fn measure_subtree_leaves_the_tree_untouched() {
+        let tree = TaffyTree::new();
+        let (root, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style::default()).unwrap();
+            let column = Style {
+                flex_direction: FlexDirection::Column,
+                size: Size { width: percent(1.0), height: auto() },
+                ..Default::default()
+            };
+            let root = inner.new_with_children(column, &[leaf]).unwrap();
+            (js_node_id(root), js_node_id(leaf))
+        };
+        let calls = Cell::new(0);
+        let text = |known, available, _, _: Option<&mut crate::JsContext>| {
+            calls.set(calls.get() + 1);
+            measure_text(known, available)
+        };
+        let layout = |width| {
+            let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::MaxContent };
+            tree.compute_layout_with_budget(root, available_space, MeasureBudget::default(), || 0.0, text).unwrap();
+        };
+        layout(200.0);
+        assert_eq!(tree.layout_height(leaf), Ok(10.0));
+
+        let available_space = Size { width: AvailableSpace::Definite(50.0), height: AvailableSpace::MaxContent };
+        let size = tree.measure_subtree_size(root, available_space, text).unwrap();
+        assert_eq!(size, Size { width: 50.0, height: 20.0 });
+
+        // The committed layout and dirty state are unchanged
+        assert_eq!(tree.layout_width(root), Ok(200.0));
+        assert_eq!(tree.layout_height(leaf), Ok(10.0));
+        assert_eq!(tree.dirty(root), Ok(false));
+
+        // A real layout at the measured width recomputes rather than reusing anything from the measurement
+        let calls_before = calls.get();
+        layout(50.0);
+        assert!(calls.get() > calls_before);
+        assert_eq!(tree.layout_height(leaf), Ok(20.0));
+    }
+
+    #[test]
+    fn intrinsic_sizes_match_min_and_max_content_root_layouts() {
+        let tree = TaffyTree::new();
+        let (root, node) = {
+            let mut inner = tree.inner.borrow_mut();
+            let texts = [inner.new_leaf(Style::default()).unwrap(), inner.new_leaf(Style::default()).unwrap()];
+            let row = Style { padding: length(5.0), gap: length(10.0), ..Default::default() };
+            let node = inner.new_with_children(row, &texts).unwrap();
+            let root = inner.new_with_children(Style::default(), &[node]).unwrap();
+            (js_node_id(root), js_node_id(node))
+        };
+        let text = |known, available, _, _: Option<&mut crate::JsContext>| measure_text(known, available);
+        let intrinsic = |mode| tree.intrinsic_size(node, mode, text).unwrap();
+        let min_content = intrinsic("min-content");
+        let max_content = intrinsic("max-content");
+        assert_eq!(max_content, Size { width: 5.0 + 100.0 + 10.0 + 100.0 + 5.0, height: 5.0 + 10.0 + 5.0 });
+        assert_eq!(min_content.width, 5.0 + 25.0 + 10.0 + 25.0 + 5.0);
+
+        // Laying the node out as a root gives the same sizes, and the rest of the tree doesn't need to be laid out
+        assert_eq!(tree.dirty(root), Ok(true));
+        for (available_space, size) in [(Size::MIN_CONTENT, min_content), (Size::MAX_CONTENT, max_content)] {
+            tree.compute_layout_with_budget(node, available_space, MeasureBudget::default(), || 0.0, text).unwrap();
+            assert_eq!((tree.layout_width(node), tree.layout_height(node)), (Ok(size.width), Ok(size.height)));
+        }
+
+        let unknown = tree.intrinsic_size(node, "fit-content", text);
+        assert!(matches!(unknown, Err(crate::TaffyError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn isolated_node_resolves_percentages_against_available_space() {
+        let tree = TaffyTree::new();
+        let (root, node, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+            let style = Style { size: Size { width: percent(0.5), height: length(40.0) }, ..Default::default() };
+            let node = inner.new_with_children(style, &[leaf]).unwrap();
+            let style = Style { size: Size { width: length(400.0), height: length(100.0) }, ..Default::default() };
+            let root = inner.new_with_children(style, &[node]).unwrap();
+            (js_node_id(root), js_node_id(node), js_node_id(leaf))
+        };
+        let none = |_, _, _, _: Option<&mut crate::JsContext>| (Size::ZERO, Point::NONE);
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, MeasureBudget::default(), || 0.0, none).unwrap();
+        assert_eq!(tree.layout_width(node), Ok(200.0));
+        assert_eq!(tree.layout_width(leaf), Ok(100.0));
+
+        let available_space = Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::MaxContent };
+        let size = tree.measure_subtree_size(node, available_space, none).unwrap();
+        assert_eq!(size, Size { width: 50.0, height: 40.0 });
+
+        // The real layout of the node, its parent and its child is unchanged
+        assert_eq!(tree.layout_width(root), Ok(400.0));
+        assert_eq!(tree.layout_width(node), Ok(200.0));
+        assert_eq!((tree.layout_width(leaf), tree.layout_height(leaf)), (Ok(100.0), Ok(20.0)));
+        assert_eq!(tree.dirty(root), Ok(false));
+    }
+
+    #[test]
+    fn measure_subtree_of_dirty_tree_keeps_it_dirty() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let style = Style { size: Size { width: length(30.0), height: length(40.0) }, ..Default::default() };
+            js_node_id(inner.new_leaf(style).unwrap())
+        };
+        let size = tree.measure_subtree_size(root, Size::MAX_CONTENT, |_, _, _, _| (Size::ZERO, Point::NONE));
+        assert_eq!(size, Ok(Size { width: 30.0, height: 40.0 }));
+        assert_eq!(tree.dirty(root), Ok(true));
+        assert_eq!(tree.layout_width(root), Ok(0.0));
+    }
+}
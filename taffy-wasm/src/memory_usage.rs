@@ -0,0 +1,82 @@
+//! Estimating the memory taken by a tree, e.g. to warn before an embedded host runs out of its memory budget
+use crate::{JsContext, TaffyError, TaffyTree};
+use core::mem::size_of;
+use serde::Serialize;
+use taffy::{Cache, DetailedLayoutInfo, Layout, NodeId, Style};
+use wasm_bindgen::prelude::*;
+
+/// The approximate number of bytes each node takes in the tree: its style, its unrounded and final layouts, its
+/// layout cache and detailed layout info, its slots in the children, parents and context maps with their
+/// versions, and its entry in the children of its parent
+const BYTES_PER_NODE: usize = size_of::<Style>()
+    + 2 * size_of::<Layout>()
+    + size_of::<Cache>()
+    + size_of::<DetailedLayoutInfo>()
+    + size_of::<bool>()
+    + size_of::<Vec<NodeId>>()
+    + size_of::<Option<NodeId>>()
+    + size_of::<Option<JsContext>>()
+    + 4 * size_of::<u32>()
+    + size_of::<NodeId>();
+
+/// An estimate of the memory taken by a tree, returned by `memory_usage`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryUsage {
+    /// The number of nodes in the tree
+    pub node_count: u32,
+    /// The approximate number of bytes taken by the nodes
+    pub approx_bytes: u32,
+}
+
+impl TaffyTree {
+    /// Estimates the memory taken by the nodes of the tree
+    fn estimate_memory_usage(&self) -> Result<MemoryUsage, TaffyError> {
+        let slots = self.tree()?.total_node_count();
+        let node_count = slots - self.retired.borrow().len();
+        Ok(MemoryUsage { node_count: node_count as u32, approx_bytes: (slots * BYTES_PER_NODE) as u32 })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns an estimate of the memory taken by the tree as `{ nodeCount, approxBytes }`, e.g. to raise an alarm
+    /// before a memory-constrained host runs out
+    ///
+    /// `approxBytes` is the number of slots in the tree times a fixed per-node size covering the style, the
+    /// layouts, the layout cache and the bookkeeping of the slot maps nodes are stored in, so it grows with every
+    /// node added. It is an estimate rather than a measurement: memory allocated by individual nodes, such as grid
+    /// templates and the tracks recorded by the last layout of a grid, and the spare capacity of the slot maps is
+    /// not counted, and neither are the maps kept by this wrapper, such as content sizes and tags. The placeholders
+    /// that keep the ids of removed nodes reserved under `stableIds` take memory like any other node, so they are
+    /// counted in `approxBytes` but not in `nodeCount`.
+    #[wasm_bindgen]
+    pub fn memory_usage(&self) -> Result<JsValue, TaffyError> {
+        let usage = self.estimate_memory_usage()?;
+        Ok(serde_wasm_bindgen::to_value(&usage).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approx_bytes_grows_with_every_node_added() {
+        let tree = TaffyTree::new();
+        let empty = tree.estimate_memory_usage().unwrap();
+        assert_eq!(empty, MemoryUsage { node_count: 0, approx_bytes: 0 });
+
+        let root = tree.inner.borrow_mut().new_leaf(Style::default()).unwrap();
+        let mut previous = tree.estimate_memory_usage().unwrap();
+        assert_eq!(previous.node_count, 1);
+        for added in 2..=20 {
+            let child = tree.inner.borrow_mut().new_leaf(Style::default()).unwrap();
+            tree.inner.borrow_mut().add_child(root, child).unwrap();
+            let usage = tree.estimate_memory_usage().unwrap();
+            assert_eq!(usage.node_count, added);
+            assert!(usage.approx_bytes > previous.approx_bytes, "{usage:?} is no larger than {previous:?}");
+            previous = usage;
+        }
+    }
+}
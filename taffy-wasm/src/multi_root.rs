@@ -0,0 +1,120 @@
+//! Laying out several independent roots of one tree in a single call, e.g. one per window or panel
+use crate::{compute_stats, resolve_node, TaffyError, TaffyTree};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const LAYOUT_REQUEST_TS: &'static str = r#"
+/** A root to lay out with `compute_layouts`, within `width` by `height` pixels */
+export interface LayoutRequest {
+  root: number;
+  width: number;
+  height: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// An array of `LayoutRequest` objects passed from JavaScript
+    #[wasm_bindgen(typescript_type = "LayoutRequest[]")]
+    pub type JsLayoutRequests;
+}
+
+/// A root to lay out and the definite space available to it, decoded from a `LayoutRequest`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct LayoutRequest {
+    /// The id of the node to lay out as a root
+    root: u32,
+    /// The space available horizontally, in pixels
+    width: f32,
+    /// The space available vertically, in pixels
+    height: f32,
+}
+
+impl TaffyTree {
+    /// Lays out the root of each request in order, returning the outcome of each
+    fn compute_requests(&self, requests: &[LayoutRequest]) -> Result<Vec<Result<(), TaffyError>>, TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let outcomes = requests
+            .iter()
+            .map(|request| {
+                let node = resolve_node(&tree, request.root)?;
+                self.check_layout_depth(&tree, node)?;
+                self.keep_previous_layouts(&tree, node)?;
+                let available_space = Size {
+                    width: AvailableSpace::Definite(request.width),
+                    height: AvailableSpace::Definite(request.height),
+                };
+                let start_ms = compute_stats::now_ms();
+                tree.compute_layout_with_measure(node, available_space, |known_dimensions, available, leaf, _, _| {
+                    self.measure_content_size(known_dimensions, available, leaf)
+                })?;
+                self.record_compute_stats(&tree, node, 0, BTreeMap::new(), compute_stats::now_ms() - start_ms);
+                self.record_layout(request.root, available_space);
+                self.validate_layout(&tree, node)?;
+                Ok(())
+            })
+            .collect();
+        Ok(outcomes)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layouts of several roots in one call, from an array of `{ root, width, height }` requests
+    ///
+    /// Each request is laid out as by `compute_layout(root, width, height)`, in order, but the tree is only
+    /// borrowed once, which saves the overhead of a call per root when relaying out several independent trees
+    /// every frame. A root listed twice is laid out twice, and `last_compute_stats` describes the last root that
+    /// was laid out.
+    ///
+    /// Returns an array with an entry per request: `null` if its root was laid out, or the `TaffyError` it failed
+    /// with otherwise, e.g. an `INVALID_NODE` error for a root that is not in the tree. A failing request does not
+    /// prevent the others from being laid out. Throws an `INVALID_ARGUMENT` error if `requests` is not an array of
+    /// `LayoutRequest` objects.
+    #[wasm_bindgen]
+    pub fn compute_layouts(&self, requests: JsLayoutRequests) -> Result<js_sys::Array, TaffyError> {
+        let requests: Vec<LayoutRequest> = serde_wasm_bindgen::from_value(requests.into())
+            .map_err(|e| TaffyError::InvalidArgument(format!("requests: {e}")))?;
+        let outcomes = self.compute_requests(&requests)?;
+        Ok(outcomes.into_iter().map(|outcome| outcome.err().map_or(JsValue::NULL, JsValue::from)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn lays_out_each_root_within_its_own_size() {
+        let tree = TaffyTree::new();
+        let (window, panel) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fill = Style { size: percent(1.0), ..Default::default() };
+            (js_node_id(inner.new_leaf(fill.clone()).unwrap()), js_node_id(inner.new_leaf(fill).unwrap()))
+        };
+
+        let requests = [
+            LayoutRequest { root: window, width: 800.0, height: 600.0 },
+            LayoutRequest { root: panel, width: 200.0, height: 100.0 },
+        ];
+        assert_eq!(tree.compute_requests(&requests), Ok(vec![Ok(()), Ok(())]));
+        assert_eq!((tree.layout_width(window), tree.layout_height(window)), (Ok(800.0), Ok(600.0)));
+        assert_eq!((tree.layout_width(panel), tree.layout_height(panel)), (Ok(200.0), Ok(100.0)));
+    }
+
+    #[test]
+    fn a_failing_root_does_not_stop_the_others() {
+        let tree = TaffyTree::new();
+        let fill = Style { size: percent(1.0), ..Default::default() };
+        let root = js_node_id(tree.inner.borrow_mut().new_leaf(fill).unwrap());
+
+        let requests =
+            [LayoutRequest { root: 100, width: 10.0, height: 10.0 }, LayoutRequest { root, width: 30.0, height: 20.0 }];
+        assert_eq!(tree.compute_requests(&requests), Ok(vec![Err(TaffyError::InvalidNode(100)), Ok(())]));
+        assert_eq!(tree.layout_width(root), Ok(30.0));
+    }
+}
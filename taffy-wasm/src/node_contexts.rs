@@ -0,0 +1,145 @@
+//! Attaching contexts to many nodes at once, e.g. when hydrating a large tree of text leaves, and finding the nodes
+//! that have one
+use crate::node_ids::{js_node_id, node_from_js_id};
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use std::collections::BTreeSet;
+use wasm_bindgen::prelude::*;
+
+/// Decodes the `{ nodeId, context }` entries passed to `set_node_contexts`
+fn decode_entries(entries: &JsValue) -> Result<Vec<(u32, JsValue)>, TaffyError> {
+    if !js_sys::Array::is_array(entries) {
+        return Err(TaffyError::InvalidArgument("entries: expected an array of { nodeId, context }".into()));
+    }
+    js_sys::Array::from(entries)
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let node_id = js_sys::Reflect::get(&entry, &"nodeId".into())
+                .ok()
+                .and_then(|node_id| node_id.as_f64())
+                .filter(|&node_id| node_id.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&node_id))
+                .ok_or_else(|| TaffyError::InvalidArgument(format!("entries[{index}].nodeId must be a node id")))?;
+            let context = js_sys::Reflect::get(&entry, &"context".into()).unwrap_or(JsValue::UNDEFINED);
+            Ok((node_id as u32, context))
+        })
+        .collect()
+}
+
+impl TaffyTree {
+    /// Sets the context of each node in `entries`, or of none of them if any node id is invalid
+    fn set_node_context_entries(&self, entries: Vec<(u32, JsValue)>) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let mut seen = BTreeSet::new();
+        let invalid: Vec<u32> = entries
+            .iter()
+            .map(|&(node_id, _)| node_id)
+            .filter(|&node_id| resolve_node(&tree, node_id).is_err() && seen.insert(node_id))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(TaffyError::InvalidNodes(invalid));
+        }
+        for (node_id, data) in entries {
+            tree.set_node_context(node_from_js_id(node_id), Some(JsContext { data }))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the ids of the nodes in the subtree rooted at `root` that have a context, in depth-first order
+    fn context_node_ids(&self, root: u32) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let mut stack = vec![resolve_node(&tree, root)?];
+        let mut node_ids = Vec::new();
+        while let Some(node) = stack.pop() {
+            if tree.get_node_context(node).is_some() {
+                node_ids.push(js_node_id(node));
+            }
+            stack.extend(tree.children(node)?.into_iter().rev());
+        }
+        Ok(node_ids)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sets the contexts of many nodes at once, from an array of `{ nodeId, context }` entries
+    ///
+    /// This is equivalent to calling `set_node_context(nodeId, context)` for each entry in order, so a node listed
+    /// twice ends up with its last context, but much cheaper for large batches. `set_node_context` remains the
+    /// simplest way to update a single node.
+    ///
+    /// Either every context is set or none is. Throws an `INVALID_NODE` error whose `nodeIds` lists every invalid
+    /// node id in the order they first appear, or an `INVALID_ARGUMENT` error if `entries` is not an array of
+    /// `{ nodeId, context }` objects.
+    #[wasm_bindgen]
+    pub fn set_node_contexts(&self, entries: JsValue) -> Result<(), TaffyError> {
+        self.set_node_context_entries(decode_entries(&entries)?)
+    }
+
+    /// Returns the ids of the nodes in the subtree rooted at `root` whose context is set, including `root` itself
+    ///
+    /// Nodes are listed in depth-first order, parents before their children and children in order, which is the
+    /// order in which they are painted. A node whose context was set to `undefined` or `null` still has one; use
+    /// `remove_node_context` to remove it. This is meant for visiting the text leaves of a tree, e.g. to paint
+    /// them, without walking every container from JS.
+    #[wasm_bindgen]
+    pub fn nodes_with_context(&self, root: u32) -> Result<Vec<u32>, TaffyError> {
+        self.context_node_ids(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use crate::{TaffyError, TaffyTree};
+    use taffy::prelude::*;
+    use wasm_bindgen::JsValue;
+
+    fn tree_with_leaves(count: usize) -> (TaffyTree, Vec<u32>) {
+        let tree = TaffyTree::new();
+        let leaves =
+            (0..count).map(|_| js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap())).collect();
+        (tree, leaves)
+    }
+
+    fn has_context(tree: &TaffyTree, node_id: u32) -> bool {
+        tree.inner.borrow().get_node_context(node_from_js_id(node_id)).is_some()
+    }
+
+    #[test]
+    fn sets_every_context() {
+        let (tree, leaves) = tree_with_leaves(3);
+        tree.set_node_context_entries(leaves.iter().map(|&leaf| (leaf, JsValue::NULL)).collect()).unwrap();
+        assert!(leaves.iter().all(|&leaf| has_context(&tree, leaf)));
+    }
+
+    #[test]
+    fn invalid_node_ids_are_all_reported_and_nothing_is_set() {
+        let (tree, leaves) = tree_with_leaves(2);
+        let entries =
+            vec![(leaves[0], JsValue::NULL), (100, JsValue::NULL), (101, JsValue::NULL), (100, JsValue::NULL)];
+        let error = tree.set_node_context_entries(entries).unwrap_err();
+        assert_eq!(error, TaffyError::InvalidNodes(vec![100, 101]));
+        assert_eq!(error.code(), "INVALID_NODE");
+        assert_eq!(error.node_id(), Some(100));
+        assert_eq!(error.to_string(), "Nodes 100, 101 are not in the TaffyTree instance");
+        assert!(!has_context(&tree, leaves[0]));
+    }
+
+    #[test]
+    fn lists_only_the_nodes_with_contexts() {
+        let (tree, leaves) = tree_with_leaves(3);
+        let (root, column) = {
+            let mut inner = tree.inner.borrow_mut();
+            let column = inner
+                .new_with_children(Style::default(), &[node_from_js_id(leaves[1]), node_from_js_id(leaves[2])])
+                .unwrap();
+            let root = inner.new_with_children(Style::default(), &[node_from_js_id(leaves[0]), column]).unwrap();
+            (js_node_id(root), js_node_id(column))
+        };
+        tree.set_node_context_entries(vec![(leaves[2], JsValue::NULL), (leaves[0], JsValue::NULL)]).unwrap();
+
+        assert_eq!(tree.context_node_ids(root), Ok(vec![leaves[0], leaves[2]]));
+        assert_eq!(tree.context_node_ids(column), Ok(vec![leaves[2]]));
+        assert_eq!(tree.context_node_ids(100), Err(TaffyError::InvalidNode(100)));
+    }
+}
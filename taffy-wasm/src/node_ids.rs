@@ -0,0 +1,236 @@
+//! The ids that identify nodes in JS, and removing nodes without letting a new node inherit a stale id
+//!
+//! The low bits of an id are the index of the node's slot in the tree, and the high bits count how many nodes have
+//! been removed from that slot before. A node created in the slot of a removed one therefore gets a new id, and
+//! the old id fails with an `INVALID_NODE` error instead of referring to the new node. A slot whose generations
+//! run out is never reused, nor is any slot of a tree created with `{ stableIds: true }`.
+//!
+//! Slot indices must therefore fit in `SLOT_INDEX_BITS` bits: a tree holds at most [`MAX_NODES`] slots, counting the
+//! retired ones, and creating a node in a slot past them fails with a `TOO_MANY_NODES` error.
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Deserialize;
+use taffy::{prelude::*, CacheTree, LayoutOutput, RunMode, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The number of low bits of a JS node id that hold the index of the node's slot
+const SLOT_INDEX_BITS: u32 = 24;
+
+/// The number of nodes a tree can hold, retired slots included
+pub(crate) const MAX_NODES: u32 = 1 << SLOT_INDEX_BITS;
+
+/// The largest generation that fits in the high bits of a JS node id
+const MAX_GENERATION: u32 = u32::MAX >> SLOT_INDEX_BITS;
+
+/// Returns the index of a node's slot and the generation of the slot, i.e. the number of nodes removed from it
+fn slot_of(node: NodeId) -> (u32, u32) {
+    let key = u64::from(node);
+    // Slot versions are odd while the slot is occupied, and go up by 2 each time it is reused
+    (key as u32, (key >> 32) as u32 >> 1)
+}
+
+/// Returns the JS id of a node
+pub(crate) fn js_node_id(node: NodeId) -> u32 {
+    let (index, generation) = slot_of(node);
+    debug_assert!(index < MAX_NODES, "slot index {index} does not fit in a JS node id");
+    index | generation << SLOT_INDEX_BITS
+}
+
+/// Returns the JS id of a node, or a `TOO_MANY_NODES` error if the index of its slot doesn't fit in one
+fn checked_js_node_id(node: NodeId) -> Result<u32, TaffyError> {
+    match slot_of(node).0 < MAX_NODES {
+        true => Ok(js_node_id(node)),
+        false => Err(TaffyError::TooManyNodes),
+    }
+}
+
+/// Returns the JS id of `node`, just created in `tree`, or removes it again and fails with a `TOO_MANY_NODES` error
+/// if the index of its slot doesn't fit in a JS id
+///
+/// Every node created by the bindings goes through this, so that no node is ever given an id that refers to another.
+pub(crate) fn new_node_id<C>(tree: &mut Taffy<C>, node: NodeId) -> Result<u32, TaffyError> {
+    checked_js_node_id(node).inspect_err(|_| {
+        let _ = tree.remove(node);
+    })
+}
+
+/// Creates nodes in `tree` with `create`, which pushes each node it creates to the list it is given, and removes them
+/// all again if it fails, e.g. with a `TOO_MANY_NODES` error from [`new_node_id`] partway through
+pub(crate) fn create_nodes<C, T>(
+    tree: &mut Taffy<C>,
+    create: impl FnOnce(&mut Taffy<C>, &mut Vec<NodeId>) -> Result<T, TaffyError>,
+) -> Result<T, TaffyError> {
+    let mut created = Vec::new();
+    create(tree, &mut created).inspect_err(|_| {
+        for &node in &created {
+            let _ = tree.remove(node);
+        }
+    })
+}
+
+/// Returns the node with a JS id, which may not be in the tree
+pub(crate) fn node_from_js_id(node_id: u32) -> NodeId {
+    let index = node_id & ((1 << SLOT_INDEX_BITS) - 1);
+    let version = (node_id >> SLOT_INDEX_BITS) << 1 | 1;
+    NodeId::from(u64::from(version) << 32 | u64::from(index))
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TAFFY_TREE_OPTIONS_TS: &'static str = r#"
+/** Options for `new TaffyTree(options)` */
+export interface TaffyTreeOptions {
+  /** Never reuse the id of a removed node, at the cost of a little memory per removed node. Defaults to false. */
+  stableIds?: boolean;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A `TaffyTreeOptions` object passed from JavaScript
+    #[wasm_bindgen(typescript_type = "TaffyTreeOptions")]
+    pub type JsTaffyTreeOptions;
+}
+
+/// Options for a new tree, decoded from `{ stableIds?: boolean }`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaffyTreeOptions {
+    /// Whether the slots of removed nodes are kept empty forever, so that no id is ever reused
+    pub stable_ids: bool,
+}
+
+/// Decodes the optional `options` argument of the `TaffyTree` constructor
+pub(crate) fn decode_options(options: Option<JsTaffyTreeOptions>) -> Result<TaffyTreeOptions, TaffyError> {
+    let Some(options) = options else {
+        return Ok(TaffyTreeOptions::default());
+    };
+    serde_wasm_bindgen::from_value(options.into()).map_err(|e| TaffyError::InvalidArgument(format!("options: {e}")))
+}
+
+impl TaffyTree {
+    /// Removes a node from the tree, keeping its slot occupied if its id must not be reused
+    pub(crate) fn remove_node(&self, node_id: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        if let Some(parent) = tree.parent(node) {
+            tree.mark_dirty(parent)?;
+        }
+        tree.remove(node)?;
+        self.node_states.borrow_mut().remove(node_id);
+        if let Some(previous) = self.previous_layouts.borrow_mut().as_mut() {
+            previous.remove(node_id);
+        }
+        if let Some(timings) = self.node_timings.borrow_mut().as_mut() {
+            timings.remove(&node_id);
+        }
+
+        let (_, generation) = slot_of(node);
+        if self.options.stable_ids || generation == MAX_GENERATION {
+            // The next node is created in the most recently freed slot: fill it with a placeholder, whose id is never
+            // handed out, and give that a cached layout so that it doesn't count as dirty
+            let placeholder = tree.new_leaf(Style { display: Display::None, ..Style::DEFAULT })?;
+            debug_assert_eq!(slot_of(placeholder).0, slot_of(node).0);
+            tree.cache_store(placeholder, Size::NONE, Size::MAX_CONTENT, RunMode::PerformLayout, LayoutOutput::HIDDEN);
+            self.retired.borrow_mut().insert(new_node_id(&mut tree, placeholder)?);
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Removes a node from the tree, and marks its parent dirty
+    ///
+    /// The node's children are not removed: they are detached and become roots, which can be added to another
+    /// parent or removed in turn. Its context and everything set on it outside its style, such as its content size,
+    /// tag, user data and scroll offset, and whether it is frozen or hidden, are dropped with it.
+    ///
+    /// The id of the removed node fails with an `INVALID_NODE` error from then on. A node created later may reuse
+    /// the memory of the removed one, but always gets a different id, unless the same slot of memory has been
+    /// reused 255 times; the slot is then retired instead. With `new TaffyTree({ stableIds: true })` every slot is
+    /// retired, so ids are never reused, but each removed node keeps taking up the memory of an empty node (a few
+    /// hundred bytes) for the lifetime of the tree.
+    #[wasm_bindgen]
+    pub fn remove(&self, node_id: u32) -> Result<(), TaffyError> {
+        self.remove_node(node_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_leaf(tree: &TaffyTree) -> u32 {
+        js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap())
+    }
+
+    #[test]
+    fn ids_round_trip() {
+        for node_id in [0, 1, 12345, (1 << SLOT_INDEX_BITS) - 1, 1 << SLOT_INDEX_BITS, u32::MAX] {
+            assert_eq!(js_node_id(node_from_js_id(node_id)), node_id);
+        }
+        // Nodes in slots that were never reused have the index of their slot as id
+        assert_eq!(node_from_js_id(7), NodeId::from(1u64 << 32 | 7));
+    }
+
+    #[test]
+    fn slots_past_the_last_js_id_are_too_many_nodes() {
+        let last = NodeId::from(1u64 << 32 | u64::from(MAX_NODES - 1));
+        assert_eq!(checked_js_node_id(last), Ok(MAX_NODES - 1));
+        let past_the_last = NodeId::from(1u64 << 32 | u64::from(MAX_NODES));
+        let error = checked_js_node_id(past_the_last).unwrap_err();
+        assert_eq!((error.clone(), error.code()), (TaffyError::TooManyNodes, "TOO_MANY_NODES"));
+
+        // Nodes created before the error are removed with it
+        let tree = TaffyTree::new();
+        let mut inner = tree.inner.borrow_mut();
+        inner.new_leaf(Style::default()).unwrap();
+        let result: Result<(), _> = create_nodes(&mut inner, |tree, created| {
+            let leaf = tree.new_leaf(Style::default())?;
+            created.push(leaf);
+            created.push(tree.new_with_children(Style::default(), &[leaf])?);
+            Err(error)
+        });
+        assert_eq!(result, Err(TaffyError::TooManyNodes));
+        assert_eq!(inner.total_node_count(), 1);
+    }
+
+    #[test]
+    fn reused_slot_gets_a_new_id() {
+        let tree = TaffyTree::new();
+        let parent = new_leaf(&tree);
+        let removed = new_leaf(&tree);
+        tree.add_child(parent, removed).unwrap();
+        tree.inner.borrow_mut().compute_layout(node_from_js_id(parent), Size::MAX_CONTENT).unwrap();
+
+        tree.remove_node(removed).unwrap();
+        assert_eq!(tree.dirty(parent), Ok(true));
+        assert_eq!(tree.inner.borrow().child_count(node_from_js_id(parent)), 0);
+        assert_eq!(tree.layout_width(removed), Err(TaffyError::InvalidNode(removed)));
+        assert_eq!(tree.remove_node(removed), Err(TaffyError::InvalidNode(removed)));
+
+        // The new node takes the removed node's slot, but not its id
+        let created = new_leaf(&tree);
+        assert_eq!(created & ((1 << SLOT_INDEX_BITS) - 1), removed);
+        assert_ne!(created, removed);
+        assert_eq!(tree.add_child(parent, created), Ok(()));
+        assert_eq!(tree.layout_width(removed), Err(TaffyError::InvalidNode(removed)));
+    }
+
+    #[test]
+    fn stable_ids_are_never_reused() {
+        let tree = TaffyTree::with_options(TaffyTreeOptions { stable_ids: true });
+        let root = new_leaf(&tree);
+        let removed = new_leaf(&tree);
+        tree.remove_node(removed).unwrap();
+
+        let created = new_leaf(&tree);
+        assert_ne!(created, removed);
+        assert!(created < 1 << SLOT_INDEX_BITS, "the new node is in a slot that was never used");
+        assert_eq!(tree.layout_width(removed), Err(TaffyError::InvalidNode(removed)));
+
+        // The placeholder left in the removed node's slot doesn't keep the tree dirty
+        tree.inner.borrow_mut().compute_layout(node_from_js_id(root), Size::MAX_CONTENT).unwrap();
+        tree.inner.borrow_mut().compute_layout(node_from_js_id(created), Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.has_dirty(), Ok(false));
+    }
+}
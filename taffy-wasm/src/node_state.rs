@@ -0,0 +1,156 @@
+//! The state the wrapper keeps for each node alongside the core tree
+use crate::object_fit::ObjectFit;
+use crate::pinned_size::PinnedSize;
+use crate::tap_target::TapTarget;
+use std::collections::BTreeMap;
+use taffy::{prelude::*, Point};
+
+/// What was set on a node outside its style, and what was recorded about it by earlier layouts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct NodeState {
+    /// Whether the subtree rooted at the node was frozen with `freeze`
+    pub(crate) frozen: bool,
+    /// The display of the node from before it was hidden with `set_hidden`
+    pub(crate) hidden_display: Option<Display>,
+    /// The display of the node from before it was taken out of flow with `set_out_of_flow`
+    pub(crate) out_of_flow_display: Option<Display>,
+    /// The content size of the leaf, set with `set_content_size`
+    pub(crate) content_size: Option<Size<f32>>,
+    /// The object fit of the leaf, set with `set_object_fit`
+    pub(crate) object_fit: Option<ObjectFit>,
+    /// The available space the node was most recently laid out in as a root
+    pub(crate) last_available_space: Option<Size<AvailableSpace>>,
+    /// The tag the node was created with by `new_leaf_tagged`, after the number of nodes tagged before it
+    pub(crate) tag: Option<(u64, String)>,
+    /// The value stored for the node with `set_user_data`
+    pub(crate) user_data: Option<u32>,
+    /// The scroll offset of the node, set with `set_scroll_offset`
+    pub(crate) scroll_offset: Option<Point<f32>>,
+    /// The size the node is pinned to with `pin_size`, and the sizes of its style the pin replaces
+    pub(crate) pinned_size: Option<PinnedSize>,
+    /// The tap target of the node, set with `set_min_tap_target`, and the `min_size` of its style it raises
+    pub(crate) tap_target: Option<TapTarget>,
+    /// The available space of a relayout of the node requested with `request_relayout` and not yet flushed
+    pub(crate) pending_relayout: Option<Size<AvailableSpace>>,
+    /// The maximum number of lines of the leaf, set with `set_max_lines`
+    pub(crate) max_lines: Option<u32>,
+    /// Whether the content of the leaf was truncated to its maximum number of lines when last measured
+    pub(crate) truncated: bool,
+}
+
+impl NodeState {
+    /// The state of a node nothing was set on
+    const EMPTY: NodeState = NodeState {
+        frozen: false,
+        hidden_display: None,
+        out_of_flow_display: None,
+        content_size: None,
+        object_fit: None,
+        last_available_space: None,
+        tag: None,
+        user_data: None,
+        scroll_offset: None,
+        pinned_size: None,
+        tap_target: None,
+        pending_relayout: None,
+        max_lines: None,
+        truncated: false,
+    };
+}
+
+/// The [`NodeState`] of each node, by id
+///
+/// Only nodes with some state have an entry, so removing a node or copying the tree's state handles all of it at
+/// once.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NodeStates {
+    states: BTreeMap<u32, NodeState>,
+    /// The number of nodes tagged so far, which orders the tags by when they were given
+    tags_given: u64,
+}
+
+impl NodeStates {
+    /// Returns the state of the node with the id `node_id`, which is empty if nothing was set on it
+    pub(crate) fn get(&self, node_id: u32) -> &NodeState {
+        self.states.get(&node_id).unwrap_or(&NodeState::EMPTY)
+    }
+
+    /// Changes the state of the node with the id `node_id` with `update`, and returns what it returns
+    pub(crate) fn update<T>(&mut self, node_id: u32, update: impl FnOnce(&mut NodeState) -> T) -> T {
+        let state = self.states.entry(node_id).or_default();
+        let result = update(state);
+        if *state == NodeState::EMPTY {
+            self.states.remove(&node_id);
+        }
+        result
+    }
+
+    /// Drops the state of the node with the id `node_id`, e.g. when it is removed
+    pub(crate) fn remove(&mut self, node_id: u32) {
+        self.states.remove(&node_id);
+    }
+
+    /// Returns the id and state of every node with some state, in order of their ids
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u32, &NodeState)> {
+        self.states.iter().map(|(&node_id, state)| (node_id, state))
+    }
+
+    /// Tags the node with the id `node_id` with `tag`
+    pub(crate) fn tag(&mut self, node_id: u32, tag: String) {
+        let order = self.tags_given;
+        self.tags_given += 1;
+        self.update(node_id, |state| state.tag = Some((order, tag)));
+    }
+
+    /// Returns the id and tag of every tagged node, in the order they were tagged
+    pub(crate) fn tags(&self) -> Vec<(u32, String)> {
+        let mut tags: Vec<_> = self.iter().filter_map(|(node_id, state)| Some((state.tag.clone()?, node_id))).collect();
+        tags.sort_by_key(|((order, _), _)| *order);
+        tags.into_iter().map(|((_, tag), node_id)| (node_id, tag)).collect()
+    }
+
+    /// Takes the request of the root with the lowest id that has a pending relayout request
+    pub(crate) fn take_pending_relayout(&mut self) -> Option<(u32, Size<AvailableSpace>)> {
+        let (root, _) = self.iter().find(|(_, state)| state.pending_relayout.is_some())?;
+        Some((root, self.update(root, |state| state.pending_relayout.take())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodes_whose_state_is_emptied_have_no_entry() {
+        let mut node_states = NodeStates::default();
+        node_states.update(3, |state| state.user_data = Some(7));
+        node_states.update(3, |state| state.frozen = true);
+        assert_eq!(node_states.get(3).user_data, Some(7));
+        assert_eq!(node_states.iter().count(), 1);
+
+        node_states.update(3, |state| state.user_data = None);
+        node_states.update(3, |state| state.frozen = false);
+        assert_eq!(node_states.get(3), &NodeState::default());
+        assert_eq!(node_states.iter().count(), 0);
+    }
+
+    #[test]
+    fn tags_are_returned_in_the_order_they_were_given() {
+        let mut node_states = NodeStates::default();
+        node_states.tag(5, String::from("first"));
+        node_states.tag(2, String::from("second"));
+        node_states.tag(9, String::from("third"));
+        node_states.remove(9);
+        assert_eq!(node_states.tags(), vec![(5, String::from("first")), (2, String::from("second"))]);
+    }
+
+    #[test]
+    fn pending_relayouts_are_taken_in_order_of_their_roots() {
+        let mut node_states = NodeStates::default();
+        node_states.update(8, |state| state.pending_relayout = Some(Size::MAX_CONTENT));
+        node_states.update(4, |state| state.pending_relayout = Some(Size::MIN_CONTENT));
+        assert_eq!(node_states.take_pending_relayout(), Some((4, Size::MIN_CONTENT)));
+        assert_eq!(node_states.take_pending_relayout(), Some((8, Size::MAX_CONTENT)));
+        assert_eq!(node_states.take_pending_relayout(), None);
+    }
+}
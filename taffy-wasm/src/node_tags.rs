@@ -0,0 +1,91 @@
+//! Tagging nodes with caller-supplied string keys, and reading layouts back keyed by tag
+use crate::absolute_layout::AbsoluteLayout;
+use crate::{TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns the absolute layout of every tagged node that still exists, keyed by tag
+    ///
+    /// Tags are visited in the order they were given, so the node tagged last wins if several share a tag.
+    fn layouts_by_tag(&self) -> Result<BTreeMap<String, AbsoluteLayout>, TaffyError> {
+        let mut layouts = BTreeMap::new();
+        let tags = self.node_states.borrow().tags();
+        for (node_id, tag) in tags {
+            match self.absolute_box(node_id) {
+                Ok(layout) => layouts.insert(tag, layout),
+                Err(TaffyError::InvalidNode(_)) => continue,
+                Err(error) => return Err(error),
+            };
+        }
+        Ok(layouts)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Creates a leaf like `new_leaf`, tagged with the caller's own key `tag`, and returns its id
+    ///
+    /// The tag is only used to key the output of `layout_by_tag`. Tags don't need to be unique; see
+    /// `layout_by_tag` for how duplicates are resolved. The tag is dropped when the node is removed.
+    #[wasm_bindgen]
+    pub fn new_leaf_tagged(&self, style: JsValue, tag: String) -> Result<u32, TaffyError> {
+        let node_id = self.new_leaf(style)?;
+        self.node_states.borrow_mut().tag(node_id, tag);
+        Ok(node_id)
+    }
+
+    /// Returns the layout of every node created with `new_leaf_tagged` as an object
+    /// `{ [tag]: { x, y, width, height } }`
+    ///
+    /// Each box is relative to the root of the node's tree, as returned by `absolute_layout`. If several nodes share
+    /// a tag, the last one created wins. Nodes whose ids have become invalid, e.g. after `restore`, are omitted.
+    #[wasm_bindgen]
+    pub fn layout_by_tag(&self) -> Result<JsValue, TaffyError> {
+        let layouts = self.layouts_by_tag()?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        Ok(layouts.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::prelude::{length, FlexDirection, Size, Style, TaffyMaxContent};
+
+    #[test]
+    fn layouts_are_keyed_by_tag() {
+        let tree = TaffyTree::new();
+        let (root, [header, body, stale]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = |height: f32| Style {
+                size: Size { width: length(50.0), height: length(height) },
+                ..Default::default()
+            };
+            let leaves = [10.0, 20.0, 30.0].map(|height| inner.new_leaf(leaf(height)).unwrap());
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            (js_node_id(inner.new_with_children(column, &leaves).unwrap()), leaves.map(js_node_id))
+        };
+        let tags = [(header, "header"), (body, "body"), (stale, "header")];
+        for (id, tag) in tags {
+            tree.node_states.borrow_mut().tag(id, tag.to_string());
+        }
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // The last node tagged "header" wins
+        let layouts = tree.layouts_by_tag().unwrap();
+        assert_eq!(layouts.len(), 2);
+        assert_eq!(layouts["header"], AbsoluteLayout { x: 0.0, y: 30.0, width: 50.0, height: 30.0 });
+        assert_eq!(layouts["body"], AbsoluteLayout { x: 0.0, y: 10.0, width: 50.0, height: 20.0 });
+
+        // Removing a node drops its tag
+        tree.remove_node(stale).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(
+            tree.layouts_by_tag().unwrap()["header"],
+            AbsoluteLayout { x: 0.0, y: 0.0, width: 50.0, height: 10.0 }
+        );
+    }
+}
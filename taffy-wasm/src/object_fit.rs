@@ -0,0 +1,136 @@
+//! Sizing leaves with a fixed aspect ratio to fit or cover their available space, e.g. images with `object-fit`
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// How a leaf given an object fit with `set_object_fit` is sized within its available space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ObjectFitMode {
+    /// The largest box with the aspect ratio that fits within the available space
+    Contain,
+    /// The smallest box with the aspect ratio that covers the available space
+    Cover,
+}
+
+/// The object fit of a leaf: a mode and the aspect ratio (width divided by height) of its box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ObjectFit {
+    /// How the box is fitted to the available space
+    mode: ObjectFitMode,
+    /// The width of the box divided by its height
+    ratio: f32,
+}
+
+impl ObjectFit {
+    /// Returns the size of the box within the known dimensions of the leaf, or else its definite available space
+    ///
+    /// An axis without a bound doesn't constrain the box, and a box bounded in neither axis is empty.
+    pub(crate) fn size_within(
+        &self,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+    ) -> Size<f32> {
+        let bounds = known_dimensions.or(available_space.into_options());
+        let width = match (bounds.width, bounds.height.map(|height| height * self.ratio)) {
+            (Some(width), Some(width_from_height)) => match self.mode {
+                ObjectFitMode::Contain => width.min(width_from_height),
+                ObjectFitMode::Cover => width.max(width_from_height),
+            },
+            (Some(width), None) | (None, Some(width)) => width,
+            (None, None) => 0.0,
+        };
+        Size { width, height: width / self.ratio }
+    }
+}
+
+impl TaffyTree {
+    /// Sets or removes the object fit of a node, removing its content size, and marks it dirty
+    fn update_object_fit(&self, node_id: u32, object_fit: Option<ObjectFit>) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        self.node_states.borrow_mut().update(node_id, |state| {
+            state.object_fit = object_fit;
+            state.content_size = None;
+        });
+        tree.mark_dirty(node)?;
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sizes a leaf as a box with the aspect ratio `ratio` (width divided by height) fitted to its available space
+    ///
+    /// With `mode` `"contain"` the box is the largest that fits within the available space, and with `"cover"` the
+    /// smallest that covers it, as for an image with `object-fit: contain` or `object-fit: cover` whose element fills
+    /// the available space. Like a content size given with `set_content_size`, which this replaces, the box is used
+    /// as the result of measuring the leaf, in place of the measure function, and the leaf's style still applies on
+    /// top. An axis whose size is known, e.g. from a `size` or stretching, bounds the box in place of the available
+    /// space. An axis whose available space is a min-content or max-content constraint doesn't bound it, and a box
+    /// bounded in neither axis is empty. The node is marked dirty.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `mode` is neither `"contain"` nor `"cover"`, or if `ratio` is not a
+    /// positive finite number.
+    #[wasm_bindgen]
+    pub fn set_object_fit(&self, node_id: u32, mode: &str, ratio: f32) -> Result<(), TaffyError> {
+        let mode = match mode {
+            "contain" => ObjectFitMode::Contain,
+            "cover" => ObjectFitMode::Cover,
+            mode => return Err(TaffyError::InvalidArgument(format!("unknown object fit mode {mode:?}"))),
+        };
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(TaffyError::InvalidArgument(format!("ratio must be a positive number, got {ratio}")));
+        }
+        self.update_object_fit(node_id, Some(ObjectFit { mode, ratio }))
+    }
+
+    /// Removes the object fit given to a node with `set_object_fit`, so that it is measured by the measure function
+    /// again, and marks it dirty
+    #[wasm_bindgen]
+    pub fn remove_object_fit(&self, node_id: u32) -> Result<(), TaffyError> {
+        self.update_object_fit(node_id, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    /// Lays out a fitted leaf with `mode` and `ratio` as a root within 300x200 pixels, returning its size
+    fn fitted_size(mode: &str, ratio: f32) -> (f32, f32) {
+        let tree = TaffyTree::new();
+        let leaf = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        tree.set_object_fit(leaf, mode, ratio).unwrap();
+        let available = Size { width: AvailableSpace::Definite(300.0), height: AvailableSpace::Definite(200.0) };
+        tree.compute_subtree(leaf, available).unwrap();
+        (tree.layout_width(leaf).unwrap(), tree.layout_height(leaf).unwrap())
+    }
+
+    #[test]
+    fn contain_fits_within_the_available_space() {
+        assert_eq!(fitted_size("contain", 2.0), (300.0, 150.0));
+        assert_eq!(fitted_size("contain", 1.0), (200.0, 200.0));
+        assert_eq!(fitted_size("contain", 0.5), (100.0, 200.0));
+    }
+
+    #[test]
+    fn cover_covers_the_available_space() {
+        assert_eq!(fitted_size("cover", 2.0), (400.0, 200.0));
+        assert_eq!(fitted_size("cover", 1.0), (300.0, 300.0));
+        assert_eq!(fitted_size("cover", 0.5), (300.0, 600.0));
+    }
+
+    #[test]
+    fn known_width_bounds_the_box() {
+        let tree = TaffyTree::new();
+        let sized = Style { size: Size { width: length(100.0), height: auto() }, ..Default::default() };
+        let leaf = js_node_id(tree.inner.borrow_mut().new_leaf(sized).unwrap());
+        tree.set_object_fit(leaf, "cover", 0.5).unwrap();
+        tree.compute_subtree(leaf, Size::MAX_CONTENT).unwrap();
+        assert_eq!((tree.layout_width(leaf), tree.layout_height(leaf)), (Ok(100.0), Ok(200.0)));
+
+        assert!(matches!(tree.set_object_fit(leaf, "fill", 1.0), Err(TaffyError::InvalidArgument(_))));
+        assert!(matches!(tree.set_object_fit(leaf, "contain", 0.0), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,361 @@
+//! Detecting whether the content of a root overflowed the space it was laid out in, or a node overflowed a container
+//! that clips it, and resolving the `overflow` of each axis as CSS does
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::{prelude::*, style::Overflow, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// How far, in pixels, content may extend past the available space before it counts as overflowing
+///
+/// Layout is computed in `f32`, so content that exactly fits a fractional size can come out a few ULPs larger.
+const OVERFLOW_TOLERANCE: f32 = 0.001;
+
+/// Whether the content of a root overflowed its available space, returned by `compute_layout_with_overflow`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootOverflow {
+    /// Whether the root or its content is wider than the available width
+    pub overflowed_width: bool,
+    /// Whether the root or its content is taller than the available height
+    pub overflowed_height: bool,
+}
+
+/// The `overflow` of each axis of a node after the CSS resolution of one axis against the other, returned by
+/// `resolved_overflow`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResolvedOverflow {
+    /// The overflow of the horizontal axis: `"Visible"`, `"Clip"`, `"Hidden"`, `"Scroll"` or `"Auto"`
+    pub x: &'static str,
+    /// The overflow of the vertical axis: `"Visible"`, `"Clip"`, `"Hidden"`, `"Scroll"` or `"Auto"`
+    pub y: &'static str,
+}
+
+/// Returns the overflow of one axis given the overflow of `other` axis, as CSS resolves it
+///
+/// A box can't scroll in one axis while letting its content spill out of the other, so if the other axis is neither
+/// `Visible` nor `Clip`, then `Visible` becomes `Auto` and `Clip` becomes `Hidden`.
+fn resolve_overflow_axis(overflow: Overflow, other: Overflow) -> &'static str {
+    let other_scrolls = matches!(other, Overflow::Hidden | Overflow::Scroll);
+    match overflow {
+        Overflow::Visible if other_scrolls => "Auto",
+        Overflow::Clip if other_scrolls => "Hidden",
+        Overflow::Visible => "Visible",
+        Overflow::Clip => "Clip",
+        Overflow::Hidden => "Hidden",
+        Overflow::Scroll => "Scroll",
+    }
+}
+
+/// Returns whether the extent of a child, from `start` to `end`, extends past the padding box of a container from
+/// `padding_start` to `padding_end`, if the container clips its overflow in that axis
+fn overflows_clip(overflow: Overflow, start: f32, end: f32, padding_start: f32, padding_end: f32) -> bool {
+    matches!(overflow, Overflow::Clip | Overflow::Hidden)
+        && (start < padding_start - OVERFLOW_TOLERANCE || end > padding_end + OVERFLOW_TOLERANCE)
+}
+
+/// Returns the first node in the subtree rooted at `root`, in paint order, whose box extends past the padding box
+/// of a parent that clips it
+fn first_clipped_node(tree: &Taffy<JsContext>, root: NodeId) -> Result<Option<NodeId>, TaffyError> {
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let style = tree.style(node)?;
+        if style.display == Display::None {
+            continue;
+        }
+        let layout = tree.unrounded_layout(node);
+        let (size, border) = (layout.size, layout.border);
+        let children = tree.children(node)?;
+        for &child in &children {
+            let child_layout = tree.unrounded_layout(child);
+            let (location, child_size) = (child_layout.location, child_layout.size);
+            if tree.style(child)?.display == Display::None {
+                continue;
+            }
+            let (left, right) = (location.x, location.x + child_size.width);
+            let (top, bottom) = (location.y, location.y + child_size.height);
+            if overflows_clip(style.overflow.x, left, right, border.left, size.width - border.right)
+                || overflows_clip(style.overflow.y, top, bottom, border.top, size.height - border.bottom)
+            {
+                return Ok(Some(child));
+            }
+        }
+        stack.extend(children.into_iter().rev());
+    }
+    Ok(None)
+}
+
+/// Returns the smallest whole number of pixels from 0 to `bound` for which `fits` returns `true`, by a binary
+/// search that assumes that `fits` holds for every size larger than one it holds for, and for `bound` itself
+fn smallest_fitting(bound: f32, mut fits: impl FnMut(f32) -> Result<bool, TaffyError>) -> Result<f32, TaffyError> {
+    let (mut too_small, mut smallest) = (-1.0, bound.ceil().max(0.0));
+    while smallest - too_small > 1.0 {
+        let middle = ((too_small + smallest) / 2.0_f32).floor();
+        if fits(middle)? {
+            smallest = middle;
+        } else {
+            too_small = middle;
+        }
+    }
+    Ok(smallest)
+}
+
+impl TaffyTree {
+    /// Returns the `overflow` of each axis of a node as resolved by CSS
+    fn resolve_overflow(&self, node_id: u32) -> Result<ResolvedOverflow, TaffyError> {
+        let tree = self.tree()?;
+        let overflow = tree.style(resolve_node(&tree, node_id)?)?.overflow;
+        Ok(ResolvedOverflow {
+            x: resolve_overflow_axis(overflow.x, overflow.y),
+            y: resolve_overflow_axis(overflow.y, overflow.x),
+        })
+    }
+
+    /// Lays out the tree rooted at `root` within `available` pixels, and checks whether it overflowed them
+    fn compute_root_overflow(&self, root: u32, available: Size<f32>) -> Result<RootOverflow, TaffyError> {
+        self.compute_subtree(root, available.map(AvailableSpace::Definite))?;
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, root)?;
+        // The unrounded layout is used so that rounding a fractional size up doesn't register as overflow
+        let layout = tree.unrounded_layout(node);
+        let extent = layout.size.zip_map(layout.content_size, f32::max);
+        let overflowed = |extent: f32, available: f32| extent > available + OVERFLOW_TOLERANCE;
+        Ok(RootOverflow {
+            overflowed_width: overflowed(extent.width, available.width),
+            overflowed_height: overflowed(extent.height, available.height),
+        })
+    }
+
+    /// Returns the extent of the root laid out within `available_space`, including the content overflowing it
+    fn root_extent(&self, root: u32, available_space: Size<AvailableSpace>) -> Result<Size<f32>, TaffyError> {
+        self.compute_subtree(root, available_space)?;
+        let tree = self.tree()?;
+        let layout = tree.unrounded_layout(resolve_node(&tree, root)?);
+        Ok(layout.size.zip_map(layout.content_size, f32::max))
+    }
+
+    /// Lays out the tree rooted at `root` within `available` pixels, and checks that neither the root nor any node
+    /// clipped by its parent overflowed
+    fn fits_without_overflow(&self, root: u32, available: Size<f32>) -> Result<bool, TaffyError> {
+        let overflow = self.compute_root_overflow(root, available)?;
+        if overflow.overflowed_width || overflow.overflowed_height {
+            return Ok(false);
+        }
+        let tree = self.tree()?;
+        Ok(first_clipped_node(&tree, resolve_node(&tree, root)?)?.is_none())
+    }
+
+    /// Searches for the smallest available size, in whole pixels, at which the tree rooted at `root` doesn't
+    /// overflow, leaving the tree laid out at that size
+    fn smallest_non_overflowing_size(&self, root: u32) -> Result<Size<f32>, TaffyError> {
+        let min_content = self.root_extent(root, Size::MIN_CONTENT)?;
+        let max_content = self.root_extent(root, Size::MAX_CONTENT)?;
+        // The content is at its tallest when it is at its narrowest, so the width is searched at a height that
+        // doesn't constrain it, then the height at the width found
+        let tallest = min_content.height.max(max_content.height).ceil();
+        let width = smallest_fitting(min_content.width.max(max_content.width), |width| {
+            self.fits_without_overflow(root, Size { width, height: tallest })
+        })?;
+        let natural = self
+            .root_extent(root, Size { width: AvailableSpace::Definite(width), height: AvailableSpace::MaxContent })?;
+        let height =
+            smallest_fitting(natural.height, |height| self.fits_without_overflow(root, Size { width, height }))?;
+        let size = Size { width, height };
+        self.compute_subtree(root, size.map(AvailableSpace::Definite))?;
+        Ok(size)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `root` like `compute_layout`, and reports whether it overflowed the
+    /// available space, as `{ overflowedWidth, overflowedHeight }`
+    ///
+    /// An axis has overflowed if the root itself, or any content overflowing it, extends past `width` or `height`,
+    /// e.g. to decide whether to show a scrollbar. Fractional sizes are compared exactly, before layouts are rounded
+    /// to whole pixels, so content that exactly fits a fractional width does not count as overflowing.
+    #[wasm_bindgen]
+    pub fn compute_layout_with_overflow(&self, root: u32, width: f32, height: f32) -> Result<JsValue, TaffyError> {
+        let overflow = self.compute_root_overflow(root, Size { width, height })?;
+        Ok(serde_wasm_bindgen::to_value(&overflow).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the id of the first node in the subtree rooted at `root` that overflows a container that clips it, or
+    /// `undefined` if none does
+    ///
+    /// A node overflows if its border box extends past the padding box of its parent, in an axis in which the
+    /// parent's `overflow` is `clip` or `hidden`, so that part of it is cut off, e.g. to warn that some content
+    /// doesn't fit. Parents whose `overflow` is `visible` don't cut off their children, and those whose `overflow`
+    /// is `scroll` let them be scrolled to, so their children never count as overflowing. Nodes are checked in
+    /// paint order, as in `layout_buffer`, so an overflowing container is reported before any overflowing
+    /// descendants. Nodes with `display: "None"` are skipped, along with their subtrees.
+    ///
+    /// This reads the layout last computed, before it was rounded to whole pixels, so that rounding doesn't make a
+    /// node that exactly fits overflow.
+    #[wasm_bindgen]
+    pub fn first_overflow(&self, root: u32) -> Result<Option<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        Ok(first_clipped_node(&tree, root)?.map(js_node_id))
+    }
+
+    /// Returns the smallest available size, as `{ width, height }` in whole pixels, at which the tree rooted at
+    /// `root` doesn't overflow, e.g. to shrink a window to fit its content
+    ///
+    /// A size fits if neither the root nor its content extends past it, as reported by
+    /// `compute_layout_with_overflow`, and no node overflows a parent that clips it, as reported by
+    /// `first_overflow`. The width is found first, by a binary search between 0 and the wider of the min-content and
+    /// max-content widths of the tree, at a height that leaves the content room to grow; the height is then found
+    /// by a binary search between 0 and the height of the content at that width. The search assumes that a tree
+    /// that fits a size fits any larger one. If the tree overflows whatever the size, e.g. because a container
+    /// with a fixed size clips its content, the upper bound of the search is returned.
+    ///
+    /// Each step of the search is a full layout of the tree, as by `compute_layout`, so this runs two layouts at
+    /// the min-content and max-content sizes, about `log2(width + 1)` layouts to find the width, one layout of the
+    /// content at that width, about `log2(height + 1)` layouts to find the height, and a final layout at the size
+    /// found: around 25 layouts for content of 1000x1000 pixels. Only the subtrees that change between steps are
+    /// laid out again. The tree is left laid out at the size returned. Leaves are sized as by `compute_layout`,
+    /// without a measure function.
+    #[wasm_bindgen]
+    pub fn min_non_overflowing_size(&self, root: u32) -> Result<JsValue, TaffyError> {
+        let size = self.smallest_non_overflowing_size(root)?;
+        Ok(serde_wasm_bindgen::to_value(&size).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns the `overflow` of each axis of a node as resolved by CSS, as `{ x, y }`
+    ///
+    /// If one axis is `"Hidden"` or `"Scroll"`, a `"Visible"` other axis resolves to `"Auto"` and a `"Clip"` one to
+    /// `"Hidden"`, since a box that scrolls in one axis can't let its content spill out of the other. Otherwise each
+    /// axis keeps its style value. The resolved values are the ones to use when deciding whether a node is a scroll
+    /// container and which of its axes to clip: an `"Auto"` axis is clipped and shows a scrollbar only when its
+    /// content overflows. Taffy has no `"Auto"` value and lays out each axis from its own style value, so an axis
+    /// that resolves to `"Auto"` or `"Hidden"` from `"Visible"` or `"Clip"` keeps a content-based automatic minimum
+    /// size and no space is reserved for its scrollbar.
+    #[wasm_bindgen]
+    pub fn resolved_overflow(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let overflow = self.resolve_overflow(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&overflow).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::Point;
+
+    /// Lays out a content item of the given size in a root that fills 100.25x50.5 pixels of available space
+    fn overflow(content: Size<f32>) -> RootOverflow {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = inner
+                .new_leaf(Style {
+                    size: Size { width: length(content.width), height: length(content.height) },
+                    flex_shrink: 0.0,
+                    ..Default::default()
+                })
+                .unwrap();
+            let root_style = Style { size: Size { width: percent(1.0), height: percent(1.0) }, ..Default::default() };
+            js_node_id(inner.new_with_children(root_style, &[item]).unwrap())
+        };
+        tree.compute_root_overflow(root, Size { width: 100.25, height: 50.5 }).unwrap()
+    }
+
+    #[test]
+    fn exactly_fitting_fractional_content_does_not_overflow() {
+        let fits = RootOverflow { overflowed_width: false, overflowed_height: false };
+        assert_eq!(overflow(Size { width: 100.25, height: 50.5 }), fits);
+    }
+
+    #[test]
+    fn underfilling_content_does_not_overflow() {
+        let fits = RootOverflow { overflowed_width: false, overflowed_height: false };
+        assert_eq!(overflow(Size { width: 60.0, height: 10.0 }), fits);
+    }
+
+    #[test]
+    fn overflowing_content_is_reported_per_axis() {
+        let wide = overflow(Size { width: 120.0, height: 10.0 });
+        assert_eq!(wide, RootOverflow { overflowed_width: true, overflowed_height: false });
+        let tall = overflow(Size { width: 10.0, height: 50.75 });
+        assert_eq!(tall, RootOverflow { overflowed_width: false, overflowed_height: true });
+    }
+
+    #[test]
+    fn first_overflow_is_the_first_clipped_node_in_paint_order() {
+        let tree = TaffyTree::new();
+        let fixed = |width| Style { size: length(width), flex_shrink: 0.0, ..Default::default() };
+        let clipping =
+            |overflow| Style { overflow: Point { x: overflow, y: overflow }, padding: length(5.0), ..fixed(50.0) };
+        let (root, [fitting_card, overflowing]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fits = inner.new_leaf(fixed(40.0)).unwrap();
+            let fitting_card = inner.new_with_children(clipping(Overflow::Hidden), &[fits]).unwrap();
+            let spills = inner.new_leaf(fixed(80.0)).unwrap();
+            let visible_card = inner.new_with_children(clipping(Overflow::Visible), &[spills]).unwrap();
+            let scrolls = inner.new_leaf(fixed(80.0)).unwrap();
+            let scroll_card = inner.new_with_children(clipping(Overflow::Scroll), &[scrolls]).unwrap();
+            let overflowing = inner.new_leaf(fixed(50.0)).unwrap();
+            let clipped_card = inner.new_with_children(clipping(Overflow::Clip), &[overflowing]).unwrap();
+            let children = [fitting_card, visible_card, scroll_card, clipped_card];
+            let root = inner.new_with_children(Style { flex_wrap: FlexWrap::Wrap, ..Default::default() }, &children);
+            (js_node_id(root.unwrap()), [fitting_card, overflowing].map(js_node_id))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // Only the leaf as wide as its clipping card, which it starts 5px into because of the padding, is cut off
+        assert_eq!(tree.first_overflow(root), Ok(Some(overflowing)));
+        assert_eq!(tree.first_overflow(fitting_card), Ok(None));
+    }
+
+    #[test]
+    fn visible_axis_resolves_to_auto_next_to_a_hidden_one() {
+        let tree = TaffyTree::new();
+        let node = |x, y| {
+            let style = Style { overflow: Point { x, y }, ..Default::default() };
+            js_node_id(tree.inner.borrow_mut().new_leaf(style).unwrap())
+        };
+        let resolved = |node| tree.resolve_overflow(node).unwrap();
+        let (visible, clip, hidden) = (Overflow::Visible, Overflow::Clip, Overflow::Hidden);
+
+        assert_eq!(resolved(node(visible, hidden)), ResolvedOverflow { x: "Auto", y: "Hidden" });
+        assert_eq!(resolved(node(Overflow::Scroll, clip)), ResolvedOverflow { x: "Scroll", y: "Hidden" });
+        assert_eq!(resolved(node(clip, visible)), ResolvedOverflow { x: "Clip", y: "Visible" });
+        assert_eq!(resolved(node(visible, visible)), ResolvedOverflow { x: "Visible", y: "Visible" });
+        assert_eq!(tree.resolve_overflow(100), Err(TaffyError::InvalidNode(100)));
+    }
+
+    #[test]
+    fn smallest_size_that_fits_is_the_min_size_of_the_child() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = Style {
+                flex_grow: 1.0,
+                flex_basis: length(0.0),
+                min_size: Size { width: length(150.0), height: length(30.0) },
+                ..Default::default()
+            };
+            let child = inner.new_leaf(child).unwrap();
+            let root = Style {
+                overflow: Point { x: Overflow::Hidden, y: Overflow::Hidden },
+                size: percent(1.0),
+                ..Default::default()
+            };
+            js_node_id(inner.new_with_children(root, &[child]).unwrap())
+        };
+
+        let size = tree.smallest_non_overflowing_size(root).unwrap();
+        assert_eq!(size, Size { width: 150.0, height: 30.0 });
+        // The tree is left laid out at that size, which a pixel less in either axis overflows
+        assert_eq!(tree.layout_width(root), Ok(150.0));
+        assert_eq!(tree.fits_without_overflow(root, Size { width: 149.0, height: 30.0 }), Ok(false));
+        assert_eq!(tree.fits_without_overflow(root, Size { width: 150.0, height: 29.0 }), Ok(false));
+    }
+
+    #[test]
+    fn overflow_of_invalid_root() {
+        let tree = TaffyTree::new();
+        assert_eq!(tree.compute_root_overflow(1, Size { width: 10.0, height: 10.0 }), Err(TaffyError::InvalidNode(1)));
+    }
+}
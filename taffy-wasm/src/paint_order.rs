@@ -0,0 +1,106 @@
+//! Ordering the nodes of a tree back to front as CSS paints them, for renderers drawing overlapping boxes
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// Appends the in-flow descendants of `node` to `order` in tree order, and its absolutely positioned descendants,
+/// which are painted later, to `positioned`
+///
+/// Hidden nodes are left out along with their subtrees, and the descendants of positioned nodes are left for when
+/// the positioned node is painted.
+fn paint_in_flow(
+    tree: &Taffy<JsContext>,
+    node: NodeId,
+    order: &mut Vec<u32>,
+    positioned: &mut Vec<NodeId>,
+) -> Result<(), TaffyError> {
+    for child in tree.children(node)? {
+        let style = tree.style(child)?;
+        if style.display == Display::None {
+            continue;
+        }
+        if style.position == Position::Absolute {
+            positioned.push(child);
+        } else {
+            order.push(js_node_id(child));
+            paint_in_flow(tree, child, order, positioned)?;
+        }
+    }
+    Ok(())
+}
+
+/// Appends `node` and its in-flow descendants to `order`, followed by each of its positioned descendants in tree
+/// order along with their own in-flow descendants
+fn paint(tree: &Taffy<JsContext>, node: NodeId, order: &mut Vec<u32>) -> Result<(), TaffyError> {
+    order.push(js_node_id(node));
+    let mut positioned = Vec::new();
+    paint_in_flow(tree, node, order, &mut positioned)?;
+    // Positioned nodes are painted after all in-flow content. Any positioned descendants of a positioned node come
+    // before its following siblings in tree order, so each is painted in full before the next.
+    for node in positioned {
+        paint(tree, node, order)?;
+    }
+    Ok(())
+}
+
+/// Returns the ids of the nodes in the subtree rooted at `root` in the order CSS paints them, back to front
+fn paint_order_of(tree: &Taffy<JsContext>, root: NodeId) -> Result<Vec<u32>, TaffyError> {
+    let mut order = Vec::new();
+    if tree.style(root)?.display != Display::None {
+        paint(tree, root, &mut order)?;
+    }
+    Ok(order)
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the ids of the visible nodes in the subtree rooted at `root`, sorted back to front in the order CSS
+    /// paints them
+    ///
+    /// taffy has no `z-index`, so this is the painting order of CSS with every `z-index` `auto`: first `root`, then
+    /// its in-flow descendants in tree order, then its absolutely positioned descendants in tree order, each
+    /// followed by its own in-flow descendants. An absolutely positioned node is therefore painted over in-flow
+    /// nodes that come after it in the tree, and over every earlier positioned node. Only `position: "Absolute"`
+    /// counts as positioned: `"Relative"` is taffy's default and is painted in flow. Nodes with
+    /// `display: "None"` are left out, along with their subtrees.
+    ///
+    /// This differs from the order of `layout_buffer`, which lists every node in plain tree order.
+    #[wasm_bindgen]
+    pub fn paint_order(&self, root: u32) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        paint_order_of(&tree, root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positioned_nodes_are_painted_after_in_flow_content() {
+        let tree = TaffyTree::new();
+        let absolute = || Style { position: Position::Absolute, size: length(50.0), ..Default::default() };
+        let (root, [first, overlay, overlay_text, badge, second, later_overlay, hidden]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let badge = inner.new_leaf(absolute()).unwrap();
+            let overlay_text = inner.new_leaf(Style::default()).unwrap();
+            let overlay = inner.new_with_children(absolute(), &[badge, overlay_text]).unwrap();
+            let first = inner.new_with_children(Style::default(), &[overlay]).unwrap();
+            let second = inner.new_leaf(Style { size: length(60.0), ..Default::default() }).unwrap();
+            let later_overlay = inner.new_leaf(absolute()).unwrap();
+            let hidden = inner.new_leaf(Style { display: Display::None, ..Default::default() }).unwrap();
+            let root = inner.new_with_children(Style::default(), &[first, second, later_overlay, hidden]).unwrap();
+            let nodes = [first, overlay, overlay_text, badge, second, later_overlay, hidden];
+            (js_node_id(root), nodes.map(js_node_id))
+        };
+
+        // The overlay comes before `second` in the tree, but overlaps it, so it is painted after it, with its text,
+        // and its positioned badge is painted over both
+        let order = tree.paint_order(root).unwrap();
+        assert_eq!(order, [root, first, second, overlay, overlay_text, badge, later_overlay]);
+        assert!(!order.contains(&hidden));
+        assert_eq!(tree.paint_order(hidden), Ok(vec![]));
+    }
+}
@@ -0,0 +1,154 @@
+//! Pinning the used size of a node while the rest of the tree reflows around it, e.g. during a drag-resize gesture
+use crate::{resolve_node, set_style_if_changed, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// The size a node is pinned to, and the sizes of its style that the pin replaces
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PinnedSize {
+    /// The size the node is pinned to, in layout pixels
+    size: Size<f32>,
+    /// The `size` of the node's style
+    authored_size: Size<Dimension>,
+    /// The `min_size` of the node's style
+    authored_min_size: Size<Dimension>,
+    /// The `max_size` of the node's style
+    authored_max_size: Size<Dimension>,
+}
+
+impl PinnedSize {
+    /// Pins a node with `style` to `size`
+    fn new(size: Size<f32>, style: &Style) -> Self {
+        PinnedSize {
+            size,
+            authored_size: style.size,
+            authored_min_size: style.min_size,
+            authored_max_size: style.max_size,
+        }
+    }
+
+    /// Returns `style` with its sizes fixed to the pinned size
+    fn apply(&self, style: Style) -> Style {
+        let fixed = Size { width: length(self.size.width), height: length(self.size.height) };
+        Style { size: fixed, min_size: fixed, max_size: fixed, ..style }
+    }
+
+    /// Returns `style` with the sizes the pin replaced
+    fn restore(&self, style: Style) -> Style {
+        Style { size: self.authored_size, min_size: self.authored_min_size, max_size: self.authored_max_size, ..style }
+    }
+}
+
+impl TaffyTree {
+    /// Returns `style`, to be given to `node_id`, with the sizes of the pin on the node if it is pinned, remembering
+    /// the sizes of `style` for when it is unpinned
+    pub(crate) fn apply_pinned_size(&self, node_id: u32, style: Style) -> Style {
+        self.node_states.borrow_mut().update(node_id, |state| match &mut state.pinned_size {
+            Some(pinned) => {
+                *pinned = PinnedSize::new(pinned.size, &style);
+                pinned.apply(style)
+            }
+            None => style,
+        })
+    }
+
+    /// Returns `style`, the style of `node_id` in the tree, with the sizes it had before it was pinned or given a tap
+    /// target
+    pub(crate) fn authored_style(&self, node_id: u32, style: Style) -> Style {
+        let style = match &self.node_states.borrow().get(node_id).pinned_size {
+            Some(pinned) => pinned.restore(style),
+            None => style,
+        };
+        self.remove_tap_target(node_id, style)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Forces the size of a node to `width` by `height` pixels in every layout until `unpin_size` is called, without
+    /// changing its style
+    ///
+    /// The pinned size takes precedence over the `size`, `min_size` and `max_size` of the node, and over
+    /// `flex_grow`, `flex_shrink` and stretch alignment, so its siblings grow or shrink to take up the difference,
+    /// e.g. while the node is resized by a drag gesture. Other style properties, such as its padding and position,
+    /// still apply. The size is in layout pixels, as returned by `layout_width`, so it is not scaled by
+    /// `set_length_scale`.
+    ///
+    /// The style of the node is left as it was: `get_styles` and `non_default_fields` keep reporting its own sizes,
+    /// and `update_style` replaces them while the node stays pinned. Pinning a pinned node again changes its pinned
+    /// size. The node is marked dirty if this changes its size.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `width` or `height` is negative or not finite.
+    #[wasm_bindgen]
+    pub fn pin_size(&self, node_id: u32, width: f32, height: f32) -> Result<(), TaffyError> {
+        if !(width.is_finite() && height.is_finite() && width >= 0.0 && height >= 0.0) {
+            return Err(TaffyError::InvalidArgument(format!(
+                "a pinned size must be finite and non-negative, got {width} by {height}"
+            )));
+        }
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let style = tree.style(node)?.clone();
+        let mut node_states = self.node_states.borrow_mut();
+        let authored = match &node_states.get(node_id).pinned_size {
+            Some(pinned) => pinned.restore(style),
+            None => style,
+        };
+        let pinned = PinnedSize::new(Size { width, height }, &authored);
+        set_style_if_changed(&mut tree, node, pinned.apply(authored))?;
+        node_states.update(node_id, |state| state.pinned_size = Some(pinned));
+        Ok(())
+    }
+
+    /// Undoes `pin_size`, so that the node is sized by its style again from the next layout
+    ///
+    /// The node is marked dirty if this changes its size. Unpinning a node that is not pinned does nothing.
+    #[wasm_bindgen]
+    pub fn unpin_size(&self, node_id: u32) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let pinned = self.node_states.borrow_mut().update(node_id, |state| state.pinned_size.take());
+        if let Some(pinned) = pinned {
+            let style = pinned.restore(tree.style(node)?.clone());
+            set_style_if_changed(&mut tree, node, style)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn sibling_absorbs_the_difference_until_unpinned() {
+        let tree = TaffyTree::new();
+        let (row, [pinned, sibling]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { flex_grow: 1.0, flex_basis: length(0.0), ..Default::default() };
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item).unwrap()];
+            let row = Style { size: Size { width: length(300.0), height: length(50.0) }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        let widths = || {
+            tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+            [pinned, sibling].map(|node| tree.layout_width(node).unwrap())
+        };
+        assert_eq!(widths(), [150.0, 150.0]);
+
+        tree.pin_size(pinned, 100.0, 20.0).unwrap();
+        assert_eq!(widths(), [100.0, 200.0]);
+        assert_eq!(tree.layout_height(pinned), Ok(20.0));
+        assert_eq!(tree.layout_height(sibling), Ok(50.0));
+        // The style still has the node's own sizes
+        let style = tree.styles(&[pinned]).unwrap().remove(0).unwrap().0;
+        assert_eq!((style.size, style.flex_grow), (Size::auto(), 1.0));
+
+        tree.unpin_size(pinned).unwrap();
+        assert_eq!(widths(), [150.0, 150.0]);
+        assert_eq!(tree.layout_height(pinned), Ok(50.0));
+
+        assert!(matches!(tree.pin_size(pinned, -1.0, 0.0), Err(TaffyError::InvalidArgument(_))));
+    }
+}
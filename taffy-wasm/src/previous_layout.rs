@@ -0,0 +1,120 @@
+//! Keeping the layouts nodes had before the most recent layout, e.g. to animate the change with the FLIP technique
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use std::collections::{BTreeMap, BTreeSet};
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The layouts kept while `set_keep_previous_layouts` is enabled
+#[derive(Debug, Default)]
+pub(crate) struct PreviousLayouts {
+    /// The ids of the nodes that have been laid out since keeping previous layouts was enabled
+    laid_out: BTreeSet<u32>,
+    /// The layout each node had before the most recent layout of it, if it had been laid out before that
+    layouts: BTreeMap<u32, Layout>,
+}
+
+impl PreviousLayouts {
+    /// Forgets a removed node
+    pub(crate) fn remove(&mut self, node_id: u32) {
+        self.laid_out.remove(&node_id);
+        self.layouts.remove(&node_id);
+    }
+}
+
+impl TaffyTree {
+    /// Records the current layout of every node in the subtree rooted at `root` as its previous layout, if previous
+    /// layouts are kept, before the subtree is laid out
+    pub(crate) fn keep_previous_layouts(&self, tree: &Taffy<JsContext>, root: NodeId) -> Result<(), TaffyError> {
+        let mut previous = self.previous_layouts.borrow_mut();
+        let Some(previous) = previous.as_mut() else {
+            return Ok(());
+        };
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let node_id = js_node_id(node);
+            if previous.laid_out.insert(node_id) {
+                previous.layouts.remove(&node_id);
+            } else {
+                previous.layouts.insert(node_id, *tree.layout(node)?);
+            }
+            stack.extend(tree.children(node)?);
+        }
+        Ok(())
+    }
+
+    /// Returns the layout a node had before its most recent layout, if it has one
+    fn previous_box(&self, node_id: u32) -> Result<Option<Layout>, TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        let previous = self.previous_layouts.borrow();
+        Ok(previous.as_ref().and_then(|previous| previous.layouts.get(&node_id).copied()))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Enables or disables keeping the layout each node had before the most recent layout, for `previous_layout`
+    ///
+    /// While enabled, every method that lays out a root, such as `compute_layout` or `recompute`, first copies the
+    /// current layout of each node in the root's subtree, which costs time and memory proportional to the size of the
+    /// subtree. Disabled by default. Disabling it discards the kept layouts, and enabling it again starts afresh:
+    /// nodes only have a previous layout once they have been laid out twice while it is enabled.
+    #[wasm_bindgen]
+    pub fn set_keep_previous_layouts(&self, enabled: bool) {
+        let mut previous = self.previous_layouts.borrow_mut();
+        if enabled != previous.is_some() {
+            *previous = enabled.then(PreviousLayouts::default);
+        }
+    }
+
+    /// Returns the full layout a node had before the most recent layout of its tree, as `try_layout` returns it, or
+    /// `undefined` if it has none
+    ///
+    /// Together with the current layout, this gives the first and last box of the node for animating a layout
+    /// change with the FLIP technique: read the boxes after the new layout, and transform each node from its
+    /// previous box to its current one. A node has no previous layout unless `set_keep_previous_layouts` is enabled
+    /// and it has been laid out at least twice since, e.g. if it was added to the tree just before the most recent
+    /// layout. A layout in which nothing changed makes the previous layout the same as the current one.
+    ///
+    /// Throws an `INVALID_NODE` error if the node is not in the tree.
+    #[wasm_bindgen]
+    pub fn previous_layout(&self, node_id: u32) -> Result<Option<JsValue>, TaffyError> {
+        let layout = self.previous_box(node_id)?;
+        Ok(layout.map(|layout| serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previous_layout_is_the_box_before_the_latest_layout() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+            let root = Style { size: percent(1.0), ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[child]).unwrap()), js_node_id(child))
+        };
+        let layout = |width| {
+            let available_space =
+                Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(100.0) };
+            tree.compute_subtree(root, available_space).unwrap();
+        };
+        let previous_width = |node| tree.previous_box(node).unwrap().map(|layout| layout.size.width);
+
+        tree.set_keep_previous_layouts(true);
+        layout(200.0);
+        assert_eq!(previous_width(child), None);
+
+        layout(300.0);
+        assert_eq!(previous_width(root), Some(200.0));
+        assert_eq!(previous_width(child), Some(100.0));
+        assert_eq!(tree.layout_width(child), Ok(150.0));
+
+        tree.set_keep_previous_layouts(false);
+        assert_eq!(previous_width(child), None);
+        assert_eq!(tree.previous_box(root + 1), Err(TaffyError::InvalidNode(root + 1)));
+    }
+}
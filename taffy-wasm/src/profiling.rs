@@ -0,0 +1,127 @@
+//! Timing the layout of each node, to find the subtrees that make a layout slow
+use crate::node_ids::{js_node_id, node_from_js_id};
+use crate::{JsContext, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// Returns the cumulative time in microseconds of each node of the tree laid out from `root`: the duration of the
+/// whole pass for `root`, and the time spent measuring the leaves of its subtree for every other node, leaving out
+/// the nodes whose subtree wasn't measured
+fn cumulative_timings(
+    tree: &Taffy<JsContext>,
+    root: NodeId,
+    pass_ms: f64,
+    measure_ms: &BTreeMap<u32, f64>,
+) -> BTreeMap<u32, f64> {
+    let mut timings = BTreeMap::new();
+    for (&leaf, &elapsed_ms) in measure_ms {
+        let mut node = Some(node_from_js_id(leaf));
+        while let Some(current) = node.filter(|&current| current != root) {
+            *timings.entry(js_node_id(current)).or_insert(0.0) += elapsed_ms * 1000.0;
+            node = tree.parent(current);
+        }
+    }
+    timings.insert(js_node_id(root), pass_ms * 1000.0);
+    timings
+}
+
+impl TaffyTree {
+    /// Returns whether layout passes are being profiled
+    pub(crate) fn is_profiling(&self) -> bool {
+        self.node_timings.borrow().is_some()
+    }
+
+    /// Replaces the recorded timings with those of the pass that laid out `root` in `pass_ms` milliseconds, spending
+    /// `measure_ms` milliseconds measuring each measured leaf, by id, if layout passes are being profiled
+    pub(crate) fn record_node_timings(
+        &self,
+        tree: &Taffy<JsContext>,
+        root: NodeId,
+        pass_ms: f64,
+        measure_ms: &BTreeMap<u32, f64>,
+    ) {
+        if let Some(timings) = self.node_timings.borrow_mut().as_mut() {
+            *timings = cumulative_timings(tree, root, pass_ms, measure_ms);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Starts or stops recording how long each node takes to lay out in `compute_layout_with_measure`, returned by
+    /// `node_timings`
+    ///
+    /// Profiling is off by default, since reading the clock around every measure call slows the layout down.
+    /// Stopping it discards the recorded timings.
+    #[wasm_bindgen]
+    pub fn set_profiling(&self, enabled: bool) {
+        let mut timings = self.node_timings.borrow_mut();
+        match enabled {
+            true => *timings = Some(timings.take().unwrap_or_default()),
+            false => *timings = None,
+        }
+    }
+
+    /// Returns the time spent laying out each node during the last `compute_layout_with_measure` profiled with
+    /// `set_profiling`, as an object mapping node ids to microseconds, e.g. `{ 1: 5200, 4: 4800 }`
+    ///
+    /// The time of a node is cumulative: it includes the time of its descendants, so the expensive subtrees can be
+    /// found by following the largest times down from the root. The time of the root of the pass is the duration
+    /// of the whole pass. Taffy doesn't report when it starts and finishes each of the other nodes, so their time
+    /// is the time spent in the measure function for the leaves of their subtree, summed over every time each leaf
+    /// was measured during the pass; nodes whose subtree wasn't measured are left out. The root's time minus the
+    /// time of its children is the time the layout algorithms themselves took.
+    ///
+    /// The times are read from `Date.now()`, which only has a resolution of a millisecond, and less in browsers that
+    /// coarsen timers, so a measure call that takes less than that is usually counted as 0 or as a whole
+    /// millisecond. The timings are only reliable for nodes taking several milliseconds over the whole pass. Each
+    /// profiled pass replaces the timings of the previous one, and the result is empty until one completes.
+    #[wasm_bindgen]
+    pub fn node_timings(&self) -> JsValue {
+        let timings = self.node_timings.borrow().clone().unwrap_or_default();
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        timings.serialize(&serializer).unwrap_or(JsValue::NULL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure_budget::MeasureBudget;
+    use std::cell::Cell;
+    use taffy::style::Style;
+    use taffy::Point;
+
+    #[test]
+    fn slow_measure_function_shows_in_its_node_and_ancestors() {
+        let tree = TaffyTree::new();
+        let (root, column, [slow, fast, other]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let [slow, fast, other] = [(); 3].map(|_| inner.new_leaf(Style::default()).unwrap());
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            let column = inner.new_with_children(column, &[slow, fast]).unwrap();
+            let root = inner.new_with_children(Style::default(), &[column, other]).unwrap();
+            (js_node_id(root), js_node_id(column), [slow, fast, other].map(js_node_id))
+        };
+        // A fake clock, advanced by the measure function by how long each leaf takes to measure
+        let now_ms = Cell::new(0.0);
+        let measure = |_, _, node, _: Option<&mut JsContext>| {
+            now_ms.set(now_ms.get() + if js_node_id(node) == slow { 8.0 } else { 0.5 });
+            (Size { width: 10.0, height: 10.0 }, Point::NONE)
+        };
+        tree.set_profiling(true);
+        tree.compute_layout_with_budget(root, Size::MAX_CONTENT, MeasureBudget::default(), || now_ms.get(), measure)
+            .unwrap();
+
+        let timings = tree.node_timings.borrow().clone().unwrap();
+        assert!(timings[&slow] > timings[&fast] && timings[&slow] > timings[&other], "{timings:?}");
+        assert!(timings[&slow] >= 8000.0, "{timings:?}");
+        assert_eq!(timings[&column], timings[&slow] + timings[&fast]);
+        assert!(timings[&root] >= timings[&column] + timings[&other], "{timings:?}");
+
+        tree.set_profiling(false);
+        assert!(tree.node_timings.borrow().is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! Building random but valid trees from a seed, e.g. for property tests and fuzzers
+use crate::node_ids::{create_nodes, js_node_id, new_node_id};
+use crate::{TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// The SplitMix64 generator, which is small and produces the same numbers from a seed on every platform
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..n`, for `n > 0`
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, choices: &[T]) -> T {
+        choices[self.below(choices.len())]
+    }
+
+    /// Returns `auto`, a length of up to 200 in steps of 10, or a percentage of the parent
+    fn dimension(&mut self) -> Dimension {
+        match self.below(3) {
+            0 => auto(),
+            1 => length(10.0 * self.below(21) as f32),
+            _ => percent(self.pick(&[0.25, 0.5, 1.0])),
+        }
+    }
+
+    /// Returns between one and three grid tracks
+    #[cfg(feature = "grid")]
+    fn tracks(&mut self) -> Vec<TrackSizingFunction> {
+        let tracks: [TrackSizingFunction; 4] = [length(50.0), fr(1.0), auto(), min_content()];
+        (0..=self.below(3)).map(|_| tracks[self.below(tracks.len())].clone()).collect()
+    }
+
+    /// Returns a random style, which is never hidden for the root so that the tree has something to lay out
+    fn style(&mut self, is_root: bool, default_style: &Style) -> Style {
+        let display = match self.below(8) {
+            0..=4 => Display::Flex,
+            #[cfg(feature = "grid")]
+            5 | 6 => Display::Grid,
+            #[cfg(not(feature = "grid"))]
+            5 | 6 => Display::Flex,
+            _ if is_root => Display::Flex,
+            _ => Display::None,
+        };
+        #[cfg(feature = "grid")]
+        let (grid_template_columns, grid_template_rows) = match display {
+            Display::Grid => (self.tracks(), self.tracks()),
+            _ => (Vec::new(), Vec::new()),
+        };
+        Style {
+            display,
+            position: if !is_root && self.below(6) == 0 { Position::Absolute } else { Position::Relative },
+            flex_direction: self.pick(&[FlexDirection::Row, FlexDirection::Column]),
+            flex_wrap: self.pick(&[FlexWrap::NoWrap, FlexWrap::Wrap]),
+            flex_grow: self.pick(&[0.0, 1.0]),
+            size: Size { width: self.dimension(), height: self.dimension() },
+            padding: length(self.pick(&[0.0, 5.0])),
+            gap: length(self.pick(&[0.0, 10.0])),
+            #[cfg(feature = "grid")]
+            grid_template_columns,
+            #[cfg(feature = "grid")]
+            grid_template_rows,
+            ..default_style.clone()
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Builds a random tree of `node_count` nodes from `seed`, returning the id of its root
+    ///
+    /// Each node has a random `display` (`"Flex"`, `"Grid"`, or for nodes other than the root, sometimes `"None"`),
+    /// and random sizes, padding, gaps, flex properties and grid tracks, some nodes being absolutely positioned.
+    /// Each node after the root is appended as the last child of a random earlier node, so the tree has a random
+    /// shape, and is laid out like any other tree, e.g. with `compute_layout`. Properties not set at random have the
+    /// tree's defaults, as for `new_flex_row`.
+    ///
+    /// The same seed and node count always build the same tree, with the same styles and shape, on every platform,
+    /// so that a failing property test can be reproduced from its seed. The node ids are only the same if the trees
+    /// are built into trees with the same nodes, e.g. fresh ones.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `node_count` is 0, and a `TOO_MANY_NODES` error, without creating any
+    /// node, if the tree can't hold `node_count` more nodes.
+    #[wasm_bindgen]
+    pub fn new_random_tree(&self, seed: u64, node_count: u32) -> Result<u32, TaffyError> {
+        if node_count == 0 {
+            return Err(TaffyError::InvalidArgument("node_count: a tree has at least one node".to_string()));
+        }
+        let default_style = self.default_style();
+        let mut random = SplitMix64(seed);
+        create_nodes(&mut *self.tree_mut()?, |tree, nodes| {
+            nodes.reserve(node_count as usize);
+            for index in 0..node_count as usize {
+                let node = tree.new_leaf(random.style(index == 0, &default_style))?;
+                new_node_id(tree, node)?;
+                if index > 0 {
+                    let parent = nodes[random.below(index)];
+                    tree.add_child(parent, node)?;
+                }
+                nodes.push(node);
+            }
+            Ok(js_node_id(nodes[0]))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::node_from_js_id;
+
+    /// Returns the styles and children of every node in the subtree rooted at `root`, in tree order, as JSON
+    fn serialized(tree: &TaffyTree, root: u32) -> String {
+        let inner = tree.inner.borrow();
+        let mut nodes = Vec::new();
+        let mut stack = vec![node_from_js_id(root)];
+        while let Some(node) = stack.pop() {
+            let children = inner.children(node).unwrap();
+            let child_ids: Vec<u32> = children.iter().copied().map(js_node_id).collect();
+            nodes.push((js_node_id(node), inner.style(node).unwrap().clone(), child_ids));
+            stack.extend(children.into_iter().rev());
+        }
+        serde_json::to_string(&nodes).unwrap()
+    }
+
+    #[test]
+    fn the_same_seed_builds_the_same_tree() {
+        let build = |seed| {
+            let tree = TaffyTree::new();
+            let root = tree.new_random_tree(seed, 40).unwrap();
+            assert_eq!(tree.inner.borrow().total_node_count(), 40);
+            tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+            serialized(&tree, root)
+        };
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7), build(8));
+
+        let tree = TaffyTree::new();
+        assert!(matches!(tree.new_random_tree(7, 0), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,157 @@
+//! Laying out a root again in the space it was last laid out in, e.g. after marking some of its nodes dirty, and
+//! skipping layouts that wouldn't change anything
+use crate::measure_budget::MeasureBudget;
+use crate::{compute_stats, resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Returns the space in which the subtree rooted at `root` was last laid out
+    fn last_available_space(&self, root: u32) -> Result<Size<AvailableSpace>, TaffyError> {
+        resolve_node(&*self.tree()?, root)?;
+        self.node_states.borrow().get(root).last_available_space.ok_or_else(|| {
+            TaffyError::InvalidArgument(format!(
+                "node {root} has never been laid out as a root, so it can't be recomputed"
+            ))
+        })
+    }
+
+    /// Lays out the subtree rooted at `root` in `available_space` unless it is up to date and was last laid out in a
+    /// space that rounds to the same whole pixels, returning whether it was laid out
+    pub(crate) fn compute_subtree_if_changed(
+        &self,
+        root: u32,
+        available_space: Size<AvailableSpace>,
+    ) -> Result<bool, TaffyError> {
+        let dirty = {
+            let tree = self.tree()?;
+            tree.dirty(resolve_node(&tree, root)?)?
+        };
+        let last_available_space = self.node_states.borrow().get(root).last_available_space;
+        let same_pixels = |last: AvailableSpace, requested: AvailableSpace| match (last, requested) {
+            (AvailableSpace::Definite(last), AvailableSpace::Definite(requested)) => last.round() == requested.round(),
+            (last, requested) => last == requested,
+        };
+        let unchanged = last_available_space.is_some_and(|last| {
+            same_pixels(last.width, available_space.width) && same_pixels(last.height, available_space.height)
+        });
+        if unchanged && !dirty {
+            return Ok(false);
+        }
+        self.compute_subtree(root, available_space)?;
+        Ok(true)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `root` again, in the same available space as the last time it was
+    /// laid out as a root
+    ///
+    /// This is meant for relaying out a tree after changing or marking dirty some of its nodes, without passing its
+    /// size again, so the size can't change by accident between frames. Every method that lays out a root records
+    /// its available space: `compute_layout`, `compute_layout_with_measure`, `compute_subtree_layout`,
+    /// `compute_layout_v2` and the others. A root laid out with `compute_layout_v2` and a size limit it exceeded is
+    /// recomputed in the space it was finally laid out in, and one laid out with `compute_layout_with_containing_block`
+    /// resolves its percentages against the available space rather than the containing block.
+    ///
+    /// The measure function is not kept between calls: pass `measure_func` to size leaves as by
+    /// `compute_layout_with_measure`, with the default budget, or leave it out to size them as by `compute_layout`.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `root` has never been laid out as a root.
+    #[wasm_bindgen]
+    pub fn recompute(&self, root: u32, measure_func: Option<js_sys::Function>) -> Result<(), TaffyError> {
+        let available_space = self.last_available_space(root)?;
+        let Some(measure_func) = measure_func else {
+            return self.compute_subtree(root, available_space);
+        };
+        self.compute_layout_with_budget(
+            root,
+            available_space,
+            MeasureBudget::default(),
+            compute_stats::now_ms,
+            |known_dimensions, available_space, node, context| {
+                self.call_js_measure_for(
+                    &measure_func,
+                    &JsValue::NULL,
+                    known_dimensions,
+                    available_space,
+                    node,
+                    context,
+                )
+            },
+        )
+    }
+
+    /// Lays out the tree rooted at `root` as `compute_layout` does, unless the layout would come out the same,
+    /// returning whether it was laid out
+    ///
+    /// The layout is skipped when nothing in the tree has been marked dirty since `root` was last laid out as a root,
+    /// and `width` and `height` round to the same whole pixels as the space it was last laid out in, e.g. 800.2 and
+    /// 800.4 but not 800.4 and 800.6. This is meant for resize handlers, which can call it on every resize event
+    /// without laying the tree out again for events that don't change its size. A skipped layout leaves the last
+    /// layout, and the space recorded for `recompute`, as they were, so the layout stays the one for the unrounded
+    /// size it was computed for.
+    #[wasm_bindgen]
+    pub fn compute_layout_if_changed(&self, root: u32, width: f32, height: f32) -> Result<bool, TaffyError> {
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        self.compute_subtree_if_changed(root, available_space)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+
+    #[test]
+    fn recompute_reuses_the_last_available_space() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let root = Style { size: percent(0.5), ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[child]).unwrap()), js_node_id(child))
+        };
+        let available_space = Size { width: AvailableSpace::Definite(300.0), height: AvailableSpace::Definite(200.0) };
+        tree.compute_subtree(root, available_space).unwrap();
+        let size = (tree.layout_width(root), tree.layout_height(root));
+        assert_eq!(size, (Ok(150.0), Ok(100.0)));
+
+        let wider = Style { size: Size { width: length(40.0), height: length(10.0) }, ..Default::default() };
+        tree.inner.borrow_mut().set_style(node_from_js_id(child), wider).unwrap();
+        tree.recompute(root, None).unwrap();
+        assert_eq!((tree.layout_width(root), tree.layout_height(root)), size);
+        assert_eq!(tree.layout_width(child), Ok(40.0));
+    }
+
+    #[test]
+    fn layout_is_skipped_when_neither_the_space_nor_the_tree_changed() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+            let root = Style { size: percent(1.0), ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[child]).unwrap()), js_node_id(child))
+        };
+        assert_eq!(tree.compute_layout_if_changed(root, 800.0, 600.0), Ok(true));
+        assert_eq!(tree.compute_layout_if_changed(root, 800.0, 600.0), Ok(false));
+        assert_eq!(tree.compute_layout_if_changed(root, 800.4, 599.6), Ok(false));
+        assert_eq!(tree.layout_width(child), Ok(400.0));
+
+        assert_eq!(tree.compute_layout_if_changed(root, 900.0, 600.0), Ok(true));
+        assert_eq!(tree.layout_width(child), Ok(450.0));
+        tree.inner.borrow_mut().set_style(node_from_js_id(child), Style::default()).unwrap();
+        assert_eq!(tree.compute_layout_if_changed(root, 900.0, 600.0), Ok(true));
+        assert_eq!(tree.layout_width(child), Ok(0.0));
+        assert_eq!(tree.compute_layout_if_changed(100, 900.0, 600.0), Err(TaffyError::InvalidNode(100)));
+    }
+
+    #[test]
+    fn recompute_without_a_previous_layout_fails() {
+        let tree = TaffyTree::new();
+        let root = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        assert!(matches!(tree.recompute(root, None), Err(TaffyError::InvalidArgument(_))));
+        assert_eq!(tree.recompute(100, None), Err(TaffyError::InvalidNode(100)));
+    }
+}
@@ -0,0 +1,136 @@
+//! Finding the nodes within a rectangle, e.g. for a rubber-band selection in an editor
+use crate::node_ids::js_node_id;
+use crate::node_state::NodeStates;
+use crate::scroll_offset::scrolled_origin;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use taffy::{prelude::*, Point, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// Returns whether the box starting at `origin` of size `size` is selected by the rectangle starting at `min` and
+/// ending at `max`
+///
+/// With `contained` the box must lie entirely within the rectangle, edges included. Otherwise it must overlap the
+/// rectangle by a non-zero area, so boxes that only touch it along an edge are not selected.
+fn is_selected(origin: Point<f32>, size: Size<f32>, min: Point<f32>, max: Point<f32>, contained: bool) -> bool {
+    let end = Point { x: origin.x + size.width, y: origin.y + size.height };
+    if contained {
+        origin.x >= min.x && origin.y >= min.y && end.x <= max.x && end.y <= max.y
+    } else {
+        origin.x < max.x && min.x < end.x && origin.y < max.y && min.y < end.y
+    }
+}
+
+/// Returns the nodes of the subtree rooted at `root` selected by the rectangle starting at `min` and ending at
+/// `max`, in tree order, moving the children of each node with a scroll offset in `node_states` up and left by it
+fn nodes_in_rect(
+    tree: &Taffy<JsContext>,
+    node_states: &NodeStates,
+    root: NodeId,
+    min: Point<f32>,
+    max: Point<f32>,
+    contained: bool,
+) -> Result<Vec<NodeId>, TaffyError> {
+    let mut selected = Vec::new();
+    let mut stack = vec![(root, Point::ZERO)];
+    while let Some((node, parent_origin)) = stack.pop() {
+        let layout = tree.layout(node)?;
+        let origin = parent_origin + layout.location;
+        if is_selected(origin, layout.size, min, max, contained) {
+            selected.push(node);
+        }
+        let children_origin = scrolled_origin(node_states, js_node_id(node), origin);
+        stack.extend(tree.children(node)?.into_iter().rev().map(|child| (child, children_origin)));
+    }
+    Ok(selected)
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the ids of the nodes in the subtree rooted at `root` whose border boxes intersect the rectangle at
+    /// `(x, y)` of size `width` by `height`, or lie entirely within it if `contained` is `true`
+    ///
+    /// The rectangle is in the same coordinates as `layout_buffer`, i.e. relative to the parent of `root`, and the
+    /// ids are in tree order, parents before their children. A box intersects the rectangle if they overlap by a
+    /// non-zero area, so boxes that only touch its edges are not included, while a box lies within it if no part of
+    /// it is outside, edges included. Every node is tested, whether or not it is clipped by an ancestor. The
+    /// descendants of a node scrolled with `set_scroll_offset` are tested where they are scrolled to.
+    ///
+    /// The layout must have been computed beforehand.
+    #[wasm_bindgen]
+    pub fn nodes_in_rect(
+        &self,
+        root: u32,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        contained: bool,
+    ) -> Result<Vec<u32>, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let (min, max) = (Point { x, y }, Point { x: x + width, y: y + height });
+        Ok(nodes_in_rect(&tree, &self.node_states.borrow(), root, min, max, contained)?
+            .into_iter()
+            .map(js_node_id)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::style::Style;
+
+    fn absolute(left: f32, top: f32, width: f32, height: f32) -> Style {
+        Style {
+            position: Position::Absolute,
+            inset: Rect { left: length(left), right: auto(), top: length(top), bottom: auto() },
+            size: Size { width: length(width), height: length(height) },
+            ..Default::default()
+        }
+    }
+
+    /// Builds a 100x100 root containing a box at (10, 10), which contains a box at (20, 20) relative to it, and a
+    /// box at (60, 60), each 20x20
+    fn boxes() -> (TaffyTree, u32, [u32; 3]) {
+        let tree = TaffyTree::new();
+        let (root, boxes) = {
+            let mut inner = tree.inner.borrow_mut();
+            let inner_box = inner.new_leaf(absolute(20.0, 20.0, 20.0, 20.0)).unwrap();
+            let outer_box = inner.new_with_children(absolute(10.0, 10.0, 20.0, 20.0), &[inner_box]).unwrap();
+            let far_box = inner.new_leaf(absolute(60.0, 60.0, 20.0, 20.0)).unwrap();
+            let root = Style { size: Size { width: length(100.0), height: length(100.0) }, ..Default::default() };
+            let root = inner.new_with_children(root, &[outer_box, far_box]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (root, [outer_box, inner_box, far_box].map(js_node_id))
+        };
+        (tree, js_node_id(root), boxes)
+    }
+
+    #[test]
+    fn intersecting_nodes_include_partially_covered_boxes() {
+        let (tree, root, [outer_box, inner_box, far_box]) = boxes();
+
+        // Covers the bottom right of the outer box and the top left of the inner box, at (30, 30) to (50, 50)
+        assert_eq!(tree.nodes_in_rect(root, 25.0, 25.0, 10.0, 10.0, false), Ok(vec![root, outer_box, inner_box]));
+        assert_eq!(tree.nodes_in_rect(root, 55.0, 55.0, 10.0, 10.0, false), Ok(vec![root, far_box]));
+
+        // Touching an edge is not an intersection
+        assert_eq!(tree.nodes_in_rect(root, 80.0, 80.0, 10.0, 10.0, false), Ok(vec![root]));
+        assert_eq!(tree.nodes_in_rect(root, 100.0, 0.0, 10.0, 10.0, false), Ok(vec![]));
+    }
+
+    #[test]
+    fn contained_nodes_exclude_partially_covered_boxes() {
+        let (tree, root, [outer_box, inner_box, far_box]) = boxes();
+
+        assert_eq!(tree.nodes_in_rect(root, 25.0, 25.0, 10.0, 10.0, true), Ok(vec![]));
+        assert_eq!(tree.nodes_in_rect(root, 25.0, 25.0, 30.0, 30.0, true), Ok(vec![inner_box]));
+        assert_eq!(tree.nodes_in_rect(root, 10.0, 10.0, 70.0, 70.0, true), Ok(vec![outer_box, inner_box, far_box]));
+        assert_eq!(
+            tree.nodes_in_rect(root, 0.0, 0.0, 100.0, 100.0, true),
+            Ok(vec![root, outer_box, inner_box, far_box])
+        );
+        assert_eq!(tree.nodes_in_rect(root + 10, 0.0, 0.0, 1.0, 1.0, true), Err(TaffyError::InvalidNode(root + 10)));
+    }
+}
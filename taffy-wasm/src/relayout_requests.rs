@@ -0,0 +1,88 @@
+//! Coalescing the relayouts requested while styles change, to lay out each root at most once per frame
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Lays out every root with a pending relayout request, returning how many were laid out
+    fn flush_pending_relayouts(&self) -> Result<u32, TaffyError> {
+        // Checked before any request is taken, so that the requests stay pending until the batch ends
+        self.check_no_batch()?;
+        let mut laid_out = 0;
+        // Requests are taken one at a time, so that those after a failed one stay pending
+        loop {
+            let Some((root, available_space)) = self.node_states.borrow_mut().take_pending_relayout() else {
+                break;
+            };
+            if self.compute_subtree_if_changed(root, available_space)? {
+                laid_out += 1;
+            }
+        }
+        Ok(laid_out)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Requests that the tree rooted at `root` be laid out within `width` by `height` at the next
+    /// `flush_relayouts`, without laying it out now
+    ///
+    /// This lets code that changes styles request a relayout after every change, e.g. as each property of a live
+    /// edit arrives, while the tree is laid out only once per frame by calling `flush_relayouts` from
+    /// `requestAnimationFrame`. Requests for the same root coalesce: the available space of the latest request
+    /// wins, and the root is laid out once. A request for a node that is removed before the flush is dropped.
+    #[wasm_bindgen]
+    pub fn request_relayout(&self, root: u32, width: f32, height: f32) -> Result<(), TaffyError> {
+        resolve_node(&*self.tree()?, root)?;
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        self.node_states.borrow_mut().update(root, |state| state.pending_relayout = Some(available_space));
+        Ok(())
+    }
+
+    /// Lays out every root with a pending `request_relayout`, in order of their ids, and returns how many were laid
+    /// out
+    ///
+    /// Each root is laid out as by `compute_layout_if_changed`, so a root that hasn't changed since its last layout
+    /// and was requested at a size that rounds to the same whole pixels is not laid out again, and isn't counted.
+    /// The requests are cleared as they are carried out. If a layout throws, e.g. with a `LAYOUT_TOO_DEEP` error,
+    /// its request is dropped and the requests for the roots after it stay pending for the next flush.
+    #[wasm_bindgen]
+    pub fn flush_relayouts(&self) -> Result<u32, TaffyError> {
+        self.flush_pending_relayouts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::style::Style;
+
+    #[test]
+    fn many_requests_lay_out_once_at_flush() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: percent(0.5), ..Default::default() }).unwrap();
+            let root = Style { size: percent(1.0), ..Default::default() };
+            (js_node_id(inner.new_with_children(root, &[child]).unwrap()), js_node_id(child))
+        };
+
+        for width in 1..=100 {
+            tree.request_relayout(root, width as f32 * 10.0, 200.0).unwrap();
+        }
+        // Nothing is laid out until the flush
+        assert_eq!(tree.dirty(root), Ok(true));
+        assert_eq!(tree.flush_relayouts(), Ok(1));
+        assert_eq!(tree.last_compute_stats.get().nodes_laid_out, 2);
+        // The latest request wins
+        assert_eq!(tree.layout_width(child), Ok(500.0));
+        assert_eq!(tree.layout_height(child), Ok(100.0));
+
+        // The requests were cleared, and an unchanged root isn't laid out again
+        assert_eq!(tree.flush_relayouts(), Ok(0));
+        tree.request_relayout(root, 1000.0, 200.0).unwrap();
+        assert_eq!(tree.flush_relayouts(), Ok(0));
+        assert_eq!(tree.request_relayout(root + 1, 1.0, 1.0), Err(TaffyError::InvalidNode(root + 1)));
+    }
+}
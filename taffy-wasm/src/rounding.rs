@@ -0,0 +1,146 @@
+//! Taffy's rounding of layout values to whole pixels, for rounding values computed in JS consistently
+use crate::{TaffyError, TaffyTree};
+use wasm_bindgen::prelude::*;
+
+/// Rounds `value` to a whole number of pixels exactly as taffy rounds layouts
+///
+/// This is the rounding applied to the layouts these bindings return, unless changed with `set_rounding_mode`: to the nearest whole number, with halves
+/// rounded away from zero. It differs from JS's `Math.round`, which rounds halves towards positive infinity, so
+/// `round_layout_value(-2.5)` is `-3` where `Math.round(-2.5)` is `-2`.
+///
+/// taffy rounds the edges of boxes rather than their sizes: a node's rounded `x` is the rounded offset from its
+/// parent, and its rounded width is `round(absoluteX + width) - round(absoluteX)`, using unrounded absolute
+/// positions. Positions derived in JS line up with the boxes without seams when they are rounded the same way.
+#[wasm_bindgen]
+pub fn round_layout_value(value: f32) -> f32 {
+    value.round()
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sets which layouts are rounded to whole pixels: `"all"` (the default), `"none"`, `"leaf-only"` or
+    /// `"position-only"`
+    ///
+    /// With `"all"`, every node's box is rounded as described for `round_layout_value`, and with `"none"` layouts
+    /// are returned exactly as computed. With `"leaf-only"`, only nodes without children, such as text and images,
+    /// are rounded: their boxes start and end on whole pixels relative to the root, while containers keep their
+    /// fractional boxes. This keeps content crisp without the containers it is aligned in drifting from their
+    /// computed positions, but a leaf no longer lines up exactly with its container: a leaf's `x` and `y` relative
+    /// to a fractional parent are fractional, and a leaf that fills its container may over- or underhang it by up
+    /// to half a pixel on each side. Use `absolute_layout` for a leaf's whole-pixel position.
+    ///
+    /// With `"position-only"`, every node's `x` and `y` are rounded as with `"all"`, so boxes start on the same
+    /// whole pixels, but sizes, padding and borders are left as computed. Items of equal computed width keep equal
+    /// widths, e.g. the columns of a list laid out in a fractional container, where `"all"` makes some of them a
+    /// pixel wider than others to close the gaps between them. The tradeoff is that sizes are not whole numbers,
+    /// so the far edges of boxes fall between pixels and can look blurry, and a box can end up to a pixel before or
+    /// after the start of the next one.
+    ///
+    /// Switching to `"none"` takes effect immediately; the other modes apply from the next layout. Throws an
+    /// `INVALID_ARGUMENT` error for any other mode.
+    #[wasm_bindgen]
+    pub fn set_rounding_mode(&self, mode: &str) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        match mode {
+            "all" => tree.enable_rounding(),
+            "none" => tree.disable_rounding(),
+            "leaf-only" => tree.enable_leaf_rounding(),
+            "position-only" => tree.enable_position_rounding(),
+            mode => return Err(TaffyError::InvalidArgument(format!("unknown rounding mode {mode:?}"))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::absolute_layout::AbsoluteLayout;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use crate::TaffyTree;
+    use taffy::prelude::{length, FlexDirection, Rect, Size, Style, TaffyMaxContent};
+
+    #[test]
+    fn halves_are_rounded_away_from_zero() {
+        let cases =
+            [(0.0, 0.0), (0.49, 0.0), (0.5, 1.0), (1.5, 2.0), (2.5, 3.0), (-0.5, -1.0), (-1.4, -1.0), (-2.5, -3.0)];
+        for (value, rounded) in cases {
+            assert_eq!(round_layout_value(value), rounded, "{value}");
+        }
+    }
+
+    #[test]
+    fn matches_rounded_layouts() {
+        // Two 10.5px wide leaves in a row: the first ends and the second starts at the same rounded edge
+        let tree = TaffyTree::new();
+        let (row, leaves) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = Style { size: Size { width: length(10.5), height: length(10.0) }, ..Default::default() };
+            let leaves = [inner.new_leaf(leaf.clone()).unwrap(), inner.new_leaf(leaf).unwrap()];
+            (js_node_id(inner.new_with_children(Style::default(), &leaves).unwrap()), leaves.map(js_node_id))
+        };
+        tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(leaves[0]), Ok(round_layout_value(10.5)));
+        assert_eq!(tree.layout_left(leaves[1]), Ok(round_layout_value(10.5)));
+        assert_eq!(tree.layout_width(leaves[1]), Ok(round_layout_value(21.0) - round_layout_value(10.5)));
+    }
+
+    /// Lays out two text leaves in a row with fractional padding and heights under `mode`, returning the boxes of
+    /// the row and the leaves relative to the root
+    fn text_row_boxes(mode: &str) -> [AbsoluteLayout; 3] {
+        let tree = TaffyTree::new();
+        let (root, nodes) = {
+            let mut inner = tree.inner.borrow_mut();
+            let texts = [inner.new_leaf(Style::default()).unwrap(), inner.new_leaf(Style::default()).unwrap()];
+            let row_style = Style { padding: Rect { left: length(0.25), ..Rect::zero() }, ..Default::default() };
+            let row = inner.new_with_children(row_style, &texts).unwrap();
+            let column = Style { flex_direction: FlexDirection::Column, padding: length(0.5), ..Default::default() };
+            let root = inner.new_with_children(column, &[row]).unwrap();
+            (js_node_id(root), [row, texts[0], texts[1]].map(js_node_id))
+        };
+        tree.set_content_size(nodes[1], 10.25, 7.5).unwrap();
+        tree.set_content_size(nodes[2], 20.5, 7.5).unwrap();
+        tree.set_rounding_mode(mode).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        nodes.map(|node| tree.absolute_box(node).unwrap())
+    }
+
+    #[test]
+    fn rounding_modes_round_all_none_or_only_leaves() {
+        let boxes = |mode| text_row_boxes(mode).map(|b| (b.x, b.y, b.width, b.height));
+        assert_eq!(boxes("none"), [(0.5, 0.5, 31.0, 7.5), (0.75, 0.5, 10.25, 7.5), (11.0, 0.5, 20.5, 7.5)]);
+        // The second text's `x` relative to the row is rounded on its own, from 10.5 to 11
+        assert_eq!(boxes("all"), [(1.0, 1.0, 31.0, 7.0), (1.0, 1.0, 10.0, 7.0), (12.0, 1.0, 21.0, 7.0)]);
+        // The row keeps its fractional box while the text within it snaps to whole pixels
+        assert_eq!(boxes("leaf-only"), [(0.5, 0.5, 31.0, 7.5), (1.0, 1.0, 10.0, 7.0), (11.0, 1.0, 21.0, 7.0)]);
+
+        let tree = TaffyTree::new();
+        assert!(matches!(tree.set_rounding_mode("leaves"), Err(TaffyError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn position_only_rounding_keeps_equal_widths() {
+        // Three items sharing a 100px row equally, each 33.33px wide
+        let tree = TaffyTree::new();
+        let (row, items) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { flex_grow: 1.0, flex_basis: length(0.0), ..Default::default() };
+            let items = [(); 3].map(|_| inner.new_leaf(item.clone()).unwrap());
+            let row = Style { size: Size { width: length(100.0), height: length(10.0) }, ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        let boxes = |mode| {
+            tree.set_rounding_mode(mode).unwrap();
+            tree.mark_dirty(row).unwrap();
+            tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+            items.map(|item| (tree.layout_left(item).unwrap(), tree.layout_width(item).unwrap()))
+        };
+
+        // Full rounding closes the gaps between the items by making the middle one a pixel wider
+        assert_eq!(boxes("all"), [(0.0, 33.0), (33.0, 34.0), (67.0, 33.0)]);
+        // Position-only rounding starts the items on the same pixels, but keeps them as wide as each other
+        let third = tree.inner.borrow().unrounded_layout(node_from_js_id(items[0])).size.width;
+        assert!((third - 100.0 / 3.0).abs() < 0.001, "{third}");
+        assert_eq!(boxes("position-only"), [(0.0, third), (33.0, third), (67.0, third)]);
+    }
+}
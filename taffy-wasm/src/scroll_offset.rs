@@ -0,0 +1,81 @@
+//! Storing how far scroll containers are scrolled, so that hit testing finds the content scrolled under a point
+use crate::node_state::NodeStates;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::Point;
+use wasm_bindgen::prelude::*;
+
+/// Returns the location of the children of a node at `origin` that is scrolled by its scroll offset in
+/// `node_states`, if it has one, relative to the same origin
+pub(crate) fn scrolled_origin(node_states: &NodeStates, node_id: u32, origin: Point<f32>) -> Point<f32> {
+    match node_states.get(node_id).scroll_offset {
+        Some(offset) => Point { x: origin.x - offset.x, y: origin.y - offset.y },
+        None => origin,
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sets how far the content of a node is scrolled, `x` pixels right and `y` pixels down, for hit testing
+    ///
+    /// The children of the node, and all their descendants, are moved up and left by the offset when looking for
+    /// the nodes under a point with `node_at_point` or within a rectangle with `nodes_in_rect`, as they are when the
+    /// node is scrolled on screen, while the node itself stays in place. Offsets of nested scroll containers add up.
+    /// Layouts, `layout_buffer` and hit indices built with `build_hit_index` are not affected, and setting an offset
+    /// doesn't mark the node dirty. An offset of `(0, 0)` removes the stored offset, and it is dropped when the node
+    /// is removed. The offset is not clamped to the scrollable area, so a caller with its own overscroll can pass it
+    /// through unchanged.
+    #[wasm_bindgen]
+    pub fn set_scroll_offset(&self, node_id: u32, x: f32, y: f32) -> Result<(), TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        let scroll_offset = (x != 0.0 || y != 0.0).then_some(Point { x, y });
+        self.node_states.borrow_mut().update(node_id, |state| state.scroll_offset = scroll_offset);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::prelude::{length, FlexDirection, Size, Style, TaffyMaxContent};
+    use taffy::style::Overflow;
+
+    #[test]
+    fn scrolled_out_child_is_no_longer_hit() {
+        let tree = TaffyTree::new();
+        let (root, scroller, [first, second]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style {
+                size: Size { width: length(50.0), height: length(40.0) },
+                flex_shrink: 0.0,
+                ..Default::default()
+            };
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item).unwrap()];
+            let scroller = Style {
+                flex_direction: FlexDirection::Column,
+                overflow: Point { x: Overflow::Visible, y: Overflow::Scroll },
+                size: Size { width: length(50.0), height: length(50.0) },
+                ..Default::default()
+            };
+            let scroller = inner.new_with_children(scroller, &items).unwrap();
+            let root = inner.new_with_children(Style::default(), &[scroller]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (js_node_id(root), js_node_id(scroller), items.map(js_node_id))
+        };
+        assert_eq!(tree.node_at_point(root, 10.0, 30.0), Ok(Some(first)));
+        assert_eq!(tree.nodes_in_rect(root, 0.0, 45.0, 50.0, 5.0, false), Ok(vec![root, scroller, second]));
+
+        // Scrolling down by 40px brings the second item to the top of the scroller
+        tree.set_scroll_offset(scroller, 0.0, 40.0).unwrap();
+        assert_eq!(tree.node_at_point(root, 10.0, 30.0), Ok(Some(second)));
+        assert_eq!(tree.node_at_point(root, 10.0, 5.0), Ok(Some(second)));
+        assert_eq!(tree.nodes_in_rect(root, 0.0, 0.0, 50.0, 50.0, true), Ok(vec![root, scroller, second]));
+
+        // Only the padding box of the scroller can be hit, so the first item scrolled above it can't be
+        assert_eq!(tree.node_at_point(root, 10.0, -10.0), Ok(None));
+
+        tree.set_scroll_offset(scroller, 0.0, 0.0).unwrap();
+        assert_eq!(tree.node_at_point(root, 10.0, 30.0), Ok(Some(first)));
+        assert_eq!(tree.set_scroll_offset(99, 0.0, 1.0), Err(TaffyError::InvalidNode(99)));
+    }
+}
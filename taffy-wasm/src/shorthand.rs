@@ -0,0 +1,80 @@
+//! Parsing CSS shorthand strings, e.g. `"10px 20px"`, into the shapes used by style objects
+use crate::TaffyError;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// Parses a non-negative `<length-percentage>` written as pixels (`"10px"`), a percentage (`"50%"`) or a unitless zero
+fn parse_length_percentage(token: &str) -> Option<LengthPercentage> {
+    let (number, unit) = match token {
+        "0" => return Some(LengthPercentage::ZERO),
+        _ if token.ends_with("px") => (&token[..token.len() - 2], "px"),
+        _ if token.ends_with('%') => (&token[..token.len() - 1], "%"),
+        _ => return None,
+    };
+    let value: f32 = number.parse().ok().filter(|value: &f32| value.is_finite() && *value >= 0.0)?;
+    Some(if unit == "px" { length(value) } else { percent(value / 100.0) })
+}
+
+/// Parses a CSS `gap` shorthand: one value for both axes, or a row gap followed by a column gap
+pub(crate) fn parse_gap(input: &str) -> Result<Size<LengthPercentage>, TaffyError> {
+    let invalid = || {
+        TaffyError::InvalidArgument(format!(
+            "{input:?} is not a valid gap, expected one or two lengths such as \"10px\" or \"10px 5%\""
+        ))
+    };
+    let values = input
+        .split_whitespace()
+        .map(|token| parse_length_percentage(token).ok_or_else(invalid))
+        .collect::<Result<Vec<_>, _>>()?;
+    match values[..] {
+        [gap] => Ok(Size { width: gap, height: gap }),
+        [row, column] => Ok(Size { width: column, height: row }),
+        _ => Err(invalid()),
+    }
+}
+
+/// Returns the `gap` of a style object for a CSS `gap` shorthand string
+///
+/// `"10px"` sets both gaps, and `"10px 20px"` sets the gap between rows to 10px and the gap between columns to 20px, as
+/// in CSS. Each value is a number of pixels (`"10px"`), a percentage of the container's size (`"5%"`) or `"0"`. The
+/// result can be used as-is, e.g. `tree.new_leaf({ gap: gap("8px 16px") })`: the row gap is its `height` and the
+/// column gap its `width`.
+///
+/// Throws an `INVALID_ARGUMENT` error if `input` is not one or two non-negative lengths or percentages.
+#[wasm_bindgen]
+pub fn gap(input: &str) -> Result<JsValue, TaffyError> {
+    let gap = parse_gap(input)?;
+    serde_wasm_bindgen::to_value(&gap).map_err(|e| TaffyError::InvalidArgument(format!("gap: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_value_sets_both_axes() {
+        assert_eq!(parse_gap("10px"), Ok(Size { width: length(10.0), height: length(10.0) }));
+        assert_eq!(parse_gap(" 0 "), Ok(Size::zero()));
+    }
+
+    #[test]
+    fn two_values_are_row_then_column() {
+        assert_eq!(parse_gap("10px 25%"), Ok(Size { width: percent(0.25), height: length(10.0) }));
+        assert_eq!(parse_gap("1.5px\t2px"), Ok(Size { width: length(2.0), height: length(1.5) }));
+    }
+
+    #[test]
+    fn parsed_gap_decodes_as_a_style_gap() {
+        let gap = parse_gap("8px 50%").unwrap();
+        let json = serde_json::json!({ "gap": serde_json::to_value(gap).unwrap() });
+        let crate::JsStyle(style) = serde_json::from_value(json).unwrap();
+        assert_eq!(style.gap, gap);
+    }
+
+    #[test]
+    fn malformed_gaps_are_rejected() {
+        for input in ["", "10", "10px 20px 30px", "-1px", "10em", "px", "10 px", "NaNpx", "auto"] {
+            assert!(matches!(parse_gap(input), Err(TaffyError::InvalidArgument(_))), "{input:?} should be rejected");
+        }
+    }
+}
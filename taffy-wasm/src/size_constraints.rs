@@ -0,0 +1,141 @@
+//! Reporting the min and max sizes a node was clamped to, for debugging why it doesn't grow or shrink
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::prelude::*;
+use taffy::{MaybeMath, MaybeResolve};
+use wasm_bindgen::prelude::*;
+
+/// The min and max border-box sizes of a node in pixels, returned by `resolved_size_constraints`
+///
+/// An axis is `None` where the node is unconstrained: its min or max size is `auto`, or a percentage of an
+/// indefinite size.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResolvedSizeConstraints {
+    /// The resolved `min_size` of the node
+    pub min: Size<Option<f32>>,
+    /// The resolved `max_size` of the node
+    pub max: Size<Option<f32>>,
+}
+
+/// Returns the total size of the edges of `rect` in each axis
+fn edges_size(rect: Rect<f32>) -> Size<f32> {
+    Size { width: rect.horizontal_components().sum(), height: rect.vertical_components().sum() }
+}
+
+impl TaffyTree {
+    /// Returns the size that the percentages of `node_id` resolved against in its last layout
+    ///
+    /// This is the content box of its parent, or the padding box for an absolutely positioned node. A root resolves
+    /// against the definite axes of the space it was last laid out in.
+    fn percentage_basis(&self, node_id: u32) -> Result<Size<Option<f32>>, TaffyError> {
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let Some(parent) = tree.parent(node) else {
+            let available_space = self.node_states.borrow().get(node_id).last_available_space;
+            return Ok(available_space.map_or(Size::NONE, |space| space.map(AvailableSpace::into_option)));
+        };
+        let layout = tree.layout(parent)?;
+        let basis = match tree.style(node)?.position {
+            Position::Absolute => layout.size - edges_size(layout.border),
+            Position::Relative => layout.content_box_size(),
+        };
+        Ok(basis.map(Some))
+    }
+
+    /// Resolves the min and max sizes of `node_id` as its last layout did
+    fn size_constraints(&self, node_id: u32) -> Result<ResolvedSizeConstraints, TaffyError> {
+        let basis = self.percentage_basis(node_id)?;
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        let style = tree.style(node)?;
+        let layout = tree.layout(node)?;
+        // Sizes are clamped as border boxes, so content-box sizes grow by the padding and border the node was laid
+        // out with
+        let box_sizing_adjustment = match style.box_sizing {
+            BoxSizing::BorderBox => Size::ZERO,
+            BoxSizing::ContentBox => edges_size(layout.padding + layout.border),
+        };
+        let resolve = |size: Size<Dimension>| size.maybe_resolve(basis, |_, _| 0.0).maybe_add(box_sizing_adjustment);
+        Ok(ResolvedSizeConstraints { min: resolve(style.min_size), max: resolve(style.max_size) })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the min and max sizes of a node in pixels, as resolved by the last layout, as
+    /// `{ min: { width, height }, max: { width, height } }`
+    ///
+    /// These are the bounds that the node's size was clamped to: a node that won't grow past 200px likely has a
+    /// `max.width` of 200. Percentages are resolved against the size of the parent in its last layout: its content
+    /// box, or its padding box for an absolutely positioned node. A root resolves them against the space it was
+    /// last laid out in. The sizes are border-box sizes, so the padding and border of a node with
+    /// `box_sizing: "ContentBox"` are included. An axis that is unconstrained, because its min or max size is
+    /// `auto` or a percentage of an indefinite size, is `null`.
+    ///
+    /// Grid items are resolved against their parent like other nodes, although the grid algorithm resolves their
+    /// percentages against their grid area, so their percentage constraints may differ from the ones applied. The
+    /// result is only meaningful once the node has been laid out.
+    #[wasm_bindgen]
+    pub fn resolved_size_constraints(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let constraints = self.size_constraints(node_id)?;
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_missing_as_null(true);
+        Ok(constraints.serialize(&serializer).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn percentage_max_width_resolves_against_parent() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner
+                .new_leaf(Style {
+                    size: length(300.0),
+                    min_size: Size { width: length(20.0), height: auto() },
+                    max_size: Size { width: percent(0.5), height: percent(0.5) },
+                    ..Default::default()
+                })
+                .unwrap();
+            let root = Style {
+                size: Size { width: length(400.0), height: auto() },
+                padding: length(10.0),
+                ..Default::default()
+            };
+            (js_node_id(inner.new_with_children(root, &[child]).unwrap()), js_node_id(child))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        // The parent's content box is 380px wide, and its height depended on the child so is indefinite to it
+        let constraints = tree.size_constraints(child).unwrap();
+        assert_eq!(constraints.min, Size { width: Some(20.0), height: None });
+        assert_eq!(constraints.max.width, Some(190.0));
+        assert_eq!(tree.layout_width(child), Ok(190.0));
+
+        let root_constraints = tree.size_constraints(root).unwrap();
+        assert_eq!(root_constraints, ResolvedSizeConstraints { min: Size::NONE, max: Size::NONE });
+    }
+
+    #[test]
+    fn content_box_constraints_include_padding() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let style = Style {
+                box_sizing: BoxSizing::ContentBox,
+                padding: length(5.0),
+                max_size: Size { width: percent(0.5), height: length(30.0) },
+                ..Default::default()
+            };
+            js_node_id(inner.new_leaf(style).unwrap())
+        };
+        let available_space = Size { width: AvailableSpace::Definite(200.0), height: AvailableSpace::MaxContent };
+        tree.compute_subtree(root, available_space).unwrap();
+        let constraints = tree.size_constraints(root).unwrap();
+        assert_eq!(constraints.max, Size { width: Some(110.0), height: Some(40.0) });
+    }
+}
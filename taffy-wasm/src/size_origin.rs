@@ -0,0 +1,156 @@
+//! Reporting what decided each dimension of a node's size, for inspectors answering "why is this box this size"
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// What decided the width and height of a node, returned by `size_origin`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct SizeOrigin {
+    width: &'static str,
+    height: &'static str,
+}
+
+impl TaffyTree {
+    /// Returns what decided each dimension of a node's size, inferred from its style and its parent's
+    fn size_origin_of(&self, node_id: u32) -> Result<SizeOrigin, TaffyError> {
+        let alignment = self.item_alignment(node_id)?;
+        let tree = self.tree()?;
+        let node = resolve_node(&tree, node_id)?;
+        for ancestor in std::iter::successors(Some(node), |&ancestor| tree.parent(ancestor)) {
+            if tree.style(ancestor)?.display == Display::None {
+                return Ok(SizeOrigin { width: "none", height: "none" });
+            }
+        }
+        let style = tree.style(node)?;
+        let parent = tree.parent(node).map(|parent| tree.style(parent)).transpose()?;
+        let is_absolute = parent.is_some() && style.position == Position::Absolute;
+        // Either axis is definite if it has a size, or if the other one does and an aspect ratio transfers it
+        let has_size =
+            |size: Dimension, other: Dimension| !size.is_auto() || (style.aspect_ratio.is_some() && !other.is_auto());
+        let (width_is_definite, height_is_definite) =
+            (has_size(style.size.width, style.size.height), has_size(style.size.height, style.size.width));
+        // Whether a flex item's size in the main axis is decided by the flex algorithm rather than by its base size
+        let flexes = style.flex_grow > 0.0 || !style.flex_basis.is_auto();
+
+        let origin = |is_main_axis: bool,
+                      is_definite: bool,
+                      align: Option<AlignSelf>,
+                      start: LengthPercentageAuto,
+                      end: LengthPercentageAuto| {
+            if is_absolute {
+                // An absolutely positioned node with both insets set in an axis fills the space between them
+                return if is_definite {
+                    "definite"
+                } else if !start.is_auto() && !end.is_auto() {
+                    "stretch"
+                } else {
+                    "content"
+                };
+            }
+            if is_main_axis && flexes {
+                "flex"
+            } else if is_definite {
+                "definite"
+            } else if align == Some(AlignSelf::Stretch) {
+                "stretch"
+            } else {
+                "content"
+            }
+        };
+
+        let (width_is_main, height_is_main, width_align, height_align) = match parent {
+            None => (false, false, None, None),
+            Some(parent) => match parent.display {
+                Display::Flex if matches!(parent.flex_direction, FlexDirection::Row | FlexDirection::RowReverse) => {
+                    (true, false, None, alignment.align_self)
+                }
+                Display::Flex => (false, true, alignment.align_self, None),
+                #[cfg(feature = "grid")]
+                Display::Grid => (false, false, alignment.justify_self, alignment.align_self),
+                // Block-level children fill the width of their container
+                _ => (false, false, Some(AlignSelf::Stretch), None),
+            },
+        };
+        Ok(SizeOrigin {
+            width: origin(width_is_main, width_is_definite, width_align, style.inset.left, style.inset.right),
+            height: origin(height_is_main, height_is_definite, height_align, style.inset.top, style.inset.bottom),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns what decided each dimension of a node's size, as `{ width, height }`
+    ///
+    /// Each is one of:
+    ///
+    /// - `"definite"`: the node's `size` in that axis, or its size in the other axis transferred by `aspect_ratio`
+    /// - `"flex"`: the flex algorithm, for the main axis of a flex item that grows or has a `flex_basis`
+    /// - `"stretch"`: the space the parent gives the node, for a grid item or the cross axis of a flex item that is
+    ///   stretched as in `resolved_item_alignment`, or an absolutely positioned node with both insets in the axis set
+    /// - `"content"`: the node's content, i.e. its children, or its content size or measure function for a leaf
+    /// - `"none"`: nothing, for a node with `display: "None"` or with an ancestor that has it
+    ///
+    /// The origin is inferred from the current styles of the node and its parent, following the rules the layout
+    /// sizes it by, so it describes the last layout unless either has changed since. It names where the size
+    /// started from, before `min_size` and `max_size` clamp it, and a percentage `size` is `"definite"` even if its
+    /// parent's size is not, in which case the layout treats it as `auto`. A flex item that doesn't grow is
+    /// `"content"` or `"definite"` even if `flex_shrink` shrinks it to fit its container.
+    ///
+    /// Throws an `INVALID_NODE` error if the node is not in the tree.
+    #[wasm_bindgen]
+    pub fn size_origin(&self, node_id: u32) -> Result<JsValue, TaffyError> {
+        let origin = self.size_origin_of(node_id)?;
+        Ok(serde_wasm_bindgen::to_value(&origin).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(all(test, feature = "grid"))]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    #[test]
+    fn size_origin_follows_style_flex_and_stretch() {
+        let tree = TaffyTree::new();
+        let [grower, sized, text, grid_item, overlay, hidden, root] = {
+            let mut inner = tree.inner.borrow_mut();
+            let grower = inner.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+            let sized = inner.new_leaf(Style { size: length(20.0), ..Default::default() }).unwrap();
+            let text = inner
+                .new_leaf(Style { align_self: Some(AlignSelf::Center), aspect_ratio: Some(2.0), ..Default::default() })
+                .unwrap();
+            let grid_item = inner
+                .new_leaf(Style { size: Size { width: auto(), height: length(10.0) }, ..Default::default() })
+                .unwrap();
+            let grid =
+                inner.new_with_children(Style { display: Display::Grid, ..Default::default() }, &[grid_item]).unwrap();
+            let overlay = inner
+                .new_leaf(Style {
+                    position: Position::Absolute,
+                    inset: Rect { left: length(0.0), right: length(0.0), top: length(0.0), bottom: auto() },
+                    ..Default::default()
+                })
+                .unwrap();
+            let hidden = inner.new_leaf(Style::default()).unwrap();
+            let hidden_parent =
+                inner.new_with_children(Style { display: Display::None, ..Default::default() }, &[hidden]).unwrap();
+            let root = inner
+                .new_with_children(Style::default(), &[grower, sized, text, grid, overlay, hidden_parent])
+                .unwrap();
+            [grower, sized, text, grid_item, overlay, hidden, root].map(js_node_id)
+        };
+
+        let origin = |node| tree.size_origin_of(node).unwrap();
+        let origins = |width, height| SizeOrigin { width, height };
+        // The root lays its children out in a row, so a growing width is flexed, and heights stretch unless aligned
+        assert_eq!(origin(grower), origins("flex", "stretch"));
+        assert_eq!(origin(sized), origins("definite", "definite"));
+        assert_eq!(origin(text), origins("content", "content"));
+        assert_eq!(origin(grid_item), origins("stretch", "definite"));
+        assert_eq!(origin(overlay), origins("stretch", "content"));
+        assert_eq!(origin(hidden), origins("none", "none"));
+        assert_eq!(origin(root), origins("content", "content"));
+    }
+}
@@ -0,0 +1,174 @@
+//! Capturing and restoring the whole state of a [`TaffyTree`], e.g. to implement undo and redo
+use crate::node_state::NodeStates;
+use crate::previous_layout::PreviousLayouts;
+use crate::{JsContext, TaffyError, TaffyTree};
+use std::collections::BTreeSet;
+use taffy::TaffyTree as Taffy;
+use wasm_bindgen::prelude::*;
+
+/// A copy of the whole state of a [`TaffyTree`], created by `TaffyTree.snapshot`
+///
+/// The snapshot is independent of the tree it was taken from, and can be restored into it any number of times.
+#[wasm_bindgen]
+pub struct TreeSnapshot {
+    pub(crate) tree: Taffy<JsContext>,
+    /// The ids of the placeholders that keep the slots of removed nodes occupied
+    pub(crate) retired: BTreeSet<u32>,
+    /// The state of each node kept outside the core tree
+    node_states: NodeStates,
+    /// The factor the pixel lengths of the styles in `tree` were multiplied by
+    length_scale: f32,
+}
+
+impl TaffyTree {
+    /// Replaces the state kept for each node outside the core tree, and discards what was derived from the layouts
+    /// the tree had before
+    fn restore_node_states(&self, node_states: NodeStates) {
+        *self.node_states.borrow_mut() = node_states;
+        self.last_measured_nodes.borrow_mut().clear();
+        self.hit_indices.borrow_mut().clear();
+        self.lod_indices.borrow_mut().clear();
+        if let Some(previous) = self.previous_layouts.borrow_mut().as_mut() {
+            *previous = PreviousLayouts::default();
+        }
+        if let Some(timings) = self.node_timings.borrow_mut().as_mut() {
+            timings.clear();
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TreeSnapshot {
+    /// The number of nodes in the snapshot
+    #[wasm_bindgen(getter)]
+    pub fn node_count(&self) -> usize {
+        self.tree.total_node_count() - self.retired.len()
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Captures the whole state of the tree: its nodes and their ids, children, styles, contexts and layouts, and
+    /// what was set on each node outside its style, such as content sizes, tags, user data, pinned sizes and
+    /// whether it is frozen or hidden
+    ///
+    /// Node contexts are copied by reference: the snapshot holds the same JS objects as the tree, so changes made
+    /// to a context object after the snapshot is taken are visible after restoring it. Set a new context object
+    /// with `set_node_context` instead to keep the old one in the snapshot.
+    #[wasm_bindgen]
+    pub fn snapshot(&self) -> Result<TreeSnapshot, TaffyError> {
+        Ok(TreeSnapshot {
+            tree: self.tree()?.clone(),
+            retired: self.retired.borrow().clone(),
+            node_states: self.node_states.borrow().clone(),
+            length_scale: self.length_scale.get(),
+        })
+    }
+
+    /// Replaces the whole state of the tree with a snapshot taken by `snapshot`
+    ///
+    /// Node ids are stable: every node in the snapshot has the id it had when the snapshot was taken, so ids held
+    /// in JS keep referring to the same nodes. Nodes created after the snapshot was taken do not exist once it is
+    /// restored, and using their ids throws an `INVALID_NODE` error until the id is reused by a newly created node.
+    /// Layouts are restored too, so there is no need to recompute the layout of an unchanged snapshot. What was set
+    /// on each node outside its style is restored with it, as is the length scale the styles were scaled by, while
+    /// what was recorded about earlier layouts, such as previous layouts, profiling timings and hit indices, is
+    /// discarded. The tree's other settings, such as its default box sizing, are kept.
+    ///
+    /// The snapshot is left intact and can be restored again. Snapshots can be restored into any `TaffyTree`,
+    /// not only the one they were taken from.
+    #[wasm_bindgen]
+    pub fn restore(&self, snapshot: &TreeSnapshot) -> Result<(), TaffyError> {
+        *self.tree_mut()? = snapshot.tree.clone();
+        *self.retired.borrow_mut() = snapshot.retired.clone();
+        self.restore_node_states(snapshot.node_states.clone());
+        self.length_scale.set(snapshot.length_scale);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use crate::TaffyTree;
+    use taffy::prelude::*;
+
+    #[test]
+    fn restore_preserves_ids_structure_and_styles() {
+        let tree = TaffyTree::new();
+        let (root, child) = {
+            let mut inner = tree.inner.borrow_mut();
+            let child = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let root = inner.new_with_children(Style::default(), &[child]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            (root, child)
+        };
+        let snapshot = tree.snapshot().unwrap();
+        assert_eq!(snapshot.node_count(), 2);
+
+        // Edit the tree after the snapshot was taken
+        let added = {
+            let mut inner = tree.inner.borrow_mut();
+            inner.set_style(child, Style { size: length(50.0), ..Default::default() }).unwrap();
+            let added = inner.new_leaf(Style::default()).unwrap();
+            inner.add_child(root, added).unwrap();
+            added
+        };
+
+        tree.restore(&snapshot).unwrap();
+        {
+            let inner = tree.inner.borrow();
+            assert_eq!(inner.children(root).unwrap(), vec![child]);
+            assert_eq!(inner.style(child).unwrap().size, length(10.0));
+        }
+        assert_eq!(tree.layout_width(js_node_id(child)), Ok(10.0));
+        assert_eq!(tree.layout_width(js_node_id(added)), Err(TaffyError::InvalidNode(js_node_id(added))));
+
+        // The snapshot can be restored again
+        tree.inner.borrow_mut().remove(child).unwrap();
+        tree.restore(&snapshot).unwrap();
+        assert_eq!(tree.layout_width(js_node_id(child)), Ok(10.0));
+    }
+
+    #[test]
+    fn restore_brings_back_what_was_set_on_removed_nodes() {
+        let tree = TaffyTree::new();
+        let (root, leaf) = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style::default()).unwrap();
+            let root = inner.new_with_children(Style::default(), &[leaf]).unwrap();
+            (js_node_id(root), js_node_id(leaf))
+        };
+        tree.set_content_size(leaf, 40.0, 20.0).unwrap();
+        tree.set_user_data(leaf, 7).unwrap();
+        let snapshot = tree.snapshot().unwrap();
+
+        tree.remove(leaf).unwrap();
+        tree.restore(&snapshot).unwrap();
+        tree.inner.borrow_mut().mark_dirty(node_from_js_id(root)).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!((tree.layout_width(leaf), tree.layout_height(leaf)), (Ok(40.0), Ok(20.0)));
+        assert_eq!(tree.get_user_data(leaf), Ok(Some(7)));
+
+        // A node created after the restore takes the id of a node discarded by it, but none of its state
+        let discarded = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        tree.set_content_size(discarded, 90.0, 90.0).unwrap();
+        tree.set_user_data(discarded, 9).unwrap();
+        tree.restore(&snapshot).unwrap();
+        let created = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        assert_eq!(created, discarded);
+        assert_eq!(tree.get_user_data(created), Ok(None));
+        tree.compute_subtree(created, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(created), Ok(0.0));
+    }
+
+    #[test]
+    fn snapshot_fails_while_tree_is_in_use() {
+        let tree = TaffyTree::new();
+        let snapshot = tree.snapshot().unwrap();
+        let _guard = tree.inner.borrow_mut();
+        assert!(matches!(tree.snapshot(), Err(TaffyError::BorrowConflict)));
+        assert_eq!(tree.restore(&snapshot), Err(TaffyError::BorrowConflict));
+    }
+}
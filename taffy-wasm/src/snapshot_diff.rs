@@ -0,0 +1,221 @@
+//! Comparing two snapshots of a tree, e.g. to show what changed between two frames in a devtools timeline
+use crate::node_ids::js_node_id;
+use crate::snapshot::TreeSnapshot;
+use crate::{JsContext, TaffyError};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// A node whose parent differs between two snapshots, listed in `SnapshotDiff::reparented`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reparenting {
+    /// The id of the node
+    pub node: u32,
+    /// The id of the node's parent in the first snapshot, or `None` if it was a root
+    pub from: Option<u32>,
+    /// The id of the node's parent in the second snapshot, or `None` if it is a root
+    pub to: Option<u32>,
+}
+
+/// The style properties and layout fields of a node that differ between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeChanges {
+    /// The id of the node
+    pub node: u32,
+    /// The keys of the style properties that changed, as in the style objects passed to `new_leaf`
+    pub style: Vec<String>,
+    /// The fields of the layout that changed: `order`, `location`, `size`, `content_size`, `scrollbar_size`,
+    /// `border`, `padding` or `margin`
+    pub layout: Vec<String>,
+}
+
+/// The differences between two snapshots, returned by `diff_snapshots`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    /// The ids of the nodes that are only in the second snapshot
+    pub added: Vec<u32>,
+    /// The ids of the nodes that are only in the first snapshot
+    pub removed: Vec<u32>,
+    /// The nodes in both snapshots whose parent changed
+    pub reparented: Vec<Reparenting>,
+    /// The nodes in both snapshots whose style or layout changed
+    pub changed: Vec<NodeChanges>,
+}
+
+/// Returns the nodes of a snapshot keyed by JS id, leaving out the placeholders of removed nodes
+fn nodes_of(snapshot: &TreeSnapshot) -> BTreeMap<u32, NodeId> {
+    snapshot
+        .tree
+        .node_ids()
+        .map(|node| (js_node_id(node), node))
+        .filter(|(id, _)| !snapshot.retired.contains(id))
+        .collect()
+}
+
+/// Returns the keys of the top-level fields that differ between two serialized values, in alphabetical order
+fn changed_fields(a: impl Serialize, b: impl Serialize) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(a)), Ok(serde_json::Value::Object(b))) =
+        (serde_json::to_value(a), serde_json::to_value(b))
+    else {
+        return Vec::new();
+    };
+    a.into_iter().filter(|(key, value)| b.get(key) != Some(value)).map(|(key, _)| key).collect()
+}
+
+/// Returns the changes to a node that is in both trees, or `None` if it is unchanged
+fn node_changes(
+    a: &Taffy<JsContext>,
+    b: &Taffy<JsContext>,
+    node_id: u32,
+    node: NodeId,
+) -> Result<Option<NodeChanges>, TaffyError> {
+    let style = changed_fields(a.style(node)?, b.style(node)?);
+    let layout = changed_fields(a.layout(node)?, b.layout(node)?);
+    Ok((!style.is_empty() || !layout.is_empty()).then_some(NodeChanges { node: node_id, style, layout }))
+}
+
+/// Compares two snapshots, treating nodes with the same id as the same node
+fn diff(a: &TreeSnapshot, b: &TreeSnapshot) -> Result<SnapshotDiff, TaffyError> {
+    let a_nodes = nodes_of(a);
+    let b_nodes = nodes_of(b);
+    let mut diff = SnapshotDiff {
+        added: b_nodes.keys().filter(|id| !a_nodes.contains_key(id)).copied().collect(),
+        removed: a_nodes.keys().filter(|id| !b_nodes.contains_key(id)).copied().collect(),
+        ..SnapshotDiff::default()
+    };
+
+    for (&node_id, &node) in a_nodes.iter().filter(|(id, _)| b_nodes.contains_key(id)) {
+        let from = a.tree.parent(node).map(js_node_id);
+        let to = b.tree.parent(node).map(js_node_id);
+        if from != to {
+            diff.reparented.push(Reparenting { node: node_id, from, to });
+        }
+        diff.changed.extend(node_changes(&a.tree, &b.tree, node_id, node)?);
+    }
+    Ok(diff)
+}
+
+/// Returns what changed between two snapshots taken by `TaffyTree.snapshot`, as
+/// `{ added, removed, reparented, changed }`
+///
+/// Nodes are matched by id, so the snapshots should come from the same tree (or from trees restored from a common
+/// snapshot). Every list is sorted by node id:
+///
+/// - `added` holds the ids of the nodes that are only in `b`, and `removed` those of the nodes only in `a`
+/// - `reparented` holds `{ node, from, to }` for each node in both snapshots whose parent changed, where `from`
+///   and `to` are the ids of its parent in `a` and `b`, or `null` for a root. Reordering children under the same
+///   parent is not reparenting.
+/// - `changed` holds `{ node, style, layout }` for each node in both snapshots whose style or layout changed.
+///   `style` lists the keys of the style properties that differ, as in the style objects passed to `new_leaf`,
+///   and `layout` the fields of the layout that differ, among `order`, `location`, `size`, `content_size`,
+///   `scrollbar_size`, `border`, `padding` and `margin`. Layouts are compared as last computed, so a node whose
+///   style changed but whose layout was not recomputed lists no layout changes.
+///
+/// Node contexts are not compared. The schema is stable: fields may be added in later versions, but existing ones
+/// keep their meaning.
+#[wasm_bindgen]
+pub fn diff_snapshots(a: &TreeSnapshot, b: &TreeSnapshot) -> Result<JsValue, TaffyError> {
+    let diff = diff(a, b)?;
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_missing_as_null(true);
+    Ok(diff.serialize(&serializer).unwrap_or(JsValue::NULL))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaffyTree;
+
+    /// Creates a tree with a root holding two leaves, returning it with the ids of the root and leaves
+    fn tree() -> (TaffyTree, [NodeId; 3]) {
+        let tree = TaffyTree::new();
+        let nodes = {
+            let mut inner = tree.inner.borrow_mut();
+            let first = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let second = inner.new_leaf(Style { size: length(20.0), ..Default::default() }).unwrap();
+            let root = inner.new_with_children(Style::default(), &[first, second]).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            [root, first, second]
+        };
+        (tree, nodes)
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let (tree, _) = tree();
+        let snapshot = tree.snapshot().unwrap();
+        assert_eq!(diff(&snapshot, &tree.snapshot().unwrap()), Ok(SnapshotDiff::default()));
+    }
+
+    #[test]
+    fn added_and_removed_nodes_are_listed() {
+        let (tree, [root, first, _]) = tree();
+        let before = tree.snapshot().unwrap();
+        let added = {
+            let mut inner = tree.inner.borrow_mut();
+            inner.remove_child(root, first).unwrap();
+            inner.remove(first).unwrap();
+            let added = inner.new_leaf(Style::default()).unwrap();
+            inner.add_child(root, added).unwrap();
+            added
+        };
+        let diff = diff(&before, &tree.snapshot().unwrap()).unwrap();
+        assert_eq!(diff.added, vec![js_node_id(added)]);
+        assert_eq!(diff.removed, vec![js_node_id(first)]);
+        assert!(diff.reparented.is_empty());
+    }
+
+    #[test]
+    fn placeholders_of_removed_nodes_are_not_added() {
+        let tree = TaffyTree::with_options(crate::TaffyTreeOptions { stable_ids: true });
+        let node = js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap());
+        let before = tree.snapshot().unwrap();
+        tree.remove(node).unwrap();
+        let diff = diff(&before, &tree.snapshot().unwrap()).unwrap();
+        assert_eq!(diff, SnapshotDiff { removed: vec![node], ..SnapshotDiff::default() });
+    }
+
+    #[test]
+    fn reparented_nodes_are_listed() {
+        let (tree, [root, first, second]) = tree();
+        let before = tree.snapshot().unwrap();
+        tree.inner.borrow_mut().remove_child(root, second).unwrap();
+        tree.inner.borrow_mut().add_child(first, second).unwrap();
+        let diff = diff(&before, &tree.snapshot().unwrap()).unwrap();
+        let moved = Reparenting { node: js_node_id(second), from: Some(js_node_id(root)), to: Some(js_node_id(first)) };
+        assert_eq!(diff.reparented, vec![moved]);
+
+        // Detaching a node makes it a root
+        tree.inner.borrow_mut().remove_child(first, second).unwrap();
+        let diff = super::diff(&before, &tree.snapshot().unwrap()).unwrap();
+        assert_eq!(diff.reparented[0].to, None);
+    }
+
+    #[test]
+    fn style_and_layout_changes_are_listed() {
+        let (tree, [root, first, second]) = tree();
+        let before = tree.snapshot().unwrap();
+        {
+            let mut inner = tree.inner.borrow_mut();
+            inner.set_style(first, Style { size: length(15.0), ..Default::default() }).unwrap();
+            let style = Style { flex_grow: 1.0, size: length(20.0), ..Default::default() };
+            inner.set_style(second, style).unwrap();
+            inner.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        }
+        let diff = diff(&before, &tree.snapshot().unwrap()).unwrap();
+        let changes: BTreeMap<_, _> = diff.changed.into_iter().map(|changes| (changes.node, changes)).collect();
+        // Style properties are named as in style objects, while layout fields are always snake case
+        let flex_grow = if cfg!(feature = "camel-case-style") { "flexGrow" } else { "flex_grow" };
+
+        assert_eq!(changes[&js_node_id(first)].style, vec!["size"]);
+        assert_eq!(changes[&js_node_id(first)].layout, vec!["size"]);
+        // The second leaf moved along by the 5px its sibling grew by
+        assert_eq!(changes[&js_node_id(second)].style, vec![flex_grow]);
+        assert_eq!(changes[&js_node_id(second)].layout, vec!["location"]);
+        assert_eq!(changes[&js_node_id(root)].style, Vec::<String>::new());
+        assert_eq!(changes[&js_node_id(root)].layout, vec!["content_size", "size"]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Serializing the shape of a subtree, without styles or layouts, for structural assertions, and checking that it
+//! is a tree
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use taffy::{NodeId, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// A node and its descendants, returned by `serialize_structure`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeStructure {
+    /// The id of the node
+    pub id: u32,
+    /// The structure of each child of the node, in order
+    pub children: Vec<NodeStructure>,
+}
+
+/// Returns the structure of the subtree rooted at `node`
+fn node_structure(tree: &Taffy<JsContext>, node: NodeId) -> Result<NodeStructure, TaffyError> {
+    let children =
+        tree.children(node)?.into_iter().map(|child| node_structure(tree, child)).collect::<Result<_, _>>()?;
+    Ok(NodeStructure { id: js_node_id(node), children })
+}
+
+/// Returns whether no node is reachable from itself through the children of the nodes in the subtree rooted at
+/// `root`, nor through the parents of `root`
+///
+/// Each node of a tree has a single parent, so a node reached twice from `root` means that some node is its own
+/// descendant, or that a node is listed more than once among the children of its parent: both break the tree.
+fn is_acyclic(tree: &Taffy<JsContext>, root: NodeId) -> Result<bool, TaffyError> {
+    let mut ancestors = BTreeSet::new();
+    for ancestor in std::iter::successors(Some(root), |&ancestor| tree.parent(ancestor)) {
+        if !ancestors.insert(js_node_id(ancestor)) {
+            return Ok(false);
+        }
+    }
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if !visited.insert(js_node_id(node)) {
+            return Ok(false);
+        }
+        stack.extend(tree.children(node)?);
+    }
+    Ok(true)
+}
+
+impl TaffyTree {
+    /// Returns the structure of the subtree rooted at `root`
+    fn structure(&self, root: u32) -> Result<NodeStructure, TaffyError> {
+        let tree = self.tree()?;
+        node_structure(&tree, resolve_node(&tree, root)?)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the shape of the subtree rooted at `root` as nested `{ id, children: [...] }` objects
+    ///
+    /// Only ids and children are included, not styles, layouts or contexts, so this is cheap to produce and to
+    /// compare, e.g. to check in a test that a reconciler built the expected tree. Two trees built by the same
+    /// sequence of calls have the same ids, so their structures can be compared directly.
+    #[wasm_bindgen]
+    pub fn serialize_structure(&self, root: u32) -> Result<JsValue, TaffyError> {
+        let structure = self.structure(root)?;
+        Ok(serde_wasm_bindgen::to_value(&structure).unwrap_or(JsValue::NULL))
+    }
+
+    /// Returns whether the subtree rooted at `root` is a tree, i.e. has no cycles, e.g. to check the result of
+    /// untrusted structural edits before laying it out
+    ///
+    /// `add_child` doesn't check that the child isn't an ancestor of the parent, so adding a node under one of its
+    /// own descendants forms a cycle, which makes layout recurse forever or overflow the stack. This walks the
+    /// subtree, and the ancestors of `root`, and returns `false` if any node is reached twice, i.e. is its own
+    /// descendant or ancestor, or appears twice among the children of its parent. `add_edges` already rejects edges
+    /// that would form a cycle. The walk takes time proportional to the size of the subtree, and stops at the first
+    /// node reached twice.
+    #[wasm_bindgen]
+    pub fn validate_acyclic(&self, root: u32) -> Result<bool, TaffyError> {
+        let tree = self.tree()?;
+        is_acyclic(&tree, resolve_node(&tree, root)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taffy::prelude::Style;
+
+    /// Builds a tree of a root with two children, the first of which has `grandchildren` children
+    fn build(grandchildren: usize) -> (TaffyTree, u32) {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaves: Vec<_> = (0..grandchildren).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            let first = inner.new_with_children(Style::default(), &leaves).unwrap();
+            let second = inner.new_leaf(Style::default()).unwrap();
+            js_node_id(inner.new_with_children(Style::default(), &[first, second]).unwrap())
+        };
+        (tree, root)
+    }
+
+    #[test]
+    fn structures_of_identically_built_trees_are_equal() {
+        let (tree, root) = build(2);
+        let structure = tree.structure(root).unwrap();
+        assert_eq!(structure.children.len(), 2);
+        assert_eq!(structure.children[0].children.len(), 2);
+        assert!(structure.children[1].children.is_empty());
+
+        let (same, same_root) = build(2);
+        assert_eq!(same.structure(same_root).unwrap(), structure);
+        let (other, other_root) = build(1);
+        assert_ne!(other.structure(other_root).unwrap(), structure);
+    }
+
+    #[test]
+    fn adding_an_ancestor_as_a_child_forms_a_detected_cycle() {
+        let (tree, root) = build(2);
+        let first = tree.structure(root).unwrap().children[0].id;
+        let grandchild = tree.structure(root).unwrap().children[0].children[1].id;
+        assert_eq!(tree.validate_acyclic(root), Ok(true));
+
+        // The low-level child edge is added without a check, making `first` its own descendant
+        tree.add_child(grandchild, first).unwrap();
+        assert_eq!(tree.validate_acyclic(root), Ok(false));
+        assert_eq!(tree.validate_acyclic(grandchild), Ok(false));
+        assert_eq!(tree.validate_acyclic(root + 1), Err(TaffyError::InvalidNode(root + 1)));
+    }
+
+    #[test]
+    fn structure_of_invalid_node() {
+        assert_eq!(TaffyTree::new().structure(1), Err(TaffyError::InvalidNode(1)));
+    }
+}
@@ -0,0 +1,186 @@
+//! Tree-wide defaults for style properties that are omitted from JS style objects, and finding the properties of a
+//! style that differ from them
+use crate::{decode_style, resolve_node, TaffyError, TaffyTree};
+use serde::Deserialize;
+use serde_json::Value;
+use taffy::style::{BoxSizing, Style};
+use wasm_bindgen::prelude::*;
+
+/// The properties of a JS style object that fall back to a tree-wide default when they are omitted
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "camel-case-style", serde(rename_all = "camelCase"))]
+struct DefaultableProperties {
+    box_sizing: Option<BoxSizing>,
+}
+
+/// Parses a `box-sizing` keyword, accepting both the CSS spelling (`"border-box"`) and the style object spelling
+/// (`"BorderBox"`)
+fn parse_box_sizing(mode: &str) -> Result<BoxSizing, TaffyError> {
+    match mode {
+        "border-box" | "BorderBox" => Ok(BoxSizing::BorderBox),
+        "content-box" | "ContentBox" => Ok(BoxSizing::ContentBox),
+        _ => Err(TaffyError::InvalidArgument(format!(
+            "{mode:?} is not a box-sizing mode, expected \"border-box\" or \"content-box\""
+        ))),
+    }
+}
+
+/// Fills in the properties of `style` that were omitted from the JS object it was decoded from, using
+/// `default_box_sizing` for its box sizing
+fn apply_defaults(style: Style, explicit: DefaultableProperties, default_box_sizing: BoxSizing) -> Style {
+    Style { box_sizing: explicit.box_sizing.unwrap_or(default_box_sizing), ..style }
+}
+
+/// Returns the names of the properties of `style` whose values differ from those of `default`, in alphabetical order
+///
+/// The names are those of the style objects, so they follow the `camel-case-style` feature.
+pub(crate) fn differing_fields(style: &Style, default: &Style) -> Vec<String> {
+    let (Ok(Value::Object(style)), Ok(default)) = (serde_json::to_value(style), serde_json::to_value(default)) else {
+        return Vec::new();
+    };
+    style.into_iter().filter(|(name, value)| default.get(name) != Some(value)).map(|(name, _)| name).collect()
+}
+
+/// Decodes a JS style object, using `default_box_sizing` if it omits `box_sizing`
+pub(crate) fn decode_style_with_box_sizing(style: JsValue, default_box_sizing: BoxSizing) -> Result<Style, TaffyError> {
+    let decoded = decode_style(style.clone())?;
+    let explicit = serde_wasm_bindgen::from_value(style).map_err(|e| TaffyError::StyleDecode(e.to_string()))?;
+    Ok(apply_defaults(decoded, explicit, default_box_sizing))
+}
+
+impl TaffyTree {
+    /// Fills in the properties of `style` that were omitted from the JS object it was decoded from
+    fn apply_style_defaults(&self, style: Style, explicit: DefaultableProperties) -> Style {
+        apply_defaults(style, explicit, self.default_box_sizing.get())
+    }
+
+    /// Decodes a JS style object, using the tree's defaults for the properties it omits, or its decode fallback style
+    /// if it fails to decode, and scaling its lengths by the tree's length scale
+    pub(crate) fn decode_style_with_defaults(&self, style: JsValue) -> Result<Style, TaffyError> {
+        let decoded = decode_style_with_box_sizing(style, self.default_box_sizing.get());
+        Ok(self.scale_style(self.style_or_fallback(decoded)?))
+    }
+
+    /// Returns the style used for nodes created without a JS style object, e.g. by `new_flex_row`
+    pub(crate) fn default_style(&self) -> Style {
+        self.apply_style_defaults(Style::default(), DefaultableProperties::default())
+    }
+
+    /// Returns the names of the style properties of a node that differ from the tree's default style
+    fn non_default_style_fields(&self, node_id: u32) -> Result<Vec<String>, TaffyError> {
+        let tree = self.tree()?;
+        let style = tree.style(resolve_node(&tree, node_id)?)?.clone();
+        let style = self.unscale_style(self.authored_style(node_id, style));
+        Ok(differing_fields(&style, &self.default_style()))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Sets the `box_sizing` used by styles that don't specify one, either `"border-box"` (the initial default) or
+    /// `"content-box"`
+    ///
+    /// The default applies to styles passed to `new_leaf` and `update_style` from then on, and to the nodes created
+    /// by `new_flex_row`, `new_flex_column` and `new_grid`. A style that sets `box_sizing` explicitly always uses
+    /// its own value. Existing nodes keep their current box sizing until their style is next updated.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `mode` is not a box-sizing mode.
+    #[wasm_bindgen]
+    pub fn set_default_box_sizing(&self, mode: &str) -> Result<(), TaffyError> {
+        self.default_box_sizing.set(parse_box_sizing(mode)?);
+        Ok(())
+    }
+
+    /// Returns the names of the style properties of a node whose values differ from the default style, in
+    /// alphabetical order
+    ///
+    /// The default style is `Style::default()`, with the `box_sizing` set by `set_default_box_sizing`, i.e. the
+    /// style a node gets from an empty style object. Omitting every other property from a saved style object gives
+    /// back the same style, and an inspector can highlight the returned properties as overridden. A property that
+    /// was set explicitly to its default value is not returned. The names are the keys of the style objects
+    /// returned by `get_style`, e.g. `flex_direction`, or `flexDirection` with the `camel-case-style` feature, and a
+    /// compound property such as `size` is returned whole if any part of it differs.
+    #[wasm_bindgen]
+    pub fn non_default_fields(&self, node_id: u32) -> Result<Vec<String>, TaffyError> {
+        self.non_default_style_fields(node_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use taffy::prelude::{length, Size, TaffyMaxContent};
+
+    #[test]
+    fn nodes_created_after_setting_the_default_use_it() {
+        let tree = TaffyTree::new();
+        let before = tree.new_flex_row().unwrap();
+        tree.set_default_box_sizing("content-box").unwrap();
+        let after = tree.new_flex_column().unwrap();
+
+        let inner = tree.inner.borrow();
+        let box_sizing = |node: u32| inner.style(node_from_js_id(node)).unwrap().box_sizing;
+        assert_eq!(box_sizing(before), BoxSizing::BorderBox);
+        assert_eq!(box_sizing(after), BoxSizing::ContentBox);
+    }
+
+    #[test]
+    fn explicit_box_sizing_overrides_the_default() {
+        let tree = TaffyTree::new();
+        tree.set_default_box_sizing("ContentBox").unwrap();
+
+        // Decode the style object through serde_json, since serde_wasm_bindgen needs a JS host
+        let decode = |json: serde_json::Value| {
+            let crate::JsStyle(style) = serde_json::from_value(json.clone()).unwrap();
+            tree.apply_style_defaults(style, serde_json::from_value(json).unwrap())
+        };
+        assert_eq!(decode(serde_json::json!({})).box_sizing, BoxSizing::ContentBox);
+        let box_sizing = if cfg!(feature = "camel-case-style") { "boxSizing" } else { "box_sizing" };
+        assert_eq!(decode(serde_json::json!({ box_sizing: "BorderBox" })).box_sizing, BoxSizing::BorderBox);
+    }
+
+    #[test]
+    fn default_box_sizing_affects_layout() {
+        let tree = TaffyTree::new();
+        tree.set_default_box_sizing("content-box").unwrap();
+        let style = Style {
+            size: Size { width: length(100.0), height: length(50.0) },
+            padding: length(10.0),
+            ..tree.default_style()
+        };
+        let node = js_node_id(tree.inner.borrow_mut().new_leaf(style).unwrap());
+        tree.inner.borrow_mut().compute_layout(node_from_js_id(node), Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(node), Ok(120.0));
+    }
+
+    #[test]
+    fn non_default_fields_are_the_overridden_properties() {
+        let tree = TaffyTree::new();
+        let style = Style {
+            flex_grow: 1.0,
+            size: Size { width: length(100.0), height: taffy::prelude::auto() },
+            align_items: None,
+            ..Default::default()
+        };
+        let node = js_node_id(tree.inner.borrow_mut().new_leaf(style).unwrap());
+        let names = |names: [&str; 2]| names.map(String::from).to_vec();
+        if cfg!(feature = "camel-case-style") {
+            assert_eq!(tree.non_default_style_fields(node), Ok(names(["flexGrow", "size"])));
+        } else {
+            assert_eq!(tree.non_default_style_fields(node), Ok(names(["flex_grow", "size"])));
+        }
+
+        // A content-box default makes the border-box sizing of the node an override
+        tree.set_default_box_sizing("content-box").unwrap();
+        assert_eq!(tree.non_default_style_fields(node).unwrap().len(), 3);
+        assert_eq!(tree.non_default_style_fields(99), Err(TaffyError::InvalidNode(99)));
+    }
+
+    #[test]
+    fn invalid_box_sizing_mode() {
+        let tree = TaffyTree::new();
+        assert!(matches!(tree.set_default_box_sizing("padding-box"), Err(TaffyError::InvalidArgument(_))));
+        assert_eq!(tree.default_style().box_sizing, BoxSizing::BorderBox);
+    }
+}
@@ -0,0 +1,101 @@
+//! Previewing the layout a tree would have with the style of one node changed, without changing the tree, e.g. to
+//! show the effect of a property value hovered in an inspector
+use crate::available_space::{decode_available_space, BoundedAvailableSpace};
+use crate::layout_json::{node_boxes, NodeBox};
+use crate::{resolve_node, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `root` within `available` on a scratch copy of the tree in which `node_id` has
+    /// `style`, returning the boxes of its nodes
+    fn preview_style(
+        &self,
+        node_id: u32,
+        style: Style,
+        root: u32,
+        available: BoundedAvailableSpace,
+    ) -> Result<Vec<NodeBox>, TaffyError> {
+        let mut scratch = {
+            let tree = self.tree()?;
+            let root = resolve_node(&tree, root)?;
+            resolve_node(&tree, node_id)?;
+            self.check_layout_depth(&tree, root)?;
+            tree.clone()
+        };
+        let (node, root) = (resolve_node(&scratch, node_id)?, resolve_node(&scratch, root)?);
+        scratch.set_style(node, style)?;
+
+        let mut layout = |space| {
+            scratch.compute_layout_with_measure(root, space, |known, available, leaf, _, _| {
+                self.measure_content_size(known, available, leaf)
+            })?;
+            Ok::<_, TaffyError>(scratch.unrounded_layout(root).size)
+        };
+        let size = layout(available.space)?;
+        if let Some(space) = available.relayout_space(size) {
+            layout(space)?;
+        }
+        node_boxes(&scratch, root)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the layout the subtree rooted at `root` would have within `available` if the style of `node_id` were
+    /// `style`, as an array of `{ id, x, y, w, h }`, without changing the tree
+    ///
+    /// This is a transient preview, not a mutation: the override is applied to a copy of the tree that is discarded
+    /// afterwards, so the style, layouts, caches and dirty flags of every node are exactly as they were before the
+    /// call, and nothing is recorded that an undo history built on `update_style` would see. `style` replaces the
+    /// whole style of the node, as with `update_style`, and `available` is a `LayoutAvailableSpace`, as for
+    /// `compute_layout_v2`. The boxes are those of every node in the subtree, in paint order, with `x` and `y`
+    /// relative to the parent of `root`, as from `compute_layout_to_json`. Leaves are sized by their content sizes
+    /// and object fits, as by `compute_layout`. The copy takes time proportional to the size of the whole tree.
+    ///
+    /// Throws a `STYLE_DECODE` error if `style` is not a valid style, and an `INVALID_ARGUMENT` error if
+    /// `available` is not a valid `LayoutAvailableSpace`.
+    #[wasm_bindgen]
+    pub fn compute_with_style_override(
+        &self,
+        node_id: u32,
+        style: JsValue,
+        root: u32,
+        available: JsValue,
+    ) -> Result<JsValue, TaffyError> {
+        let style = self.decode_style_with_defaults(style)?;
+        let boxes = self.preview_style(node_id, style, root, decode_available_space(available)?)?;
+        Ok(serde_wasm_bindgen::to_value(&boxes).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use taffy::style::Style;
+
+    #[test]
+    fn preview_leaves_the_persisted_style_unchanged() {
+        let tree = TaffyTree::new();
+        let item = Style { size: length(20.0), ..Default::default() };
+        let (root, [first, second]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let items = [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(item.clone()).unwrap()];
+            (js_node_id(inner.new_with_children(Style::default(), &items).unwrap()), items.map(js_node_id))
+        };
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+
+        let wider = Style { size: Size { width: length(50.0), height: length(20.0) }, ..Default::default() };
+        let available = BoundedAvailableSpace { space: Size::MAX_CONTENT, limits: Size::NONE };
+        let boxes = tree.preview_style(first, wider, root, available).unwrap();
+        let node_box = |id, x, w| NodeBox { id, x, y: 0.0, w, h: 20.0 };
+        assert_eq!(boxes, vec![node_box(root, 0.0, 70.0), node_box(first, 0.0, 50.0), node_box(second, 50.0, 20.0)]);
+
+        // The tree keeps its own style and layout, and still doesn't need a relayout
+        assert_eq!(*tree.inner.borrow().style(node_from_js_id(first)).unwrap(), item);
+        assert_eq!(tree.layout_width(root), Ok(40.0));
+        assert_eq!(tree.layout_left(second), Ok(20.0));
+        assert_eq!(tree.dirty(root), Ok(false));
+    }
+}
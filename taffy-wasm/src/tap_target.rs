@@ -0,0 +1,152 @@
+//! Minimum tap target sizes, so that interactive nodes are large enough to tap whatever their content or style
+use crate::{resolve_node, set_style_if_changed, TaffyError, TaffyTree};
+use taffy::prelude::*;
+use taffy::style::CompactLength;
+use wasm_bindgen::prelude::*;
+
+/// The minimum size of a node given a tap target, and the `min_size` of its style that the tap target raises
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TapTarget {
+    /// The length the node is at least as wide and as tall as, in layout pixels
+    size: f32,
+    /// The `min_size` of the node's style
+    authored_min_size: Size<Dimension>,
+}
+
+impl TapTarget {
+    /// Gives a node with `style` a tap target of `size`
+    fn new(size: f32, style: &Style) -> Self {
+        TapTarget { size, authored_min_size: style.min_size }
+    }
+
+    /// Returns `style` with its `min_size` raised to the tap target
+    fn apply(&self, style: Style) -> Style {
+        let raise = |min: Dimension| match min.tag() {
+            CompactLength::LENGTH_TAG => length(min.value().max(self.size)),
+            _ => length(self.size),
+        };
+        Style { min_size: style.min_size.map(raise), ..style }
+    }
+
+    /// Returns `style` with the `min_size` the tap target raised
+    fn restore(&self, style: Style) -> Style {
+        Style { min_size: self.authored_min_size, ..style }
+    }
+}
+
+impl TaffyTree {
+    /// Returns `style`, to be given to `node_id`, with its `min_size` raised to the node's tap target if it has one,
+    /// remembering the `min_size` of `style` for when the tap target is removed
+    pub(crate) fn apply_tap_target(&self, node_id: u32, style: Style) -> Style {
+        self.node_states.borrow_mut().update(node_id, |state| match &mut state.tap_target {
+            Some(tap_target) => {
+                *tap_target = TapTarget::new(tap_target.size, &style);
+                tap_target.apply(style)
+            }
+            None => style,
+        })
+    }
+
+    /// Returns `style`, the style of `node_id` without its pinned size, with the `min_size` it had before its tap
+    /// target raised it
+    pub(crate) fn remove_tap_target(&self, node_id: u32, style: Style) -> Style {
+        match &self.node_states.borrow().get(node_id).tap_target {
+            Some(tap_target) => tap_target.restore(style),
+            None => style,
+        }
+    }
+
+    /// Gives `node_id` the tap target `size`, or takes its tap target away if `size` is `None`
+    fn update_tap_target(&self, node_id: u32, size: Option<f32>) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let authored = self.authored_style(node_id, tree.style(node)?.clone());
+        let tap_target = size.map(|size| TapTarget::new(size, &authored));
+        self.node_states.borrow_mut().update(node_id, |state| state.tap_target = tap_target);
+        let style = self.apply_pinned_size(node_id, self.apply_tap_target(node_id, authored));
+        set_style_if_changed(&mut tree, node, style)?;
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Makes a node at least `size` pixels wide and tall in every layout until `remove_min_tap_target` is called,
+    /// without changing its style, e.g. `44` for the buttons and links of a touch UI
+    ///
+    /// The tap target is a minimum on the used size of the node, like `min_size`, so it applies however small the
+    /// node's content, its `size`, `max_size` or `flex_shrink` would make it, while a node that is already larger
+    /// is left as it is. Where the node's style has a `min_size` in pixels, the larger of the two wins. A
+    /// percentage `min_size` can't be compared with the tap target before layout, so the tap target takes its
+    /// place. The size is in layout pixels, as returned by `layout_width`, so it is not scaled by
+    /// `set_length_scale`. A size pinned with `pin_size` takes precedence over the tap target.
+    ///
+    /// The style of the node is left as it was: `get_styles` and `non_default_fields` keep reporting its own
+    /// `min_size`, and `update_style` replaces it while the tap target stays in place. Setting the tap target of a
+    /// node again replaces it. The node is marked dirty if this changes its size constraints.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `size` is negative or not finite.
+    #[wasm_bindgen]
+    pub fn set_min_tap_target(&self, node_id: u32, size: f32) -> Result<(), TaffyError> {
+        if !(size.is_finite() && size >= 0.0) {
+            return Err(TaffyError::InvalidArgument(format!(
+                "a tap target must be finite and non-negative, got {size}"
+            )));
+        }
+        self.update_tap_target(node_id, Some(size))
+    }
+
+    /// Undoes `set_min_tap_target`, so that the node is sized by its style alone again from the next layout
+    ///
+    /// The node is marked dirty if this changes its size constraints. Removing the tap target of a node that has
+    /// none does nothing.
+    #[wasm_bindgen]
+    pub fn remove_min_tap_target(&self, node_id: u32) -> Result<(), TaffyError> {
+        self.update_tap_target(node_id, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::{js_node_id, node_from_js_id};
+
+    #[test]
+    fn flagged_leaf_grows_to_the_tap_target_and_its_sibling_does_not() {
+        let tree = TaffyTree::new();
+        let (row, [flagged, sibling]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let icon = Style { size: length(16.0), flex_shrink: 0.0, ..Default::default() };
+            let items = [inner.new_leaf(icon.clone()).unwrap(), inner.new_leaf(icon).unwrap()];
+            let row = Style { align_items: Some(AlignItems::Start), ..Default::default() };
+            (js_node_id(inner.new_with_children(row, &items).unwrap()), items.map(js_node_id))
+        };
+        let sizes = |node| {
+            tree.compute_subtree(row, Size::MAX_CONTENT).unwrap();
+            (tree.layout_width(node).unwrap(), tree.layout_height(node).unwrap())
+        };
+
+        tree.set_min_tap_target(flagged, 44.0).unwrap();
+        assert_eq!(sizes(flagged), (44.0, 44.0));
+        assert_eq!(sizes(sibling), (16.0, 16.0));
+        assert_eq!(tree.layout_left(sibling), Ok(44.0));
+        // The tap target is in layout pixels, so scaling styles doesn't scale it
+        tree.set_length_scale(2.0).unwrap();
+        assert_eq!((sizes(flagged), sizes(sibling)), ((44.0, 44.0), (32.0, 32.0)));
+        tree.set_length_scale(1.0).unwrap();
+        // The style still has the node's own min_size
+        let style = tree.styles(&[flagged]).unwrap().remove(0).unwrap().0;
+        assert_eq!(style.min_size, Size::auto());
+
+        // A larger min_size wins over the tap target, and a smaller one doesn't, on each axis
+        let style = Style { size: length(16.0), min_size: Size { width: length(60.0), height: length(30.0) }, ..style };
+        // Set as by update_style, which decodes the style from JS
+        let style = tree.apply_tap_target(flagged, style);
+        tree.inner.borrow_mut().set_style(node_from_js_id(flagged), style).unwrap();
+        assert_eq!(sizes(flagged), (60.0, 44.0));
+
+        tree.remove_min_tap_target(flagged).unwrap();
+        assert_eq!(sizes(flagged), (60.0, 30.0));
+        assert!(matches!(tree.set_min_tap_target(flagged, f32::NAN), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,397 @@
+//! Recording each call taffy's layout algorithms make on each node as a tree of events, to see how a layout came
+//! about. Only built with the `trace` feature.
+use crate::node_ids::js_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taffy::{
+    compute_cached_layout, compute_flexbox_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout,
+    prelude::*, Cache, CacheTree, LayoutFlexboxContainer, LayoutInput, LayoutOutput, LayoutPartialTree, RequestedAxis,
+    RunMode, SizingMode, TraversePartialTree, TraverseTree,
+};
+use wasm_bindgen::prelude::*;
+
+/// One call of the layout algorithms on a node, with the calls made on its descendants to answer it, returned by
+/// `compute_layout_traced`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TraceEvent {
+    /// The node that was laid out or sized
+    node: u32,
+    /// Whether the node was laid out, only sized, or laid out as hidden by an ancestor with `display: "None"`
+    run_mode: RunMode,
+    /// Whether the style's size of the node was taken into account
+    sizing_mode: SizingMode,
+    /// The axes the caller needed a size in
+    axis: RequestedAxis,
+    /// The sizes the caller had already settled on for the node
+    known_dimensions: Size<Option<f32>>,
+    /// The size percentages of the node resolve against
+    parent_size: Size<Option<f32>>,
+    /// The space the node was given to fit into
+    available_space: Size<AvailableSpace>,
+    /// The algorithm that computed the result: `"flexbox"`, `"grid"`, `"block"`, `"leaf"` or `"hidden"`, or `None`
+    /// if it came from the cache of the node
+    algorithm: Option<&'static str>,
+    /// The border-box size the node was given
+    size: Size<f32>,
+    /// The size of the content of the node, which overflows it if it is larger
+    content_size: Size<f32>,
+    /// The calls made on the children of the node, in the order they were made
+    children: Vec<TraceEvent>,
+}
+
+/// A node of the subtree being traced
+struct TracedNode<'t> {
+    style: &'t Style,
+    children: Vec<NodeId>,
+    cache: Cache,
+}
+
+/// A layout tree over the nodes of a `TaffyTree` that lays them out with the same algorithms as taffy, but with
+/// caches of its own and discarding the layouts, recording each call on a node along the way
+struct Tracer<'t, M> {
+    nodes: BTreeMap<u32, TracedNode<'t>>,
+    measure: M,
+    /// The events of the calls in progress, innermost last
+    open: Vec<TraceEvent>,
+    /// The events of the calls made on the root
+    finished: Vec<TraceEvent>,
+}
+
+impl<'t, M> Tracer<'t, M> {
+    fn node(&self, node: NodeId) -> &TracedNode<'t> {
+        &self.nodes[&js_node_id(node)]
+    }
+
+    fn node_mut(&mut self, node: NodeId) -> &mut TracedNode<'t> {
+        self.nodes.get_mut(&js_node_id(node)).expect("only the nodes of the traced subtree are laid out")
+    }
+}
+
+impl<'t, M> Tracer<'t, M>
+where
+    M: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId) -> Size<f32>,
+{
+    /// Dispatches to the algorithm for the display of `node`, as taffy's own tree does
+    fn compute_uncached(&mut self, node: NodeId, inputs: LayoutInput) -> (&'static str, LayoutOutput) {
+        let TracedNode { style, children, .. } = self.node(node);
+        let (style, has_children): (&'t Style, bool) = (style, !children.is_empty());
+        match (style.display, has_children) {
+            (Display::None, _) => ("hidden", compute_hidden_layout(self, node)),
+            #[cfg(feature = "block")]
+            (Display::Block, true) => ("block", taffy::compute_block_layout(self, node, inputs)),
+            (Display::Flex, true) => ("flexbox", compute_flexbox_layout(self, node, inputs)),
+            #[cfg(feature = "grid")]
+            (Display::Grid, true) => ("grid", taffy::compute_grid_layout(self, node, inputs)),
+            _ => {
+                let measure = &mut self.measure;
+                let output =
+                    compute_leaf_layout(inputs, style, |_, _| 0.0, |known, available| measure(known, available, node));
+                ("leaf", output)
+            }
+        }
+    }
+}
+
+impl<'t, M> TraversePartialTree for Tracer<'t, M> {
+    type ChildIter<'a>
+        = std::iter::Copied<std::slice::Iter<'a, NodeId>>
+    where
+        Self: 'a;
+
+    fn child_ids(&self, parent_node_id: NodeId) -> Self::ChildIter<'_> {
+        self.node(parent_node_id).children.iter().copied()
+    }
+
+    fn child_count(&self, parent_node_id: NodeId) -> usize {
+        self.node(parent_node_id).children.len()
+    }
+
+    fn get_child_id(&self, parent_node_id: NodeId, child_index: usize) -> NodeId {
+        self.node(parent_node_id).children[child_index]
+    }
+}
+
+impl<'t, M> TraverseTree for Tracer<'t, M> {}
+
+impl<'t, M> CacheTree for Tracer<'t, M> {
+    fn cache_get(
+        &self,
+        node_id: NodeId,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        run_mode: RunMode,
+    ) -> Option<LayoutOutput> {
+        self.node(node_id).cache.get(known_dimensions, available_space, run_mode)
+    }
+
+    fn cache_store(
+        &mut self,
+        node_id: NodeId,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        run_mode: RunMode,
+        layout_output: LayoutOutput,
+    ) {
+        self.node_mut(node_id).cache.store(known_dimensions, available_space, run_mode, layout_output)
+    }
+
+    fn cache_clear(&mut self, node_id: NodeId) {
+        self.node_mut(node_id).cache.clear();
+    }
+}
+
+impl<'t, M> LayoutPartialTree for Tracer<'t, M>
+where
+    M: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId) -> Size<f32>,
+{
+    type CoreContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_core_container_style(&self, node_id: NodeId) -> Self::CoreContainerStyle<'_> {
+        self.node(node_id).style
+    }
+
+    // The layouts are only traced, the tree's own layout pass stores them
+    fn set_unrounded_layout(&mut self, _node_id: NodeId, _layout: &Layout) {}
+
+    fn compute_child_layout(&mut self, node_id: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        self.open.push(TraceEvent {
+            node: js_node_id(node_id),
+            run_mode: inputs.run_mode,
+            sizing_mode: inputs.sizing_mode,
+            axis: inputs.axis,
+            known_dimensions: inputs.known_dimensions,
+            parent_size: inputs.parent_size,
+            available_space: inputs.available_space,
+            algorithm: None,
+            size: Size::ZERO,
+            content_size: Size::ZERO,
+            children: Vec::new(),
+        });
+        let output = match inputs.run_mode {
+            RunMode::PerformHiddenLayout => {
+                if let Some(event) = self.open.last_mut() {
+                    event.algorithm = Some("hidden");
+                }
+                compute_hidden_layout(self, node_id)
+            }
+            _ => compute_cached_layout(self, node_id, inputs, |tracer, node, inputs| {
+                let (algorithm, output) = tracer.compute_uncached(node, inputs);
+                if let Some(event) = tracer.open.last_mut() {
+                    event.algorithm = Some(algorithm);
+                }
+                output
+            }),
+        };
+
+        let mut event = self.open.pop().expect("the event of this call was opened above");
+        (event.size, event.content_size) = (output.size, output.content_size);
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(event),
+            None => self.finished.push(event),
+        }
+        output
+    }
+}
+
+impl<'t, M> LayoutFlexboxContainer for Tracer<'t, M>
+where
+    M: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId) -> Size<f32>,
+{
+    type FlexboxContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type FlexboxItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_flexbox_container_style(&self, node_id: NodeId) -> Self::FlexboxContainerStyle<'_> {
+        self.node(node_id).style
+    }
+
+    fn get_flexbox_child_style(&self, child_node_id: NodeId) -> Self::FlexboxItemStyle<'_> {
+        self.node(child_node_id).style
+    }
+}
+
+#[cfg(feature = "grid")]
+impl<'t, M> taffy::LayoutGridContainer for Tracer<'t, M>
+where
+    M: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId) -> Size<f32>,
+{
+    type GridContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type GridItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_grid_container_style(&self, node_id: NodeId) -> Self::GridContainerStyle<'_> {
+        self.node(node_id).style
+    }
+
+    fn get_grid_child_style(&self, child_node_id: NodeId) -> Self::GridItemStyle<'_> {
+        self.node(child_node_id).style
+    }
+}
+
+#[cfg(feature = "block")]
+impl<'t, M> taffy::LayoutBlockContainer for Tracer<'t, M>
+where
+    M: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId) -> Size<f32>,
+{
+    type BlockContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type BlockItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_block_container_style(&self, node_id: NodeId) -> Self::BlockContainerStyle<'_> {
+        self.node(node_id).style
+    }
+
+    fn get_block_child_style(&self, child_node_id: NodeId) -> Self::BlockItemStyle<'_> {
+        self.node(child_node_id).style
+    }
+}
+
+impl TaffyTree {
+    /// Lays out the subtree rooted at `root` within `available_space` from empty caches, without storing the
+    /// layouts, returning the event of the call that laid out `root`
+    fn trace_layout(&self, root: u32, available_space: Size<AvailableSpace>) -> Result<TraceEvent, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        self.check_layout_depth(&tree, root)?;
+        let mut nodes = BTreeMap::new();
+        let mut pending = vec![root];
+        while let Some(node) = pending.pop() {
+            let children = tree.children(node)?;
+            pending.extend(&children);
+            nodes.insert(js_node_id(node), TracedNode { style: tree.style(node)?, children, cache: Cache::new() });
+        }
+
+        let mut tracer = Tracer {
+            nodes,
+            measure: |known, available, leaf| self.measure_content_size(known, available, leaf),
+            open: Vec::new(),
+            finished: Vec::new(),
+        };
+        // The root is laid out by a single call, which every other call is nested in
+        compute_root_layout(&mut tracer, root, available_space);
+        Ok(tracer.finished.swap_remove(0))
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `node_id` like `compute_layout`, returning a trace of every call
+    /// taffy's layout algorithms made on its nodes
+    ///
+    /// The trace is a tree of events, each of them `{ node, runMode, sizingMode, axis, knownDimensions, parentSize,
+    /// availableSpace, algorithm, size, contentSize, children }`: a node was asked for its size
+    /// (`runMode: "ComputeSize"`) or to lay itself out (`"PerformLayout"`) under the constraints `knownDimensions`,
+    /// `parentSize` and `availableSpace`, `algorithm` computed the `size` it returned, and `children` are the calls
+    /// made on its children to do so, in order. The returned event is the one that laid out `node_id`. A node
+    /// usually has several events, since flexbox and grid size their items under several constraints before laying
+    /// them out; `algorithm` is `null` for the calls answered from the node's cache.
+    ///
+    /// Only available with the `trace` feature. Tracing has a cost, which is why it is not part of the default
+    /// build: the traced pass starts from empty caches, so that it shows how every node is laid out rather than only
+    /// the dirty ones, and allocates an event for each call, of which there can be several times as many as nodes.
+    /// The layout itself is then computed and stored by a regular `compute_layout`, so this takes more than twice as
+    /// long, and the trace of a large tree can be large. The events of leaves are traced from their content size
+    /// like `compute_layout` does, without calling a measure function.
+    #[wasm_bindgen]
+    pub fn compute_layout_traced(&self, node_id: u32, width: f32, height: f32) -> Result<JsValue, TaffyError> {
+        let available_space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        let trace = self.trace_layout(node_id, available_space)?;
+        self.compute_layout(node_id, width, height)?;
+        Ok(serde_wasm_bindgen::to_value(&trace).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Calls `visit` with each event of the trace rooted at `event`
+    fn visit_events<'e>(event: &'e TraceEvent, visit: &mut impl FnMut(&'e TraceEvent)) {
+        visit(event);
+        for child in &event.children {
+            visit_events(child, visit);
+        }
+    }
+
+    #[test]
+    fn every_node_is_traced_with_its_constraints_and_size() {
+        let tree = TaffyTree::new();
+        let (root, column, [fixed, text]) = {
+            let mut inner = tree.inner.borrow_mut();
+            let fixed = inner.new_leaf(Style { size: length(40.0), ..Default::default() }).unwrap();
+            let text = inner.new_leaf(Style::default()).unwrap();
+            let column = Style { flex_direction: FlexDirection::Column, flex_grow: 1.0, ..Default::default() };
+            let column = inner.new_with_children(column, &[text]).unwrap();
+            let root = Style { size: percent(1.0), padding: length(10.0), ..Default::default() };
+            let root = inner.new_with_children(root, &[fixed, column]).unwrap();
+            (js_node_id(root), js_node_id(column), [fixed, text].map(js_node_id))
+        };
+        tree.set_content_size(text, 30.0, 20.0).unwrap();
+
+        let trace = tree
+            .trace_layout(
+                root,
+                Size { width: AvailableSpace::Definite(200.0), height: AvailableSpace::Definite(100.0) },
+            )
+            .unwrap();
+        assert_eq!((trace.node, trace.run_mode, trace.algorithm), (root, RunMode::PerformLayout, Some("flexbox")));
+        assert_eq!(trace.parent_size, Size { width: Some(200.0), height: Some(100.0) });
+        assert_eq!(trace.size, Size { width: 200.0, height: 100.0 });
+
+        let mut traced = BTreeSet::new();
+        let mut final_layouts = BTreeMap::new();
+        visit_events(&trace, &mut |event| {
+            traced.insert(event.node);
+            if event.run_mode == RunMode::PerformLayout {
+                final_layouts.insert(event.node, event);
+            }
+        });
+        assert_eq!(traced, BTreeSet::from([root, column, fixed, text]));
+
+        // The items of the row are laid out within the root's content box, and stretched to its height
+        let fixed_layout = final_layouts[&fixed];
+        assert_eq!(fixed_layout.algorithm, Some("leaf"));
+        assert_eq!(fixed_layout.parent_size, Size { width: Some(180.0), height: Some(80.0) });
+        assert_eq!(fixed_layout.size, Size { width: 40.0, height: 40.0 });
+        let column_layout = final_layouts[&column];
+        assert_eq!(column_layout.known_dimensions, Size { width: Some(140.0), height: Some(80.0) });
+        assert_eq!(
+            (column_layout.algorithm, column_layout.size),
+            (Some("flexbox"), Size { width: 140.0, height: 80.0 })
+        );
+        assert_eq!(final_layouts[&text].size, Size { width: 140.0, height: 20.0 });
+
+        // Tracing doesn't lay the tree out, and the regular layout agrees with the trace
+        assert_eq!(tree.layout_width(root), Ok(0.0));
+        tree.compute_subtree(
+            root,
+            Size { width: AvailableSpace::Definite(200.0), height: AvailableSpace::Definite(100.0) },
+        )
+        .unwrap();
+        assert_eq!((tree.layout_width(column), tree.layout_height(text)), (Ok(140.0), Ok(20.0)));
+    }
+}
@@ -0,0 +1,69 @@
+//! Creating a node together with its children, so that a tree can be built bottom-up in one call per node, as the
+//! `h` and `buildTree` helpers emitted into `index.ts` do
+use crate::node_ids::new_node_id;
+use crate::{resolve_node, TaffyError, TaffyTree};
+use std::collections::BTreeSet;
+use taffy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+impl TaffyTree {
+    /// Creates a node with `style` whose children are the existing nodes `children`, in order
+    fn new_node_with_children(&self, style: Style, children: &[u32]) -> Result<u32, TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let mut seen = BTreeSet::new();
+        let mut child_nodes = Vec::with_capacity(children.len());
+        for &child_id in children {
+            let child = resolve_node(&tree, child_id)?;
+            if tree.parent(child).is_some() {
+                return Err(TaffyError::InvalidArgument(format!("node {child_id} already has a parent")));
+            }
+            if !seen.insert(child_id) {
+                return Err(TaffyError::InvalidArgument(format!("node {child_id} is given as a child twice")));
+            }
+            child_nodes.push(child);
+        }
+        let node = tree.new_with_children(style, &child_nodes)?;
+        new_node_id(&mut tree, node)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Creates a node from a JS style object, like `new_leaf`, with the existing nodes `children` as its children
+    ///
+    /// This builds a tree bottom-up in one call per node, rather than one call per node and per child with
+    /// `add_child`: the `buildTree(taffy, h(style, ...children))` helper exported by `index.ts` creates trees with
+    /// it. The children must not have a parent yet, so that a node can't end up in two trees.
+    ///
+    /// Throws a `STYLE_DECODE` error if the object is not a valid style, as `new_leaf` does, an `INVALID_NODE` error
+    /// if a child is not in the tree, and an `INVALID_ARGUMENT` error if a child already has a parent or is given
+    /// twice. No node is created if it throws.
+    #[wasm_bindgen]
+    pub fn new_with_children(&self, style: JsValue, children: Vec<u32>) -> Result<u32, TaffyError> {
+        let style = self.decode_style_with_defaults(style)?;
+        self.new_node_with_children(style, &children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_are_attached_in_order() {
+        let tree = TaffyTree::new();
+        let items = [(); 3].map(|_| tree.new_node_with_children(Style::default(), &[]).unwrap());
+        let row = tree.new_node_with_children(Style::default(), &items).unwrap();
+        let root = tree.new_node_with_children(Style::default(), &[row]).unwrap();
+
+        assert_eq!(tree.descendants(root, Some(true)), Ok(vec![root, row, items[0], items[1], items[2]]));
+        assert_eq!(tree.ancestors(items[2]), Ok(vec![row, root]));
+
+        let orphan = tree.new_node_with_children(Style::default(), &[]).unwrap();
+        let already_placed = tree.new_node_with_children(Style::default(), &[orphan, items[0]]);
+        assert!(matches!(already_placed, Err(TaffyError::InvalidArgument(_))));
+        let repeated = tree.new_node_with_children(Style::default(), &[orphan, orphan]);
+        assert!(matches!(repeated, Err(TaffyError::InvalidArgument(_))));
+        assert_eq!(tree.ancestors(orphan), Ok(vec![]));
+    }
+}
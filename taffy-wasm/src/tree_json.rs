@@ -0,0 +1,272 @@
+//! Saving a subtree with its styles and JSON node contexts as a JSON string, and loading it back, e.g. for documents
+//! of text-based UIs
+use crate::node_ids::{create_nodes, js_node_id, new_node_id};
+use crate::{resolve_node, JsContext, JsStyle, TaffyError, TaffyTree};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use taffy::{prelude::*, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// A node and its descendants as saved by `serialize_tree`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedNode {
+    /// The style of the node, with every property set
+    style: JsStyle,
+    /// The context of the node as JSON, if it has one that could be converted to JSON
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    context: Option<Value>,
+    /// Whether the node had a context that could not be converted to JSON, and was left out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    context_skipped: bool,
+    /// Each child of the node, in order
+    #[serde(default)]
+    children: Vec<SerializedNode>,
+}
+
+/// Deserializes a field that is present, including a `null` one, as `Some`
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// Returns the subtree rooted at `node`, converting styles with `style` and contexts with `context_json`, which
+/// returns `None` for a context that can't be converted to JSON
+fn serialize_node<C>(
+    tree: &Taffy<C>,
+    node: NodeId,
+    style: &impl Fn(&Style) -> Style,
+    context_json: &impl Fn(&C) -> Option<Value>,
+) -> Result<SerializedNode, TaffyError> {
+    let context = tree.get_node_context(node).map(context_json);
+    let children = tree
+        .children(node)?
+        .into_iter()
+        .map(|child| serialize_node(tree, child, style, context_json))
+        .collect::<Result<_, _>>()?;
+    Ok(SerializedNode {
+        style: JsStyle(style(tree.style(node)?)),
+        context_skipped: context.as_ref().is_some_and(Option::is_none),
+        context: context.flatten(),
+        children,
+    })
+}
+
+/// Creates the nodes of a saved subtree, converting styles with `style` and contexts with `context`, returning its
+/// root, and pushing each node it creates to `created`
+fn build_node<C>(
+    tree: &mut Taffy<C>,
+    node: SerializedNode,
+    style: &impl Fn(Style) -> Style,
+    context: &impl Fn(Value) -> C,
+    created: &mut Vec<NodeId>,
+) -> Result<NodeId, TaffyError> {
+    let children = node
+        .children
+        .into_iter()
+        .map(|child| build_node(tree, child, style, context, created))
+        .collect::<Result<Vec<_>, _>>()?;
+    let id = tree.new_with_children(style(node.style.0), &children)?;
+    new_node_id(tree, id)?;
+    created.push(id);
+    if let Some(data) = node.context {
+        tree.set_node_context(id, Some(context(data)))?;
+    }
+    Ok(id)
+}
+
+/// Parses a subtree saved by `serialize_tree`
+fn parse_saved_tree(json: &str) -> Result<SerializedNode, TaffyError> {
+    serde_json::from_str(json).map_err(|e| TaffyError::InvalidArgument(format!("json: {e}")))
+}
+
+/// Converts a context to JSON as `JSON.stringify` does, or returns `None` if it can't be
+fn context_to_json(context: &JsContext) -> Option<Value> {
+    let json = js_sys::JSON::stringify(&context.data).ok()?.as_string()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Converts JSON saved by `context_to_json` back to a JS value
+fn context_from_json(json: Value) -> JsContext {
+    let data = js_sys::JSON::parse(&json.to_string()).unwrap_or(JsValue::NULL);
+    JsContext { data }
+}
+
+impl TaffyTree {
+    /// Creates the nodes of a saved subtree in this tree, scaling their styles by its length scale, returning the id
+    /// of its root
+    fn load_subtree(&self, saved: SerializedNode) -> Result<u32, TaffyError> {
+        create_nodes(&mut *self.tree_mut()?, |tree, created| {
+            let root = build_node(tree, saved, &|style| self.scale_style(style), &context_from_json, created)?;
+            Ok(js_node_id(root))
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Returns the subtree rooted at `root` as a JSON string, with the style and context of every node, for
+    /// `deserialize_tree` to load
+    ///
+    /// Each node is saved as `{ style, context?, contextSkipped?, children }`, with its style as returned by
+    /// `get_styles` and its children in order. Node ids and layouts are not saved: loading the string creates new
+    /// nodes, which need to be laid out again.
+    ///
+    /// Node contexts are opaque JS values, so a context is only saved if it can be converted to JSON: it is saved
+    /// as `JSON.stringify` converts it, so strings, numbers, booleans, `null`, and arrays and plain objects of
+    /// those are saved as they are, but e.g. a `Date` is saved as a string, and a class instance as a plain object
+    /// of its fields. A context that can't be converted, such as a function, a symbol, `undefined`, a `BigInt`, or
+    /// an object with a reference cycle, is left out rather than failing the whole tree, and its node is marked
+    /// with `"contextSkipped": true`, so that the missing context can be found and restored by other means after
+    /// loading. Nodes without a context have neither `context` nor `contextSkipped`.
+    #[wasm_bindgen]
+    pub fn serialize_tree(&self, root: u32) -> Result<String, TaffyError> {
+        let tree = self.tree()?;
+        let root = resolve_node(&tree, root)?;
+        let saved = serialize_node(&tree, root, &|style| self.unscale_style(style.clone()), &context_to_json)?;
+        Ok(serde_json::to_string(&saved).unwrap_or_default())
+    }
+
+    /// Creates the nodes of a subtree saved with `serialize_tree`, returning the id of its root
+    ///
+    /// Every node is created with the style it was saved with, and its context, if one was saved, parsed back into
+    /// a new JS value with `JSON.parse`. Nodes whose context was skipped are created without one. The nodes are
+    /// new, so their ids differ from the ones they were saved from, and need to be laid out before their layouts
+    /// are read.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error, without creating any node, if `json` is not a saved subtree, and a
+    /// `TOO_MANY_NODES` error, also without creating any node, if the tree can't hold all of its nodes.
+    #[wasm_bindgen]
+    pub fn deserialize_tree(&self, json: &str) -> Result<u32, TaffyError> {
+        self.load_subtree(parse_saved_tree(json)?)
+    }
+
+    /// Recreates in this tree a subtree serialized by `serialize_tree` of another `TaffyTree`, returning the id of
+    /// its root, e.g. to combine subtrees built in web workers under a root of the main tree
+    ///
+    /// Node ids are only meaningful within the tree that issued them, so a subtree is moved between trees by
+    /// serializing it in one and importing it in the other. `serialized` is either the JSON string returned by
+    /// `serialize_tree`, as posted from a worker, or the object it parses to. The imported root has no parent:
+    /// attach it with `add_child`. Nodes are created as by `deserialize_tree`, with their styles in the units of
+    /// this tree's length scale, and their contexts restored from JSON.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error, without creating any node, if `serialized` is not a serialized subtree,
+    /// and a `TOO_MANY_NODES` error, also without creating any node, if the tree can't hold all of its nodes.
+    #[wasm_bindgen]
+    pub fn import_subtree(&self, serialized: JsValue) -> Result<u32, TaffyError> {
+        let saved = match serialized.as_string() {
+            Some(json) => parse_saved_tree(&json)?,
+            None => serde_wasm_bindgen::from_value(serialized)
+                .map_err(|e| TaffyError::InvalidArgument(format!("serialized: {e}")))?,
+        };
+        self.load_subtree(saved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A node context that is either plain JSON, or an opaque value that can't be converted to JSON
+    #[derive(Debug, Clone, PartialEq)]
+    enum TestContext {
+        Json(Value),
+        Opaque,
+    }
+
+    #[test]
+    fn json_contexts_round_trip_and_others_are_skipped() {
+        let mut tree: Taffy<TestContext> = Taffy::new();
+        let label = Value::String("Hello".into());
+        let props = serde_json::json!({ "fontSize": 12, "bold": true });
+        let [text, styled, empty, opaque, root] = {
+            let mut leaf = |context| tree.new_leaf_with_context(Style::default(), context).unwrap();
+            let text = leaf(TestContext::Json(label));
+            let styled = leaf(TestContext::Json(props));
+            let empty = leaf(TestContext::Json(Value::Null));
+            let opaque = leaf(TestContext::Opaque);
+            let column = Style { flex_direction: FlexDirection::Column, ..Default::default() };
+            [text, styled, empty, opaque, tree.new_with_children(column, &[text, styled, empty, opaque]).unwrap()]
+        };
+        let context_json = |context: &TestContext| match context {
+            TestContext::Json(value) => Some(value.clone()),
+            TestContext::Opaque => None,
+        };
+        let saved = serialize_node(&tree, root, &Style::clone, &context_json).unwrap();
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(json.contains(r#""context":"Hello""#), "{json}");
+        assert!(json.contains(r#""contextSkipped":true"#), "{json}");
+
+        let mut loaded: Taffy<TestContext> = Taffy::new();
+        let parsed: SerializedNode = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, saved);
+        let loaded_root = build_node(&mut loaded, parsed, &|style| style, &TestContext::Json, &mut Vec::new()).unwrap();
+        assert_eq!(loaded.style(loaded_root).unwrap(), tree.style(root).unwrap());
+        let children = loaded.children(loaded_root).unwrap();
+        assert_eq!(children.len(), 4);
+        for (loaded_child, child) in children.iter().zip([text, styled, empty]) {
+            assert_eq!(loaded.get_node_context(*loaded_child), tree.get_node_context(child));
+        }
+        // The opaque context couldn't be saved, so its node is loaded without one
+        assert!(tree.get_node_context(opaque).is_some());
+        assert_eq!(loaded.get_node_context(children[3]), None);
+    }
+
+    #[test]
+    fn serialized_trees_load_with_their_styles() {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let leaf = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            js_node_id(inner.new_with_children(Style { padding: length(4.0), ..Default::default() }, &[leaf]).unwrap())
+        };
+        let json = tree.serialize_tree(root).unwrap();
+        // Nodes without a context are saved without a context field
+        assert!(!json.contains("context"), "{json}");
+
+        let loaded = tree.deserialize_tree(&json).unwrap();
+        assert_ne!(loaded, root);
+        assert_eq!(tree.serialize_tree(loaded).unwrap(), json);
+        tree.compute_subtree(loaded, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(loaded), Ok(18.0));
+
+        assert!(matches!(tree.deserialize_tree("{}"), Err(TaffyError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn subtrees_move_between_trees() {
+        let worker = TaffyTree::new();
+        let (card, [title, body]) = {
+            let mut inner = worker.inner.borrow_mut();
+            let title = inner.new_leaf(Style { size: length(20.0), ..Default::default() }).unwrap();
+            let text = inner.new_leaf(Style { size: length(30.0), ..Default::default() }).unwrap();
+            let body = inner.new_with_children(Style { padding: length(5.0), ..Default::default() }, &[text]).unwrap();
+            let card = Style { flex_direction: FlexDirection::Column, gap: length(2.0), ..Default::default() };
+            let card = inner.new_with_children(card, &[title, body]).unwrap();
+            (js_node_id(card), [title, body].map(js_node_id))
+        };
+        let json = worker.serialize_tree(card).unwrap();
+
+        let main = TaffyTree::new();
+        let (root, header) = {
+            let mut inner = main.inner.borrow_mut();
+            let header = inner.new_leaf(Style { size: length(10.0), ..Default::default() }).unwrap();
+            let root = inner.new_with_children(Style::default(), &[header]).unwrap();
+            (js_node_id(root), js_node_id(header))
+        };
+        let imported = main.load_subtree(parse_saved_tree(&json).unwrap()).unwrap();
+        main.add_child(root, imported).unwrap();
+        assert_eq!(main.serialize_tree(imported).unwrap(), json);
+
+        // The structure and styles survive, so the imported nodes lay out as they did in the worker's tree
+        let imported_nodes = main.descendants(imported, Some(true)).unwrap();
+        assert_eq!(imported_nodes.len(), 4);
+        assert_eq!(main.descendants(root, None), Ok([&[header][..], &imported_nodes].concat()));
+        worker.compute_subtree(card, Size::MAX_CONTENT).unwrap();
+        main.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        for (node, &imported_node) in [card, title, body].into_iter().zip(&imported_nodes) {
+            assert_eq!(main.layout_width(imported_node), worker.layout_width(node));
+            assert_eq!(main.layout_height(imported_node), worker.layout_height(node));
+        }
+        assert_eq!((main.layout_left(imported), main.layout_width(root)), (Ok(10.0), Ok(50.0)));
+    }
+}
@@ -0,0 +1,50 @@
+//! Storing a plain integer per node, e.g. an index into the caller's own arrays
+use crate::{resolve_node, TaffyError, TaffyTree};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Stores a `u32` alongside a node, replacing any value stored before, for `get_user_data` to return
+    ///
+    /// This is a cheap alternative to `set_node_context` for when all a node needs to carry is a key back into the
+    /// caller's own data, such as an index into an array of elements: the value is a plain integer rather than a
+    /// JS value, and never reaches the measure function. Setting it doesn't mark the node dirty. The value is
+    /// dropped when the node is removed.
+    #[wasm_bindgen]
+    pub fn set_user_data(&self, node_id: u32, value: u32) -> Result<(), TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        self.node_states.borrow_mut().update(node_id, |state| state.user_data = Some(value));
+        Ok(())
+    }
+
+    /// Returns the value stored for a node with `set_user_data`, or `undefined` if none was
+    #[wasm_bindgen]
+    pub fn get_user_data(&self, node_id: u32) -> Result<Option<u32>, TaffyError> {
+        resolve_node(&*self.tree()?, node_id)?;
+        Ok(self.node_states.borrow().get(node_id).user_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+    use taffy::prelude::Style;
+
+    #[test]
+    fn user_data_round_trips() {
+        let tree = TaffyTree::new();
+        let nodes = [(); 4].map(|_| js_node_id(tree.inner.borrow_mut().new_leaf(Style::default()).unwrap()));
+        for (index, &node) in nodes.iter().enumerate().skip(1) {
+            tree.set_user_data(node, 1000 + index as u32).unwrap();
+        }
+        tree.set_user_data(nodes[3], u32::MAX).unwrap();
+
+        let values = nodes.map(|node| tree.get_user_data(node).unwrap());
+        assert_eq!(values, [None, Some(1001), Some(1002), Some(u32::MAX)]);
+
+        tree.remove(nodes[1]).unwrap();
+        assert_eq!(tree.get_user_data(nodes[1]), Err(TaffyError::InvalidNode(nodes[1])));
+        assert_eq!(tree.set_user_data(nodes[1], 1), Err(TaffyError::InvalidNode(nodes[1])));
+    }
+}
@@ -0,0 +1,155 @@
+//! Hiding nodes with `display: none` while remembering how to show them again, and taking nodes out of the flow of
+//! their parents while keeping them measurable
+use crate::{resolve_node, set_style_if_changed, TaffyError, TaffyTree};
+use taffy::style::{Display, Style};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Hides or shows a node, keeping it and its subtree in the tree
+    ///
+    /// Hiding sets the node's `display` to `"None"`, so that it and its descendants are laid out with zero size and
+    /// don't affect the layout of their siblings. Showing restores the `display` the node had when it was hidden, or
+    /// the default `display` if it was hidden by its style instead. Hiding a hidden node or showing a visible one
+    /// does nothing.
+    ///
+    /// If the style of a hidden node is replaced with `update_style`, the new style takes precedence: a later
+    /// `set_hidden(node_id, false)` only restores the remembered `display` if the node is still hidden.
+    #[wasm_bindgen]
+    pub fn set_hidden(&self, node_id: u32, hidden: bool) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let current = tree.style(node)?.display;
+        let mut node_states = self.node_states.borrow_mut();
+        let display = if hidden {
+            if current == Display::None {
+                return Ok(());
+            }
+            node_states.update(node_id, |state| state.hidden_display = Some(current));
+            Display::None
+        } else {
+            let remembered = node_states.update(node_id, |state| state.hidden_display.take());
+            if current != Display::None {
+                return Ok(());
+            }
+            remembered.unwrap_or_default()
+        };
+        let style = Style { display, ..tree.style(node)?.clone() };
+        tree.set_style(node, style)?;
+        Ok(())
+    }
+
+    /// Takes a node out of the layout of its parent, or puts it back, while keeping it measurable on its own
+    ///
+    /// This is meant for template nodes, which are measured with `node_intrinsic_size` or `measure_subtree` but
+    /// never shown. While out of flow, the node takes no space in its parent, as with `display: "None"`: it doesn't
+    /// move its siblings or size its parent, and its own layout is empty. Unlike a node hidden with `set_hidden`,
+    /// `node_intrinsic_size` and `measure_subtree` still measure it with the `display` it had when it was taken out
+    /// of flow, so its intrinsic size stays available. Unlike an absolutely positioned node, which also doesn't move
+    /// its siblings, it isn't laid out when its parent is, so it costs nothing in the parent's layout and is never
+    /// positioned within it or sized against it.
+    ///
+    /// The node is marked dirty if this changes its `display`. Taking it out of flow twice or putting back a node
+    /// that is in flow does nothing. If its style is replaced with `update_style` while it is out of flow, the new
+    /// style takes precedence, as for `set_hidden`.
+    #[wasm_bindgen]
+    pub fn set_out_of_flow(&self, node_id: u32, out_of_flow: bool) -> Result<(), TaffyError> {
+        let mut tree = self.tree_mut()?;
+        let node = resolve_node(&tree, node_id)?;
+        let current = tree.style(node)?.display;
+        let mut node_states = self.node_states.borrow_mut();
+        let display = if out_of_flow {
+            if node_states.get(node_id).out_of_flow_display.is_some() {
+                return Ok(());
+            }
+            node_states.update(node_id, |state| state.out_of_flow_display = Some(current));
+            Display::None
+        } else {
+            match node_states.update(node_id, |state| state.out_of_flow_display.take()) {
+                Some(display) if current == Display::None => display,
+                _ => return Ok(()),
+            }
+        };
+        let style = Style { display, ..tree.style(node)?.clone() };
+        set_style_if_changed(&mut tree, node, style)?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "grid"))]
+mod tests {
+    use crate::node_ids::{js_node_id, node_from_js_id};
+    use crate::{TaffyError, TaffyTree};
+    use taffy::prelude::{length, Display, Size, Style, TaffyMaxContent};
+
+    /// A 200px wide row of three 50px wide items, the middle one a grid container
+    fn row() -> (TaffyTree, u32, [u32; 3]) {
+        let tree = TaffyTree::new();
+        let (root, items) = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(50.0), height: length(10.0) }, ..Default::default() };
+            let grid = Style { display: Display::Grid, ..item.clone() };
+            let items =
+                [inner.new_leaf(item.clone()).unwrap(), inner.new_leaf(grid).unwrap(), inner.new_leaf(item).unwrap()];
+            let root = inner.new_with_children(Style { size: length(200.0), ..Default::default() }, &items).unwrap();
+            (js_node_id(root), items.map(js_node_id))
+        };
+        (tree, root, items)
+    }
+
+    #[test]
+    fn hiding_reflows_siblings_and_showing_restores_display() {
+        let (tree, root, [_, middle, last]) = row();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_left(last), Ok(100.0));
+
+        tree.set_hidden(middle, true).unwrap();
+        tree.set_hidden(middle, true).unwrap();
+        assert!(tree.dirty(root).unwrap());
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_width(middle), Ok(0.0));
+        assert_eq!(tree.layout_height(middle), Ok(0.0));
+        assert_eq!(tree.layout_left(last), Ok(50.0));
+
+        tree.set_hidden(middle, false).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.inner.borrow().style(node_from_js_id(middle)).unwrap().display, Display::Grid);
+        assert_eq!(tree.layout_width(middle), Ok(50.0));
+        assert_eq!(tree.layout_left(last), Ok(100.0));
+    }
+
+    #[test]
+    fn showing_a_node_hidden_by_its_style_uses_the_default_display() {
+        let (tree, _, [first, ..]) = row();
+        let style = Style { display: Display::None, ..Default::default() };
+        tree.inner.borrow_mut().set_style(node_from_js_id(first), style).unwrap();
+        tree.set_hidden(first, false).unwrap();
+        assert_eq!(tree.inner.borrow().style(node_from_js_id(first)).unwrap().display, Display::default());
+    }
+
+    #[test]
+    fn out_of_flow_nodes_take_no_space_but_stay_measurable() {
+        let (tree, root, [_, middle, last]) = row();
+        tree.set_out_of_flow(middle, true).unwrap();
+        tree.set_out_of_flow(middle, true).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.layout_left(last), Ok(50.0));
+        assert_eq!(tree.layout_width(middle), Ok(0.0));
+
+        // Measured on its own, the node is still a 50x10 grid container
+        let measure = |_, _, _, _: Option<&mut crate::JsContext>| (Size::ZERO, taffy::Point::NONE);
+        let size = tree.intrinsic_size(middle, "max-content", measure).unwrap();
+        assert_eq!(size, Size { width: 50.0, height: 10.0 });
+
+        tree.set_out_of_flow(middle, false).unwrap();
+        tree.compute_subtree(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(tree.inner.borrow().style(node_from_js_id(middle)).unwrap().display, Display::Grid);
+        assert_eq!(tree.layout_left(last), Ok(100.0));
+    }
+
+    #[test]
+    fn set_hidden_invalid_node() {
+        let tree = TaffyTree::new();
+        assert_eq!(tree.set_hidden(1, true), Err(TaffyError::InvalidNode(1)));
+    }
+}
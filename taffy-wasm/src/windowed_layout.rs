@@ -0,0 +1,252 @@
+//! Laying out only a window of the children of a long list, e.g. the visible rows of a virtualized list, with the
+//! other children stood in for by estimates
+use crate::available_space::{decode_available_space, BoundedAvailableSpace};
+use crate::layout_json::{node_boxes, NodeBox};
+use crate::node_ids::{js_node_id, new_node_id};
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use taffy::{prelude::*, style::Style, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// The layout of a window of the children of a container, returned by `compute_layout_windowed`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WindowedLayout {
+    /// The distance along the main axis from the container's border box to the border box of the first child of
+    /// the window
+    offset: f32,
+    /// The size along the main axis, margins included, that each child outside the window was estimated at
+    item_size: f32,
+    /// The boxes of the container and of the children of the window and their descendants, in paint order
+    boxes: Vec<NodeBox>,
+}
+
+/// Returns whether a container with `style` lays its children out along the horizontal axis
+fn is_horizontal(style: &Style) -> bool {
+    style.display == Display::Flex && matches!(style.flex_direction, FlexDirection::Row | FlexDirection::RowReverse)
+}
+
+/// Returns the size of the margin box of a node along the main axis of its parent
+fn main_size(layout: &Layout, horizontal: bool) -> f32 {
+    match horizontal {
+        true => layout.margin.left + layout.size.width + layout.margin.right,
+        false => layout.margin.top + layout.size.height + layout.margin.bottom,
+    }
+}
+
+impl TaffyTree {
+    /// Lays out a copy of the subtree rooted at `container` within `available`, in which the children outside
+    /// `window` are replaced by empty leaves of `item_size` along the main axis, or of the average size of the
+    /// children of the window if it is `None`
+    ///
+    /// `measure` sizes the leaves of the window without a content size, as the measure function of
+    /// `compute_layout_with_measure` does. It is never called for the children outside the window.
+    fn windowed_layout(
+        &self,
+        container: u32,
+        available: BoundedAvailableSpace,
+        window: Range<u32>,
+        item_size: Option<f32>,
+        mut measure: impl FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut JsContext>) -> Size<f32>,
+    ) -> Result<WindowedLayout, TaffyError> {
+        if let Some(item_size) = item_size.filter(|size| !(size.is_finite() && *size >= 0.0)) {
+            return Err(TaffyError::InvalidArgument(format!(
+                "itemSize must be a non-negative number of pixels, got {item_size}"
+            )));
+        }
+        if window.start >= window.end {
+            return Err(TaffyError::InvalidArgument(format!(
+                "startIndex ({}) must be less than endIndex ({})",
+                window.start, window.end
+            )));
+        }
+        let mut scratch = {
+            let tree = self.tree()?;
+            let node = resolve_node(&tree, container)?;
+            self.check_layout_depth(&tree, node)?;
+            tree.clone()
+        };
+        let container_node = resolve_node(&scratch, container)?;
+        let children = scratch.children(container_node)?;
+        if window.end as usize > children.len() {
+            return Err(TaffyError::InvalidArgument(format!(
+                "endIndex ({}) is greater than the number of children of node {container} ({})",
+                window.end,
+                children.len()
+            )));
+        }
+        let horizontal = is_horizontal(scratch.style(container_node)?);
+        let items = &children[window.start as usize..window.end as usize];
+
+        let mut lay_out = |scratch: &mut Taffy<JsContext>, placeholders: &BTreeSet<u32>| {
+            let mut compute = |scratch: &mut Taffy<JsContext>, space| {
+                scratch.compute_layout_with_measure(
+                    container_node,
+                    space,
+                    |known_dimensions, available, node, context, _| match placeholders.contains(&js_node_id(node)) {
+                        true => known_dimensions.unwrap_or(Size::ZERO),
+                        false => self
+                            .fixed_content_size(known_dimensions, available, node)
+                            .unwrap_or_else(|| measure(known_dimensions, available, node, context)),
+                    },
+                )
+            };
+            compute(scratch, available.space)?;
+            if let Some(space) = available.relayout_space(scratch.unrounded_layout(container_node).size) {
+                compute(scratch, space)?;
+            }
+            Ok::<_, TaffyError>(())
+        };
+
+        let item_size = match item_size {
+            Some(item_size) => item_size,
+            None => {
+                // The window is laid out on its own first, to measure the size of its children
+                scratch.set_children(container_node, items)?;
+                lay_out(&mut scratch, &BTreeSet::new())?;
+                let sizes = items.iter().map(|&item| main_size(scratch.unrounded_layout(item), horizontal));
+                sizes.sum::<f32>() / items.len() as f32
+            }
+        };
+
+        let placeholder = {
+            let main = length(item_size);
+            let size = match horizontal {
+                true => Size { width: main, height: auto() },
+                false => Size { width: auto(), height: main },
+            };
+            Style { size, flex_shrink: 0.0, ..Default::default() }
+        };
+        let mut placeholders = BTreeSet::new();
+        let mut estimated_children = Vec::with_capacity(children.len());
+        for (index, &child) in (0..).zip(&children) {
+            if window.contains(&index) {
+                estimated_children.push(child);
+            } else {
+                let node = scratch.new_leaf(placeholder.clone())?;
+                placeholders.insert(new_node_id(&mut scratch, node)?);
+                estimated_children.push(node);
+            }
+        }
+        scratch.set_children(container_node, &estimated_children)?;
+        lay_out(&mut scratch, &placeholders)?;
+
+        let first = scratch.layout(items[0])?.location;
+        Ok(WindowedLayout {
+            offset: if horizontal { first.x } else { first.y },
+            item_size,
+            boxes: node_boxes(&scratch, container_node)?
+                .into_iter()
+                .filter(|node_box| !placeholders.contains(&node_box.id))
+                .collect(),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Lays out only the children of `container` from `start_index` up to but not including `end_index`, estimating
+    /// the size of the others, and returns `{ offset, itemSize, boxes: [{ id, x, y, w, h }] }`
+    ///
+    /// This is meant for virtualized lists, which only create and draw the rows in view plus a buffer: `offset` is
+    /// the distance from the start of the container to the first child of the window along the main axis, where
+    /// the window is drawn within a scrolled area of the size of the container. The main axis is horizontal for a
+    /// flex row, and vertical for a flex column and for block and grid containers. `boxes` are the boxes of the
+    /// container, whose size along the main axis is the estimated size of the whole list, and of the children of
+    /// the window and their descendants, as from `compute_layout_to_json`.
+    ///
+    /// Each child outside the window is stood in for by an empty box of `item_size` pixels along the main axis,
+    /// margins included, which is never measured and has no descendants, so `measure_func` is only called for the
+    /// leaves of the window. If `item_size` is not given, it is estimated as the average size of the children of
+    /// the window, by laying the window out on its own first. Gaps, padding and alignment apply to the stand-ins as
+    /// to the children they replace. The estimate is exact for a list of rows of one size; otherwise, `offset` is
+    /// off by the difference between the estimate and the actual sizes of the children before the window, and
+    /// changes as the window moves over rows of other sizes, so the scroll position may jump. Passing the average
+    /// of the sizes measured so far as `item_size` makes the estimate converge as the list is scrolled.
+    ///
+    /// Leaves are sized by their content size if they have one, or else by `measure_func`, called as by
+    /// `compute_layout_with_measure`, and are empty if there is no `measure_func`. As with
+    /// `compute_layout_vertical`, the subtree is laid out on a copy of the tree, so the results are only returned,
+    /// not stored: the layouts read with `layout_width` and friends are unchanged.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available` is not a valid `LayoutAvailableSpace`, if `start_index` is
+    /// not less than `end_index`, if `end_index` is greater than the number of children of `container`, or if
+    /// `item_size` is negative or not finite.
+    #[wasm_bindgen]
+    pub fn compute_layout_windowed(
+        &self,
+        container: u32,
+        available: JsValue,
+        start_index: u32,
+        end_index: u32,
+        item_size: Option<f32>,
+        measure_func: Option<js_sys::Function>,
+    ) -> Result<JsValue, TaffyError> {
+        let available = decode_available_space(available)?;
+        let window = start_index..end_index;
+        let layout = self.windowed_layout(
+            container,
+            available,
+            window,
+            item_size,
+            |known_dimensions, available, node, context| match &measure_func {
+                Some(measure_func) => {
+                    self.call_js_measure_for(measure_func, &JsValue::NULL, known_dimensions, available, node, context).0
+                }
+                None => Size::ZERO,
+            },
+        )?;
+        Ok(serde_wasm_bindgen::to_value(&layout).unwrap_or(JsValue::NULL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_window_of_a_long_list_is_measured() {
+        let tree = TaffyTree::new();
+        let (list, rows) = {
+            let mut inner = tree.inner.borrow_mut();
+            let rows: Vec<_> = (0..10_000).map(|_| inner.new_leaf(Style::default()).unwrap()).collect();
+            let list = Style {
+                flex_direction: FlexDirection::Column,
+                size: Size { width: length(300.0), height: auto() },
+                gap: length(4.0),
+                ..Default::default()
+            };
+            (
+                js_node_id(inner.new_with_children(list, &rows).unwrap()),
+                rows.into_iter().map(js_node_id).collect::<Vec<_>>(),
+            )
+        };
+        let available = BoundedAvailableSpace { space: Size::MAX_CONTENT, limits: Size::NONE };
+        let window = &rows[100..120];
+
+        let mut measured = BTreeSet::new();
+        let layout = tree
+            .windowed_layout(list, available, 100..120, None, |_, _, node, _| {
+                measured.insert(js_node_id(node));
+                Size { width: 300.0, height: 20.0 }
+            })
+            .unwrap();
+        assert_eq!(measured, window.iter().copied().collect());
+        // Every row before the window is estimated at the 20px of the rows of the window, plus the gap
+        assert_eq!((layout.offset, layout.item_size), (2400.0, 20.0));
+        assert_eq!(layout.boxes[0], NodeBox { id: list, x: 0.0, y: 0.0, w: 300.0, h: 10_000.0 * 24.0 - 4.0 });
+        assert_eq!(layout.boxes[1..].iter().map(|node_box| node_box.id).collect::<Vec<_>>(), window);
+        assert_eq!(layout.boxes[2], NodeBox { id: rows[101], x: 0.0, y: 2424.0, w: 300.0, h: 20.0 });
+
+        // A given item size is used as it is
+        let layout = tree.windowed_layout(list, available, 100..120, Some(30.0), |_, _, _, _| Size::ZERO).unwrap();
+        assert_eq!(layout.offset, 3400.0);
+        // The tree itself is not laid out
+        assert_eq!(tree.layout_height(rows[100]), Ok(0.0));
+
+        let past_the_end = tree.windowed_layout(list, available, 9990..10_001, None, |_, _, _, _| Size::ZERO);
+        assert!(matches!(past_the_end, Err(TaffyError::InvalidArgument(_))));
+    }
+}
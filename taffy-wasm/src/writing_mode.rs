@@ -0,0 +1,254 @@
+//! Emulating vertical writing modes, which taffy doesn't support, by laying out a transposed copy of a subtree
+use crate::available_space::{decode_available_space, BoundedAvailableSpace};
+use crate::{resolve_node, JsContext, TaffyError, TaffyTree};
+use js_sys::Float32Array;
+use taffy::{prelude::*, style::Style, Point, TaffyTree as Taffy};
+use wasm_bindgen::prelude::*;
+
+/// A vertical CSS `writing-mode`: the inline axis runs top to bottom, and the block axis horizontally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VerticalWritingMode {
+    /// `vertical-rl`: blocks stack from right to left, as in vertical CJK text
+    RightToLeft,
+    /// `vertical-lr`: blocks stack from left to right, as in vertical Mongolian text
+    LeftToRight,
+}
+
+/// Parses the CSS keyword of a vertical writing mode
+fn parse_writing_mode(mode: &str) -> Result<VerticalWritingMode, TaffyError> {
+    match mode {
+        "vertical-rl" => Ok(VerticalWritingMode::RightToLeft),
+        "vertical-lr" => Ok(VerticalWritingMode::LeftToRight),
+        _ => Err(TaffyError::InvalidArgument(format!(
+            "{mode:?} is not a vertical writing mode, expected \"vertical-rl\" or \"vertical-lr\""
+        ))),
+    }
+}
+
+/// Swaps the width and height of a size
+fn transpose<T>(size: Size<T>) -> Size<T> {
+    Size { width: size.height, height: size.width }
+}
+
+impl VerticalWritingMode {
+    /// Maps the physical edges of a box to its logical edges in a horizontal layout: the inline-start edge (top) to
+    /// the left, and the block-start edge (right or left) to the top
+    fn logical_edges<T>(self, edges: Rect<T>) -> Rect<T> {
+        match self {
+            VerticalWritingMode::RightToLeft => {
+                Rect { left: edges.top, right: edges.bottom, top: edges.right, bottom: edges.left }
+            }
+            VerticalWritingMode::LeftToRight => {
+                Rect { left: edges.top, right: edges.bottom, top: edges.left, bottom: edges.right }
+            }
+        }
+    }
+
+    /// Returns the style that lays a node out horizontally as `style` would be laid out in this writing mode
+    ///
+    /// Physical properties are transposed, while properties that are already logical in CSS, such as
+    /// `flex_direction`, the grid templates, `gap` and the alignment properties, are kept as they are.
+    fn logical_style(self, style: &Style) -> Style {
+        Style {
+            size: transpose(style.size),
+            min_size: transpose(style.min_size),
+            max_size: transpose(style.max_size),
+            aspect_ratio: style.aspect_ratio.map(|ratio| 1.0 / ratio),
+            margin: self.logical_edges(style.margin),
+            padding: self.logical_edges(style.padding),
+            border: self.logical_edges(style.border),
+            inset: self.logical_edges(style.inset),
+            overflow: style.overflow.transpose(),
+            ..style.clone()
+        }
+    }
+}
+
+/// Returns the physical `[x, y, width, height]` of every node in the subtree rooted at `root` of `tree`, laid out
+/// horizontally with logical styles, in paint order
+fn physical_layout_values(
+    tree: &Taffy<JsContext>,
+    root: NodeId,
+    mode: VerticalWritingMode,
+) -> Result<Vec<f32>, TaffyError> {
+    let mut values = Vec::new();
+    // Each node is visited with the absolute location of its parent and the physical width of its parent, which
+    // the block offsets of `vertical-rl` are measured back from
+    let mut stack = vec![(root, Point::ZERO, None)];
+    while let Some((node, parent_location, parent_width)) = stack.pop() {
+        let layout = tree.layout(node)?;
+        let size = transpose(layout.size);
+        let x = match (mode, parent_width) {
+            (VerticalWritingMode::RightToLeft, Some(parent_width)) => parent_width - layout.location.y - size.width,
+            _ => layout.location.y,
+        };
+        let location = Point { x: parent_location.x + x, y: parent_location.y + layout.location.x };
+        values.extend([location.x, location.y, size.width, size.height]);
+        stack.extend(tree.children(node)?.into_iter().rev().map(|child| (child, location, Some(size.width))));
+    }
+    Ok(values)
+}
+
+impl TaffyTree {
+    /// Lays out a copy of the subtree rooted at `root` in a vertical writing mode, returning the physical layout of
+    /// each of its nodes as by `layout_buffer`
+    ///
+    /// `measure` sizes leaves without a content size in physical terms, as the measure function of
+    /// `compute_layout_with_measure` does.
+    fn vertical_layout_values(
+        &self,
+        root: u32,
+        available: BoundedAvailableSpace,
+        mode: VerticalWritingMode,
+        mut measure: impl FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut JsContext>) -> Size<f32>,
+    ) -> Result<Vec<f32>, TaffyError> {
+        let mut logical = self.tree()?.clone();
+        let root_node = resolve_node(&logical, root)?;
+        self.check_layout_depth(&logical, root_node)?;
+        let mut stack = vec![root_node];
+        while let Some(node) = stack.pop() {
+            let style = mode.logical_style(logical.style(node)?);
+            logical.set_style(node, style)?;
+            stack.extend(logical.children(node)?);
+        }
+
+        let mut compute = |logical: &mut Taffy<JsContext>, space: Size<AvailableSpace>| {
+            logical.compute_layout_with_measure(
+                root_node,
+                transpose(space),
+                |known_dimensions, available, leaf, context, _| {
+                    let (known_dimensions, available) = (transpose(known_dimensions), transpose(available));
+                    let size = self
+                        .fixed_content_size(known_dimensions, available, leaf)
+                        .unwrap_or_else(|| measure(known_dimensions, available, leaf, context));
+                    transpose(size)
+                },
+            )
+        };
+        compute(&mut logical, available.space)?;
+        let size = transpose(logical.unrounded_layout(root_node).size);
+        if let Some(space) = available.relayout_space(size) {
+            compute(&mut logical, space)?;
+        }
+        physical_layout_values(&logical, root_node, mode)
+    }
+}
+
+#[wasm_bindgen]
+impl TaffyTree {
+    /// Computes the layout of the tree rooted at `root` as if it were in the vertical writing mode `mode`, and
+    /// returns it as a `Float32Array` of `[x, y, width, height]` per node, in the same order and coordinates as
+    /// `layout_buffer`
+    ///
+    /// taffy only lays out horizontal writing modes. This approximates CSS `writing-mode: vertical-rl` (`mode` of
+    /// `"vertical-rl"`, as used for CJK text) or `writing-mode: vertical-lr` (`"vertical-lr"`) applied to `root`
+    /// and every node below it, by laying out a copy of the subtree with its physical style properties transposed
+    /// and mapping the result back to physical coordinates:
+    ///
+    /// - The inline axis runs from top to bottom, and the block axis from right to left (`vertical-rl`) or left
+    ///   to right (`vertical-lr`). `flex_direction: "Row"` therefore lays items out top to bottom, and `"Column"`
+    ///   stacks them along the block axis. Grid columns run along the inline axis and rows along the block axis.
+    /// - `size`, `min_size`, `max_size`, `aspect_ratio`, `margin`, `padding`, `border`, `inset` and `overflow` are
+    ///   physical, as in CSS, and keep their meaning. `gap`, the grid templates and the alignment properties are
+    ///   logical: `justify_content` aligns along the inline axis of a block container, for instance.
+    /// - `available` is a physical `LayoutAvailableSpace`, as taken by `compute_layout_v2`.
+    ///
+    /// Leaves are sized by their content size if they have one, or else by `measure_func`, called as by
+    /// `compute_layout_with_measure` with physical known dimensions and constraints, so a measure function for
+    /// vertical text must itself lay the text out vertically. Leaves are empty if there is no `measure_func`.
+    ///
+    /// Limitations: the whole subtree is in the same writing mode, so there are no orthogonal flows; `direction:
+    /// "Rtl"` makes the inline axis run from bottom to top; baselines are horizontal, so baseline alignment is not
+    /// meaningful; and the results are only returned, not stored: the layouts read with `layout_width` and friends
+    /// are unchanged. Each call copies the whole tree and lays out the subtree from scratch.
+    ///
+    /// Throws an `INVALID_ARGUMENT` error if `available` is not a valid `LayoutAvailableSpace`, or if `mode` is not
+    /// `"vertical-rl"` or `"vertical-lr"`.
+    #[wasm_bindgen]
+    pub fn compute_layout_vertical(
+        &self,
+        root: u32,
+        available: JsValue,
+        mode: &str,
+        measure_func: Option<js_sys::Function>,
+    ) -> Result<Float32Array, TaffyError> {
+        let available = decode_available_space(available)?;
+        let mode = parse_writing_mode(mode)?;
+        let values =
+            self.vertical_layout_values(root, available, mode, |known_dimensions, available, node, context| {
+                match &measure_func {
+                    Some(measure_func) => {
+                        self.call_js_measure_for(
+                            measure_func,
+                            &JsValue::NULL,
+                            known_dimensions,
+                            available,
+                            node,
+                            context,
+                        )
+                        .0
+                    }
+                    None => Size::ZERO,
+                }
+            })?;
+        Ok(Float32Array::from(values.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_ids::js_node_id;
+
+    /// Lays out a flex column of three 10x20 items in a 100x50 root with the given writing mode
+    fn column_values(mode: VerticalWritingMode) -> Vec<f32> {
+        let tree = TaffyTree::new();
+        let root = {
+            let mut inner = tree.inner.borrow_mut();
+            let item = Style { size: Size { width: length(10.0), height: length(20.0) }, ..Default::default() };
+            let items = [(); 3].map(|_| inner.new_leaf(item.clone()).unwrap());
+            let column = Style { flex_direction: FlexDirection::Column, padding: length(2.0), ..Default::default() };
+            js_node_id(inner.new_with_children(column, &items).unwrap())
+        };
+        let available = BoundedAvailableSpace {
+            space: Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::Definite(50.0) },
+            limits: Size::NONE,
+        };
+        tree.vertical_layout_values(root, available, mode, |_, _, _, _| Size::ZERO).unwrap()
+    }
+
+    #[test]
+    fn column_stacks_along_the_block_axis() {
+        // The column is sized to its content: its inline size (height) to an item's 20px height and its block size
+        // (width) to its three 10px items, each plus 2px of padding on either side. In vertical-rl the first item
+        // is at the right.
+        #[rustfmt::skip]
+        assert_eq!(column_values(VerticalWritingMode::RightToLeft), [
+            0.0, 0.0, 34.0, 24.0,
+            22.0, 2.0, 10.0, 20.0,
+            12.0, 2.0, 10.0, 20.0,
+            2.0, 2.0, 10.0, 20.0,
+        ]);
+        #[rustfmt::skip]
+        assert_eq!(column_values(VerticalWritingMode::LeftToRight), [
+            0.0, 0.0, 34.0, 24.0,
+            2.0, 2.0, 10.0, 20.0,
+            12.0, 2.0, 10.0, 20.0,
+            22.0, 2.0, 10.0, 20.0,
+        ]);
+    }
+
+    #[test]
+    fn physical_edges_map_to_logical_edges() {
+        let edges = Rect { left: 1, right: 2, top: 3, bottom: 4 };
+        let rl = VerticalWritingMode::RightToLeft.logical_edges(edges);
+        assert_eq!((rl.left, rl.right, rl.top, rl.bottom), (3, 4, 2, 1));
+        let lr = VerticalWritingMode::LeftToRight.logical_edges(edges);
+        assert_eq!((lr.left, lr.right, lr.top, lr.bottom), (3, 4, 1, 2));
+    }
+
+    #[test]
+    fn invalid_writing_mode_is_rejected() {
+        assert!(matches!(parse_writing_mode("horizontal-tb"), Err(TaffyError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,33 @@
+// Checks the validation of the GridPlacement helpers generated into pkg/GridPlacement.js. Run
+// `cargo build -p taffy-wasm` (or `npm run build`) first, then `npm test`.
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { GridPlacement } from '../pkg/GridPlacement.js';
+
+test('GridPlacement builds auto, line and span placements', () => {
+  assert.equal(GridPlacement.auto(), 'Auto');
+  assert.deepEqual(GridPlacement.line(1), { Line: 1 });
+  assert.deepEqual(GridPlacement.line(-1), { Line: -1 });
+  assert.deepEqual(GridPlacement.line(-32768), { Line: -32768 });
+  assert.deepEqual(GridPlacement.line(32767), { Line: 32767 });
+  assert.deepEqual(GridPlacement.span(1), { Span: 1 });
+  assert.deepEqual(GridPlacement.span(65535), { Span: 65535 });
+});
+
+test('GridPlacement.line throws for line 0, fractions and lines out of range', () => {
+  for (const index of [0, 1.5, NaN, Infinity, -32769, 32768]) {
+    assert.throws(() => GridPlacement.line(index), {
+      name: 'RangeError',
+      message: `GridPlacement.line: expected a non-zero integer line index, got ${index}`,
+    });
+  }
+});
+
+test('GridPlacement.span throws for spans that are not positive integers or are out of range', () => {
+  for (const count of [0, -1, 2.5, NaN, 65536]) {
+    assert.throws(() => GridPlacement.span(count), {
+      name: 'RangeError',
+      message: `GridPlacement.span: expected a positive integer span, got ${count}`,
+    });
+  }
+});
@@ -0,0 +1,53 @@
+// Checks the receiver the measure functions of the wasm bindings are called with. Needs the bindings built into
+// pkg/ with `npm run build` first; skipped otherwise.
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { existsSync, readFileSync } from 'node:fs';
+
+const wasmUrl = new URL('../pkg/taffy_wasm_bg.wasm', import.meta.url);
+const skip = !existsSync(wasmUrl) && 'the wasm bindings are not built; run `npm run build` first';
+const bindings = skip ? null : await import('../pkg/taffy_wasm.js');
+bindings?.initSync({ module: readFileSync(wasmUrl) });
+
+/** A tree of a root with one leaf, which has a context so that it is measured */
+function treeWithLeaf() {
+  const tree = new bindings.TaffyTree();
+  const leaf = tree.new_leaf({});
+  tree.set_node_context(leaf, 'text');
+  const root = tree.new_leaf({});
+  tree.add_child(root, leaf);
+  return { tree, root, leaf };
+}
+
+test('compute_layout_with_measure_this calls measure_func with thisArg as this', { skip }, () => {
+  const { tree, root, leaf } = treeWithLeaf();
+  const measurer = {
+    width: 42,
+    measure(context) {
+      assert.equal(context, 'text');
+      return { width: this.width, height: 10 };
+    },
+  };
+
+  tree.compute_layout_with_measure_this(root, 100, 100, measurer, measurer.measure);
+  assert.equal(tree.layout_width(leaf), 42);
+});
+
+test('the measure functions not given a receiver call measure_func with a this of null', { skip }, () => {
+  const { tree, root, leaf } = treeWithLeaf();
+  const assertCalledWithNull = (layOut) => {
+    const receivers = [];
+    layOut(function () {
+      receivers.push(this);
+      return { width: 10, height: 10 };
+    });
+    assert.ok(receivers.length > 0, 'measure_func was not called');
+    assert.ok(receivers.every((receiver) => receiver === null), `called with ${receivers}`);
+  };
+
+  assertCalledWithNull((measure) => tree.compute_layout_with_measure(root, 100, 100, measure));
+  assertCalledWithNull((measure) => tree.compute_layout_eager_measure(root, 100, 100, measure));
+  tree.mark_dirty(leaf);
+  assertCalledWithNull((measure) => tree.recompute(root, measure));
+  assertCalledWithNull((measure) => tree.measure_subtree(root, { width: 50, height: 50 }, measure));
+});
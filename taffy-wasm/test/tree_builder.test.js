@@ -0,0 +1,42 @@
+// Checks the shape of the trees built by the h and buildTree helpers generated into pkg/TreeBuilder.js. Run
+// `cargo build -p taffy-wasm` (or `npm run build`) first, then `npm test`.
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { h, buildTree } from '../pkg/TreeBuilder.js';
+
+/** A stand-in for TaffyTree that records the style and children of each node created with new_with_children */
+function recordingTree() {
+  const nodes = [];
+  return {
+    nodes,
+    new_with_children(style, children) {
+      assert.ok(children instanceof Uint32Array);
+      for (const child of children) {
+        assert.ok(child < nodes.length, `child ${child} was created after its parent`);
+      }
+      nodes.push({ style, children: Array.from(children) });
+      return nodes.length - 1;
+    },
+  };
+}
+
+test('buildTree creates each node once, after its children', () => {
+  const rowStyle = { display: 'Flex' };
+  const itemStyle = { flex_grow: 1 };
+  const taffy = recordingTree();
+
+  const root = buildTree(taffy, h(rowStyle, h(itemStyle), h(itemStyle, h({})), [h({}), h({})]));
+
+  assert.equal(taffy.nodes.length, 6);
+  const { style, children } = taffy.nodes[root];
+  assert.equal(style, rowStyle);
+  assert.equal(children.length, 4);
+  assert.deepEqual(taffy.nodes[children[0]], { style: itemStyle, children: [] });
+  assert.equal(taffy.nodes[children[1]].children.length, 1);
+});
+
+test('buildTree of a single node creates a leaf', () => {
+  const taffy = recordingTree();
+  assert.equal(buildTree(taffy, h({ display: 'None' })), 0);
+  assert.deepEqual(taffy.nodes, [{ style: { display: 'None' }, children: [] }]);
+});
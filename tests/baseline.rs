@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod baseline {
+    use taffy::prelude::*;
+    use taffy_test_helpers::new_test_tree;
+
+    /// Aligns a 10x40 leaf by its baseline with a 60px tall flex container with `style`, holding a 10x30 leaf then
+    /// a 10x10 leaf, and returns the vertical offsets of the leaf and of the container
+    ///
+    /// The leaf's baseline is its bottom edge, as is that of the container's first item, which sets the container's
+    /// baseline.
+    fn aligned_offsets(style: Style) -> (f32, f32) {
+        let mut taffy = new_test_tree();
+        let leaf =
+            |height: f32| Style { size: Size { width: length(10.0), height: length(height) }, ..Default::default() };
+        let items = [taffy.new_leaf(leaf(30.0)).unwrap(), taffy.new_leaf(leaf(10.0)).unwrap()];
+        let container = taffy
+            .new_with_children(Style { size: Size { width: auto(), height: length(60.0) }, ..style }, &items)
+            .unwrap();
+        let sibling = taffy.new_leaf(leaf(40.0)).unwrap();
+        let root = taffy
+            .new_with_children(
+                Style { align_items: Some(AlignItems::Baseline), ..Default::default() },
+                &[sibling, container],
+            )
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        (taffy.layout(sibling).unwrap().location.y, taffy.layout(container).unwrap().location.y)
+    }
+
+    #[test]
+    fn flex_row_baseline_counts_item_cross_offset_once() {
+        // The first item is aligned to the bottom of the row, so the container's baseline is 30 + 30 = 60px from its
+        // top, and the leaf is moved 20px down to line up with it
+        let style = Style { align_items: Some(AlignItems::FlexEnd), ..Default::default() };
+        assert_eq!(aligned_offsets(style), (20.0, 0.0));
+    }
+
+    #[test]
+    fn flex_column_baseline_counts_item_main_offset_once() {
+        // The items are packed at the bottom of the column, so the container's baseline is 20 + 30 = 50px from its
+        // top, and the leaf is moved 10px down to line up with it
+        let style = Style {
+            flex_direction: FlexDirection::Column,
+            justify_content: Some(JustifyContent::FlexEnd),
+            ..Default::default()
+        };
+        assert_eq!(aligned_offsets(style), (10.0, 0.0));
+    }
+}
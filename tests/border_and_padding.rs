@@ -109,3 +109,56 @@ fn vertical_border_and_padding_percentage_values_use_available_space_correctly()
     assert_eq!(layout.size.width, 200.0);
     assert_eq!(layout.size.height, 200.0);
 }
+
+/// Lays out a 200x400 container of the given display holding a content-box leaf with 10% vertical padding, returning
+/// the layout of the leaf
+fn percentage_padding_child_layout(display: Display) -> Layout {
+    let mut taffy = new_test_tree();
+    let child = taffy
+        .new_leaf(Style {
+            box_sizing: BoxSizing::ContentBox,
+            padding: Rect { top: LengthPercentage::from_percent(0.1), ..Rect::zero() },
+            size: Size { width: Dimension::from_length(50.0), height: Dimension::from_length(30.0) },
+            ..Default::default()
+        })
+        .unwrap();
+    let container_size = Size { width: Dimension::from_length(200.0), height: Dimension::from_length(400.0) };
+    let container =
+        taffy.new_with_children(Style { display, size: container_size, ..Default::default() }, &[child]).unwrap();
+    taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+    *taffy.layout(child).unwrap()
+}
+
+#[test]
+fn vertical_percentage_padding_resolves_against_container_width() {
+    for display in [Display::Block, Display::Flex, Display::Grid] {
+        let layout = percentage_padding_child_layout(display);
+        // 10% of the 200px wide container, not of its 400px height
+        assert_eq!(layout.padding.top, 20.0, "{display:?}");
+        assert_eq!(layout.size.height, 50.0, "{display:?}");
+    }
+}
+
+#[test]
+fn stretched_flex_item_percentage_padding_resolves_against_container_width() {
+    let mut taffy = new_test_tree();
+    // A content-box item with an auto height is stretched to the 400px height of the row, but no further than its
+    // max height plus its vertical padding
+    let child = taffy
+        .new_leaf(Style {
+            box_sizing: BoxSizing::ContentBox,
+            padding: Rect { top: LengthPercentage::from_percent(0.1), ..Rect::zero() },
+            size: Size { width: Dimension::from_length(50.0), height: Dimension::auto() },
+            max_size: Size { width: Dimension::auto(), height: Dimension::from_length(100.0) },
+            ..Default::default()
+        })
+        .unwrap();
+    let container_size = Size { width: Dimension::from_length(200.0), height: Dimension::from_length(400.0) };
+    let container = taffy.new_with_children(Style { size: container_size, ..Default::default() }, &[child]).unwrap();
+    taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+    let layout = taffy.layout(child).unwrap();
+    // 10% of the 200px wide container, not of its 400px height
+    assert_eq!(layout.padding.top, 20.0);
+    assert_eq!(layout.size.height, 120.0);
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod detailed_flex_info {
+    use taffy::prelude::*;
+    use taffy::{DetailedFlexLineInfo, DetailedLayoutInfo};
+    use taffy_test_helpers::new_test_tree;
+
+    /// Lays out three 40x20 items in a wrapping 100x100 row with 5px padding and a 4px column gap, so that two items
+    /// fit on the first line and one on the second, returning the container's flex lines
+    fn flex_lines(flex_wrap: FlexWrap) -> Vec<DetailedFlexLineInfo> {
+        let mut taffy = new_test_tree();
+        let item = Style { size: Size { width: length(40.0), height: length(20.0) }, ..Default::default() };
+        let items: Vec<_> = (0..3).map(|_| taffy.new_leaf(item.clone()).unwrap()).collect();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    flex_wrap,
+                    justify_content: Some(JustifyContent::SpaceBetween),
+                    align_content: Some(AlignContent::SpaceBetween),
+                    gap: Size { width: length(4.0), height: zero() },
+                    padding: length(5.0),
+                    size: Size { width: length(100.0), height: length(100.0) },
+                    ..Default::default()
+                },
+                &items,
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        match taffy.detailed_layout_info(container) {
+            DetailedLayoutInfo::Flexbox(info) => info.lines.clone(),
+            info => panic!("expected flexbox info, got {info:?}"),
+        }
+    }
+
+    #[test]
+    fn wrapping_lines_report_their_positions_and_free_space() {
+        assert_eq!(
+            flex_lines(FlexWrap::Wrap),
+            vec![
+                DetailedFlexLineInfo {
+                    cross_offset: 5.0,
+                    cross_size: 20.0,
+                    main_free_space: 6.0,
+                    initial_main_free_space: 6.0,
+                    main_offsets: vec![0.0, 10.0],
+                    flex_bases: vec![40.0, 40.0],
+                },
+                // A single item can't be spread out, so space-between falls back to flex-start
+                DetailedFlexLineInfo {
+                    cross_offset: 75.0,
+                    cross_size: 20.0,
+                    main_free_space: 50.0,
+                    initial_main_free_space: 50.0,
+                    main_offsets: vec![0.0],
+                    flex_bases: vec![40.0],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_reverse_lines_are_stacked_from_the_cross_end() {
+        let lines = flex_lines(FlexWrap::WrapReverse);
+        assert_eq!(lines.iter().map(|line| line.cross_offset).collect::<Vec<_>>(), vec![75.0, 5.0]);
+        assert_eq!(lines[0].main_offsets, vec![0.0, 10.0]);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod direction {
+    use taffy::prelude::*;
+    use taffy::Point;
+    use taffy_test_helpers::new_test_tree;
+
+    fn fixed(width: f32, height: f32) -> Style {
+        Style { size: Size { width: length(width), height: length(height) }, ..Default::default() }
+    }
+
+    /// Lays out a 100x100 container with the given style and children, returning the location of each child
+    fn child_locations(container_style: Style, children: &[Style]) -> Vec<Point<f32>> {
+        let mut taffy = new_test_tree();
+        let children: Vec<_> = children.iter().map(|style| taffy.new_leaf(style.clone()).unwrap()).collect();
+        let container = taffy.new_with_children(container_style, &children).unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        children.iter().map(|&child| taffy.layout(child).unwrap().location).collect()
+    }
+
+    fn rtl(style: Style) -> Style {
+        Style { direction: Direction::Rtl, size: Size { width: length(100.0), height: length(100.0) }, ..style }
+    }
+
+    #[test]
+    fn rtl_row_places_first_child_on_the_right() {
+        let locations = child_locations(rtl(Style::default()), &[fixed(20.0, 10.0), fixed(30.0, 10.0)]);
+        assert_eq!(locations, vec![Point { x: 80.0, y: 0.0 }, Point { x: 50.0, y: 0.0 }]);
+    }
+
+    #[test]
+    fn rtl_row_reverse_places_first_child_on_the_left() {
+        let style = Style { flex_direction: FlexDirection::RowReverse, ..Default::default() };
+        let locations = child_locations(rtl(style), &[fixed(20.0, 10.0), fixed(30.0, 10.0)]);
+        assert_eq!(locations, vec![Point { x: 0.0, y: 0.0 }, Point { x: 20.0, y: 0.0 }]);
+    }
+
+    #[test]
+    fn rtl_row_justify_content() {
+        let children = [fixed(20.0, 10.0), fixed(30.0, 10.0)];
+        let x = |justify_content| {
+            let style = Style { justify_content: Some(justify_content), ..Default::default() };
+            child_locations(rtl(style), &children).iter().map(|location| location.x).collect::<Vec<_>>()
+        };
+
+        assert_eq!(x(JustifyContent::Start), vec![80.0, 50.0]);
+        assert_eq!(x(JustifyContent::FlexStart), vec![80.0, 50.0]);
+        assert_eq!(x(JustifyContent::Stretch), vec![80.0, 50.0]);
+        assert_eq!(x(JustifyContent::End), vec![30.0, 0.0]);
+        assert_eq!(x(JustifyContent::FlexEnd), vec![30.0, 0.0]);
+        assert_eq!(x(JustifyContent::Center), vec![55.0, 25.0]);
+        assert_eq!(x(JustifyContent::SpaceBetween), vec![80.0, 0.0]);
+    }
+
+    #[test]
+    fn rtl_column_aligns_items_to_the_right() {
+        let children = [fixed(20.0, 10.0), Style { align_self: Some(AlignSelf::End), ..fixed(30.0, 10.0) }];
+        let style =
+            Style { flex_direction: FlexDirection::Column, align_items: Some(AlignItems::Start), ..Default::default() };
+        let locations = child_locations(rtl(style), &children);
+        assert_eq!(locations, vec![Point { x: 80.0, y: 0.0 }, Point { x: 0.0, y: 10.0 }]);
+    }
+
+    #[test]
+    fn rtl_absolute_children_keep_physical_insets() {
+        let absolute =
+            |inset: Rect<LengthPercentageAuto>| Style { position: Position::Absolute, inset, ..fixed(20.0, 10.0) };
+        let children = [
+            absolute(Rect { left: length(5.0), right: auto(), top: auto(), bottom: auto() }),
+            absolute(Rect { left: auto(), right: length(5.0), top: auto(), bottom: auto() }),
+        ];
+        let locations = child_locations(rtl(Style::default()), &children);
+        assert_eq!(locations, vec![Point { x: 5.0, y: 0.0 }, Point { x: 75.0, y: 0.0 }]);
+    }
+
+    #[test]
+    fn rtl_absolute_child_static_position() {
+        let absolute = Style { position: Position::Absolute, ..fixed(20.0, 10.0) };
+        let x = |justify_content| {
+            let style = Style { justify_content, ..Default::default() };
+            child_locations(rtl(style), std::slice::from_ref(&absolute))[0].x
+        };
+
+        assert_eq!(x(None), 80.0);
+        assert_eq!(x(Some(JustifyContent::Start)), 80.0);
+        assert_eq!(x(Some(JustifyContent::FlexStart)), 80.0);
+        assert_eq!(x(Some(JustifyContent::End)), 0.0);
+        assert_eq!(x(Some(JustifyContent::FlexEnd)), 0.0);
+        assert_eq!(x(Some(JustifyContent::Center)), 40.0);
+    }
+
+    #[test]
+    fn ltr_is_the_default() {
+        assert_eq!(Style::default().direction, Direction::Ltr);
+        let locations = child_locations(fixed(100.0, 100.0), &[fixed(20.0, 10.0), fixed(30.0, 10.0)]);
+        assert_eq!(locations, vec![Point { x: 0.0, y: 0.0 }, Point { x: 20.0, y: 0.0 }]);
+    }
+}
@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod flex_basis {
+    use taffy::prelude::*;
+    use taffy_test_helpers::{new_test_tree, test_measure_function, TestNodeContext, WritingMode};
+
+    /// Lays out a single item that neither grows nor shrinks in a 500x500 container, so that its final main size is
+    /// its flex base size (clamped by its min and max sizes), and returns that main size
+    fn base_main_size(direction: FlexDirection, item_style: Style, content: Option<TestNodeContext>) -> f32 {
+        let mut taffy = new_test_tree();
+        let item_style = Style { flex_grow: 0.0, flex_shrink: 0.0, ..item_style };
+        let item = match content {
+            Some(context) => taffy.new_leaf_with_context(item_style, context).unwrap(),
+            None => taffy.new_leaf(item_style).unwrap(),
+        };
+        let container = taffy
+            .new_with_children(
+                Style {
+                    flex_direction: direction,
+                    align_items: Some(AlignItems::Start),
+                    size: Size { width: length(500.0), height: length(500.0) },
+                    ..Default::default()
+                },
+                &[item],
+            )
+            .unwrap();
+        taffy.compute_layout_with_measure(container, Size::MAX_CONTENT, test_measure_function).unwrap();
+
+        let size = taffy.layout(item).unwrap().size;
+        match direction {
+            FlexDirection::Row | FlexDirection::RowReverse => size.width,
+            FlexDirection::Column | FlexDirection::ColumnReverse => size.height,
+        }
+    }
+
+    fn auto_basis(size: Size<Dimension>) -> Style {
+        Style { flex_basis: auto(), size, ..Default::default() }
+    }
+
+    const CONTENT: Option<TestNodeContext> = Some(TestNodeContext::fixed(60.0, 20.0));
+
+    // flex-basis: auto uses the item's main size property (`width` in a row)...
+
+    #[test]
+    fn auto_basis_with_width_and_no_content() {
+        let style = auto_basis(Size { width: length(100.0), height: auto() });
+        assert_eq!(base_main_size(FlexDirection::Row, style, None), 100.0);
+    }
+
+    #[test]
+    fn auto_basis_with_width_ignores_content() {
+        let style = auto_basis(Size { width: length(100.0), height: auto() });
+        assert_eq!(base_main_size(FlexDirection::Row, style, CONTENT), 100.0);
+    }
+
+    #[test]
+    fn auto_basis_with_width_smaller_than_content() {
+        // The automatic minimum size of a flex item with a definite `width` is no larger than that `width`
+        let style = auto_basis(Size { width: length(40.0), height: auto() });
+        assert_eq!(base_main_size(FlexDirection::Row, style, CONTENT), 40.0);
+    }
+
+    #[test]
+    fn auto_basis_with_percentage_width() {
+        let style = auto_basis(Size { width: percent(0.5), height: auto() });
+        assert_eq!(base_main_size(FlexDirection::Row, style, CONTENT), 250.0);
+    }
+
+    // ...and is `content` (the max-content size) if that property is `auto`
+
+    #[test]
+    fn auto_basis_with_auto_width_and_no_content() {
+        let style = auto_basis(Size::auto());
+        assert_eq!(base_main_size(FlexDirection::Row, style, None), 0.0);
+    }
+
+    #[test]
+    fn auto_basis_with_auto_width_uses_content() {
+        let style = auto_basis(Size::auto());
+        assert_eq!(base_main_size(FlexDirection::Row, style, CONTENT), 60.0);
+    }
+
+    #[test]
+    fn auto_basis_with_auto_width_uses_max_content_of_text() {
+        // Two 20px wide words, which are laid out on a single line at the max-content size
+        let text = Some(TestNodeContext::ahem_text("HH\u{200b}HH", WritingMode::Horizontal));
+        let style = auto_basis(Size::auto());
+        assert_eq!(base_main_size(FlexDirection::Row, style, text), 40.0);
+    }
+
+    // The same applies to `height` in a column
+
+    #[test]
+    fn auto_basis_with_height_in_column() {
+        let style = auto_basis(Size { width: auto(), height: length(100.0) });
+        assert_eq!(base_main_size(FlexDirection::Column, style, CONTENT), 100.0);
+    }
+
+    #[test]
+    fn auto_basis_with_auto_height_in_column_uses_content() {
+        let style = auto_basis(Size::auto());
+        assert_eq!(base_main_size(FlexDirection::Column, style, CONTENT), 20.0);
+    }
+
+    // A definite flex-basis takes precedence over both
+
+    #[test]
+    fn definite_basis_overrides_width_and_content() {
+        let style = Style {
+            flex_basis: length(80.0),
+            size: Size { width: length(100.0), height: auto() },
+            ..Default::default()
+        };
+        assert_eq!(base_main_size(FlexDirection::Row, style, CONTENT), 80.0);
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod inset {
+    use taffy::prelude::*;
+    use taffy::Point;
+    use taffy_test_helpers::new_test_tree;
+
+    /// Lays out a 10x10 child with the given position and inset in a 200x100 container, returning its location
+    ///
+    /// The container is wider than it is tall, so a percentage resolved against the wrong axis is off by 2x.
+    fn child_location(display: Display, position: Position, inset: Rect<LengthPercentageAuto>) -> Point<f32> {
+        let mut taffy = new_test_tree();
+        let child = taffy
+            .new_leaf(Style {
+                position,
+                inset,
+                size: Size { width: length(10.0), height: length(10.0) },
+                ..Default::default()
+            })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style { display, size: Size { width: length(200.0), height: length(100.0) }, ..Default::default() },
+                &[child],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        taffy.layout(child).unwrap().location
+    }
+
+    fn top_left(top: f32, left: f32) -> Rect<LengthPercentageAuto> {
+        Rect { left: percent(left), right: auto(), top: percent(top), bottom: auto() }
+    }
+
+    fn bottom_right(bottom: f32, right: f32) -> Rect<LengthPercentageAuto> {
+        Rect { left: auto(), right: percent(right), top: auto(), bottom: percent(bottom) }
+    }
+
+    #[test]
+    fn absolute_vertical_percentages_resolve_against_height() {
+        for display in [Display::Flex, Display::Grid, Display::Block] {
+            let location = child_location(display, Position::Absolute, top_left(0.5, 0.0));
+            assert_eq!(location, Point { x: 0.0, y: 50.0 }, "top: 50% in {display:?}");
+
+            let location = child_location(display, Position::Absolute, bottom_right(0.5, 0.0));
+            assert_eq!(location, Point { x: 190.0, y: 40.0 }, "bottom: 50% in {display:?}");
+        }
+    }
+
+    #[test]
+    fn absolute_horizontal_percentages_resolve_against_width() {
+        for display in [Display::Flex, Display::Grid, Display::Block] {
+            let location = child_location(display, Position::Absolute, top_left(0.0, 0.25));
+            assert_eq!(location, Point { x: 50.0, y: 0.0 }, "left: 25% in {display:?}");
+
+            let location = child_location(display, Position::Absolute, bottom_right(0.0, 0.25));
+            assert_eq!(location, Point { x: 140.0, y: 90.0 }, "right: 25% in {display:?}");
+        }
+    }
+
+    #[test]
+    fn relative_percentages_resolve_against_the_matching_axis() {
+        for display in [Display::Flex, Display::Grid, Display::Block] {
+            let location = child_location(display, Position::Relative, top_left(0.5, 0.25));
+            assert_eq!(location, Point { x: 50.0, y: 50.0 }, "relative inset in {display:?}");
+        }
+    }
+
+    #[test]
+    fn relative_vertical_percentages_in_auto_height_block_are_ignored() {
+        let mut taffy = new_test_tree();
+        let inset = Rect { left: percent(0.25), right: auto(), top: percent(0.5), bottom: auto() };
+        let child = taffy
+            .new_leaf(Style { inset, size: Size { width: length(10.0), height: length(10.0) }, ..Default::default() })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    display: Display::Block,
+                    size: Size { width: length(200.0), height: auto() },
+                    ..Default::default()
+                },
+                &[child],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(child).unwrap().location, Point { x: 50.0, y: 0.0 });
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod measure {
     use taffy::prelude::*;
+    use taffy::Point;
     use taffy_test_helpers::{new_test_tree, test_measure_function, TestNodeContext};
 
     const HUNDRED_HUNDRED: TestNodeContext = TestNodeContext::fixed(100.0, 100.0);
@@ -368,4 +369,37 @@ mod measure {
         assert_eq!(taffy.layout(child).unwrap().size.width, 100.0);
         assert_eq!(taffy.layout(child).unwrap().size.height, 100.0);
     }
+
+    #[test]
+    fn measured_baselines_are_offset_by_padding_and_border() {
+        let mut taffy = new_test_tree();
+        let padded = Style {
+            padding: Rect { left: zero(), right: zero(), top: length(6.0), bottom: zero() },
+            border: Rect { left: zero(), right: zero(), top: length(2.0), bottom: zero() },
+            ..Default::default()
+        };
+        let text_with_padding = taffy.new_leaf_with_context(padded, HUNDRED_FIFTY).unwrap();
+        let text = taffy.new_leaf_with_context(Style::default(), FIFTY_FIFTY).unwrap();
+        let node = taffy
+            .new_with_children(
+                Style { align_items: Some(AlignItems::Baseline), ..Default::default() },
+                &[text_with_padding, text],
+            )
+            .unwrap();
+
+        // Both nodes have their first baseline 40px below the top of their content box
+        taffy
+            .compute_layout_with_measure_and_baselines(
+                node,
+                Size::MAX_CONTENT,
+                |known, available, node, context, style| {
+                    let size = test_measure_function(known, available, node, context, style);
+                    (size, Point { x: None, y: Some(40.0) })
+                },
+            )
+            .unwrap();
+
+        assert_eq!(taffy.layout(text_with_padding).unwrap().location.y, 0.0);
+        assert_eq!(taffy.layout(text).unwrap().location.y, 8.0);
+    }
 }
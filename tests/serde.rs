@@ -52,4 +52,69 @@ mod serde {
         }"###;
         let _: Value = serde_json::from_str(&json).unwrap();
     }
+
+    #[test]
+    fn dimensions_reject_intrinsic_sizing_keywords() {
+        use taffy::style::CompactLength;
+
+        for keyword in [CompactLength::min_content(), CompactLength::max_content(), CompactLength::fit_content_px(50.0)]
+        {
+            let bits = keyword.to_serialized();
+            let properties = if cfg!(feature = "camel_case_style") {
+                ["size", "minSize", "maxSize"]
+            } else {
+                ["size", "min_size", "max_size"]
+            };
+            for property in properties {
+                let json = serde_json::json!({ property: { "width": bits, "height": bits } });
+                assert!(serde_json::from_value::<Style>(json).is_err(), "{property} accepted {keyword:?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "camel_case_style")]
+    mod camel_case_style {
+        use serde_json::json;
+        use taffy::prelude::*;
+
+        fn style() -> Style {
+            Style {
+                display: Display::Grid,
+                min_size: Size { width: length(10.0), height: percent(0.5) },
+                flex_direction: FlexDirection::ColumnReverse,
+                grid_template_rows: vec![fr(1.0), length(20.0)],
+                grid_auto_flow: GridAutoFlow::RowDense,
+                scrollbar_width: 4.0,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn serializes_field_names_in_camel_case() {
+            let json = serde_json::to_value(style()).unwrap();
+            let object = json.as_object().unwrap();
+            for key in ["minSize", "flexDirection", "gridTemplateRows", "gridAutoFlow", "scrollbarWidth", "display"] {
+                assert!(object.contains_key(key), "missing {key}");
+            }
+            assert!(object.keys().all(|key| !key.contains('_')), "snake_case key in {json}");
+            // Values are unchanged
+            assert_eq!(object["flexDirection"], json!("ColumnReverse"));
+        }
+
+        #[test]
+        fn round_trips() {
+            let json = serde_json::to_string(&style()).unwrap();
+            assert_eq!(serde_json::from_str::<Style>(&json).unwrap(), style());
+        }
+
+        #[test]
+        fn deserializes_partial_camel_case_styles() {
+            let json = json!({ "flexDirection": "Column", "alignItems": "Center", "flex_grow": 2.0 });
+            let style: Style = serde_json::from_value(json).unwrap();
+            assert_eq!(style.flex_direction, FlexDirection::Column);
+            assert_eq!(style.align_items, Some(AlignItems::Center));
+            // snake_case keys are no longer recognised, so they fall back to the default
+            assert_eq!(style.flex_grow, 0.0);
+        }
+    }
 }